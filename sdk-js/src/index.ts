@@ -115,6 +115,7 @@ export type {
   PluginHttpResponse,
   PluginI18nBridge,
   PluginI18nManifestSpec,
+  PluginKeyValueBridge,
   PluginLogger,
   PluginManifest,
   PluginMeta,
@@ -125,6 +126,7 @@ export type {
   PluginResult,
   PluginRuntimeLanguage,
   PluginRuntimeSpec,
+  PluginSecretsBridge,
   PluginSignaturePayload,
   PluginTrigger,
   ToolRunner,
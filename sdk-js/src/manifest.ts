@@ -13,6 +13,7 @@ import type {
 const PROVIDERS_BY_LANGUAGE: Record<PluginRuntimeLanguage, PluginProvider[]> = {
   javascript: ['deno'],
   python: [],
+  wasm: ['wasm'],
 };
 
 const ALLOWED_TRIGGERS = new Set([
@@ -311,6 +312,40 @@ export function getManifestValidationErrors(manifest: PluginManifest): string[]
     }
   }
 
+  const networkHosts = manifest.permissions?.networkHosts ?? [];
+  if (networkHosts.length > 0 && !manifest.permissions?.network) {
+    errors.push('permissions.networkHosts requires permissions.network to be true.');
+  }
+  const seenNetworkHosts = new Set<string>();
+  for (const host of networkHosts) {
+    if (!/^[A-Za-z0-9.\-[\]:]+$/.test(host) || host.includes('://')) {
+      errors.push(
+        `permissions.networkHosts contains "${host}", which must be a bare host or host:port without wildcards.`,
+      );
+    }
+    if (seenNetworkHosts.has(host.toLowerCase())) {
+      errors.push(`permissions.networkHosts contains duplicate host "${host}".`);
+    }
+    seenNetworkHosts.add(host.toLowerCase());
+  }
+  if (manifest.resolver) {
+    if (!manifest.permissions?.resolver) {
+      errors.push('resolver requires permissions.resolver to be true.');
+    }
+    if (!manifest.resolver.urlPatterns?.length) {
+      errors.push('resolver.urlPatterns must contain at least one URL pattern.');
+    }
+    for (const pattern of manifest.resolver.urlPatterns ?? []) {
+      if (!/^https?:\/\//.test(pattern.trim())) {
+        errors.push(
+          `resolver.urlPatterns contains "${pattern}", which must start with http:// or https://.`,
+        );
+      }
+    }
+  } else if (manifest.permissions?.resolver) {
+    errors.push('permissions.resolver requires a resolver declaration.');
+  }
+
   if (manifest.i18n?.defaultLocale) {
     if (
       manifest.i18n.supportedLocales?.length &&
@@ -21,9 +21,11 @@ import type {
   PluginHttpRequestOptions,
   PluginHttpResponse,
   PluginI18nBridge,
+  PluginKeyValueBridge,
   PluginLogger,
   PluginPayload,
   PluginResult,
+  PluginSecretsBridge,
   ToolRunner,
   YoutubeSearchOptions,
   YoutubeSearchResponse,
@@ -352,6 +354,42 @@ function encodeBytesToBase64(value: Uint8Array | ArrayBuffer | number[]): string
   return btoa(binary);
 }
 
+function createKeyValueBridge(): PluginKeyValueBridge {
+  return {
+    async get<T = unknown>(key: string, namespace?: string) {
+      return await bridgeRequest<T | null>('/kv/get', { key, namespace });
+    },
+    async set(key, value, namespace) {
+      if (value === undefined) {
+        throw new Error(
+          'ctx.youwee.kv.set(...) cannot store undefined. Use ctx.youwee.kv.delete(...) instead.',
+        );
+      }
+      await bridgeRequest<null>('/kv/set', { key, value, namespace });
+    },
+    async delete(key, namespace) {
+      return await bridgeRequest<boolean>('/kv/delete', { key, namespace });
+    },
+    async list(prefix, namespace) {
+      return await bridgeRequest<string[]>('/kv/list', { prefix, namespace });
+    },
+  };
+}
+
+function createSecretsBridge(): PluginSecretsBridge {
+  return {
+    async get(key) {
+      return await bridgeRequest<string | null>('/secrets/get', { key });
+    },
+    async set(key, value) {
+      await bridgeRequest<null>('/secrets/set', { key, value: String(value) });
+    },
+    async delete(key) {
+      return await bridgeRequest<boolean>('/secrets/delete', { key });
+    },
+  };
+}
+
 function createHttpBridge(): PluginHttpBridge {
   return {
     async request(url, options = {}) {
@@ -512,6 +550,8 @@ function createYouweeBridge(logger: PluginLogger): YouweeBridge {
       ytdlp: createCommandRunner('yt-dlp', 'ytdlp'),
     },
     fs: createFileSystemBridge(),
+    kv: createKeyValueBridge(),
+    secrets: createSecretsBridge(),
     http: createHttpBridge(),
     youtube: createYouTubeBridge(),
     ai: createAIBridge(logger),
@@ -4,8 +4,8 @@ export type PluginTrigger =
   | 'download.completed'
   | 'download.failed';
 
-export type PluginRuntimeLanguage = 'javascript' | 'python';
-export type PluginProvider = 'deno' | 'python';
+export type PluginRuntimeLanguage = 'javascript' | 'python' | 'wasm';
+export type PluginProvider = 'deno' | 'python' | 'wasm';
 export type PluginManifestIconName = string;
 
 export interface ParsedSemver {
@@ -204,6 +204,19 @@ export interface PluginFileSystemBridge {
   tempDir(prefix?: string): Promise<string>;
 }
 
+export interface PluginKeyValueBridge {
+  get<T = unknown>(key: string, namespace?: string): Promise<T | null>;
+  set(key: string, value: unknown, namespace?: string): Promise<void>;
+  delete(key: string, namespace?: string): Promise<boolean>;
+  list(prefix?: string, namespace?: string): Promise<string[]>;
+}
+
+export interface PluginSecretsBridge {
+  get(key: string): Promise<string | null>;
+  set(key: string, value: string): Promise<void>;
+  delete(key: string): Promise<boolean>;
+}
+
 export interface PluginDirectoryEntry {
   name: string;
   path: string;
@@ -395,6 +408,8 @@ export interface YouweeBridge {
     ytdlp: ToolRunner;
   };
   fs: PluginFileSystemBridge;
+  kv: PluginKeyValueBridge;
+  secrets: PluginSecretsBridge;
   http: PluginHttpBridge;
   youtube: YouweeYouTubeBridge;
   ai: AIBridge;
@@ -478,8 +493,10 @@ export interface PluginDefinition<THooks extends PluginHooks = PluginHooks> {
 
 export interface PluginPermissionRequest {
   network?: boolean;
+  networkHosts?: string[];
   fs?: PluginFilesystemPermission[];
   tools?: PluginToolPermission[];
+  resolver?: boolean;
 }
 
 export type PluginToolPermission = 'tool.ffmpeg.run' | 'tool.ytdlp.run';
@@ -515,6 +532,10 @@ export interface PluginManifest {
     appVersion?: string;
     sdkVersion?: string;
   };
+  resolver?: {
+    urlPatterns: string[];
+    priority?: number;
+  };
 }
 
 export interface ManifestValidationResult {
@@ -1,8 +1,13 @@
-use crate::database::update_history_summary;
+use crate::database::{
+    assign_history_tags_in_db, clear_ai_response_cache_in_db, get_ai_usage_report_from_db,
+    get_history_entries_by_ids_from_db, get_history_transcript_from_db, update_history_summary,
+};
 use crate::services::{
-    generate_raw, generate_summary_custom_with_hooks, test_connection, AIConfig, LongSummaryFormat,
-    LongSummaryHooks, LongSummaryProgress, SummaryStyle,
+    generate_raw, generate_structured_insights, generate_summary_custom_with_hooks,
+    test_connection, AIConfig, LongSummaryFormat, LongSummaryHooks, LongSummaryProgress,
+    StructuredOutputKind, StructuredVideoInsights, SummaryStyle,
 };
+use crate::types::AIUsageReportRow;
 use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
@@ -56,6 +61,14 @@ struct SummaryProgressPayload {
     chunk_count: usize,
 }
 
+/// A chunk of summary text, emitted as the provider streams it
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SummaryStreamPayload {
+    request_id: String,
+    delta: String,
+}
+
 async fn generate_summary_with_progress(
     app: &AppHandle,
     config: &AIConfig,
@@ -91,6 +104,17 @@ async fn generate_summary_with_progress(
             .map(is_summary_request_cancelled)
             .unwrap_or(false)
     };
+    let delta_request_id = request_id.clone();
+    let delta_app = app.clone();
+    let delta = move |delta: &str| {
+        if let Some(request_id) = delta_request_id.as_deref() {
+            let payload = SummaryStreamPayload {
+                request_id: request_id.to_string(),
+                delta: delta.to_string(),
+            };
+            delta_app.emit("summary-stream", payload).ok();
+        }
+    };
     let hooks = LongSummaryHooks {
         progress: request_id
             .as_ref()
@@ -98,6 +122,9 @@ async fn generate_summary_with_progress(
         should_cancel: request_id
             .as_ref()
             .map(|_| &should_cancel as &(dyn Fn() -> bool + Send + Sync)),
+        delta: request_id
+            .as_ref()
+            .map(|_| &delta as &(dyn Fn(&str) + Send + Sync)),
     };
 
     let result = generate_summary_custom_with_hooks(
@@ -138,7 +165,11 @@ pub async fn save_ai_config(app: AppHandle, config: AIConfig) -> Result<(), Stri
 /// Load AI configuration
 #[tauri::command]
 pub async fn get_ai_config(app: AppHandle) -> Result<AIConfig, String> {
-    let path = get_config_path(&app)?;
+    load_ai_config(&app)
+}
+
+pub(crate) fn load_ai_config(app: &AppHandle) -> Result<AIConfig, String> {
+    let path = get_config_path(app)?;
 
     if !path.exists() {
         return Ok(AIConfig::default());
@@ -168,8 +199,10 @@ pub async fn generate_video_summary(
     history_id: Option<String>,
     title: Option<String>,
     request_id: Option<String>,
+    refresh: Option<bool>,
 ) -> Result<String, String> {
-    let config = get_ai_config(app.clone()).await?;
+    let mut config = get_ai_config(app.clone()).await?;
+    config.refresh_response_cache = refresh.unwrap_or(false);
 
     if !config.enabled {
         return Err("AI features are disabled. Enable them in Settings.".to_string());
@@ -207,8 +240,10 @@ pub async fn generate_summary_with_options(
     long_summary_format: Option<String>,
     long_summary_words: Option<u32>,
     request_id: Option<String>,
+    refresh: Option<bool>,
 ) -> Result<SummaryResult, String> {
-    let config = get_ai_config(app.clone()).await?;
+    let mut config = get_ai_config(app.clone()).await?;
+    config.refresh_response_cache = refresh.unwrap_or(false);
 
     if !config.enabled {
         return Err("AI features are disabled. Enable them in Settings.".to_string());
@@ -365,6 +400,20 @@ pub fn get_ai_models(provider: String) -> Vec<ModelOption> {
                 label: "Gemma 3 12B".to_string(),
             },
         ],
+        "anthropic" => vec![
+            ModelOption {
+                value: "claude-sonnet-4-5".to_string(),
+                label: "Claude Sonnet 4.5 (Recommended)".to_string(),
+            },
+            ModelOption {
+                value: "claude-haiku-4-5".to_string(),
+                label: "Claude Haiku 4.5".to_string(),
+            },
+            ModelOption {
+                value: "claude-opus-4-1".to_string(),
+                label: "Claude Opus 4.1".to_string(),
+            },
+        ],
         "deepseek" => vec![
             ModelOption {
                 value: "deepseek-v4-flash".to_string(),
@@ -498,3 +547,74 @@ pub async fn generate_ai_response(app: AppHandle, prompt: String) -> Result<Stri
 
     Ok(result.summary)
 }
+
+/// Generate chapters, highlight clips, tags, and/or a title from a Library item's transcript.
+///
+/// With `apply_tags`, suggested tags are added to the item's existing tags.
+#[tauri::command]
+pub async fn generate_video_insights(
+    app: AppHandle,
+    history_id: String,
+    kinds: Vec<StructuredOutputKind>,
+    language: Option<String>,
+    apply_tags: Option<bool>,
+) -> Result<StructuredVideoInsights, String> {
+    let config = get_ai_config(app).await?;
+    if !config.enabled {
+        return Err("AI features are disabled. Enable them in Settings.".to_string());
+    }
+    if kinds.is_empty() {
+        return Err("Select at least one output to generate".to_string());
+    }
+
+    let entry = get_history_entries_by_ids_from_db(vec![history_id.clone()])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Library item not found: {}", history_id))?;
+    let transcript = get_history_transcript_from_db(&history_id)?.ok_or_else(|| {
+        "No transcript saved for this item. Summarize or transcribe it first.".to_string()
+    })?;
+    let duration_seconds = entry.duration.map(|d| d as f64).or_else(|| {
+        transcript
+            .segments
+            .last()
+            .map(|segment| segment.end_ms as f64 / 1000.0)
+    });
+    let language = language.unwrap_or_else(|| config.summary_language.clone());
+
+    let insights = generate_structured_insights(
+        &config,
+        &kinds,
+        &transcript.segments,
+        Some(&entry.title),
+        duration_seconds,
+        &language,
+    )
+    .await
+    .map_err(|e| e.to_wire_string())?;
+
+    if apply_tags.unwrap_or(false) && !insights.tags.is_empty() {
+        let mut tags = entry
+            .tags
+            .iter()
+            .map(|tag| tag.name.clone())
+            .collect::<Vec<_>>();
+        tags.extend(insights.tags.iter().cloned());
+        assign_history_tags_in_db(history_id, tags)?;
+    }
+
+    Ok(insights)
+}
+
+/// Token usage and estimated cost per day, provider, and model. `days` limits the report
+/// to the most recent days.
+#[tauri::command]
+pub fn get_ai_usage_report(days: Option<u32>) -> Result<Vec<AIUsageReportRow>, String> {
+    get_ai_usage_report_from_db(days)
+}
+
+/// Drop every cached AI response so the next request goes to the provider
+#[tauri::command]
+pub fn clear_ai_response_cache() -> Result<usize, String> {
+    clear_ai_response_cache_in_db()
+}
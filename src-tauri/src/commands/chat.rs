@@ -0,0 +1,114 @@
+use tauri::AppHandle;
+
+use super::ai::load_ai_config;
+use crate::database::{
+    add_history_chat_message_in_db, add_log_internal, clear_history_chat_from_db,
+    get_history_chat_from_db, get_history_entries_by_ids_from_db, get_history_transcript_from_db,
+};
+use crate::services::{
+    build_chat_prompt, build_transcript_passages, extract_chat_citations, generate_raw,
+    retrieve_passages, CHAT_FULL_CONTEXT_CHARS, CHAT_PASSAGE_CHARS, CHAT_RETRIEVED_PASSAGES,
+};
+use crate::types::{HistoryChatMessage, HistoryChatRole};
+
+/// Answer a question about one or more Library items from their saved transcripts.
+///
+/// The conversation is stored under the set of selected items, so asking about the same
+/// items in any order continues it, and the answer cites transcript timestamps.
+#[tauri::command]
+pub async fn ask_history_question(
+    app: AppHandle,
+    history_ids: Vec<String>,
+    question: String,
+    language: Option<String>,
+) -> Result<HistoryChatMessage, String> {
+    let config = load_ai_config(&app)?;
+    if !config.enabled {
+        return Err("AI features are disabled. Enable them in Settings.".to_string());
+    }
+    let question = question.trim().to_string();
+    if question.is_empty() {
+        return Err("Question cannot be empty".to_string());
+    }
+
+    let mut ids: Vec<String> = Vec::new();
+    for id in history_ids {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    if ids.is_empty() {
+        return Err("Select at least one item to ask about".to_string());
+    }
+
+    let entries = get_history_entries_by_ids_from_db(ids.clone())?;
+    let mut titles = Vec::new();
+    let mut passages = Vec::new();
+    for (index, id) in ids.iter().enumerate() {
+        let title = entries
+            .iter()
+            .find(|entry| &entry.id == id)
+            .map(|entry| entry.title.clone())
+            .ok_or_else(|| format!("Library item not found: {}", id))?;
+        let transcript = get_history_transcript_from_db(id)?.ok_or_else(|| {
+            format!(
+                "No transcript saved for \"{}\". Summarize or transcribe it first.",
+                title
+            )
+        })?;
+        passages.extend(build_transcript_passages(
+            index + 1,
+            id,
+            &transcript.segments,
+            CHAT_PASSAGE_CHARS,
+        ));
+        titles.push(title);
+    }
+
+    let total_chars = passages
+        .iter()
+        .map(|passage| passage.text.chars().count())
+        .sum::<usize>();
+    let passages = if total_chars > CHAT_FULL_CONTEXT_CHARS {
+        retrieve_passages(&question, &passages, CHAT_RETRIEVED_PASSAGES)
+    } else {
+        passages
+    };
+
+    let history = get_history_chat_from_db(&ids)?;
+    let language = language.unwrap_or_else(|| config.summary_language.clone());
+    let prompt = build_chat_prompt(&question, &titles, &passages, &history, &language);
+    add_log_internal(
+        "info",
+        &format!(
+            "Answering question over {} video(s) with {} transcript passages",
+            titles.len(),
+            passages.len()
+        ),
+        None,
+        None,
+    )
+    .ok();
+
+    let answer = generate_raw(&config, &prompt)
+        .await
+        .map_err(|e| e.to_wire_string())?
+        .summary
+        .trim()
+        .to_string();
+    let citations = extract_chat_citations(&answer, &passages);
+
+    add_history_chat_message_in_db(&ids, HistoryChatRole::User, &question, &[])?;
+    add_history_chat_message_in_db(&ids, HistoryChatRole::Assistant, &answer, &citations)
+}
+
+/// Saved Q&A conversation over exactly these Library items, oldest first
+#[tauri::command]
+pub fn get_history_chat(history_ids: Vec<String>) -> Result<Vec<HistoryChatMessage>, String> {
+    get_history_chat_from_db(&history_ids)
+}
+
+#[tauri::command]
+pub fn clear_history_chat(history_ids: Vec<String>) -> Result<usize, String> {
+    clear_history_chat_from_db(&history_ids)
+}
@@ -1,16 +1,18 @@
 use crate::services::{
     check_deno_internal, check_deno_update_internal, check_ffmpeg_internal,
-    check_ffmpeg_update_internal, check_gallerydl_internal, get_all_ytdlp_versions,
-    get_channel_api_url, get_deno_download_url, get_ffmpeg_download_info, get_ffmpeg_path,
-    get_ffmpeg_source, get_latest_ffmpeg_release_info, get_ytdlp_channel,
-    get_ytdlp_channel_download_url, get_ytdlp_download_info, get_ytdlp_source,
-    get_ytdlp_version_internal, parse_ffmpeg_version, set_ffmpeg_source, set_ytdlp_channel,
+    check_ffmpeg_update_internal, check_gallerydl_internal, check_local_whisper_internal,
+    get_all_ytdlp_versions, get_channel_api_url, get_deno_download_url, get_ffmpeg_download_info,
+    get_ffmpeg_path, get_ffmpeg_source, get_latest_ffmpeg_release_info,
+    get_local_whisper_model_download, get_ytdlp_channel, get_ytdlp_channel_download_url,
+    get_ytdlp_download_info, get_ytdlp_source, get_ytdlp_version_internal,
+    list_local_whisper_models_internal, parse_ffmpeg_version, set_ffmpeg_source, set_ytdlp_channel,
     set_ytdlp_source, system_ffmpeg_upgrade_message, system_ytdlp_upgrade_message, verify_sha256,
     write_app_ffmpeg_release_version, DenoUpdateInfo, FfmpegUpdateInfo,
 };
 use crate::types::{
-    BackendError, DenoStatus, DependencySource, FfmpegStatus, GalleryDlStatus, YtdlpAllVersions,
-    YtdlpChannel, YtdlpChannelUpdateInfo, YtdlpVersionInfo,
+    BackendError, DenoStatus, DependencySource, FfmpegStatus, GalleryDlStatus, LocalWhisperEngine,
+    LocalWhisperModelInfo, LocalWhisperStatus, YtdlpAllVersions, YtdlpChannel,
+    YtdlpChannelUpdateInfo, YtdlpVersionInfo,
 };
 use crate::utils::{
     extract_deno_zip, extract_tar_gz, extract_tar_xz, extract_zip, firefox_profiles_from_ini,
@@ -790,6 +792,153 @@ pub async fn get_ffmpeg_path_for_ytdlp(app: AppHandle) -> Result<Option<String>,
         .map(|p| p.to_string_lossy().to_string()))
 }
 
+#[tauri::command]
+pub async fn check_local_whisper(
+    app: AppHandle,
+    engine: String,
+    binary_path: Option<String>,
+) -> Result<LocalWhisperStatus, String> {
+    let engine = LocalWhisperEngine::from_str(&engine);
+    check_local_whisper_internal(&app, engine, binary_path.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn list_local_whisper_models(
+    app: AppHandle,
+) -> Result<Vec<LocalWhisperModelInfo>, String> {
+    list_local_whisper_models_internal(&app)
+}
+
+/// Download a whisper.cpp ggml model into app data and check its SHA256 for corruption
+#[tauri::command]
+pub async fn download_local_whisper_model(app: AppHandle, model: String) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+
+    let _ = app.emit(
+        "whisper-model-download-progress",
+        DownloadProgress {
+            stage: "checksum".to_string(),
+            percent: 0,
+            downloaded: 0,
+            total: 0,
+        },
+    );
+
+    let info = get_local_whisper_model_download(&app, &model).await?;
+    let models_dir = info
+        .path
+        .parent()
+        .ok_or("Failed to get Whisper models directory")?;
+    tokio::fs::create_dir_all(models_dir)
+        .await
+        .map_err(|e| format!("Failed to create Whisper models directory: {}", e))?;
+
+    let client = reqwest::Client::builder()
+        .user_agent(format!("Youwee/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(&info.url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download Whisper model: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Download failed with status: {}",
+            response.status()
+        ));
+    }
+
+    let total_size = response.content_length().unwrap_or(info.size);
+    let mut downloaded: u64 = 0;
+    let mut last_percent: u8 = 0;
+
+    // Models are hundreds of MB, so hash while streaming instead of reading the file back.
+    let temp_path = info.path.with_extension("bin.tmp");
+    let mut file = tokio::fs::File::create(&temp_path)
+        .await
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                drop(file);
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                return Err(format!("Download error: {}", e));
+            }
+        };
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write chunk: {}", e))?;
+
+        downloaded += chunk.len() as u64;
+
+        let percent = if total_size > 0 {
+            ((downloaded as f64 / total_size as f64) * 100.0) as u8
+        } else {
+            0
+        };
+
+        if percent >= last_percent + 5 || percent == 100 {
+            last_percent = percent;
+            let _ = app.emit(
+                "whisper-model-download-progress",
+                DownloadProgress {
+                    stage: "downloading".to_string(),
+                    percent,
+                    downloaded,
+                    total: total_size,
+                },
+            );
+        }
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to flush file: {}", e))?;
+    drop(file);
+
+    let _ = app.emit(
+        "whisper-model-download-progress",
+        DownloadProgress {
+            stage: "verifying".to_string(),
+            percent: 100,
+            downloaded,
+            total: total_size,
+        },
+    );
+
+    let computed_hash = hex::encode(hasher.finalize());
+    if downloaded != info.size || !computed_hash.eq_ignore_ascii_case(&info.sha256) {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(
+            "Whisper model download is incomplete or corrupted (SHA256 mismatch). Try again."
+                .to_string(),
+        );
+    }
+
+    tokio::fs::rename(&temp_path, &info.path)
+        .await
+        .map_err(|e| format!("Failed to save Whisper model: {}", e))?;
+
+    let _ = app.emit(
+        "whisper-model-download-progress",
+        DownloadProgress {
+            stage: "complete".to_string(),
+            percent: 100,
+            downloaded,
+            total: total_size,
+        },
+    );
+
+    Ok(info.path.to_string_lossy().to_string())
+}
+
 #[tauri::command]
 pub async fn check_deno(app: AppHandle) -> Result<DenoStatus, String> {
     check_deno_internal(&app).await
@@ -27,12 +27,12 @@ use crate::services::{
     add_safe_filename_args, build_cookie_args, build_proxy_args, build_site_header_args,
     build_youtube_extractor_args, build_ytdlp_advanced_args, enqueue_post_download_workflow,
     get_deno_path, get_ffmpeg_path, get_ytdlp_path, get_ytdlp_source, is_upcoming_live_error,
-    redact_ytdlp_advanced_args, resolve_download_workflow_snapshot, run_ytdlp_with_stderr,
-    system_ytdlp_not_found_message, YtdlpAdvancedOption,
+    redact_ytdlp_advanced_args, resolve_download_workflow_snapshot, resolve_url_with_plugins,
+    run_ytdlp_with_stderr, system_ytdlp_not_found_message, YtdlpAdvancedOption,
 };
 use crate::types::{
-    BackendError, DependencySource, DownloadProgress, PluginWorkflowStepSnapshot,
-    PostDownloadPluginPayload,
+    BackendError, DependencySource, DownloadProgress, PluginResolvedUrl,
+    PluginWorkflowStepSnapshot, PostDownloadPluginPayload,
 };
 use crate::utils::{
     build_format_string, format_size, parse_progress, sanitize_output_path, CommandExt,
@@ -501,6 +501,7 @@ mod playlist_chapter_tests {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn skipped_live_status(
     app: &AppHandle,
     url: &str,
@@ -510,6 +511,7 @@ async fn skipped_live_status(
     cookie_file_path: Option<&str>,
     cookie_skip_patterns: Option<&[String]>,
     proxy_url: Option<&str>,
+    header_args: &[String],
 ) -> Result<Option<String>, String> {
     let mut args = vec![
         "--print".to_string(),
@@ -533,6 +535,7 @@ async fn skipped_live_status(
     args.push(url.to_string());
 
     let mut extra_args = build_site_header_args(url);
+    extra_args.extend(header_args.iter().cloned());
     extra_args.extend(build_cookie_args(
         url,
         cookie_mode,
@@ -917,6 +920,33 @@ fn normalize_aria2_args(raw_args: &str) -> Option<String> {
     Some(format!("aria2c:{}", trimmed))
 }
 
+/// Resolver results are validated to carry identical headers on every media item, so the first
+/// item's headers apply to the whole yt-dlp invocation.
+fn build_resolved_media_header_args(resolution: &PluginResolvedUrl) -> Vec<String> {
+    resolution
+        .media
+        .first()
+        .map(|media| {
+            media
+                .headers
+                .iter()
+                .flat_map(|(name, value)| {
+                    ["--add-header".to_string(), format!("{}:{}", name, value)]
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn reported_live_status(resolution: &PluginResolvedUrl) -> Option<String> {
+    resolution
+        .live_status
+        .as_deref()
+        .map(str::trim)
+        .filter(|status| !status.is_empty() && *status != "not_live")
+        .map(str::to_string)
+}
+
 fn build_download_error_message(exit_code: Option<i32>, recent_lines: &[String]) -> BackendError {
     if recent_lines
         .iter()
@@ -1060,19 +1090,61 @@ pub async fn download_video(
     let emit_failed_workflow = emit_failed_workflow.unwrap_or(true);
     let download_kind = download_kind.unwrap_or_else(|| "download".to_string());
 
+    // Plugin resolvers claim URLs yt-dlp cannot extract and hand back direct media URLs.
+    let plugin_resolution = match resolve_url_with_plugins(&app, &url).await {
+        Ok(resolution) => resolution,
+        Err(error) => {
+            add_log_internal(
+                "error",
+                "Plugin URL resolver failed, falling back to yt-dlp",
+                Some(&error),
+                Some(&url),
+            )
+            .ok();
+            None
+        }
+    };
+    let title = title.or_else(|| {
+        plugin_resolution
+            .as_ref()
+            .and_then(|resolution| resolution.title.clone())
+    });
+    let thumbnail = thumbnail.or_else(|| {
+        plugin_resolution
+            .as_ref()
+            .and_then(|resolution| resolution.thumbnail.clone())
+    });
+
     if skip_live.unwrap_or(false) {
-        if let Some(live_status) = skipped_live_status(
-            &app,
-            &url,
-            cookie_mode.as_deref(),
-            cookie_browser.as_deref(),
-            cookie_browser_profile.as_deref(),
-            cookie_file_path.as_deref(),
-            cookie_skip_patterns.as_deref(),
-            proxy_url.as_deref(),
-        )
-        .await?
-        {
+        // Resolved downloads use the resolver's own status, or probe the first media URL since
+        // yt-dlp usually cannot extract the page URL a resolver claimed.
+        let live_status = match plugin_resolution.as_ref() {
+            Some(resolution) if resolution.live_status.is_some() => {
+                reported_live_status(resolution)
+            }
+            _ => {
+                let (probe_url, probe_header_args) = match plugin_resolution.as_ref() {
+                    Some(resolution) => (
+                        resolution.media[0].url.clone(),
+                        build_resolved_media_header_args(resolution),
+                    ),
+                    None => (url.clone(), Vec::new()),
+                };
+                skipped_live_status(
+                    &app,
+                    &probe_url,
+                    cookie_mode.as_deref(),
+                    cookie_browser.as_deref(),
+                    cookie_browser_profile.as_deref(),
+                    cookie_file_path.as_deref(),
+                    cookie_skip_patterns.as_deref(),
+                    proxy_url.as_deref(),
+                    &probe_header_args,
+                )
+                .await?
+            }
+        };
+        if let Some(live_status) = live_status {
             add_log_internal(
                 "info",
                 &format!(
@@ -1363,8 +1435,17 @@ pub async fn download_video(
         }
     }
 
-    args.push("--".to_string());
-    args.push(url.clone());
+    match plugin_resolution.as_ref() {
+        Some(resolution) => {
+            args.extend(build_resolved_media_header_args(resolution));
+            args.push("--".to_string());
+            args.extend(resolution.media.iter().map(|media| media.url.clone()));
+        }
+        None => {
+            args.push("--".to_string());
+            args.push(url.clone());
+        }
+    }
 
     // Get binary info for logging
     let binary_info = get_ytdlp_path(&app).await;
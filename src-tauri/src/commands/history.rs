@@ -3,15 +3,17 @@ use std::path::{Path, PathBuf};
 use crate::database::{
     add_history_internal, add_history_with_summary, assign_history_collections_in_db,
     assign_history_tags_in_db, clear_history_from_db, create_collection_in_db,
-    delete_collection_from_db, delete_history_from_db, find_duplicate_downloads_in_history_db,
-    get_collections_from_db, get_history_count_from_db, get_history_entries_by_ids_from_db,
-    get_history_from_db, get_tags_from_db, remove_history_from_collection_in_db,
-    remove_history_tag_from_db, rename_collection_in_db, update_history_filepath_and_title,
+    delete_collection_from_db, delete_history_from_db, delete_history_transcript_from_db,
+    find_duplicate_downloads_in_history_db, get_collections_from_db, get_history_count_from_db,
+    get_history_entries_by_ids_from_db, get_history_from_db, get_history_transcript_from_db,
+    get_tags_from_db, remove_history_from_collection_in_db, remove_history_tag_from_db,
+    rename_collection_in_db, update_history_filepath_and_title,
     update_history_filepath_and_title_by_id, update_history_summary,
 };
+use crate::services::render_transcript;
 use crate::types::{
     DownloadDuplicateIdentity, DownloadDuplicateMatch, HistoryAdvancedFilters, HistoryCollection,
-    HistoryEntry, HistorySort, HistoryTag,
+    HistoryEntry, HistorySort, HistoryTag, HistoryTranscript,
 };
 
 #[tauri::command]
@@ -171,6 +173,32 @@ pub fn remove_history_from_collection(
     remove_history_from_collection_in_db(history_id, collection_id)
 }
 
+/// Timed transcript saved for a history entry, if one was fetched or transcribed
+#[tauri::command]
+pub fn get_history_transcript(history_id: String) -> Result<Option<HistoryTranscript>, String> {
+    get_history_transcript_from_db(&history_id)
+}
+
+/// Write a saved transcript to disk as `srt`, `vtt`, or `txt`
+#[tauri::command]
+pub fn export_history_transcript(
+    history_id: String,
+    format: String,
+    output_path: String,
+) -> Result<String, String> {
+    let transcript = get_history_transcript_from_db(&history_id)?
+        .ok_or_else(|| "No transcript saved for this item".to_string())?;
+    let content = render_transcript(&transcript.segments, &format)?;
+    std::fs::write(&output_path, content)
+        .map_err(|e| format!("Failed to write transcript file: {}", e))?;
+    Ok(output_path)
+}
+
+#[tauri::command]
+pub fn delete_history_transcript(history_id: String) -> Result<bool, String> {
+    delete_history_transcript_from_db(&history_id)
+}
+
 #[tauri::command]
 pub fn check_file_exists(filepath: String) -> bool {
     std::path::Path::new(&filepath).exists()
@@ -5,14 +5,24 @@ use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tokio::process::Command;
 
+use super::detect_shot_changes;
+use super::processing::get_ffprobe_path;
 use crate::database::{
     add_history_internal, assign_history_collections_in_db, delete_history_from_db,
     ensure_collection_for_download_in_db,
 };
-use crate::services::get_ffmpeg_path;
+use crate::services::{get_ffmpeg_path, parse_silences};
 use crate::utils::CommandExt;
 
-#[derive(Debug, Clone, Deserialize)]
+const DEFAULT_SILENCE_NOISE_DB: f64 = -35.0;
+const DEFAULT_MIN_SILENCE_SECONDS: f64 = 1.0;
+const DEFAULT_SCENE_THRESHOLD: f64 = 0.4;
+// Speech pauses and scene cuts come every few seconds; keep proposed parts watchable.
+const DEFAULT_MIN_SEGMENT_SECONDS: f64 = 60.0;
+const MIN_SEGMENT_SECONDS: f64 = 1.0;
+const MAX_PROPOSED_SEGMENTS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MediaSplitSegmentRequest {
     pub name: String,
@@ -20,7 +30,7 @@ pub struct MediaSplitSegmentRequest {
     pub end_seconds: f64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MediaSplitRequest {
     pub input_path: String,
@@ -32,9 +42,34 @@ pub struct MediaSplitRequest {
     pub format: Option<String>,
     pub auto_collection: Option<bool>,
     pub delete_original: Option<bool>,
+    #[serde(default)]
     pub segments: Vec<MediaSplitSegmentRequest>,
 }
 
+/// How `propose_media_split` picks cut points
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum MediaSplitMode {
+    /// Cut in the middle of silences detected with `silencedetect`
+    #[serde(rename_all = "camelCase")]
+    Silence {
+        noise_db: Option<f64>,
+        min_silence_seconds: Option<f64>,
+        min_segment_seconds: Option<f64>,
+    },
+    /// Cut at scene changes found by `detect_shot_changes`
+    #[serde(rename_all = "camelCase")]
+    Scenes {
+        threshold: Option<f64>,
+        min_segment_seconds: Option<f64>,
+    },
+    /// One segment per chapter embedded in the file
+    Chapters,
+    /// Equal parts of `segment_seconds`, with a shorter last part
+    #[serde(rename_all = "camelCase")]
+    FixedLength { segment_seconds: f64 },
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MediaSplitSegmentResult {
@@ -118,6 +153,277 @@ fn validate_segment(segment: &MediaSplitSegmentRequest) -> Result<(), String> {
     Ok(())
 }
 
+fn default_segment_name(index: usize) -> String {
+    format!("Part {:02}", index + 1)
+}
+
+/// Keep cut points in order, dropping any that would leave a part shorter than `min_segment`
+fn spaced_cut_points(mut candidates: Vec<f64>, duration: f64, min_segment: f64) -> Vec<f64> {
+    candidates.retain(|value| value.is_finite());
+    candidates.sort_by(f64::total_cmp);
+
+    let mut cuts: Vec<f64> = Vec::new();
+    for candidate in candidates {
+        let previous = cuts.last().copied().unwrap_or(0.0);
+        if candidate - previous >= min_segment && duration - candidate >= min_segment {
+            cuts.push(candidate);
+        }
+    }
+    cuts
+}
+
+fn fixed_length_cut_points(duration: f64, segment_seconds: f64) -> Vec<f64> {
+    let mut cuts = Vec::new();
+    let mut cursor = segment_seconds;
+    while duration - cursor >= MIN_SEGMENT_SECONDS {
+        cuts.push(cursor);
+        cursor += segment_seconds;
+    }
+    cuts
+}
+
+fn segments_from_cuts(cuts: &[f64], duration: f64) -> Vec<MediaSplitSegmentRequest> {
+    let mut bounds = Vec::with_capacity(cuts.len() + 2);
+    bounds.push(0.0);
+    bounds.extend_from_slice(cuts);
+    bounds.push(duration);
+    bounds
+        .windows(2)
+        .enumerate()
+        .map(|(index, pair)| MediaSplitSegmentRequest {
+            name: default_segment_name(index),
+            start_seconds: pair[0],
+            end_seconds: pair[1],
+        })
+        .collect()
+}
+
+fn probe_seconds(value: Option<&serde_json::Value>) -> Option<f64> {
+    match value? {
+        serde_json::Value::String(text) => text.trim().parse().ok(),
+        other => other.as_f64(),
+    }
+    .filter(|seconds: &f64| seconds.is_finite())
+}
+
+/// Segments from the `chapters` array of `ffprobe -show_chapters`, named after chapter titles
+fn chapter_segments(probe: &serde_json::Value, duration: f64) -> Vec<MediaSplitSegmentRequest> {
+    let Some(chapters) = probe.get("chapters").and_then(|value| value.as_array()) else {
+        return Vec::new();
+    };
+
+    let mut segments = Vec::with_capacity(chapters.len());
+    for chapter in chapters {
+        let (Some(start), Some(end)) = (
+            probe_seconds(chapter.get("start_time")),
+            probe_seconds(chapter.get("end_time")),
+        ) else {
+            continue;
+        };
+        let start = start.max(0.0);
+        let end = if duration > 0.0 {
+            end.min(duration)
+        } else {
+            end
+        };
+        if end - start < MIN_SEGMENT_SECONDS {
+            continue;
+        }
+        let title = chapter
+            .get("tags")
+            .and_then(|tags| tags.get("title"))
+            .and_then(|title| title.as_str())
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| default_segment_name(segments.len()));
+        segments.push(MediaSplitSegmentRequest {
+            name: title,
+            start_seconds: start,
+            end_seconds: end,
+        });
+    }
+    segments
+}
+
+fn min_segment_option(value: Option<f64>) -> Result<f64, String> {
+    let min_segment = value.unwrap_or(DEFAULT_MIN_SEGMENT_SECONDS);
+    if !min_segment.is_finite() || min_segment < MIN_SEGMENT_SECONDS {
+        return Err("Minimum segment length must be at least 1 second".to_string());
+    }
+    Ok(min_segment)
+}
+
+async fn probe_duration_and_chapters(
+    app: &AppHandle,
+    input_path: &Path,
+) -> Result<(f64, serde_json::Value), String> {
+    let ffprobe_path = get_ffprobe_path(app)
+        .await
+        .ok_or_else(|| "FFprobe not found. Please install FFmpeg.".to_string())?;
+
+    let mut cmd = Command::new(&ffprobe_path);
+    cmd.args([
+        "-v",
+        "quiet",
+        "-print_format",
+        "json",
+        "-show_format",
+        "-show_chapters",
+    ])
+    .arg(input_path);
+    cmd.hide_window();
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+    if !output.status.success() {
+        return Err("FFprobe failed to analyze media".to_string());
+    }
+
+    let probe: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
+    let duration = probe_seconds(probe.get("format").and_then(|f| f.get("duration")))
+        .filter(|duration| *duration > MIN_SEGMENT_SECONDS)
+        .ok_or_else(|| "Could not read a usable duration for this file".to_string())?;
+    Ok((duration, probe))
+}
+
+async fn detect_silence_midpoints(
+    app: &AppHandle,
+    input_path: &Path,
+    noise_db: f64,
+    min_silence_seconds: f64,
+) -> Result<Vec<f64>, String> {
+    let ffmpeg_path = get_ffmpeg_path(app)
+        .await
+        .ok_or_else(|| "FFmpeg is required to split media files.".to_string())?;
+    let filter = format!(
+        "silencedetect=noise={}dB:d={}",
+        noise_db, min_silence_seconds
+    );
+
+    let mut cmd = Command::new(&ffmpeg_path);
+    cmd.args(["-hide_banner", "-nostdin", "-nostats", "-i"])
+        .arg(input_path)
+        .args(["-vn", "-af", &filter, "-f", "null", "-"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    cmd.hide_window();
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg silence detection: {}", e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(format!(
+            "FFmpeg silence detection failed: {}",
+            stderr.lines().last().unwrap_or("unknown error")
+        ));
+    }
+
+    Ok(parse_silences(&stderr)
+        .into_iter()
+        .filter_map(|(start, end)| end.map(|end| (start + end) / 2.0))
+        .collect())
+}
+
+/// Propose segments for `request` without writing any files.
+///
+/// The returned request carries the proposed segments and can be edited, then passed
+/// unchanged to `split_media_segments`.
+#[tauri::command]
+pub async fn propose_media_split(
+    app: AppHandle,
+    request: MediaSplitRequest,
+    mode: MediaSplitMode,
+) -> Result<MediaSplitRequest, String> {
+    let input_path = PathBuf::from(request.input_path.trim());
+    if !input_path.exists() || !input_path.is_file() {
+        return Err("Media file not found".to_string());
+    }
+
+    let (duration, probe) = probe_duration_and_chapters(&app, &input_path).await?;
+    let segments = match mode {
+        MediaSplitMode::Silence {
+            noise_db,
+            min_silence_seconds,
+            min_segment_seconds,
+        } => {
+            let noise_db = noise_db.unwrap_or(DEFAULT_SILENCE_NOISE_DB);
+            if !(-90.0..=0.0).contains(&noise_db) {
+                return Err("Silence threshold must be between -90 and 0 dB".to_string());
+            }
+            let min_silence = min_silence_seconds.unwrap_or(DEFAULT_MIN_SILENCE_SECONDS);
+            if !(0.1..=60.0).contains(&min_silence) {
+                return Err("Minimum silence must be between 0.1 and 60 seconds".to_string());
+            }
+            let min_segment = min_segment_option(min_segment_seconds)?;
+            let midpoints =
+                detect_silence_midpoints(&app, &input_path, noise_db, min_silence).await?;
+            let cuts = spaced_cut_points(midpoints, duration, min_segment);
+            if cuts.is_empty() {
+                return Err("No silences long enough to split on were found".to_string());
+            }
+            segments_from_cuts(&cuts, duration)
+        }
+        MediaSplitMode::Scenes {
+            threshold,
+            min_segment_seconds,
+        } => {
+            let min_segment = min_segment_option(min_segment_seconds)?;
+            let shots = detect_shot_changes(
+                app.clone(),
+                input_path.to_string_lossy().to_string(),
+                Some(threshold.unwrap_or(DEFAULT_SCENE_THRESHOLD)),
+                Some(0),
+            )
+            .await?;
+            let scene_times = shots
+                .shot_times_ms
+                .into_iter()
+                .map(|ms| ms as f64 / 1000.0)
+                .collect();
+            let cuts = spaced_cut_points(scene_times, duration, min_segment);
+            if cuts.is_empty() {
+                return Err("No scene changes far enough apart to split on were found".to_string());
+            }
+            segments_from_cuts(&cuts, duration)
+        }
+        MediaSplitMode::Chapters => {
+            let segments = chapter_segments(&probe, duration);
+            if segments.len() < 2 {
+                return Err("This file has no chapters to split on".to_string());
+            }
+            segments
+        }
+        MediaSplitMode::FixedLength { segment_seconds } => {
+            if !segment_seconds.is_finite() || segment_seconds < MIN_SEGMENT_SECONDS {
+                return Err("Segment length must be at least 1 second".to_string());
+            }
+            let cuts = fixed_length_cut_points(duration, segment_seconds);
+            if cuts.is_empty() {
+                return Err("Segment length is longer than the media".to_string());
+            }
+            segments_from_cuts(&cuts, duration)
+        }
+    };
+
+    if segments.len() > MAX_PROPOSED_SEGMENTS {
+        return Err(format!(
+            "Proposed {} segments; use a longer segment length (maximum {})",
+            segments.len(),
+            MAX_PROPOSED_SEGMENTS
+        ));
+    }
+
+    Ok(MediaSplitRequest {
+        segments,
+        ..request
+    })
+}
+
 async fn rollback_split_outputs(output_paths: &[PathBuf], history_ids: &[String]) {
     for history_id in history_ids {
         let _ = delete_history_from_db(history_id.clone());
@@ -293,3 +599,45 @@ pub async fn split_media_segments(
         segments: results,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spaced_cut_points_respect_minimum_segment_length() {
+        let candidates = vec![200.0, 30.0, 95.0, 130.0, f64::NAN, 560.0, 250.0];
+        assert_eq!(
+            spaced_cut_points(candidates, 600.0, 60.0),
+            vec![95.0, 200.0]
+        );
+        assert!(spaced_cut_points(vec![10.0, 50.0], 60.0, 30.0).is_empty());
+
+        let segments = segments_from_cuts(&fixed_length_cut_points(650.5, 300.0), 650.5);
+        let bounds = segments
+            .iter()
+            .map(|segment| (segment.start_seconds, segment.end_seconds))
+            .collect::<Vec<_>>();
+        assert_eq!(bounds, vec![(0.0, 300.0), (300.0, 600.0), (600.0, 650.5)]);
+        assert_eq!(segments[2].name, "Part 03");
+        assert_eq!(fixed_length_cut_points(600.5, 300.0), vec![300.0]);
+    }
+
+    #[test]
+    fn chapter_segments_use_titles_and_skip_empty_chapters() {
+        let probe = serde_json::json!({
+            "chapters": [
+                {"start_time": "0.000000", "end_time": "95.500000", "tags": {"title": "Intro"}},
+                {"start_time": "95.500000", "end_time": "95.800000", "tags": {"title": "Blip"}},
+                {"start_time": "95.800000", "end_time": "700.000000", "tags": {"title": " "}}
+            ]
+        });
+        let segments = chapter_segments(&probe, 612.0);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].name, "Intro");
+        assert_eq!(segments[1].name, "Part 02");
+        assert_eq!(segments[1].start_seconds, 95.8);
+        assert_eq!(segments[1].end_seconds, 612.0);
+        assert!(chapter_segments(&serde_json::json!({}), 612.0).is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 mod ai;
 mod assets;
 mod channels;
+mod chat;
 mod cli;
 mod cli_shortcut;
 mod dependencies;
@@ -15,6 +16,7 @@ mod media_split;
 mod metadata;
 mod plugin;
 mod processing;
+mod subtitles;
 mod telegram;
 mod video;
 mod whisper;
@@ -23,6 +25,7 @@ mod youtube_search;
 pub use ai::*;
 pub use assets::*;
 pub use channels::*;
+pub use chat::*;
 pub use cli::*;
 pub use cli_shortcut::*;
 pub use dependencies::*;
@@ -37,6 +40,7 @@ pub use media_split::*;
 pub use metadata::*;
 pub use plugin::*;
 pub use processing::*;
+pub use subtitles::*;
 pub use telegram::*;
 pub use video::*;
 pub use whisper::*;
@@ -1,12 +1,14 @@
 use tauri::AppHandle;
 
 use crate::services::{
-    approve_plugin_permissions_internal, attach_plugin_workspace_internal,
+    apply_plugin_update_internal, approve_plugin_permissions_internal,
+    attach_plugin_workspace_internal, check_plugin_updates_internal,
     create_plugin_workspace_internal, enqueue_plugin_trigger_workflow, get_plugin_details_internal,
     get_plugin_trigger_workflow_internal, get_runtime_provider_status_internal,
     inspect_plugin_package_internal, install_plugin_package_internal,
     list_plugin_store_entries_internal, list_plugins_internal, list_runtime_providers_internal,
     open_plugin_directory_internal, prepare_plugin_store_package_internal,
+    prepare_plugin_update_internal, rollback_plugin_internal,
     set_default_provider_for_language_internal, set_plugin_provider_internal,
     set_plugin_runtime_locale_internal, set_plugin_timeout_internal, uninstall_plugin_internal,
     update_plugin_config_values_internal, update_plugin_state_internal,
@@ -16,8 +18,9 @@ use crate::services::{
 };
 use crate::types::{
     PluginPackageInspection, PluginProvider, PluginRuntimeLanguage, PluginStoreEntry,
-    PluginSummary, PluginTriggerWorkflow, PluginWorkflowStepSnapshot, PluginWorkspaceSummary,
-    PostDownloadPluginPayload, PreparedPluginStorePackage, RuntimeProviderStatus,
+    PluginSummary, PluginTriggerWorkflow, PluginUpdateInfo, PluginWorkflowStepSnapshot,
+    PluginWorkspaceSummary, PostDownloadPluginPayload, PreparedPluginStorePackage,
+    PreparedPluginUpdate, RuntimeProviderStatus,
 };
 
 #[tauri::command]
@@ -66,6 +69,37 @@ pub async fn prepare_plugin_store_package(
     prepare_plugin_store_package_internal(&app, plugin_id, version).await
 }
 
+#[tauri::command]
+pub async fn check_plugin_updates(
+    app: AppHandle,
+    force_refresh: Option<bool>,
+) -> Result<Vec<PluginUpdateInfo>, String> {
+    check_plugin_updates_internal(&app, force_refresh.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn prepare_plugin_update(
+    app: AppHandle,
+    plugin_id: String,
+) -> Result<PreparedPluginUpdate, String> {
+    prepare_plugin_update_internal(&app, plugin_id).await
+}
+
+#[tauri::command]
+pub async fn apply_plugin_update(
+    app: AppHandle,
+    prepared_id: String,
+) -> Result<PluginSummary, String> {
+    apply_plugin_update_internal(&app, prepared_id).await
+}
+
+#[tauri::command]
+pub async fn rollback_plugin(app: AppHandle, plugin_id: String) -> Result<PluginSummary, String> {
+    tokio::task::spawn_blocking(move || rollback_plugin_internal(&app, &plugin_id))
+        .await
+        .map_err(|e| format!("Failed to join plugin rollback task: {}", e))?
+}
+
 #[tauri::command]
 pub fn uninstall_plugin(app: AppHandle, plugin_id: String) -> Result<(), String> {
     uninstall_plugin_internal(&app, &plugin_id)
@@ -1,16 +1,19 @@
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::Mutex;
 
 use crate::database::get_db;
-use crate::services::{generate_raw, get_ffmpeg_path, AIConfig};
+use crate::services::{
+    build_ffmetadata_chapters, build_subtitle_embed_args, generate_raw, get_ffmpeg_path,
+    subtitle_embed_output_extension, AIChapter, AIConfig, AITask, SubtitleEmbedMode,
+};
 use crate::utils::{
     args_to_display_command, find_system_binary, parse_ffmpeg_command_args,
     unix_system_binary_dirs, validate_ffmpeg_args, CommandExt,
@@ -18,17 +21,41 @@ use crate::utils::{
 
 #[path = "processing/attachments.rs"]
 mod attachments;
+#[path = "processing/audio.rs"]
+mod audio;
+#[path = "processing/batch.rs"]
+mod batch;
+#[path = "processing/capabilities.rs"]
+mod capabilities;
+#[path = "processing/intent.rs"]
+mod intent;
 #[path = "processing/jobs.rs"]
 mod jobs;
 #[path = "processing/metadata.rs"]
 mod metadata;
 #[path = "processing/preview.rs"]
 mod preview;
+#[path = "processing/queue.rs"]
+mod queue;
+#[path = "processing/target_size.rs"]
+mod target_size;
+#[path = "processing/templates.rs"]
+mod templates;
+#[path = "processing/thumbnails.rs"]
+mod thumbnails;
 
 pub use attachments::*;
+pub use audio::*;
+pub use batch::*;
+pub use capabilities::*;
+use intent::*;
 pub use jobs::*;
 pub use metadata::*;
 pub use preview::*;
+pub use queue::*;
+pub use target_size::*;
+pub use templates::*;
+pub use thumbnails::*;
 
 static ACTIVE_JOBS: LazyLock<Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
@@ -49,6 +76,25 @@ pub struct VideoMetadata {
     pub has_audio: bool,
 }
 
+/// Two-minute 1080p H.264/AAC MP4 shared by the processing tests
+#[cfg(test)]
+pub(super) fn test_video_metadata() -> VideoMetadata {
+    VideoMetadata {
+        path: "/videos/talk.mp4".to_string(),
+        filename: "talk.mp4".to_string(),
+        duration: 120.0,
+        width: 1920,
+        height: 1080,
+        fps: 30.0,
+        video_codec: "h264".to_string(),
+        audio_codec: "aac".to_string(),
+        bitrate: 4_000_000,
+        file_size: 60_000_000,
+        format: "mp4".to_string(),
+        has_audio: true,
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FFmpegCommandResult {
     pub command: String,
@@ -58,6 +104,12 @@ pub struct FFmpegCommandResult {
     pub estimated_time_seconds: f64,
     pub output_path: String,
     pub warnings: Vec<String>,
+    /// Set for target-size compression, which runs as a checked two-pass encode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_size: Option<TargetSizeEncode>,
+    /// Set for audio actions that measure the file first, such as loudness normalization
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_analysis: Option<AudioAnalysis>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,6 +137,9 @@ pub struct ProcessingJob {
     pub error_message: Option<String>,
     pub created_at: String,
     pub completed_at: Option<String>,
+    /// Queue priority; higher runs first
+    #[serde(default)]
+    pub priority: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,6 +151,14 @@ pub struct ProcessingPreset {
     pub prompt_template: String,
     pub icon: Option<String>,
     pub created_at: String,
+    /// FFmpeg arguments with `{placeholder}`s, run without AI when set
+    #[serde(default)]
+    pub args_template: Option<Vec<String>>,
+    /// Defaults for the template's own placeholders
+    #[serde(default)]
+    pub template_params: BTreeMap<String, String>,
+    #[serde(default)]
+    pub output_extension: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -184,16 +247,6 @@ fn has_outro_hint(prompt: &str) -> bool {
     contains_any(&lower, &["outro", "kết thúc", "ending", "片尾"])
 }
 
-fn escape_subtitles_filter_path(path: &str) -> String {
-    let mut escaped = path.replace('\\', "/");
-    escaped = escaped.replace(':', "\\:");
-    escaped = escaped.replace('\'', "\\'");
-    escaped = escaped.replace(',', "\\,");
-    escaped = escaped.replace('[', "\\[");
-    escaped = escaped.replace(']', "\\]");
-    escaped
-}
-
 fn resolve_output_dir(input_path: &str, output_dir: Option<&str>) -> PathBuf {
     if let Some(dir) = output_dir {
         let trimmed = dir.trim();
@@ -211,18 +264,15 @@ fn resolve_output_dir(input_path: &str, output_dir: Option<&str>) -> PathBuf {
         .to_path_buf()
 }
 
-fn try_build_subtitle_command(
-    user_prompt: &str,
+fn build_subtitle_embed_command(
     input_path: &str,
+    subtitle_path: &str,
+    subtitle_name: &str,
     metadata: &VideoMetadata,
-    subtitle_attachments: &[ProcessingAttachment],
+    mode: SubtitleEmbedMode,
+    language: Option<&str>,
     output_dir: Option<&str>,
-) -> Result<Option<FFmpegCommandResult>, String> {
-    if !is_subtitle_request(user_prompt) || subtitle_attachments.is_empty() {
-        return Ok(None);
-    }
-
-    let subtitle = &subtitle_attachments[0];
+) -> FFmpegCommandResult {
     let output_base_dir = resolve_output_dir(input_path, output_dir);
     let input_stem = Path::new(input_path)
         .file_stem()
@@ -230,54 +280,79 @@ fn try_build_subtitle_command(
         .unwrap_or("output".to_string());
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
     let output_path = output_base_dir
-        .join(format!("{}_subtitled_{}.mp4", input_stem, timestamp))
+        .join(format!(
+            "{}_subtitled_{}.{}",
+            input_stem,
+            timestamp,
+            subtitle_embed_output_extension(input_path, mode)
+        ))
         .to_string_lossy()
         .to_string();
 
-    let mut args = vec![
-        "-y".to_string(),
-        "-i".to_string(),
-        input_path.to_string(),
-        "-vf".to_string(),
-        format!(
-            "subtitles='{}'",
-            escape_subtitles_filter_path(&subtitle.path)
+    let args = build_subtitle_embed_args(
+        input_path,
+        subtitle_path,
+        &output_path,
+        mode,
+        metadata.has_audio,
+        language,
+    );
+    let (explanation, estimated_time_seconds) = match mode {
+        SubtitleEmbedMode::Burn => (
+            format!("Burn subtitles from '{}' into the video", subtitle_name),
+            (metadata.duration / 2.0).max(5.0),
         ),
-        "-c:v".to_string(),
-        "libx264".to_string(),
-        "-preset".to_string(),
-        "medium".to_string(),
-        "-crf".to_string(),
-        "20".to_string(),
-    ];
-    if metadata.has_audio {
-        args.extend(["-c:a".to_string(), "copy".to_string()]);
-    } else {
-        args.push("-an".to_string());
+        SubtitleEmbedMode::Soft => (
+            format!(
+                "Add subtitles from '{}' as a selectable track",
+                subtitle_name
+            ),
+            (metadata.duration / 50.0).max(2.0),
+        ),
+    };
+
+    FFmpegCommandResult {
+        command: args_to_display_command(&args),
+        command_args: args,
+        explanation,
+        estimated_size_mb: (metadata.file_size as f64 / 1_000_000.0) * 1.05,
+        estimated_time_seconds,
+        output_path,
+        warnings: Vec::new(),
+        target_size: None,
+        audio_analysis: None,
     }
-    args.extend([
-        "-progress".to_string(),
-        "pipe:2".to_string(),
-        output_path.clone(),
-    ]);
+}
 
-    let mut warnings = Vec::new();
+fn try_build_subtitle_command(
+    user_prompt: &str,
+    input_path: &str,
+    metadata: &VideoMetadata,
+    subtitle_attachments: &[ProcessingAttachment],
+    output_dir: Option<&str>,
+) -> Result<Option<FFmpegCommandResult>, String> {
+    if !is_subtitle_request(user_prompt) || subtitle_attachments.is_empty() {
+        return Ok(None);
+    }
+
+    let subtitle = &subtitle_attachments[0];
+    let mut result = build_subtitle_embed_command(
+        input_path,
+        &subtitle.path,
+        &subtitle.filename,
+        metadata,
+        SubtitleEmbedMode::Burn,
+        None,
+        output_dir,
+    );
     if subtitle_attachments.len() > 1 {
-        warnings.push(format!(
+        result.warnings.push(format!(
             "Multiple subtitle files attached. Using: {}",
             subtitle.filename
         ));
     }
 
-    Ok(Some(FFmpegCommandResult {
-        command: args_to_display_command(&args),
-        command_args: args,
-        explanation: format!("Burn subtitles from '{}' into the video", subtitle.filename),
-        estimated_size_mb: (metadata.file_size as f64 / 1_000_000.0) * 1.05,
-        estimated_time_seconds: (metadata.duration / 2.0).max(5.0),
-        output_path,
-        warnings,
-    }))
+    Ok(Some(result))
 }
 
 async fn try_build_merge_command(
@@ -342,6 +417,26 @@ async fn try_build_merge_command(
         ordered_meta.push(info);
     }
 
+    Ok(Some(build_merge_command(
+        input_path,
+        metadata,
+        &ordered_paths,
+        &ordered_meta,
+        output_dir,
+        warnings,
+    )))
+}
+
+/// Join `ordered_paths` into one MP4, scaling and padding every clip to the main video's
+/// size and frame rate. Clips without audio get silence so the concat filter lines up.
+fn build_merge_command(
+    input_path: &str,
+    metadata: &VideoMetadata,
+    ordered_paths: &[String],
+    ordered_meta: &[VideoMetadata],
+    output_dir: Option<&str>,
+    warnings: Vec<String>,
+) -> FFmpegCommandResult {
     let target_width = if metadata.width > 0 {
         metadata.width
     } else {
@@ -409,7 +504,7 @@ async fn try_build_merge_command(
         .to_string();
 
     let mut args = vec!["-y".to_string()];
-    for path in &ordered_paths {
+    for path in ordered_paths {
         args.push("-i".to_string());
         args.push(path.clone());
     }
@@ -453,7 +548,7 @@ async fn try_build_merge_command(
         .sum();
     let total_duration: f64 = ordered_meta.iter().map(|m| m.duration.max(0.0)).sum();
 
-    Ok(Some(FFmpegCommandResult {
+    FFmpegCommandResult {
         command: args_to_display_command(&args),
         command_args: args,
         explanation: format!("Merge videos in order: {}", ordered_names.join(" -> ")),
@@ -461,7 +556,9 @@ async fn try_build_merge_command(
         estimated_time_seconds: (total_duration / 3.0).max(8.0),
         output_path,
         warnings,
-    }))
+        target_size: None,
+        audio_analysis: None,
+    }
 }
 
 #[tauri::command]
@@ -486,6 +583,14 @@ pub async fn generate_processing_command(
         "No timeline selection".to_string()
     };
 
+    let capabilities = ffmpeg_capabilities(&app).await;
+    let adapt = |mut result: FFmpegCommandResult| {
+        if let Some(capabilities) = &capabilities {
+            adapt_command_to_capabilities(&mut result, capabilities, &metadata);
+        }
+        result
+    };
+
     let attachments = attachments.unwrap_or_default();
     let video_attachments: Vec<ProcessingAttachment> = attachments
         .iter()
@@ -505,7 +610,7 @@ pub async fn generate_processing_command(
         &subtitle_attachments,
         output_dir.as_deref(),
     )? {
-        return Ok(result);
+        return Ok(adapt(result));
     }
     if let Some(result) = try_build_merge_command(
         &app,
@@ -517,7 +622,20 @@ pub async fn generate_processing_command(
     )
     .await?
     {
-        return Ok(result);
+        return Ok(adapt(result));
+    }
+    // Common single-edit requests map onto quick actions and never need the AI
+    if attachments.is_empty() {
+        if let Some(result) = try_build_intent_command(
+            &user_prompt,
+            &input_path,
+            timeline_start,
+            timeline_end,
+            &metadata,
+            output_dir.as_deref(),
+        ) {
+            return Ok(adapt(result));
+        }
     }
 
     let attachment_section = if !attachments.is_empty() {
@@ -605,7 +723,7 @@ If the request is NOT related to video/audio processing (e.g., general chat, que
 7. IMPORTANT: Use the exact full path provided above for input and output files
 8. Wrap file paths in double quotes
 9. Return one ffmpeg command only. Do not use shell wrappers, shell operators, redirection, or command substitution.
-
+{}
 ## Response Format (JSON only, no markdown outside)
 For valid video requests:
 ```json
@@ -633,6 +751,10 @@ For valid video requests:
         selection_info,
         attachment_section,
         user_prompt,
+        capabilities
+            .as_deref()
+            .map(encoder_prompt_rule)
+            .unwrap_or_default(),
     );
 
     let config = load_ai_config(&app).await?;
@@ -640,7 +762,7 @@ For valid video requests:
         return Err("AI is not enabled. Please configure AI in Settings.".to_string());
     }
 
-    let result = generate_raw(&config, &ai_prompt)
+    let result = generate_raw(&config.for_task(AITask::FfmpegCommand), &ai_prompt)
         .await
         .map_err(|e| e.to_wire_string())?;
 
@@ -757,7 +879,7 @@ For valid video requests:
             (command, command_args)
         };
 
-    Ok(FFmpegCommandResult {
+    Ok(adapt(FFmpegCommandResult {
         command,
         command_args,
         explanation: parsed
@@ -783,11 +905,140 @@ For valid video requests:
                     .collect()
             })
             .unwrap_or_default(),
+        target_size: None,
+        audio_analysis: None,
+    }))
+}
+
+/// Build the FFmpeg command that soft-embeds or burns a subtitle file into a video
+#[tauri::command]
+pub async fn generate_subtitle_embed_command(
+    input_path: String,
+    subtitle_path: String,
+    mode: SubtitleEmbedMode,
+    metadata: VideoMetadata,
+    language: Option<String>,
+    output_dir: Option<String>,
+) -> Result<FFmpegCommandResult, String> {
+    if !Path::new(&subtitle_path).exists() {
+        return Err(format!("Subtitle file not found: {}", subtitle_path));
+    }
+    let subtitle_name = Path::new(&subtitle_path)
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| subtitle_path.clone());
+
+    Ok(build_subtitle_embed_command(
+        &input_path,
+        &subtitle_path,
+        &subtitle_name,
+        &metadata,
+        mode,
+        language.as_deref(),
+        output_dir.as_deref(),
+    ))
+}
+
+/// Prefix of the FFmetadata files `generate_chapter_embed_command` writes to the temp dir
+const CHAPTER_METADATA_PREFIX: &str = "youwee_chapters_";
+
+/// Chapter FFmetadata files a command reads from the temp dir
+fn chapter_metadata_inputs(args: &[String]) -> Vec<PathBuf> {
+    let temp_dir = std::env::temp_dir();
+    args.windows(2)
+        .filter(|pair| pair[0] == "-i")
+        .map(|pair| PathBuf::from(&pair[1]))
+        .filter(|path| {
+            path.parent() == Some(temp_dir.as_path())
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(CHAPTER_METADATA_PREFIX))
+        })
+        .collect()
+}
+
+/// Build the FFmpeg command that writes chapter markers into a copy of the video.
+///
+/// Streams are copied; the chapters come from an FFmetadata file written to the temp dir,
+/// which is removed once the job running the command ends.
+#[tauri::command]
+pub async fn generate_chapter_embed_command(
+    input_path: String,
+    chapters: Vec<AIChapter>,
+    metadata: VideoMetadata,
+    output_dir: Option<String>,
+) -> Result<FFmpegCommandResult, String> {
+    if chapters.is_empty() {
+        return Err("Add at least one chapter before embedding".to_string());
+    }
+    let metadata_path = std::env::temp_dir().join(format!(
+        "{}{}.txt",
+        CHAPTER_METADATA_PREFIX,
+        uuid::Uuid::new_v4()
+    ));
+    std::fs::write(
+        &metadata_path,
+        build_ffmetadata_chapters(&chapters, metadata.duration),
+    )
+    .map_err(|e| format!("Failed to write chapter metadata: {}", e))?;
+    let metadata_path = metadata_path.to_string_lossy().to_string();
+
+    let input = Path::new(&input_path);
+    let input_stem = input
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or("output".to_string());
+    let extension = input
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or("mp4".to_string());
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let output_path = resolve_output_dir(&input_path, output_dir.as_deref())
+        .join(format!(
+            "{}_chapters_{}.{}",
+            input_stem, timestamp, extension
+        ))
+        .to_string_lossy()
+        .to_string();
+
+    let args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        input_path.clone(),
+        "-f".to_string(),
+        "ffmetadata".to_string(),
+        "-i".to_string(),
+        metadata_path,
+        "-map".to_string(),
+        "0".to_string(),
+        "-map_metadata".to_string(),
+        "0".to_string(),
+        "-map_chapters".to_string(),
+        "1".to_string(),
+        "-c".to_string(),
+        "copy".to_string(),
+        "-progress".to_string(),
+        "pipe:2".to_string(),
+        output_path.clone(),
+    ];
+
+    Ok(FFmpegCommandResult {
+        command: args_to_display_command(&args),
+        command_args: args,
+        explanation: format!("Add {} chapter markers without re-encoding", chapters.len()),
+        estimated_size_mb: metadata.file_size as f64 / 1_000_000.0,
+        estimated_time_seconds: (metadata.duration / 50.0).max(2.0),
+        output_path,
+        warnings: Vec::new(),
+        target_size: None,
+        audio_analysis: None,
     })
 }
 
 #[tauri::command]
 pub async fn generate_quick_action_command(
+    app: AppHandle,
     input_path: String,
     task_type: String,
     options: HashMap<String, serde_json::Value>,
@@ -796,6 +1047,140 @@ pub async fn generate_quick_action_command(
     metadata: VideoMetadata,
     output_dir: Option<String>,
 ) -> Result<FFmpegCommandResult, String> {
+    let mut result = if task_type == "concat" {
+        build_concat_command(
+            &app,
+            &input_path,
+            &options,
+            &metadata,
+            output_dir.as_deref(),
+        )
+        .await?
+    } else {
+        build_quick_action_command(
+            input_path,
+            task_type,
+            options,
+            timeline_start,
+            timeline_end,
+            &metadata,
+            output_dir,
+        )?
+    };
+    if let Some(capabilities) = ffmpeg_capabilities(&app).await {
+        adapt_command_to_capabilities(&mut result, &capabilities, &metadata);
+    }
+    Ok(result)
+}
+
+/// `concat` quick action: put the `intro_path` clip before the input and/or the
+/// `outro_path` clip after it. Needs the clips' metadata, so it is built here rather than
+/// in `build_quick_action_command`.
+async fn build_concat_command(
+    app: &AppHandle,
+    input_path: &str,
+    options: &HashMap<String, serde_json::Value>,
+    metadata: &VideoMetadata,
+    output_dir: Option<&str>,
+) -> Result<FFmpegCommandResult, String> {
+    let clip_option = |key: &str| -> Result<Option<String>, String> {
+        let Some(path) = options
+            .get(key)
+            .and_then(|value| value.as_str())
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+        else {
+            return Ok(None);
+        };
+        if !Path::new(path).is_file() {
+            return Err(format!("Clip not found: {}", path));
+        }
+        Ok(Some(path.to_string()))
+    };
+    let intro = clip_option("intro_path")?;
+    let outro = clip_option("outro_path")?;
+    if intro.is_none() && outro.is_none() {
+        return Err("Choose an intro or outro clip to join".to_string());
+    }
+
+    let ordered_paths = intro
+        .into_iter()
+        .chain(std::iter::once(input_path.to_string()))
+        .chain(outro)
+        .collect::<Vec<_>>();
+    let mut ordered_meta = Vec::with_capacity(ordered_paths.len());
+    for path in &ordered_paths {
+        if path == input_path {
+            ordered_meta.push(metadata.clone());
+        } else {
+            ordered_meta.push(get_video_metadata(app.clone(), path.clone()).await?);
+        }
+    }
+
+    Ok(build_merge_command(
+        input_path,
+        metadata,
+        &ordered_paths,
+        &ordered_meta,
+        output_dir,
+        Vec::new(),
+    ))
+}
+
+/// `extract_audio` bitrate such as `"320k"` or `320`, in kbps
+fn audio_bitrate_option(value: &serde_json::Value) -> Result<String, String> {
+    let kbps = match value {
+        serde_json::Value::Number(number) => number.as_u64(),
+        serde_json::Value::String(text) => {
+            text.trim().trim_end_matches(['k', 'K']).parse::<u64>().ok()
+        }
+        _ => None,
+    };
+    match kbps {
+        Some(kbps) if (32..=320).contains(&kbps) => Ok(format!("{}k", kbps)),
+        _ => Err("Audio bitrate must be between 32k and 320k".to_string()),
+    }
+}
+
+fn build_quick_action_command(
+    input_path: String,
+    task_type: String,
+    options: HashMap<String, serde_json::Value>,
+    timeline_start: Option<f64>,
+    timeline_end: Option<f64>,
+    metadata: &VideoMetadata,
+    output_dir: Option<String>,
+) -> Result<FFmpegCommandResult, String> {
+    if let Some(preset_id) = task_type.strip_prefix(TEMPLATE_PRESET_TASK_PREFIX) {
+        let preset = load_processing_preset(preset_id)?;
+        return build_template_preset_command(
+            &preset,
+            &input_path,
+            &options,
+            timeline_start,
+            timeline_end,
+            metadata,
+            output_dir.as_deref(),
+        );
+    }
+
+    if let Some(result) = build_audio_action_command(
+        &input_path,
+        &task_type,
+        &options,
+        metadata,
+        output_dir.as_deref(),
+    ) {
+        return result;
+    }
+    if task_type == "compress" {
+        if let Some(result) =
+            build_target_size_command(&input_path, &options, metadata, output_dir.as_deref())
+        {
+            return result;
+        }
+    }
+
     let output_base_dir = resolve_output_dir(&input_path, output_dir.as_deref());
     let input_stem = Path::new(&input_path)
         .file_stem()
@@ -841,18 +1226,29 @@ pub async fn generate_quick_action_command(
                 .get("format")
                 .and_then(|f| f.as_str())
                 .unwrap_or("mp3");
+            let bitrate = match options.get("bitrate") {
+                Some(value) => Some(audio_bitrate_option(value)?),
+                None => None,
+            };
+            let lossy_bitrate = bitrate.clone().unwrap_or("192k".to_string());
+            // The "mp3" quick action has always written AAC in .m4a; only requests parsed
+            // offline ask for a real MP3 so existing buttons and presets keep their output.
+            let encode_mp3 = options
+                .get("encode_mp3")
+                .and_then(|value| value.as_bool())
+                .unwrap_or(false);
 
             let (ext, codec_args) = match format {
-                "m4a" => ("m4a", vec!["-c:a".to_string(), "copy".to_string()]),
+                "m4a" if bitrate.is_none() => ("m4a", vec!["-c:a".to_string(), "copy".to_string()]),
                 "flac" => ("flac", vec!["-c:a".to_string(), "flac".to_string()]),
                 "wav" => ("wav", vec!["-c:a".to_string(), "pcm_s16le".to_string()]),
-                "mp3" => (
-                    "m4a",
+                "mp3" if encode_mp3 => (
+                    "mp3",
                     vec![
                         "-c:a".to_string(),
-                        "aac".to_string(),
+                        "libmp3lame".to_string(),
                         "-b:a".to_string(),
-                        "192k".to_string(),
+                        lossy_bitrate,
                     ],
                 ),
                 _ => (
@@ -861,7 +1257,7 @@ pub async fn generate_quick_action_command(
                         "-c:a".to_string(),
                         "aac".to_string(),
                         "-b:a".to_string(),
-                        "192k".to_string(),
+                        lossy_bitrate,
                     ],
                 ),
             };
@@ -884,7 +1280,12 @@ pub async fn generate_quick_action_command(
             (
                 args,
                 output.to_string_lossy().to_string(),
-                format!("Extract audio as {}", ext.to_uppercase()),
+                match &bitrate {
+                    Some(bitrate) if !matches!(ext, "flac" | "wav") => {
+                        format!("Extract audio as {} at {}", ext.to_uppercase(), bitrate)
+                    }
+                    _ => format!("Extract audio as {}", ext.to_uppercase()),
+                },
             )
         }
         "resize" => {
@@ -1165,10 +1566,12 @@ pub async fn generate_quick_action_command(
         estimated_time_seconds: estimated_time,
         output_path,
         warnings: vec![],
+        target_size: None,
+        audio_analysis: None,
     })
 }
 
-async fn get_ffprobe_path(app: &AppHandle) -> Option<std::path::PathBuf> {
+pub(crate) async fn get_ffprobe_path(app: &AppHandle) -> Option<std::path::PathBuf> {
     if let Ok(app_data_dir) = app.path().app_data_dir() {
         let bin_dir = app_data_dir.join("bin");
         #[cfg(windows)]
@@ -0,0 +1,558 @@
+use super::*;
+use crate::services::parse_silences;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// EBU R128 programme loudness
+const DEFAULT_LOUDNESS_TARGET_LUFS: f64 = -23.0;
+const DEFAULT_TRUE_PEAK_DBTP: f64 = -1.0;
+const DEFAULT_LOUDNESS_RANGE_LU: f64 = 11.0;
+const DEFAULT_SILENCE_THRESHOLD_DB: f64 = -50.0;
+const DEFAULT_MIN_SILENCE_SECONDS: f64 = 0.5;
+const DEFAULT_DENOISE_STRENGTH_DB: f64 = 12.0;
+const DEFAULT_NOISE_FLOOR_DB: f64 = -50.0;
+/// Silence that starts or ends this close to the file's edge counts as touching it
+const SILENCE_EDGE_TOLERANCE: f64 = 0.05;
+/// `loudnorm` resamples to 192 kHz internally, so pin the output rate
+const NORMALIZED_SAMPLE_RATE: &str = "48000";
+
+/// Audio actions that measure the file before processing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AudioAnalysis {
+    /// Measure with `loudnorm`, then normalize linearly using the measured values
+    Loudnorm {
+        target_lufs: f64,
+        true_peak: f64,
+        loudness_range: f64,
+    },
+    /// Find silence with `silencedetect`, then cut it from the start and end
+    SilenceTrim {
+        threshold_db: f64,
+        min_silence: f64,
+        duration: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LoudnormMeasurement {
+    input_i: f64,
+    input_tp: f64,
+    input_lra: f64,
+    input_thresh: f64,
+    target_offset: f64,
+}
+
+fn audio_option(
+    options: &HashMap<String, serde_json::Value>,
+    key: &str,
+    default: f64,
+    range: RangeInclusive<f64>,
+) -> Result<f64, String> {
+    let value = options.get(key).and_then(|v| v.as_f64()).unwrap_or(default);
+    if !range.contains(&value) {
+        return Err(format!(
+            "{} must be between {} and {}",
+            key,
+            range.start(),
+            range.end()
+        ));
+    }
+    Ok(value)
+}
+
+/// Cover art in audio files shows up as a video stream that should be copied, not encoded
+pub(super) fn has_video_track(metadata: &VideoMetadata) -> bool {
+    metadata.width > 0 && !matches!(metadata.video_codec.as_str(), "mjpeg" | "png" | "bmp")
+}
+
+fn audio_codec_args(extension: &str) -> Vec<String> {
+    let args: &[&str] = match extension {
+        "wav" => &["-c:a", "pcm_s16le"],
+        "flac" => &["-c:a", "flac"],
+        "mp3" => &["-c:a", "libmp3lame", "-b:a", "192k"],
+        "webm" | "ogg" | "opus" => &["-c:a", "libopus", "-b:a", "160k"],
+        _ => &["-c:a", "aac", "-b:a", "192k"],
+    };
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn analysis_args(analysis: &AudioAnalysis, input_path: &str) -> Vec<String> {
+    let filter = match analysis {
+        AudioAnalysis::Loudnorm {
+            target_lufs,
+            true_peak,
+            loudness_range,
+        } => format!(
+            "loudnorm=I={}:TP={}:LRA={}:print_format=json",
+            target_lufs, true_peak, loudness_range
+        ),
+        AudioAnalysis::SilenceTrim {
+            threshold_db,
+            min_silence,
+            ..
+        } => format!("silencedetect=noise={}dB:d={}", threshold_db, min_silence),
+    };
+    [
+        "-i", input_path, "-vn", "-sn", "-dn", "-af", &filter, "-f", "null", "-",
+    ]
+    .map(str::to_string)
+    .to_vec()
+}
+
+fn parse_loudnorm_measurement(log_lines: &[String]) -> Result<LoudnormMeasurement, String> {
+    let start = log_lines
+        .iter()
+        .rposition(|line| line.trim() == "{")
+        .ok_or("FFmpeg did not report the loudness measurement")?;
+    let end = log_lines[start..]
+        .iter()
+        .position(|line| line.trim() == "}")
+        .map(|offset| start + offset)
+        .ok_or("FFmpeg did not report the loudness measurement")?;
+    let json: serde_json::Value = serde_json::from_str(&log_lines[start..=end].join("\n"))
+        .map_err(|e| format!("Failed to parse loudness measurement: {}", e))?;
+
+    let field = |name: &str| {
+        json.get(name)
+            .and_then(|v| v.as_str())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| "The audio is silent or too quiet to measure".to_string())
+    };
+    Ok(LoudnormMeasurement {
+        input_i: field("input_i")?,
+        input_tp: field("input_tp")?,
+        input_lra: field("input_lra")?,
+        input_thresh: field("input_thresh")?,
+        target_offset: field("target_offset")?,
+    })
+}
+
+/// Part of the file to keep once silence touching the start and end is removed, or `None`
+/// when there is nothing to trim
+fn silence_trim_range(log_lines: &[String], duration: f64) -> Result<Option<(f64, f64)>, String> {
+    let periods = parse_silences(&log_lines.join("\n"));
+
+    let mut keep_start = 0.0;
+    let mut keep_end = duration;
+    if let Some((start, end)) = periods.first() {
+        if *start <= SILENCE_EDGE_TOLERANCE {
+            keep_start = end.unwrap_or(duration);
+        }
+    }
+    if let Some((start, end)) = periods.last() {
+        if end.map_or(true, |end| end >= duration - SILENCE_EDGE_TOLERANCE) {
+            keep_end = keep_end.min(*start);
+        }
+    }
+
+    if keep_end - keep_start <= SILENCE_EDGE_TOLERANCE {
+        return Err("The audio is silent from start to end".to_string());
+    }
+    if keep_start <= SILENCE_EDGE_TOLERANCE && keep_end >= duration - SILENCE_EDGE_TOLERANCE {
+        return Ok(None);
+    }
+    Ok(Some((keep_start, keep_end)))
+}
+
+/// Arguments for the second pass, filled in from the analysis pass's log
+fn apply_audio_analysis(
+    analysis: &AudioAnalysis,
+    mut command_args: Vec<String>,
+    log_lines: &[String],
+) -> Result<Vec<String>, String> {
+    match analysis {
+        AudioAnalysis::Loudnorm {
+            target_lufs,
+            true_peak,
+            loudness_range,
+        } => {
+            let measured = parse_loudnorm_measurement(log_lines)?;
+            log::info!("[FFMPEG] Loudness measurement: {:?}", measured);
+            let filter = format!(
+                "loudnorm=I={}:TP={}:LRA={}:measured_I={}:measured_TP={}:measured_LRA={}:measured_thresh={}:offset={}:linear=true:print_format=summary",
+                target_lufs,
+                true_peak,
+                loudness_range,
+                measured.input_i,
+                measured.input_tp,
+                measured.input_lra,
+                measured.input_thresh,
+                measured.target_offset
+            );
+            let filter_index = command_args
+                .iter()
+                .position(|arg| arg == "-af")
+                .map(|index| index + 1)
+                .filter(|index| *index < command_args.len())
+                .ok_or("Loudness command has no audio filter")?;
+            command_args[filter_index] = filter;
+        }
+        AudioAnalysis::SilenceTrim { duration, .. } => {
+            if let Some((start, end)) = silence_trim_range(log_lines, *duration)? {
+                log::info!("[FFMPEG] Keeping {:.3}s to {:.3}s", start, end);
+                let input_index = command_args
+                    .iter()
+                    .position(|arg| arg == "-i")
+                    .ok_or("Trim command has no input")?;
+                command_args.splice(
+                    input_index..input_index,
+                    [
+                        "-ss".to_string(),
+                        format_time(start),
+                        "-to".to_string(),
+                        format_time(end),
+                    ],
+                );
+            }
+        }
+    }
+    Ok(command_args)
+}
+
+/// Quick actions for audio cleanup: `normalize_loudness`, `trim_silence`, `denoise`, and
+/// `downmix`. `None` for other task types.
+pub(super) fn build_audio_action_command(
+    input_path: &str,
+    task_type: &str,
+    options: &HashMap<String, serde_json::Value>,
+    metadata: &VideoMetadata,
+    output_dir: Option<&str>,
+) -> Option<Result<FFmpegCommandResult, String>> {
+    if !matches!(
+        task_type,
+        "normalize_loudness" | "trim_silence" | "denoise" | "downmix"
+    ) {
+        return None;
+    }
+    Some(build_audio_action(
+        input_path, task_type, options, metadata, output_dir,
+    ))
+}
+
+fn build_audio_action(
+    input_path: &str,
+    task_type: &str,
+    options: &HashMap<String, serde_json::Value>,
+    metadata: &VideoMetadata,
+    output_dir: Option<&str>,
+) -> Result<FFmpegCommandResult, String> {
+    if !metadata.has_audio {
+        return Err("This file has no audio track".to_string());
+    }
+
+    let input = Path::new(input_path);
+    let input_stem = input
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or("output".to_string());
+    let mut extension = input
+        .extension()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or("mp4".to_string());
+    let mut video_args = vec!["-c:v".to_string(), "copy".to_string()];
+    let mut audio_analysis = None;
+
+    let (suffix, filter_args, explanation) = match task_type {
+        "normalize_loudness" => {
+            let target_lufs = audio_option(
+                options,
+                "target_lufs",
+                DEFAULT_LOUDNESS_TARGET_LUFS,
+                -70.0..=-5.0,
+            )?;
+            let true_peak = audio_option(options, "true_peak", DEFAULT_TRUE_PEAK_DBTP, -9.0..=0.0)?;
+            let loudness_range = audio_option(
+                options,
+                "loudness_range",
+                DEFAULT_LOUDNESS_RANGE_LU,
+                1.0..=20.0,
+            )?;
+            audio_analysis = Some(AudioAnalysis::Loudnorm {
+                target_lufs,
+                true_peak,
+                loudness_range,
+            });
+            (
+                "normalized",
+                vec![
+                    "-af".to_string(),
+                    format!(
+                        "loudnorm=I={}:TP={}:LRA={}",
+                        target_lufs, true_peak, loudness_range
+                    ),
+                    "-ar".to_string(),
+                    NORMALIZED_SAMPLE_RATE.to_string(),
+                ],
+                format!(
+                    "Measure loudness, then normalize to {} LUFS (true peak {} dBTP)",
+                    target_lufs, true_peak
+                ),
+            )
+        }
+        "trim_silence" => {
+            let threshold_db = audio_option(
+                options,
+                "threshold_db",
+                DEFAULT_SILENCE_THRESHOLD_DB,
+                -90.0..=-10.0,
+            )?;
+            let min_silence = audio_option(
+                options,
+                "min_silence",
+                DEFAULT_MIN_SILENCE_SECONDS,
+                0.1..=60.0,
+            )?;
+            audio_analysis = Some(AudioAnalysis::SilenceTrim {
+                threshold_db,
+                min_silence,
+                duration: metadata.duration,
+            });
+            // Copied video can only be cut on keyframes, which would drift from the audio
+            if has_video_track(metadata) {
+                extension = "mp4".to_string();
+                video_args = ["-c:v", "libx264", "-preset", "medium", "-crf", "18"]
+                    .map(str::to_string)
+                    .to_vec();
+            }
+            (
+                "trimmed",
+                Vec::new(),
+                format!(
+                    "Find silence below {} dB, then trim it from the start and end",
+                    threshold_db
+                ),
+            )
+        }
+        "denoise" => {
+            let strength = audio_option(
+                options,
+                "strength_db",
+                DEFAULT_DENOISE_STRENGTH_DB,
+                1.0..=97.0,
+            )?;
+            let noise_floor = audio_option(
+                options,
+                "noise_floor_db",
+                DEFAULT_NOISE_FLOOR_DB,
+                -80.0..=-20.0,
+            )?;
+            (
+                "denoised",
+                vec![
+                    "-af".to_string(),
+                    format!("afftdn=nr={}:nf={}", strength, noise_floor),
+                ],
+                format!("Reduce background noise by {} dB", strength),
+            )
+        }
+        _ => {
+            let channels = options
+                .get("channels")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(2);
+            if !matches!(channels, 1 | 2) {
+                return Err("Downmix supports 1 (mono) or 2 (stereo) channels".to_string());
+            }
+            (
+                if channels == 1 { "mono" } else { "stereo" },
+                vec!["-ac".to_string(), channels.to_string()],
+                format!(
+                    "Downmix audio to {}",
+                    if channels == 1 { "mono" } else { "stereo" }
+                ),
+            )
+        }
+    };
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let output = resolve_output_dir(input_path, output_dir).join(format!(
+        "{}_{}_{}.{}",
+        input_stem, suffix, timestamp, extension
+    ));
+    let output_path = output.to_string_lossy().to_string();
+
+    let mut args = vec!["-y".to_string(), "-i".to_string(), input_path.to_string()];
+    args.extend(video_args);
+    args.extend(filter_args);
+    args.extend(audio_codec_args(&extension));
+    args.extend([
+        "-progress".to_string(),
+        "pipe:2".to_string(),
+        output_path.clone(),
+    ]);
+
+    let command = match &audio_analysis {
+        Some(analysis) => format!(
+            "{}\n{}",
+            args_to_display_command(&analysis_args(analysis, input_path)),
+            args_to_display_command(&args)
+        ),
+        None => args_to_display_command(&args),
+    };
+    let passes = if audio_analysis.is_some() { 2.0 } else { 1.0 };
+
+    Ok(FFmpegCommandResult {
+        command,
+        command_args: args,
+        explanation,
+        estimated_size_mb: metadata.file_size as f64 / 1_000_000.0,
+        estimated_time_seconds: metadata.duration / 20.0 * passes,
+        output_path,
+        warnings: Vec::new(),
+        target_size: None,
+        audio_analysis,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_audio_analysis_passes(
+    app: &AppHandle,
+    job_id: &str,
+    analysis: &AudioAnalysis,
+    command_args: Vec<String>,
+    input_path: &str,
+    output_path: &str,
+    cancelled: &AtomicBool,
+    on_progress: &ProgressCallback,
+) -> Result<(), String> {
+    let log_lines = run_ffmpeg_analysis(
+        app,
+        format!("{}:analyze", job_id),
+        analysis_args(analysis, input_path),
+        input_path.to_string(),
+        scaled_progress(job_id, on_progress, 0.0, 50.0),
+    )
+    .await?;
+    if cancelled.load(Ordering::SeqCst) {
+        return Err("Processing cancelled".to_string());
+    }
+
+    run_ffmpeg_job(
+        app,
+        format!("{}:apply", job_id),
+        apply_audio_analysis(analysis, command_args, &log_lines)?,
+        input_path.to_string(),
+        output_path.to_string(),
+        scaled_progress(job_id, on_progress, 50.0, 50.0),
+    )
+    .await
+}
+
+/// Run the analysis pass, then the command with the measured values filled in.
+/// `cancel_ffmpeg(job_id)` stops whichever pass is running.
+pub(super) async fn run_audio_analysis_job(
+    app: &AppHandle,
+    job_id: String,
+    analysis: AudioAnalysis,
+    command_args: Vec<String>,
+    input_path: String,
+    output_path: String,
+    on_progress: ProgressCallback,
+) -> Result<(), String> {
+    let cancelled = AtomicBool::new(false);
+    let passes = run_audio_analysis_passes(
+        app,
+        &job_id,
+        &analysis,
+        command_args,
+        &input_path,
+        &output_path,
+        &cancelled,
+        &on_progress,
+    );
+    run_parent_job(&job_id, &cancelled, passes).await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn loudnorm_second_pass_uses_measured_values() {
+        let log = lines(
+            r#"[Parsed_loudnorm_0 @ 0x7f8]
+{
+	"input_i" : "-27.61",
+	"input_tp" : "-4.47",
+	"input_lra" : "18.06",
+	"input_thresh" : "-39.20",
+	"output_i" : "-23.50",
+	"output_tp" : "-1.00",
+	"output_lra" : "11.00",
+	"output_thresh" : "-34.20",
+	"normalization_type" : "dynamic",
+	"target_offset" : "0.50"
+}"#,
+        );
+        let analysis = AudioAnalysis::Loudnorm {
+            target_lufs: -16.0,
+            true_peak: -1.5,
+            loudness_range: 11.0,
+        };
+        let args = [
+            "-y",
+            "-i",
+            "in.mp4",
+            "-af",
+            "loudnorm=I=-16:TP=-1.5:LRA=11",
+            "out.mp4",
+        ]
+        .map(str::to_string)
+        .to_vec();
+
+        let applied = apply_audio_analysis(&analysis, args, &log).unwrap();
+        assert_eq!(
+            applied[4],
+            "loudnorm=I=-16:TP=-1.5:LRA=11:measured_I=-27.61:measured_TP=-4.47:measured_LRA=18.06:measured_thresh=-39.2:offset=0.5:linear=true:print_format=summary"
+        );
+
+        let silent = lines("{\n\"input_i\" : \"-inf\",\n\"input_tp\" : \"-inf\"\n}");
+        assert!(parse_loudnorm_measurement(&silent).is_err());
+    }
+
+    #[test]
+    fn silence_trim_keeps_audio_between_edge_silences() {
+        let log = lines(
+            "[silencedetect @ 0x1] silence_start: 0
+[silencedetect @ 0x1] silence_end: 1.52 | silence_duration: 1.52
+[silencedetect @ 0x1] silence_start: 30.1
+[silencedetect @ 0x1] silence_end: 31 | silence_duration: 0.9
+[silencedetect @ 0x1] silence_start: 57.25
+[silencedetect @ 0x1] silence_end: 60 | silence_duration: 2.75",
+        );
+        assert_eq!(silence_trim_range(&log, 60.0), Ok(Some((1.52, 57.25))));
+
+        // Silence running into the end of file may have no silence_end line
+        let open_end = lines("[silencedetect @ 0x1] silence_start: 42.5");
+        assert_eq!(silence_trim_range(&open_end, 60.0), Ok(Some((0.0, 42.5))));
+
+        let middle_only = lines(
+            "[silencedetect @ 0x1] silence_start: 10
+[silencedetect @ 0x1] silence_end: 12 | silence_duration: 2",
+        );
+        assert_eq!(silence_trim_range(&middle_only, 60.0), Ok(None));
+
+        let all_silent = lines("[silencedetect @ 0x1] silence_start: 0");
+        assert!(silence_trim_range(&all_silent, 60.0).is_err());
+
+        let args = ["-y", "-i", "in.mp4", "out.mp4"]
+            .map(str::to_string)
+            .to_vec();
+        let analysis = AudioAnalysis::SilenceTrim {
+            threshold_db: -50.0,
+            min_silence: 0.5,
+            duration: 60.0,
+        };
+        let applied = apply_audio_analysis(&analysis, args, &log).unwrap();
+        assert_eq!(
+            &applied[..6],
+            &["-y", "-ss", "00:00:01.520", "-to", "00:00:57.250", "-i"]
+        );
+    }
+}
@@ -0,0 +1,645 @@
+use super::*;
+use futures_util::stream::{self, StreamExt};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::database::get_history_entries_by_ids_from_db;
+
+const DEFAULT_BATCH_CONCURRENCY: usize = 2;
+const MAX_BATCH_CONCURRENCY: usize = 8;
+/// Files picked up when a folder is given without a pattern
+const BATCH_MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "mkv", "webm", "avi", "mov", "wmv", "flv", "m4v", "ts", "mts", "mp3", "m4a", "wav",
+    "flac", "ogg", "opus",
+];
+
+/// Files to process and the action to apply to each of them. Exactly one of `task_type`
+/// (a quick action such as `compress`) and `preset_id` must be set.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProcessingBatchRequest {
+    #[serde(default)]
+    pub history_ids: Vec<String>,
+    /// A folder, or a pattern such as `/Videos/**/*.mkv`
+    pub folder_glob: Option<String>,
+    pub task_type: Option<String>,
+    #[serde(default)]
+    pub options: HashMap<String, serde_json::Value>,
+    pub preset_id: Option<String>,
+    pub output_dir: Option<String>,
+    pub max_concurrent: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingBatchProgress {
+    pub batch_id: String,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingBatchItem {
+    pub job_id: String,
+    pub input_path: String,
+    pub output_path: Option<String>,
+    /// `completed`, `failed`, or `cancelled`
+    pub status: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingBatchResult {
+    pub batch_id: String,
+    pub items: Vec<ProcessingBatchItem>,
+    pub completed: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+}
+
+enum BatchAction {
+    QuickAction {
+        task_type: String,
+        options: HashMap<String, serde_json::Value>,
+    },
+    Preset {
+        task_type: String,
+        prompt: String,
+    },
+    /// Preset with an FFmpeg template, built without the AI
+    Template {
+        task_type: String,
+        preset_id: String,
+        options: HashMap<String, serde_json::Value>,
+    },
+}
+
+impl BatchAction {
+    fn task_type(&self) -> &str {
+        match self {
+            BatchAction::QuickAction { task_type, .. }
+            | BatchAction::Preset { task_type, .. }
+            | BatchAction::Template { task_type, .. } => task_type,
+        }
+    }
+
+    fn prompt(&self) -> Option<String> {
+        match self {
+            BatchAction::QuickAction { .. } | BatchAction::Template { .. } => None,
+            BatchAction::Preset { prompt, .. } => Some(prompt.clone()),
+        }
+    }
+}
+
+struct BatchProgressState {
+    percents: Vec<f64>,
+    completed: usize,
+    failed: usize,
+    cancelled: usize,
+}
+
+struct BatchContext {
+    app: AppHandle,
+    batch_id: String,
+    action: BatchAction,
+    output_dir: Option<String>,
+    cancelled: AtomicBool,
+    planned_outputs: std::sync::Mutex<HashSet<String>>,
+    progress: std::sync::Mutex<BatchProgressState>,
+}
+
+impl BatchContext {
+    fn job_id(&self, index: usize) -> String {
+        format!("{}:{}", self.batch_id, index)
+    }
+
+    fn snapshot(&self) -> Option<ProcessingBatchProgress> {
+        let state = self.progress.lock().ok()?;
+        let total = state.percents.len();
+        let percent = if total == 0 {
+            100.0
+        } else {
+            state.percents.iter().sum::<f64>() / total as f64
+        };
+        Some(ProcessingBatchProgress {
+            batch_id: self.batch_id.clone(),
+            total,
+            completed: state.completed,
+            failed: state.failed,
+            cancelled: state.cancelled,
+            percent,
+        })
+    }
+
+    fn emit_progress(&self) {
+        if let Some(progress) = self.snapshot() {
+            let _ = self.app.emit("processing-batch-progress", &progress);
+        }
+    }
+
+    fn set_percent(&self, index: usize, percent: f64) {
+        if let Ok(mut state) = self.progress.lock() {
+            if let Some(slot) = state.percents.get_mut(index) {
+                *slot = percent.clamp(0.0, 100.0);
+            }
+        }
+        self.emit_progress();
+    }
+
+    /// Record a finished file; failed and cancelled files count as done for the percentage
+    fn finish(&self, index: usize, status: &str) {
+        if let Ok(mut state) = self.progress.lock() {
+            if let Some(slot) = state.percents.get_mut(index) {
+                *slot = 100.0;
+            }
+            match status {
+                "completed" => state.completed += 1,
+                "cancelled" => state.cancelled += 1,
+                _ => state.failed += 1,
+            }
+        }
+        self.emit_progress();
+    }
+
+    /// Reserve an output path so two inputs with the same name never write the same file
+    fn reserve_output(&self, output_path: &str) -> Result<(), String> {
+        let mut planned = self
+            .planned_outputs
+            .lock()
+            .map_err(|e| format!("Failed to lock batch outputs: {}", e))?;
+        if planned.insert(output_path.to_string()) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Another file in this batch already writes {}",
+                output_path
+            ))
+        }
+    }
+}
+
+fn glob_segment_has_wildcard(segment: &str) -> bool {
+    segment.contains('*') || segment.contains('?')
+}
+
+/// Regex for a `/`-separated glob. `*` and `?` stay within one path segment and `**`
+/// spans any number of directories. Matching ignores case so `*.mp4` also finds `.MP4`.
+fn glob_to_regex(pattern: &str) -> Result<regex::Regex, String> {
+    let mut expr = String::from("(?i)^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    expr.push_str("(?:.*/)?");
+                } else {
+                    expr.push_str(".*");
+                }
+            }
+            '*' => expr.push_str("[^/]*"),
+            '?' => expr.push_str("[^/]"),
+            _ => expr.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    expr.push('$');
+    regex::Regex::new(&expr).map_err(|e| format!("Invalid folder pattern: {}", e))
+}
+
+/// Files under `dir`, descending `depth_left - 1` levels. Symlinked folders are skipped so
+/// a link back up the tree cannot loop; symlinked files are kept.
+fn collect_files(dir: &Path, depth_left: usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            if depth_left > 1 {
+                collect_files(&path, depth_left - 1, files);
+            }
+        } else if !(file_type.is_symlink() && path.is_dir()) {
+            files.push(path);
+        }
+    }
+}
+
+/// Files matching a folder pattern, sorted by path. A folder without wildcards expands
+/// to the media files directly inside it.
+fn expand_folder_glob(pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let path = Path::new(pattern);
+    if path.is_dir() {
+        let mut files = Vec::new();
+        collect_files(path, 1, &mut files);
+        files.retain(|file| {
+            file.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| BATCH_MEDIA_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        });
+        files.sort();
+        return Ok(files);
+    }
+
+    let mut base = PathBuf::new();
+    let mut rest = Vec::new();
+    for component in path.components() {
+        let segment = component.as_os_str().to_string_lossy().to_string();
+        if rest.is_empty() && !glob_segment_has_wildcard(&segment) {
+            base.push(component);
+        } else {
+            rest.push(segment);
+        }
+    }
+    if rest.is_empty() {
+        return if path.is_file() {
+            Ok(vec![path.to_path_buf()])
+        } else {
+            Err(format!("No file or folder at {}", pattern))
+        };
+    }
+    if !base.is_dir() {
+        return Err(format!("Folder not found: {}", base.display()));
+    }
+
+    let relative_pattern = rest.join("/");
+    let matcher = glob_to_regex(&relative_pattern)?;
+    let depth = if relative_pattern.contains("**") {
+        usize::MAX
+    } else {
+        rest.len()
+    };
+    let mut files = Vec::new();
+    collect_files(&base, depth, &mut files);
+    files.retain(|file| {
+        file.strip_prefix(&base).is_ok_and(|relative| {
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            matcher.is_match(&relative)
+        })
+    });
+    files.sort();
+    Ok(files)
+}
+
+fn resolve_batch_action(request: &ProcessingBatchRequest) -> Result<BatchAction, String> {
+    let task_type = request
+        .task_type
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let preset_id = request
+        .preset_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+
+    match (task_type, preset_id) {
+        (Some(_), Some(_)) => Err("Choose either a quick action or a preset, not both".to_string()),
+        (None, None) => Err("Choose a quick action or a preset for the batch".to_string()),
+        // Both need a timeline selection that differs per file
+        (Some("cut" | "gif"), None) => {
+            Err("Cut and GIF need a time range and cannot run as a batch".to_string())
+        }
+        (Some(task_type), None) => Ok(BatchAction::QuickAction {
+            task_type: task_type.to_string(),
+            options: request.options.clone(),
+        }),
+        (None, Some(preset_id)) => {
+            let preset = load_processing_preset(preset_id)?;
+            if preset.args_template.is_some() {
+                return Ok(BatchAction::Template {
+                    task_type: preset.task_type,
+                    preset_id: preset.id,
+                    options: request.options.clone(),
+                });
+            }
+            Ok(BatchAction::Preset {
+                task_type: preset.task_type,
+                prompt: preset.prompt_template,
+            })
+        }
+    }
+}
+
+/// Input files in request order: history items first, then folder matches. Duplicates
+/// are dropped.
+fn resolve_batch_inputs(request: &ProcessingBatchRequest) -> Result<Vec<String>, String> {
+    let mut inputs = Vec::new();
+    if !request.history_ids.is_empty() {
+        let entries = get_history_entries_by_ids_from_db(request.history_ids.clone())?;
+        for id in &request.history_ids {
+            if let Some(entry) = entries.iter().find(|entry| &entry.id == id) {
+                inputs.push(entry.filepath.clone());
+            }
+        }
+    }
+    if let Some(pattern) = request.folder_glob.as_deref() {
+        inputs.extend(
+            expand_folder_glob(pattern)?
+                .into_iter()
+                .map(|path| path.to_string_lossy().to_string()),
+        );
+    }
+
+    let mut seen = HashSet::new();
+    inputs.retain(|path| seen.insert(path.clone()));
+    Ok(inputs)
+}
+
+async fn build_batch_command(
+    context: &BatchContext,
+    input_path: &str,
+) -> Result<FFmpegCommandResult, String> {
+    let metadata = get_video_metadata(context.app.clone(), input_path.to_string()).await?;
+    match &context.action {
+        BatchAction::QuickAction { task_type, options } => {
+            generate_quick_action_command(
+                context.app.clone(),
+                input_path.to_string(),
+                task_type.clone(),
+                options.clone(),
+                None,
+                None,
+                metadata,
+                context.output_dir.clone(),
+            )
+            .await
+        }
+        BatchAction::Template {
+            preset_id, options, ..
+        } => {
+            generate_quick_action_command(
+                context.app.clone(),
+                input_path.to_string(),
+                format!("{}{}", TEMPLATE_PRESET_TASK_PREFIX, preset_id),
+                options.clone(),
+                None,
+                None,
+                metadata,
+                context.output_dir.clone(),
+            )
+            .await
+        }
+        BatchAction::Preset { prompt, .. } => {
+            generate_processing_command(
+                context.app.clone(),
+                input_path.to_string(),
+                prompt.clone(),
+                None,
+                None,
+                metadata,
+                None,
+                context.output_dir.clone(),
+            )
+            .await
+        }
+    }
+}
+
+async fn run_batch_item(
+    context: Arc<BatchContext>,
+    index: usize,
+    input_path: String,
+) -> ProcessingBatchItem {
+    let job_id = context.job_id(index);
+    let mut item = ProcessingBatchItem {
+        job_id: job_id.clone(),
+        input_path: input_path.clone(),
+        output_path: None,
+        status: "cancelled".to_string(),
+        error: None,
+    };
+    if context.cancelled.load(Ordering::SeqCst) {
+        context.finish(index, &item.status);
+        return item;
+    }
+
+    let command = match build_batch_command(&context, &input_path).await {
+        Ok(command) => command,
+        Err(e) => {
+            item.status = "failed".to_string();
+            item.error = Some(e);
+            context.finish(index, &item.status);
+            return item;
+        }
+    };
+    item.output_path = Some(command.output_path.clone());
+    if let Err(e) = context.reserve_output(&command.output_path) {
+        item.status = "failed".to_string();
+        item.error = Some(e);
+        context.finish(index, &item.status);
+        return item;
+    }
+    if context.cancelled.load(Ordering::SeqCst) {
+        context.finish(index, &item.status);
+        return item;
+    }
+
+    let app = context.app.clone();
+    save_processing_job(
+        app.clone(),
+        job_id.clone(),
+        input_path.clone(),
+        Some(command.output_path.clone()),
+        context.action.task_type().to_string(),
+        context.action.prompt(),
+        command.command.clone(),
+    )
+    .await
+    .ok();
+
+    let progress_context = context.clone();
+    let emitter = app.clone();
+    let on_progress: ProgressCallback = Arc::new(move |progress: &ProcessingProgress| {
+        let _ = emitter.emit("processing-progress", progress);
+        progress_context.set_percent(index, progress.percent);
+    });
+    let outcome =
+        run_processing_command(&app, job_id.clone(), command, input_path, on_progress).await;
+
+    let (status, progress) = match &outcome {
+        Ok(()) => ("completed", 100.0),
+        Err(e) if e == "Processing cancelled" => ("cancelled", 0.0),
+        Err(_) => ("failed", 0.0),
+    };
+    item.status = status.to_string();
+    item.error = outcome.err().filter(|_| status == "failed");
+    update_processing_job(
+        app,
+        job_id,
+        item.status.clone(),
+        progress,
+        item.error.clone(),
+    )
+    .await
+    .ok();
+    context.finish(index, &item.status);
+    item
+}
+
+/// Apply a quick action or saved preset to many files.
+///
+/// Each file becomes its own job (`<batch_id>:<index>`) that emits the usual
+/// `processing-progress` events and is saved to the processing history. Combined
+/// progress is emitted as `processing-batch-progress`. Cancelling `batch_id` with
+/// `cancel_ffmpeg` stops running files and skips the ones not yet started.
+#[tauri::command]
+pub async fn start_processing_batch(
+    app: AppHandle,
+    batch_id: String,
+    request: ProcessingBatchRequest,
+) -> Result<ProcessingBatchResult, String> {
+    if batch_id.trim().is_empty() || batch_id.contains(':') {
+        return Err("Invalid batch id".to_string());
+    }
+    let action = resolve_batch_action(&request)?;
+    let inputs = resolve_batch_inputs(&request)?;
+    if inputs.is_empty() {
+        return Err("No input files found for the batch".to_string());
+    }
+    let concurrency = request
+        .max_concurrent
+        .unwrap_or(DEFAULT_BATCH_CONCURRENCY)
+        .clamp(1, MAX_BATCH_CONCURRENCY);
+
+    let context = Arc::new(BatchContext {
+        app: app.clone(),
+        batch_id: batch_id.clone(),
+        action,
+        output_dir: request.output_dir.clone(),
+        cancelled: AtomicBool::new(false),
+        planned_outputs: std::sync::Mutex::new(HashSet::new()),
+        progress: std::sync::Mutex::new(BatchProgressState {
+            percents: vec![0.0; inputs.len()],
+            completed: 0,
+            failed: 0,
+            cancelled: 0,
+        }),
+    });
+    let runner = async {
+        context.emit_progress();
+        stream::iter(inputs.into_iter().enumerate())
+            .map(|(index, input_path)| run_batch_item(context.clone(), index, input_path))
+            .buffer_unordered(concurrency)
+            .collect::<Vec<_>>()
+            .await
+    };
+    let mut items = run_parent_job(&batch_id, &context.cancelled, runner).await?;
+    items.sort_by_key(|item| {
+        item.job_id
+            .rsplit(':')
+            .next()
+            .and_then(|index| index.parse::<usize>().ok())
+            .unwrap_or(usize::MAX)
+    });
+    let count = |status: &str| items.iter().filter(|item| item.status == status).count();
+    Ok(ProcessingBatchResult {
+        batch_id,
+        completed: count("completed"),
+        failed: count("failed"),
+        cancelled: count("cancelled"),
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_patterns_match_within_and_across_folders() {
+        let single = glob_to_regex("*.mp4").unwrap();
+        assert!(single.is_match("clip.mp4"));
+        assert!(single.is_match("CLIP.MP4"));
+        assert!(!single.is_match("nested/clip.mp4"));
+
+        let recursive = glob_to_regex("**/*.mkv").unwrap();
+        assert!(recursive.is_match("a.mkv"));
+        assert!(recursive.is_match("season 1/ep?.mkv"));
+        assert!(!recursive.is_match("a.mkv.part"));
+
+        let literal = glob_to_regex("ep(1)?.mp4").unwrap();
+        assert!(literal.is_match("ep(1)a.mp4"));
+        assert!(!literal.is_match("ep1a.mp4"));
+    }
+
+    #[test]
+    fn folder_globs_expand_to_sorted_matching_files() {
+        let root = std::env::temp_dir().join(format!("batch-glob-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        for file in ["b.mp4", "a.MP4", "notes.txt", "nested/c.mp4"] {
+            std::fs::write(root.join(file), b"").unwrap();
+        }
+
+        let folder = expand_folder_glob(&root.to_string_lossy()).unwrap();
+        assert_eq!(folder, vec![root.join("a.MP4"), root.join("b.mp4")]);
+
+        let recursive =
+            expand_folder_glob(&root.join("**").join("*.mp4").to_string_lossy()).unwrap();
+        assert_eq!(recursive.len(), 3);
+        assert!(recursive.contains(&root.join("nested").join("c.mp4")));
+
+        let top_level = expand_folder_glob(&root.join("*.txt").to_string_lossy()).unwrap();
+        assert_eq!(top_level, vec![root.join("notes.txt")]);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn recursive_globs_do_not_follow_symlinked_folders() {
+        let root = std::env::temp_dir().join(format!("batch-glob-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        std::fs::write(root.join("nested").join("clip.mp4"), b"").unwrap();
+        std::os::unix::fs::symlink(&root, root.join("nested").join("loop")).unwrap();
+        std::os::unix::fs::symlink(
+            root.join("nested").join("clip.mp4"),
+            root.join("linked.mp4"),
+        )
+        .unwrap();
+
+        let files = expand_folder_glob(&root.join("**").join("*.mp4").to_string_lossy()).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                root.join("linked.mp4"),
+                root.join("nested").join("clip.mp4")
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn batch_action_needs_exactly_one_action() {
+        let request = |task_type: Option<&str>, preset_id: Option<&str>| ProcessingBatchRequest {
+            history_ids: Vec::new(),
+            folder_glob: None,
+            task_type: task_type.map(str::to_string),
+            options: HashMap::new(),
+            preset_id: preset_id.map(str::to_string),
+            output_dir: None,
+            max_concurrent: None,
+        };
+
+        assert!(resolve_batch_action(&request(None, None)).is_err());
+        assert!(resolve_batch_action(&request(Some("compress"), Some("p1"))).is_err());
+        assert!(resolve_batch_action(&request(Some("cut"), None)).is_err());
+        let action = resolve_batch_action(&request(Some("compress"), None)).unwrap();
+        assert_eq!(action.task_type(), "compress");
+        assert!(action.prompt().is_none());
+    }
+}
@@ -0,0 +1,614 @@
+use super::*;
+
+use std::collections::BTreeSet;
+
+const CAPABILITIES_CACHE_FILE: &str = "ffmpeg_capabilities.json";
+
+/// Encoders for each codec, in the order they are tried when the requested one is missing.
+///
+/// Software encoders come first because they accept the quality options quick actions use.
+/// Experimental native encoders (opus, vorbis) and VAAPI, which needs a device, are left out.
+const ENCODER_FAMILIES: &[(&str, &[&str])] = &[
+    (
+        "h264",
+        &[
+            "libx264",
+            "libopenh264",
+            "h264_videotoolbox",
+            "h264_nvenc",
+            "h264_qsv",
+            "h264_amf",
+            "h264_mf",
+        ],
+    ),
+    (
+        "hevc",
+        &[
+            "libx265",
+            "hevc_videotoolbox",
+            "hevc_nvenc",
+            "hevc_qsv",
+            "hevc_amf",
+            "hevc_mf",
+        ],
+    ),
+    ("vp9", &["libvpx-vp9", "libvpx", "libsvtav1", "libaom-av1"]),
+    (
+        "av1",
+        &[
+            "libsvtav1",
+            "libaom-av1",
+            "librav1e",
+            "av1_nvenc",
+            "av1_qsv",
+        ],
+    ),
+    ("mpeg4", &["libxvid", "mpeg4"]),
+    ("aac", &["aac", "libfdk_aac", "aac_at", "aac_mf"]),
+    ("mp3", &["libmp3lame", "libshine", "mp3_mf"]),
+    ("opus", &["libopus", "libvorbis"]),
+];
+
+/// x264/x265 options that other encoders reject or read differently
+const X26X_ONLY_OPTIONS: &[&str] = &[
+    "-preset",
+    "-preset:v",
+    "-tune",
+    "-tune:v",
+    "-x264-params",
+    "-x264opts",
+    "-x265-params",
+];
+
+/// What the resolved FFmpeg binary can encode, decode and filter
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FFmpegCapabilities {
+    /// First line of `ffmpeg -version`
+    pub version: String,
+    pub ffmpeg_path: String,
+    pub encoders: BTreeSet<String>,
+    /// Codec names the encoders implement, so `-c:a mp3` counts as available via libmp3lame
+    pub encoder_codecs: BTreeSet<String>,
+    pub decoders: BTreeSet<String>,
+    pub decoder_codecs: BTreeSet<String>,
+    pub filters: BTreeSet<String>,
+}
+
+impl FFmpegCapabilities {
+    pub fn has_encoder(&self, name: &str) -> bool {
+        self.encoders.contains(name) || self.encoder_codecs.contains(name)
+    }
+
+    pub fn has_decoder(&self, codec: &str) -> bool {
+        self.decoders.contains(codec) || self.decoder_codecs.contains(codec)
+    }
+
+    pub fn has_filter(&self, name: &str) -> bool {
+        self.filters.contains(name)
+    }
+
+    /// The first available encoder in `requested`'s family, or None if the family has none
+    fn fallback_encoder(&self, requested: &str) -> Option<&'static str> {
+        let (_, candidates) = ENCODER_FAMILIES
+            .iter()
+            .find(|(codec, encoders)| *codec == requested || encoders.contains(&requested))?;
+        candidates
+            .iter()
+            .copied()
+            .find(|encoder| *encoder != requested && self.encoders.contains(*encoder))
+    }
+
+    /// Encoders from `ENCODER_FAMILIES` this build provides, for the AI prompt
+    fn known_encoders(&self) -> Vec<&'static str> {
+        let mut encoders = Vec::new();
+        for (_, candidates) in ENCODER_FAMILIES {
+            for encoder in candidates.iter() {
+                if self.encoders.contains(*encoder) && !encoders.contains(encoder) {
+                    encoders.push(*encoder);
+                }
+            }
+        }
+        encoders
+    }
+}
+
+static CAPABILITIES: LazyLock<Mutex<Option<(String, Arc<FFmpegCapabilities>)>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// Changes when the binary at `ffmpeg_path` is replaced, without running it
+fn binary_fingerprint(ffmpeg_path: &Path) -> String {
+    let metadata = std::fs::metadata(ffmpeg_path).ok();
+    let modified = metadata.as_ref().and_then(|m| m.modified().ok());
+    format!(
+        "{}|{:?}|{:?}",
+        ffmpeg_path.display(),
+        metadata.map(|m| m.len()),
+        modified
+    )
+}
+
+/// Parse `ffmpeg -encoders` or `-decoders`: `(names, codec names)`
+fn parse_codec_list(output: &str) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut names = BTreeSet::new();
+    let mut codecs = BTreeSet::new();
+    let mut in_list = false;
+    for line in output.lines() {
+        let line = line.trim();
+        if !in_list {
+            in_list = line.starts_with("------");
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (Some(_flags), Some(name)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        // "libx264 ... (codec h264)" names the codec when it differs from the encoder
+        let codec = line
+            .rsplit_once("(codec ")
+            .and_then(|(_, rest)| rest.split(')').next())
+            .unwrap_or(name);
+        names.insert(name.to_string());
+        codecs.insert(codec.trim().to_string());
+    }
+    (names, codecs)
+}
+
+/// Parse `ffmpeg -filters`, whose rows look like ` TSC scale  V->V  Scale the input video size.`
+fn parse_filter_list(output: &str) -> BTreeSet<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            match parts.as_slice() {
+                [_, name, io, ..] if io.contains("->") => Some(name.to_string()),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Filter names used in a filtergraph, ignoring quoted and escaped option values
+fn filtergraph_filter_names(graph: &str) -> Vec<String> {
+    static FILTER_NAME_RE: LazyLock<regex::Regex> =
+        LazyLock::new(|| regex::Regex::new(r"(?:^|[,;\]])\s*([A-Za-z_][A-Za-z0-9_]*)").unwrap());
+
+    let mut plain = String::with_capacity(graph.len());
+    let mut chars = graph.chars();
+    let mut quoted = false;
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                chars.next();
+                if !quoted {
+                    plain.push('_');
+                }
+            }
+            '\'' => quoted = !quoted,
+            _ if quoted => {}
+            _ => plain.push(ch),
+        }
+    }
+
+    let mut names = Vec::new();
+    for captures in FILTER_NAME_RE.captures_iter(&plain) {
+        let Some(name) = captures.get(1) else {
+            continue;
+        };
+        let next = plain[name.end()..].trim_start().chars().next();
+        if matches!(next, None | Some('=' | ',' | ';' | '[')) && !names.contains(&name.as_str()) {
+            names.push(name.as_str());
+        }
+    }
+    names.into_iter().map(String::from).collect()
+}
+
+fn is_codec_flag(flag: &str) -> bool {
+    matches!(flag, "-vcodec" | "-acodec" | "-c" | "-codec")
+        || flag.starts_with("-c:")
+        || flag.starts_with("-codec:")
+}
+
+fn is_video_codec_flag(flag: &str) -> bool {
+    flag == "-vcodec" || flag.starts_with("-c:v") || flag.starts_with("-codec:v")
+}
+
+/// The quality option `encoder` understands in place of x264's `-crf`
+fn translated_crf(encoder: &str, crf: &str) -> Option<[String; 2]> {
+    let value = crf.parse::<f64>().ok()?;
+    if encoder.ends_with("_nvenc") {
+        Some(["-cq".to_string(), crf.to_string()])
+    } else if encoder.ends_with("_qsv") {
+        Some(["-global_quality".to_string(), crf.to_string()])
+    } else if encoder.ends_with("_videotoolbox") {
+        // 0-100 with higher meaning better, so this is only a rough match (crf 23 becomes 54)
+        let quality = (100.0 - value * 2.0).clamp(1.0, 100.0).round();
+        Some(["-q:v".to_string(), quality.to_string()])
+    } else if matches!(
+        encoder,
+        "libvpx" | "libvpx-vp9" | "libaom-av1" | "libsvtav1"
+    ) {
+        Some(["-crf".to_string(), crf.to_string()])
+    } else {
+        None
+    }
+}
+
+/// Replace encoders this build lacks with one from the same family.
+///
+/// Returns a warning per replaced (or irreplaceable) encoder.
+pub(super) fn adapt_encoder_args(
+    args: &mut Vec<String>,
+    capabilities: &FFmpegCapabilities,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut replaced_video_with = None;
+
+    let mut index = 0;
+    while index + 1 < args.len() {
+        if !is_codec_flag(&args[index]) {
+            index += 1;
+            continue;
+        }
+        let requested = args[index + 1].clone();
+        if requested == "copy" || capabilities.has_encoder(&requested) {
+            index += 2;
+            continue;
+        }
+        match capabilities.fallback_encoder(&requested) {
+            Some(fallback) => {
+                warnings.push(format!(
+                    "{} is not available in this FFmpeg build; using {} instead",
+                    requested, fallback
+                ));
+                args[index + 1] = fallback.to_string();
+                if is_video_codec_flag(&args[index]) && requested.starts_with("libx26") {
+                    replaced_video_with = Some(fallback);
+                }
+            }
+            None => warnings.push(format!(
+                "This FFmpeg build has no {} encoder, so this command will likely fail",
+                requested
+            )),
+        }
+        index += 2;
+    }
+
+    if let Some(encoder) = replaced_video_with {
+        let mut adapted = Vec::with_capacity(args.len());
+        let mut iter = std::mem::take(args).into_iter();
+        while let Some(arg) = iter.next() {
+            if X26X_ONLY_OPTIONS.contains(&arg.as_str()) {
+                iter.next();
+            } else if arg == "-crf" || arg == "-crf:v" {
+                if let Some(option) = iter.next().and_then(|crf| translated_crf(encoder, &crf)) {
+                    adapted.extend(option);
+                }
+            } else {
+                adapted.push(arg);
+            }
+        }
+        *args = adapted;
+    }
+    warnings
+}
+
+/// Warnings for filters in `-vf`, `-af` and `-filter_complex` graphs this build lacks
+pub(super) fn unsupported_filter_warnings(
+    args: &[String],
+    capabilities: &FFmpegCapabilities,
+) -> Vec<String> {
+    let mut missing = Vec::new();
+    for pair in args.windows(2) {
+        let is_graph = matches!(
+            pair[0].as_str(),
+            "-vf" | "-af" | "-filter_complex" | "-lavfi"
+        ) || pair[0].starts_with("-filter:");
+        if !is_graph {
+            continue;
+        }
+        for name in filtergraph_filter_names(&pair[1]) {
+            if !capabilities.has_filter(&name) && !missing.contains(&name) {
+                missing.push(name);
+            }
+        }
+    }
+    missing
+        .into_iter()
+        .map(|name| {
+            format!(
+                "This FFmpeg build has no '{}' filter, so this command will likely fail",
+                name
+            )
+        })
+        .collect()
+}
+
+async fn run_ffmpeg_listing(ffmpeg_path: &Path, flag: &str) -> Result<String, String> {
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.args(["-hide_banner", flag])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    cmd.hide_window();
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg {}: {}", flag, e))?;
+    if !output.status.success() {
+        return Err(format!("FFmpeg {} exited with {}", flag, output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+async fn probe_ffmpeg_capabilities(
+    ffmpeg_path: &Path,
+    version: &str,
+) -> Result<FFmpegCapabilities, String> {
+    let (encoders, decoders, filters) = tokio::try_join!(
+        run_ffmpeg_listing(ffmpeg_path, "-encoders"),
+        run_ffmpeg_listing(ffmpeg_path, "-decoders"),
+        run_ffmpeg_listing(ffmpeg_path, "-filters"),
+    )?;
+    let (encoders, encoder_codecs) = parse_codec_list(&encoders);
+    let (decoders, decoder_codecs) = parse_codec_list(&decoders);
+    let filters = parse_filter_list(&filters);
+    if encoders.is_empty() || filters.is_empty() {
+        return Err("FFmpeg returned no encoders or filters".to_string());
+    }
+    Ok(FFmpegCapabilities {
+        version: version.to_string(),
+        ffmpeg_path: ffmpeg_path.to_string_lossy().to_string(),
+        encoders,
+        encoder_codecs,
+        decoders,
+        decoder_codecs,
+        filters,
+    })
+}
+
+async fn load_ffmpeg_capabilities(
+    app: &AppHandle,
+    refresh: bool,
+) -> Result<Arc<FFmpegCapabilities>, String> {
+    let ffmpeg_path = get_ffmpeg_path(app)
+        .await
+        .ok_or("FFmpeg not found. Please install FFmpeg from Settings > Dependencies.")?;
+    let fingerprint = binary_fingerprint(&ffmpeg_path);
+
+    let mut cached = CAPABILITIES.lock().await;
+    if let Some((cached_fingerprint, capabilities)) = cached.as_ref() {
+        if !refresh && *cached_fingerprint == fingerprint {
+            return Ok(capabilities.clone());
+        }
+    }
+
+    let version_output = run_ffmpeg_listing(&ffmpeg_path, "-version").await?;
+    let version = version_output.lines().next().unwrap_or_default().trim();
+    let path = ffmpeg_path.to_string_lossy().to_string();
+
+    let cache_path = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(CAPABILITIES_CACHE_FILE));
+    let from_disk = cache_path
+        .as_ref()
+        .filter(|_| !refresh)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str::<FFmpegCapabilities>(&json).ok())
+        .filter(|capabilities| capabilities.version == version && capabilities.ffmpeg_path == path);
+
+    let capabilities = match from_disk {
+        Some(capabilities) => capabilities,
+        None => {
+            log::info!("[FFMPEG] Probing capabilities of {} ({})", version, path);
+            let capabilities = probe_ffmpeg_capabilities(&ffmpeg_path, version).await?;
+            if let Some(path) = &cache_path {
+                if let Ok(json) = serde_json::to_string(&capabilities) {
+                    std::fs::write(path, json).ok();
+                }
+            }
+            capabilities
+        }
+    };
+
+    let capabilities = Arc::new(capabilities);
+    *cached = Some((fingerprint, capabilities.clone()));
+    Ok(capabilities)
+}
+
+/// Capabilities of the resolved FFmpeg, cached per FFmpeg version; None if probing fails
+pub(super) async fn ffmpeg_capabilities(app: &AppHandle) -> Option<Arc<FFmpegCapabilities>> {
+    match load_ffmpeg_capabilities(app, false).await {
+        Ok(capabilities) => Some(capabilities),
+        Err(error) => {
+            log::warn!("[FFMPEG] Capability probe failed: {}", error);
+            None
+        }
+    }
+}
+
+/// AI prompt rule restricting generated commands to encoders this build has
+pub(super) fn encoder_prompt_rule(capabilities: &FFmpegCapabilities) -> String {
+    let encoders = capabilities.known_encoders();
+    if encoders.is_empty() {
+        return String::new();
+    }
+    format!(
+        "10. Only use these encoders (this FFmpeg build has no others from their families): {}\n",
+        encoders.join(", ")
+    )
+}
+
+/// Swap in available encoders and warn about missing decoders and filters
+pub(super) fn adapt_command_to_capabilities(
+    result: &mut FFmpegCommandResult,
+    capabilities: &FFmpegCapabilities,
+    metadata: &VideoMetadata,
+) {
+    let mut warnings = Vec::new();
+    for codec in [&metadata.video_codec, &metadata.audio_codec] {
+        let known = !codec.is_empty() && !matches!(codec.as_str(), "none" | "unknown");
+        if known && !capabilities.has_decoder(codec) {
+            warnings.push(format!("This FFmpeg build cannot decode {}", codec));
+        }
+    }
+
+    let original_args = result.command_args.clone();
+    warnings.extend(adapt_encoder_args(&mut result.command_args, capabilities));
+    if result.target_size.is_some() {
+        // The passes are rebuilt when the job runs, so they take the encoder picked here
+        let encoder = result
+            .command_args
+            .windows(2)
+            .find(|pair| is_video_codec_flag(&pair[0]))
+            .map(|pair| pair[1].clone());
+        if let Some(encoder) = encoder.filter(|encoder| capabilities.has_encoder(encoder)) {
+            warnings.extend(set_target_size_encoder(result, &encoder));
+        }
+    } else if result.command_args != original_args {
+        result.command = args_to_display_command(&result.command_args);
+    }
+    warnings.extend(unsupported_filter_warnings(
+        &result.command_args,
+        capabilities,
+    ));
+
+    for warning in warnings {
+        if !result.warnings.contains(&warning) {
+            result.warnings.push(warning);
+        }
+    }
+}
+
+/// Probe (or re-probe with `refresh`) the encoders, decoders and filters of the resolved FFmpeg
+#[tauri::command]
+pub async fn get_ffmpeg_capabilities(
+    app: AppHandle,
+    refresh: Option<bool>,
+) -> Result<FFmpegCapabilities, String> {
+    load_ffmpeg_capabilities(&app, refresh.unwrap_or(false))
+        .await
+        .map(|capabilities| capabilities.as_ref().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capabilities() -> FFmpegCapabilities {
+        let (encoders, encoder_codecs) = parse_codec_list(
+            "Encoders:
+ V..... = Video
+ ------
+ V....D h264_videotoolbox    VideoToolbox H.264 Encoder (codec h264)
+ V....D mpeg4                MPEG-4 part 2
+ A....D aac                  AAC (Advanced Audio Coding)
+ A....D libmp3lame           libmp3lame MP3 (MPEG audio layer 3) (codec mp3)
+",
+        );
+        FFmpegCapabilities {
+            version: "ffmpeg version 7.1".to_string(),
+            encoders,
+            encoder_codecs,
+            filters: parse_filter_list(
+                "Filters:
+  T.. = Timeline support
+  | = Source or sink filter
+ TSC scale             V->V       Scale the input video size.
+ ... setpts            V->V       Set PTS for the output video frame.
+ ... atempo            A->A       Adjust audio tempo.
+",
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_listings_and_codec_aliases() {
+        let capabilities = capabilities();
+        assert!(capabilities.has_encoder("h264_videotoolbox"));
+        assert!(capabilities.has_encoder("mp3"));
+        assert!(!capabilities.has_encoder("libx264"));
+        assert!(capabilities.has_filter("setpts"));
+        assert!(!capabilities.has_filter("Timeline"));
+        assert_eq!(
+            filtergraph_filter_names(
+                "[0:v]setpts=0.5*PTS,drawtext=text='a,b\\:c'[v];[0:a]atempo=2[a]"
+            ),
+            vec!["setpts", "drawtext", "atempo"]
+        );
+    }
+
+    #[test]
+    fn swaps_missing_encoders_and_warns_about_filters() {
+        let capabilities = capabilities();
+        let mut args = [
+            "-y",
+            "-i",
+            "in.mkv",
+            "-vf",
+            "scale=-1:720,hqdn3d",
+            "-c:v",
+            "libx264",
+            "-preset",
+            "slow",
+            "-crf",
+            "28",
+            "-c:a",
+            "libopus",
+            "out.mp4",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        let warnings = adapt_encoder_args(&mut args, &capabilities);
+        assert_eq!(
+            args,
+            [
+                "-y",
+                "-i",
+                "in.mkv",
+                "-vf",
+                "scale=-1:720,hqdn3d",
+                "-c:v",
+                "h264_videotoolbox",
+                "-q:v",
+                "44",
+                "-c:a",
+                "libopus",
+                "out.mp4"
+            ]
+        );
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[1].contains("no libopus encoder"));
+        assert_eq!(
+            unsupported_filter_warnings(&args, &capabilities),
+            vec!["This FFmpeg build has no 'hqdn3d' filter, so this command will likely fail"]
+        );
+    }
+
+    #[test]
+    fn target_size_passes_take_the_probed_encoder() {
+        let metadata = VideoMetadata {
+            video_codec: "mpeg4".to_string(),
+            ..test_video_metadata()
+        };
+        let options = HashMap::from([("target_size_mb".to_string(), serde_json::json!(25.0))]);
+        let mut result = build_target_size_command(&metadata.path, &options, &metadata, None)
+            .unwrap()
+            .unwrap();
+
+        adapt_command_to_capabilities(&mut result, &capabilities(), &metadata);
+        let encode = result.target_size.as_ref().unwrap();
+        assert_eq!(encode.video_encoder, "h264_videotoolbox");
+        assert!(result
+            .command_args
+            .windows(2)
+            .any(|pair| pair == ["-c:v", "h264_videotoolbox"]));
+        assert!(!result.command.contains("libx264"));
+        assert!(result
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("no two-pass mode")));
+    }
+}
@@ -0,0 +1,550 @@
+use super::*;
+
+/// Longer requests usually combine several edits, so they go to the AI
+const MAX_INTENT_WORDS: usize = 16;
+const RESIZE_HEIGHTS: &[u64] = &[2160, 1440, 1080, 720, 480, 360, 240];
+/// Speeds the `speed` quick action can apply with a single `atempo`
+const MIN_SPEED: f64 = 0.5;
+const MAX_SPEED: f64 = 2.0;
+
+/// `1:20`, `01:02:03.5`, `90`, `90s`, `2 min`, `1.5 hours`
+const TIME_PATTERN: &str = r"(\d+:\d{1,2}(?::\d{1,2})?(?:\.\d+)?|\d+(?:\.\d+)?\s*(?:hours?|hrs?|h|minutes?|mins?|m|seconds?|secs?|s)?)";
+
+fn intent_regex(pattern: &str) -> regex::Regex {
+    regex::Regex::new(&pattern.replace("TIME", TIME_PATTERN)).unwrap()
+}
+
+static POLITE_PREFIX_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    intent_regex(r"^(?:(?:please|can you|could you|would you|i want to|i'd like to|i need to)\s+)+")
+});
+/// Words that chain several edits, which the quick actions cannot combine
+static JOINER_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(?:and|then|also|plus|after that)\b|[;&]"));
+static RANGE_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\bTIME\s*(?:to|until|till|-)\s*TIME\b"));
+static EDGE_RE: LazyLock<regex::Regex> = LazyLock::new(|| intent_regex(r"\b(first|last)\s+TIME\b"));
+static AT_TIME_RE: LazyLock<regex::Regex> = LazyLock::new(|| intent_regex(r"\bat\s+TIME\b"));
+static CUT_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(?:cut|trim|clip|keep|remove|delete|extract)\b"));
+static KEEP_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(?:keep|clip|extract|only)\b"));
+static SELECTION_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(?:selection|selected|selected part)\b"));
+static GIF_RE: LazyLock<regex::Regex> = LazyLock::new(|| intent_regex(r"\bgif\b"));
+static THUMBNAIL_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    intent_regex(
+        r"\b(?:thumbnail|screenshot|snapshot|still frame|poster frame|grab (?:a |the )?frame)\b",
+    )
+});
+static EXTRACT_AUDIO_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    intent_regex(
+        r"\b(?:extract|rip|export|save|pull)\b.*\baudio\b|\baudio only\b|\b(?:to|as|into)\s+(?:an?\s+)?\.?(?:mp3|m4a|aac|wav|flac)\b",
+    )
+});
+static AUDIO_FORMAT_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(mp3|m4a|aac|wav|flac)\b"));
+static AUDIO_BITRATE_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(\d{2,3})\s*k(?:bps|b/s|bit/s)?\b"));
+static CONVERT_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(?:to|as|into)\s+(?:an?\s+)?\.?(mp4|webm|mkv|mov|avi)\b"));
+static RESIZE_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(\d{3,4})p\b|\b(4k|uhd)\b"));
+static SPEED_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    intent_regex(r"\b(?:speed|faster|slower|slow|slow-mo|slowmo|playback|fast forward)\b")
+});
+static SLOW_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(?:slow|slower|slow-mo|slowmo|half)\b"));
+static SPEED_FACTOR_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(\d+(?:\.\d+)?)\s*x\b|\bx\s*(\d+(?:\.\d+)?)\b"));
+static MUTE_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    intent_regex(
+        r"\bmute\b|\b(?:remove|strip|drop|delete|get rid of|without|no)\s+(?:the\s+|all\s+)?(?:audio|sound)\b",
+    )
+});
+static ROTATE_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(?:rotate|rotation)\b|\bupside down\b"));
+static ROTATE_DEGREES_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"(-)?\b(90|180|270)\b"));
+static COMPRESS_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    intent_regex(
+        r"\b(?:compress|shrink|make (?:it|the file|the video) smaller|reduce (?:the )?(?:file )?size)\b",
+    )
+});
+static TARGET_SIZE_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    intent_regex(
+        r"\b(?:to|under|below|at most|max(?:imum)?|less than)\s+(\d+(?:\.\d+)?)\s*(mb|gb)\b",
+    )
+});
+static NORMALIZE_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    intent_regex(r"\b(?:normali[sz]e|level)\b.*\b(?:audio|loudness|volume|sound)\b")
+});
+static TRIM_SILENCE_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    intent_regex(
+        r"\b(?:remove|trim|cut|strip)\s+(?:the\s+)?(?:leading\s+|trailing\s+)?(?:silence|silent parts)\b",
+    )
+});
+static DENOISE_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    intent_regex(
+        r"\b(?:denoise|de-noise|noise reduction|(?:remove|reduce|clean up)\s+(?:the\s+)?(?:background\s+)?(?:noise|hiss|hum))\b",
+    )
+});
+static DOWNMIX_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| intent_regex(r"\b(?:downmix|to|into)\s+(mono|stereo)\b"));
+
+/// A request understood without the AI, ready for `build_quick_action_command`
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct ProcessingIntent {
+    pub task_type: &'static str,
+    pub options: HashMap<String, serde_json::Value>,
+    pub timeline_start: Option<f64>,
+    pub timeline_end: Option<f64>,
+}
+
+impl ProcessingIntent {
+    fn new(task_type: &'static str) -> Self {
+        Self {
+            task_type,
+            options: HashMap::new(),
+            timeline_start: None,
+            timeline_end: None,
+        }
+    }
+
+    fn with_option(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.options.insert(key.to_string(), value.into());
+        self
+    }
+
+    fn with_range(mut self, (start, end): (f64, f64)) -> Self {
+        self.timeline_start = Some(start);
+        self.timeline_end = Some(end);
+        self
+    }
+}
+
+/// Seconds in `1:20`, `01:02:03.5`, `90`, `90s`, `2 min` or `1.5 hours`
+fn parse_intent_time(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if text.contains(':') {
+        let mut seconds = 0.0;
+        for part in text.split(':') {
+            seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+        }
+        return Some(seconds);
+    }
+    let unit_start = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let value = text[..unit_start].parse::<f64>().ok()?;
+    let multiplier = match text[unit_start..].trim() {
+        "" | "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3600.0,
+        _ => return None,
+    };
+    Some(value * multiplier)
+}
+
+/// Keep a range inside the file; None when nothing of it is left
+fn clamp_range(start: f64, end: f64, duration: f64) -> Option<(f64, f64)> {
+    let end = if duration > 0.0 {
+        end.min(duration)
+    } else {
+        end
+    };
+    (start >= 0.0 && end > start).then_some((start, end))
+}
+
+/// `from 1:20 to 2:05`, `the first 30 seconds`, or the timeline selection
+fn requested_range(
+    text: &str,
+    timeline_start: Option<f64>,
+    timeline_end: Option<f64>,
+    duration: f64,
+) -> Option<(f64, f64)> {
+    if let Some(captures) = RANGE_RE.captures(text) {
+        let start = parse_intent_time(&captures[1])?;
+        let end = parse_intent_time(&captures[2])?;
+        return clamp_range(start, end, duration);
+    }
+    if let Some(captures) = EDGE_RE.captures(text) {
+        let length = parse_intent_time(&captures[2])?;
+        if duration <= 0.0 || length <= 0.0 || length >= duration {
+            return None;
+        }
+        // "keep the first 30s" keeps it, "cut the first 30s" removes it
+        let keep = KEEP_RE.is_match(text);
+        return match (&captures[1], keep) {
+            ("first", true) => Some((0.0, length)),
+            ("last", true) => Some((duration - length, duration)),
+            ("first", false) => Some((length, duration)),
+            _ => Some((0.0, duration - length)),
+        };
+    }
+    if SELECTION_RE.is_match(text) {
+        return clamp_range(timeline_start?, timeline_end?, duration);
+    }
+    None
+}
+
+fn speed_intent(text: &str) -> Option<ProcessingIntent> {
+    let slow = SLOW_RE.is_match(text);
+    let factor = SPEED_FACTOR_RE.captures(text).and_then(|captures| {
+        captures
+            .get(1)
+            .or(captures.get(2))
+            .and_then(|factor| factor.as_str().parse::<f64>().ok())
+    });
+    let speed = match factor {
+        // "slow down 2x" means half speed
+        Some(factor) if slow && factor > 1.0 => 1.0 / factor,
+        Some(factor) => factor,
+        None if slow => 0.5,
+        None => 2.0,
+    };
+    (MIN_SPEED..=MAX_SPEED)
+        .contains(&speed)
+        .then(|| ProcessingIntent::new("speed").with_option("speed", speed))
+}
+
+fn rotate_intent(text: &str) -> ProcessingIntent {
+    let degrees = if text.contains("upside down") {
+        180
+    } else {
+        let captures = ROTATE_DEGREES_RE.captures(text);
+        let degrees = captures
+            .as_ref()
+            .and_then(|captures| captures[2].parse::<i64>().ok())
+            .unwrap_or(90);
+        let counterclockwise = captures.is_some_and(|captures| captures.get(1).is_some())
+            || [
+                "left",
+                "counterclockwise",
+                "counter-clockwise",
+                "anticlockwise",
+            ]
+            .iter()
+            .any(|word| text.contains(word));
+        if counterclockwise {
+            (360 - degrees) % 360
+        } else {
+            degrees
+        }
+    };
+    ProcessingIntent::new("rotate").with_option("degrees", degrees)
+}
+
+fn extract_audio_intent(text: &str) -> ProcessingIntent {
+    let format = match AUDIO_FORMAT_RE
+        .captures(text)
+        .map(|captures| captures[1].to_string())
+    {
+        Some(format) if format == "aac" => "m4a".to_string(),
+        Some(format) => format,
+        None => "mp3".to_string(),
+    };
+    let mut intent = ProcessingIntent::new("extract_audio")
+        .with_option("encode_mp3", format == "mp3")
+        .with_option("format", format);
+    if let Some(captures) = AUDIO_BITRATE_RE.captures(text) {
+        intent = intent.with_option("bitrate", format!("{}k", &captures[1]));
+    }
+    intent
+}
+
+fn compress_intent(text: &str) -> ProcessingIntent {
+    let intent = ProcessingIntent::new("compress");
+    let Some(captures) = TARGET_SIZE_RE.captures(text) else {
+        return intent;
+    };
+    let Ok(size) = captures[1].parse::<f64>() else {
+        return intent;
+    };
+    let size_mb = if &captures[2] == "gb" {
+        size * 1000.0
+    } else {
+        size
+    };
+    intent.with_option("target_size_mb", size_mb)
+}
+
+/// Every quick action the request mentions; a request is understood only when this
+/// finds exactly one
+fn matching_intents(
+    text: &str,
+    timeline_start: Option<f64>,
+    timeline_end: Option<f64>,
+    duration: f64,
+) -> Vec<Option<ProcessingIntent>> {
+    let mut intents = Vec::new();
+
+    if GIF_RE.is_match(text) {
+        let mut intent = ProcessingIntent::new("gif");
+        if let Some(range) = requested_range(text, timeline_start, timeline_end, duration) {
+            intent = intent.with_range(range);
+        } else if let Some(start) = timeline_start {
+            intent.timeline_start = Some(start);
+            intent.timeline_end = timeline_end;
+        }
+        intents.push(Some(intent));
+    } else if THUMBNAIL_RE.is_match(text) {
+        let time = AT_TIME_RE
+            .captures(text)
+            .and_then(|captures| parse_intent_time(&captures[1]));
+        let mut intent = ProcessingIntent::new("thumbnail");
+        intent.timeline_start = match time {
+            Some(time) if duration <= 0.0 || time < duration => Some(time),
+            Some(_) => None,
+            None => timeline_start.or(Some(0.0)),
+        };
+        let found = intent.timeline_start.is_some();
+        intents.push(found.then_some(intent));
+    } else if CUT_RE.is_match(text) && !TRIM_SILENCE_RE.is_match(text) {
+        if let Some(range) = requested_range(text, timeline_start, timeline_end, duration) {
+            intents.push(Some(ProcessingIntent::new("cut").with_range(range)));
+        }
+    }
+
+    if EXTRACT_AUDIO_RE.is_match(text) {
+        intents.push(Some(extract_audio_intent(text)));
+    }
+    if let Some(captures) = CONVERT_RE.captures(text) {
+        intents.push(Some(
+            ProcessingIntent::new("convert").with_option("format", captures[1].to_string()),
+        ));
+    }
+    if let Some(captures) = RESIZE_RE.captures(text) {
+        let height = match captures.get(1) {
+            Some(height) => height.as_str().parse::<u64>().ok(),
+            None => Some(2160),
+        };
+        intents.push(
+            height
+                .filter(|height| RESIZE_HEIGHTS.contains(height))
+                .map(|height| {
+                    ProcessingIntent::new("resize").with_option("resolution", height.to_string())
+                }),
+        );
+    }
+    if SPEED_RE.is_match(text) {
+        intents.push(speed_intent(text));
+    }
+    if MUTE_RE.is_match(text) {
+        intents.push(Some(ProcessingIntent::new("remove_audio")));
+    }
+    if ROTATE_RE.is_match(text) {
+        intents.push(Some(rotate_intent(text)));
+    }
+    if COMPRESS_RE.is_match(text) {
+        intents.push(Some(compress_intent(text)));
+    }
+    if NORMALIZE_RE.is_match(text) {
+        intents.push(Some(ProcessingIntent::new("normalize_loudness")));
+    }
+    if TRIM_SILENCE_RE.is_match(text) {
+        intents.push(Some(ProcessingIntent::new("trim_silence")));
+    }
+    if DENOISE_RE.is_match(text) {
+        intents.push(Some(ProcessingIntent::new("denoise")));
+    }
+    if let Some(captures) = DOWNMIX_RE.captures(text) {
+        let channels = if &captures[1] == "mono" { 1 } else { 2 };
+        intents.push(Some(
+            ProcessingIntent::new("downmix").with_option("channels", channels),
+        ));
+    }
+    intents
+}
+
+/// Understand short, single-edit English requests such as "cut from 1:20 to 2:05",
+/// "convert to mp3 320k", "make it 720p", "speed up 1.5x" or "mute" without the AI.
+/// Returns None for anything else, including requests that combine several edits.
+pub(super) fn parse_processing_intent(
+    user_prompt: &str,
+    timeline_start: Option<f64>,
+    timeline_end: Option<f64>,
+    duration: f64,
+) -> Option<ProcessingIntent> {
+    let lower = user_prompt
+        .to_lowercase()
+        .replace(['–', '—'], "-")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = POLITE_PREFIX_RE
+        .replace(lower.trim_end_matches(['.', '!', '?']), "")
+        .trim_end_matches(" please")
+        .to_string();
+    if text.is_empty()
+        || text.split_whitespace().count() > MAX_INTENT_WORDS
+        || JOINER_RE.is_match(&text)
+    {
+        return None;
+    }
+
+    let mut intents = matching_intents(&text, timeline_start, timeline_end, duration);
+    if intents.len() != 1 {
+        return None;
+    }
+    intents.pop().flatten()
+}
+
+/// Build the quick action for a request understood offline. A request that parses but
+/// cannot be built, such as an out-of-range bitrate, is logged and left to the AI.
+pub(super) fn try_build_intent_command(
+    user_prompt: &str,
+    input_path: &str,
+    timeline_start: Option<f64>,
+    timeline_end: Option<f64>,
+    metadata: &VideoMetadata,
+    output_dir: Option<&str>,
+) -> Option<FFmpegCommandResult> {
+    let intent =
+        parse_processing_intent(user_prompt, timeline_start, timeline_end, metadata.duration)?;
+    match build_quick_action_command(
+        input_path.to_string(),
+        intent.task_type.to_string(),
+        intent.options,
+        intent.timeline_start,
+        intent.timeline_end,
+        metadata,
+        output_dir.map(str::to_string),
+    ) {
+        Ok(result) => Some(result),
+        Err(e) => {
+            log::warn!(
+                "[PROCESSING] Parsed {} request could not be built, asking the AI: {}",
+                intent.task_type,
+                e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(prompt: &str) -> Option<ProcessingIntent> {
+        parse_processing_intent(prompt, None, None, 600.0)
+    }
+
+    fn option(intent: &ProcessingIntent, key: &str) -> serde_json::Value {
+        intent.options.get(key).cloned().unwrap_or_default()
+    }
+
+    #[test]
+    fn common_requests_map_to_quick_actions() {
+        let cut = parse("Cut from 1:20 to 2:05").unwrap();
+        assert_eq!(cut.task_type, "cut");
+        assert_eq!(
+            (cut.timeline_start, cut.timeline_end),
+            (Some(80.0), Some(125.0))
+        );
+        let keep = parse("keep the last 30 seconds").unwrap();
+        assert_eq!(
+            (keep.timeline_start, keep.timeline_end),
+            (Some(570.0), Some(600.0))
+        );
+        let trim = parse("trim the first 2 min").unwrap();
+        assert_eq!(
+            (trim.timeline_start, trim.timeline_end),
+            (Some(120.0), Some(600.0))
+        );
+
+        let audio = parse("convert to mp3 320k").unwrap();
+        assert_eq!(audio.task_type, "extract_audio");
+        assert_eq!(option(&audio, "format"), "mp3");
+        assert_eq!(option(&audio, "bitrate"), "320k");
+
+        let resize = parse("please make it 720p").unwrap();
+        assert_eq!(resize.task_type, "resize");
+        assert_eq!(option(&resize, "resolution"), "720");
+
+        let speed = parse("speed up 1.5x").unwrap();
+        assert_eq!(option(&speed, "speed"), 1.5);
+        assert_eq!(option(&parse("slow down 2x").unwrap(), "speed"), 0.5);
+
+        assert_eq!(parse("mute").unwrap().task_type, "remove_audio");
+        assert_eq!(parse("Convert to webm").unwrap().task_type, "convert");
+        assert_eq!(
+            option(
+                &parse("rotate 90 degrees counterclockwise").unwrap(),
+                "degrees"
+            ),
+            270
+        );
+        assert_eq!(option(&parse("rotate left").unwrap(), "degrees"), 270);
+        assert_eq!(
+            option(&parse("compress to under 25 MB").unwrap(), "target_size_mb"),
+            25.0
+        );
+        assert_eq!(parse("remove silence").unwrap().task_type, "trim_silence");
+        assert_eq!(
+            parse("Extract thumbnail at 0:42").unwrap().timeline_start,
+            Some(42.0)
+        );
+    }
+
+    #[test]
+    fn unclear_or_combined_requests_go_to_the_ai() {
+        assert_eq!(parse("cut from 1:20 to 2:05 and make it 720p"), None);
+        assert_eq!(parse("convert to mp4 720p"), None);
+        assert_eq!(parse("make the colors warmer"), None);
+        assert_eq!(parse("cut from 11:00 to 12:00"), None);
+        assert_eq!(parse("speed up 4x"), None);
+        assert_eq!(parse("make it 1234p"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    fn build(prompt: &str) -> Option<FFmpegCommandResult> {
+        try_build_intent_command(
+            prompt,
+            "/videos/talk.mp4",
+            None,
+            None,
+            &test_video_metadata(),
+            Some("/out"),
+        )
+    }
+
+    #[test]
+    fn parsed_requests_build_real_mp3_and_fall_back_on_build_errors() {
+        let mp3 = build("convert to mp3 320k").unwrap();
+        assert!(mp3.output_path.ends_with(".mp3"));
+        assert!(mp3.command.contains("libmp3lame"));
+        assert!(mp3.command.contains("320k"));
+
+        // Parses as extract_audio, but the bitrate is out of range, so the AI gets the request
+        assert!(parse("convert to mp3 900k").is_some());
+        assert!(build("convert to mp3 900k").is_none());
+    }
+
+    #[test]
+    fn quick_action_mp3_keeps_aac_output_unless_opted_in() {
+        let options = HashMap::from([("format".to_string(), serde_json::json!("mp3"))]);
+        let result = build_quick_action_command(
+            "/videos/talk.mp4".to_string(),
+            "extract_audio".to_string(),
+            options,
+            None,
+            None,
+            &test_video_metadata(),
+            Some("/out".to_string()),
+        )
+        .unwrap();
+        assert!(result.output_path.ends_with(".m4a"));
+        assert!(!result.command.contains("libmp3lame"));
+    }
+
+    #[test]
+    fn times_accept_clock_and_unit_forms() {
+        assert_eq!(parse_intent_time("1:20"), Some(80.0));
+        assert_eq!(parse_intent_time("01:02:03.5"), Some(3723.5));
+        assert_eq!(parse_intent_time("90"), Some(90.0));
+        assert_eq!(parse_intent_time("2 min"), Some(120.0));
+        assert_eq!(parse_intent_time("1.5h"), Some(5400.0));
+        assert_eq!(parse_intent_time("3 days"), None);
+    }
+}
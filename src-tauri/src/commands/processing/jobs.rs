@@ -1,6 +1,137 @@
 use super::*;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
-/// Execute FFmpeg command with progress tracking
+/// Receives every progress update of a running FFmpeg job
+pub(super) type ProgressCallback = Arc<dyn Fn(&ProcessingProgress) + Send + Sync>;
+
+/// How often a cancelled parent job re-checks for child jobs that started after the cancel
+const CHILD_JOB_CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// How long to wait for the rest of FFmpeg's log after it exits
+const LOG_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+/// FFmpeg log lines kept per run; the oldest lines are dropped first
+const MAX_JOB_LOG_LINES: usize = 2000;
+/// Log kept per saved job across all of its runs and passes
+const MAX_SAVED_JOB_LOG_CHARS: i64 = 512 * 1024;
+const MAX_PIPELINE_STEPS: usize = 16;
+/// Quick actions that need the timeline selection, which only the first step receives
+const PIPELINE_TIMELINE_TASKS: &[&str] = &["cut", "gif"];
+/// Quick actions whose output later steps cannot read
+const PIPELINE_FINAL_ONLY_TASKS: &[&str] = &["thumbnail"];
+
+/// `-progress` output such as `out_time_us=1000`
+static PROGRESS_LINE_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^[a-z_0-9]+=\S*$").unwrap());
+
+/// Log of every running FFmpeg process by job id, saved to the job when it exits
+static JOB_LOGS: LazyLock<std::sync::Mutex<HashMap<String, VecDeque<String>>>> =
+    LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// One pipeline step: a quick action such as `compress`, or `preset:<id>` for a template
+/// preset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingPipelineStep {
+    pub task_type: String,
+    #[serde(default)]
+    pub options: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingPipelineRequest {
+    pub input_path: String,
+    pub steps: Vec<ProcessingPipelineStep>,
+    /// Timeline selection, used by the first step only
+    #[serde(default)]
+    pub timeline_start: Option<f64>,
+    #[serde(default)]
+    pub timeline_end: Option<f64>,
+    #[serde(default)]
+    pub output_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingPipelineProgress {
+    pub job_id: String,
+    pub step_index: usize,
+    pub step_count: usize,
+    pub step_label: String,
+    pub step_percent: f64,
+    /// Progress of the whole pipeline, each step counting equally
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingPipelineStepResult {
+    pub label: String,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingPipelineResult {
+    pub job_id: String,
+    pub output_path: String,
+    pub steps: Vec<ProcessingPipelineStepResult>,
+}
+
+/// Cancel every running child job (`<parent_id>:<n>`) of `parent_id`
+async fn cancel_child_jobs(parent_id: &str) {
+    let prefix = format!("{}:", parent_id);
+    let mut jobs = ACTIVE_JOBS.lock().await;
+    let job_ids = jobs
+        .keys()
+        .filter(|job_id| job_id.starts_with(&prefix))
+        .cloned()
+        .collect::<Vec<_>>();
+    for job_id in job_ids {
+        if let Some(cancel_tx) = jobs.remove(&job_id) {
+            cancel_tx.send(()).ok();
+        }
+    }
+}
+
+/// Run `work` registered in `ACTIVE_JOBS` as `parent_id`. `cancel_ffmpeg(parent_id)` sets
+/// `cancelled` and cancels the parent's child jobs until `work` finishes.
+pub(super) async fn run_parent_job<T>(
+    parent_id: &str,
+    cancelled: &AtomicBool,
+    work: impl Future<Output = T>,
+) -> Result<T, String> {
+    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    {
+        let mut jobs = ACTIVE_JOBS.lock().await;
+        if jobs.contains_key(parent_id) {
+            return Err("A job with this id is already running".to_string());
+        }
+        jobs.insert(parent_id.to_string(), cancel_tx);
+    }
+
+    tokio::pin!(work);
+    let output = tokio::select! {
+        output = &mut work => output,
+        _ = &mut cancel_rx => {
+            cancelled.store(true, Ordering::SeqCst);
+            // Children may register their job after a sweep, so keep sweeping until all stop
+            loop {
+                cancel_child_jobs(parent_id).await;
+                tokio::select! {
+                    output = &mut work => break output,
+                    _ = tokio::time::sleep(CHILD_JOB_CANCEL_POLL_INTERVAL) => {}
+                }
+            }
+        }
+    };
+
+    ACTIVE_JOBS.lock().await.remove(parent_id);
+    Ok(output)
+}
+
+/// Execute FFmpeg command with progress tracking. `target_size` and `audio_analysis` come
+/// from the generated command and add the passes those actions need. The job runs through
+/// the processing queue and this resolves once it has finished.
 #[tauri::command]
 pub async fn execute_ffmpeg_command(
     app: AppHandle,
@@ -8,25 +139,263 @@ pub async fn execute_ffmpeg_command(
     command_args: Vec<String>,
     input_path: String,
     output_path: String,
+    target_size: Option<TargetSizeEncode>,
+    audio_analysis: Option<AudioAnalysis>,
 ) -> Result<(), String> {
-    println!("[FFMPEG] Starting execute_ffmpeg_command");
-    println!("[FFMPEG] Job ID: {}", job_id);
-    println!("[FFMPEG] Args: {:?}", command_args);
-    println!("[FFMPEG] Input: {}", input_path);
-    println!("[FFMPEG] Output: {}", output_path);
+    let command = FFmpegCommandResult {
+        command: args_to_display_command(&command_args),
+        command_args,
+        explanation: String::new(),
+        estimated_size_mb: 0.0,
+        estimated_time_seconds: 0.0,
+        output_path,
+        warnings: Vec::new(),
+        target_size,
+        audio_analysis,
+    };
+    run_through_queue(&app, job_id, input_path, command).await
+}
 
+/// Run a generated command, then remove the temp chapter file it read whether the job
+/// succeeded, failed or was cancelled
+pub(super) async fn run_processing_command(
+    app: &AppHandle,
+    job_id: String,
+    command: FFmpegCommandResult,
+    input_path: String,
+    on_progress: ProgressCallback,
+) -> Result<(), String> {
+    let chapter_files = chapter_metadata_inputs(&command.command_args);
+    let outcome = run_command_passes(app, job_id, command, input_path, on_progress).await;
+    for path in chapter_files {
+        std::fs::remove_file(path).ok();
+    }
+    outcome
+}
+
+/// A checked two-pass encode when the command has a size target, an analysis pass
+/// followed by the command for measured audio actions, otherwise one job
+async fn run_command_passes(
+    app: &AppHandle,
+    job_id: String,
+    command: FFmpegCommandResult,
+    input_path: String,
+    on_progress: ProgressCallback,
+) -> Result<(), String> {
+    if let Some(encode) = command.target_size {
+        return run_target_size_encode(
+            app,
+            job_id,
+            input_path,
+            command.output_path,
+            encode,
+            on_progress,
+        )
+        .await;
+    }
+    if let Some(analysis) = command.audio_analysis {
+        return run_audio_analysis_job(
+            app,
+            job_id,
+            analysis,
+            command.command_args,
+            input_path,
+            command.output_path,
+            on_progress,
+        )
+        .await;
+    }
+    run_ffmpeg_job(
+        app,
+        job_id,
+        command.command_args,
+        input_path,
+        command.output_path,
+        on_progress,
+    )
+    .await
+}
+
+/// Scale one pass's progress into its share of a job made of several passes
+pub(super) fn scaled_progress(
+    job_id: &str,
+    on_progress: &ProgressCallback,
+    offset: f64,
+    share: f64,
+) -> ProgressCallback {
+    let job_id = job_id.to_string();
+    let on_progress = on_progress.clone();
+    Arc::new(move |progress: &ProcessingProgress| {
+        on_progress(&ProcessingProgress {
+            job_id: job_id.clone(),
+            percent: offset + progress.percent / 100.0 * share,
+            ..progress.clone()
+        })
+    })
+}
+
+/// Run one FFmpeg job, registered in `ACTIVE_JOBS` under `job_id` so it can be cancelled
+pub(super) async fn run_ffmpeg_job(
+    app: &AppHandle,
+    job_id: String,
+    command_args: Vec<String>,
+    input_path: String,
+    output_path: String,
+    on_progress: ProgressCallback,
+) -> Result<(), String> {
+    spawn_ffmpeg_job(
+        app,
+        job_id,
+        command_args,
+        input_path,
+        output_path,
+        on_progress,
+        false,
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Like `run_ffmpeg_job`, but returns FFmpeg's log lines (without the progress lines) for
+/// analysis passes such as `loudnorm` and `silencedetect`
+pub(super) async fn run_ffmpeg_analysis(
+    app: &AppHandle,
+    job_id: String,
+    command_args: Vec<String>,
+    input_path: String,
+    on_progress: ProgressCallback,
+) -> Result<Vec<String>, String> {
+    spawn_ffmpeg_job(
+        app,
+        job_id,
+        command_args,
+        input_path,
+        String::new(),
+        on_progress,
+        true,
+    )
+    .await
+}
+
+fn push_log_line(lines: &mut VecDeque<String>, line: &str) {
+    if lines.len() >= MAX_JOB_LOG_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(line.to_string());
+}
+
+/// Start the log of an FFmpeg run with the command it runs
+fn start_job_log(job_id: &str, args: &[String]) {
+    if let Ok(mut logs) = JOB_LOGS.lock() {
+        let mut lines = VecDeque::new();
+        push_log_line(&mut lines, &format!("$ {}", args_to_display_command(args)));
+        logs.insert(job_id.to_string(), lines);
+    }
+}
+
+/// Add a line to a running job's log, or straight to the saved log of a job with no
+/// FFmpeg process running under its id (such as a multi-pass parent)
+pub(super) fn append_job_log(job_id: &str, line: &str) {
+    if let Ok(mut logs) = JOB_LOGS.lock() {
+        if let Some(lines) = logs.get_mut(job_id) {
+            push_log_line(lines, line);
+            return;
+        }
+    }
+    save_job_log(job_id, line).ok();
+}
+
+/// End a run's log with `outcome` and save it to its job
+fn finish_job_log(job_id: &str, outcome: &str) {
+    let lines = JOB_LOGS
+        .lock()
+        .ok()
+        .and_then(|mut logs| logs.remove(job_id));
+    let Some(mut lines) = lines else {
+        return;
+    };
+    push_log_line(&mut lines, outcome);
+    let text = lines.into_iter().collect::<Vec<_>>().join("\n");
+    if let Err(e) = save_job_log(job_id, &text) {
+        log::warn!("[FFMPEG] {}", e);
+    }
+}
+
+/// Ids that can hold the log of `job_id`: the id itself, then each parent
+/// (`batch:3:pass1` → `batch:3` → `batch`)
+fn job_log_owners(job_id: &str) -> Vec<&str> {
+    let mut owners = vec![job_id];
+    let mut id = job_id;
+    while let Some((parent, _)) = id.rsplit_once(':') {
+        owners.push(parent);
+        id = parent;
+    }
+    owners
+}
+
+/// Append `text` to the log of the nearest saved job, keeping the newest part
+fn save_job_log(job_id: &str, text: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    for owner in job_log_owners(job_id) {
+        let updated = conn
+            .execute(
+                "UPDATE processing_jobs SET log = substr(COALESCE(log || char(10), '') || ?1, -?2)
+                 WHERE id = ?3",
+                params![text, MAX_SAVED_JOB_LOG_CHARS, owner],
+            )
+            .map_err(|e| format!("Failed to save job log: {}", e))?;
+        if updated > 0 {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// FFmpeg output of a job: its saved log followed by the output of runs still in progress
+#[tauri::command]
+pub async fn get_processing_job_log(_app: AppHandle, id: String) -> Result<String, String> {
+    let saved: Option<String> = get_db()?
+        .query_row(
+            "SELECT log FROM processing_jobs WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => "Processing job not found".to_string(),
+            e => format!("Failed to load job log: {}", e),
+        })?;
+
+    let mut sections = saved.into_iter().collect::<Vec<_>>();
+    if let Ok(logs) = JOB_LOGS.lock() {
+        let prefix = format!("{}:", id);
+        let mut running = logs
+            .iter()
+            .filter(|(job_id, _)| **job_id == id || job_id.starts_with(&prefix))
+            .collect::<Vec<_>>();
+        running.sort_by(|a, b| a.0.cmp(b.0));
+        for (_, lines) in running {
+            sections.push(lines.iter().cloned().collect::<Vec<_>>().join("\n"));
+        }
+    }
+    Ok(sections.join("\n"))
+}
+
+async fn spawn_ffmpeg_job(
+    app: &AppHandle,
+    job_id: String,
+    command_args: Vec<String>,
+    input_path: String,
+    output_path: String,
+    on_progress: ProgressCallback,
+    collect_log: bool,
+) -> Result<Vec<String>, String> {
     validate_ffmpeg_args(&command_args)?;
 
-    let ffmpeg_path = get_ffmpeg_path(&app).await.ok_or("FFmpeg not found")?;
-    println!("[FFMPEG] FFmpeg path: {:?}", ffmpeg_path);
+    let ffmpeg_path = get_ffmpeg_path(app).await.ok_or("FFmpeg not found")?;
 
     let metadata = get_video_metadata(app.clone(), input_path.clone()).await?;
     let total_duration_secs = metadata.duration;
     let total_frames = (metadata.duration * metadata.fps) as i64;
-    println!(
-        "[FFMPEG] Total duration: {} secs, Total frames: {}",
-        total_duration_secs, total_frames
-    );
 
     let mut args = command_args;
     if !args.iter().any(|a| a == "-progress") {
@@ -35,10 +404,8 @@ pub async fn execute_ffmpeg_command(
         args.insert(insert_pos + 1, "pipe:2".to_string());
     }
 
-    println!("[FFMPEG] Final args count: {}", args.len());
-    for (i, arg) in args.iter().enumerate() {
-        println!("[FFMPEG]   arg[{}]: '{}'", i, arg);
-    }
+    log::debug!("[FFMPEG] Job {}: {:?}", job_id, args);
+    start_job_log(&job_id, &args);
 
     let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
 
@@ -47,38 +414,42 @@ pub async fn execute_ffmpeg_command(
         jobs.insert(job_id.clone(), cancel_tx);
     }
 
-    println!("[FFMPEG] Spawning FFmpeg process...");
     let mut cmd = Command::new(&ffmpeg_path);
     cmd.args(&args)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
     cmd.hide_window();
-    let mut child = cmd.spawn().map_err(|e| {
-        println!("[FFMPEG] Failed to spawn: {}", e);
-        format!("Failed to start FFmpeg: {}", e)
-    })?;
-    println!("[FFMPEG] FFmpeg process spawned successfully");
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            ACTIVE_JOBS.lock().await.remove(&job_id);
+            let error = format!("Failed to start FFmpeg: {}", e);
+            finish_job_log(&job_id, &error);
+            return Err(error);
+        }
+    };
 
     let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
     let mut reader = BufReader::new(stderr).lines();
 
-    let app_clone = app.clone();
+    let progress_callback = on_progress.clone();
     let job_id_clone = job_id.clone();
 
-    let progress_task = tokio::spawn(async move {
+    let mut progress_task = tokio::spawn(async move {
         let mut current_frame: i64 = 0;
         let mut current_fps: f64 = 0.0;
         let mut current_time = String::new();
         let mut current_time_secs: f64 = 0.0;
         let mut current_size = String::new();
         let mut current_speed = String::new();
-        let mut error_lines: Vec<String> = Vec::new();
+        let mut log_lines: Vec<String> = Vec::new();
 
         while let Ok(Some(line)) = reader.next_line().await {
-            println!("[FFMPEG STDERR] {}", line);
-
-            if line.contains("Error") || line.contains("error") || line.contains("Invalid") {
-                error_lines.push(line.clone());
+            if !PROGRESS_LINE_RE.is_match(&line) {
+                append_job_log(&job_id_clone, &line);
+                if collect_log {
+                    log_lines.push(line.clone());
+                }
             }
 
             if line.starts_with("frame=") {
@@ -132,11 +503,6 @@ pub async fn execute_ffmpeg_command(
                     0.0
                 };
 
-                println!(
-                    "[FFMPEG PROGRESS] time_secs={}, duration={}, percent={:.1}%",
-                    current_time_secs, total_duration_secs, percent
-                );
-
                 let progress = ProcessingProgress {
                     job_id: job_id_clone.clone(),
                     percent,
@@ -148,18 +514,21 @@ pub async fn execute_ffmpeg_command(
                     size: current_size.clone(),
                 };
 
-                let _ = app_clone.emit("processing-progress", &progress);
+                progress_callback(&progress);
             }
         }
 
-        if !error_lines.is_empty() {
-            println!("[FFMPEG] Collected errors: {:?}", error_lines);
-        }
+        log_lines
     });
 
     tokio::select! {
         status = child.wait() => {
-            println!("[FFMPEG] Process exited with status: {:?}", status);
+            // Errors and analysis results are printed last, so let the reader reach the end
+            let log_lines = match tokio::time::timeout(LOG_DRAIN_TIMEOUT, &mut progress_task).await
+            {
+                Ok(Ok(lines)) => lines,
+                _ => Vec::new(),
+            };
             progress_task.abort();
 
             {
@@ -169,8 +538,8 @@ pub async fn execute_ffmpeg_command(
 
             match status {
                 Ok(exit_status) if exit_status.success() => {
-                    println!("[FFMPEG] Success! Output: {}", output_path);
-                    let _ = app.emit("processing-progress", ProcessingProgress {
+                    finish_job_log(&job_id, "FFmpeg finished successfully");
+                    on_progress(&ProcessingProgress {
                         job_id: job_id.clone(),
                         percent: 100.0,
                         frame: total_frames,
@@ -180,44 +549,393 @@ pub async fn execute_ffmpeg_command(
                         time: "".to_string(),
                         size: "".to_string(),
                     });
-                    Ok(())
+                    Ok(log_lines)
                 }
                 Ok(exit_status) => {
-                    println!("[FFMPEG] Failed with exit code: {:?}", exit_status.code());
-                    Err(format!("FFmpeg exited with code: {:?}", exit_status.code()))
+                    let error = format!("FFmpeg exited with code: {:?}", exit_status.code());
+                    finish_job_log(&job_id, &error);
+                    Err(error)
                 }
                 Err(e) => {
-                    println!("[FFMPEG] Process error: {}", e);
-                    Err(format!("FFmpeg process error: {}", e))
+                    let error = format!("FFmpeg process error: {}", e);
+                    finish_job_log(&job_id, &error);
+                    Err(error)
                 }
             }
         }
         _ = &mut cancel_rx => {
             child.kill().await.ok();
             progress_task.abort();
-            tokio::fs::remove_file(&output_path).await.ok();
+            if !output_path.is_empty() {
+                tokio::fs::remove_file(&output_path).await.ok();
+            }
 
             {
                 let mut jobs = ACTIVE_JOBS.lock().await;
                 jobs.remove(&job_id);
             }
 
+            finish_job_log(&job_id, "Processing cancelled");
             Err("Processing cancelled".to_string())
         }
     }
 }
 
+fn validate_pipeline_steps(steps: &[ProcessingPipelineStep]) -> Result<(), String> {
+    if steps.is_empty() {
+        return Err("A pipeline needs at least one step".to_string());
+    }
+    if steps.len() > MAX_PIPELINE_STEPS {
+        return Err(format!(
+            "A pipeline can have at most {} steps",
+            MAX_PIPELINE_STEPS
+        ));
+    }
+    for (index, step) in steps.iter().enumerate() {
+        let task_type = step.task_type.trim();
+        if task_type.is_empty() {
+            return Err(format!("Step {} has no action", index + 1));
+        }
+        if index > 0 && PIPELINE_TIMELINE_TASKS.contains(&task_type) {
+            return Err(format!(
+                "Step {} ({}) uses the timeline selection and must be the first step",
+                index + 1,
+                task_type
+            ));
+        }
+        if index + 1 < steps.len() && PIPELINE_FINAL_ONLY_TASKS.contains(&task_type) {
+            return Err(format!(
+                "Step {} ({}) must be the last step",
+                index + 1,
+                task_type
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn pipeline_step_label(step: &ProcessingPipelineStep) -> String {
+    step.label
+        .as_deref()
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+        .unwrap_or(&step.task_type)
+        .to_string()
+}
+
+fn pipeline_percent(step_index: usize, step_count: usize, step_percent: f64) -> f64 {
+    if step_count == 0 {
+        return 0.0;
+    }
+    ((step_index as f64 + step_percent.clamp(0.0, 100.0) / 100.0) / step_count as f64 * 100.0)
+        .min(100.0)
+}
+
+/// Final file name: the original input's name with the last step's extension
+fn pipeline_output_path(
+    input_path: &str,
+    output_dir: Option<&str>,
+    last_intermediate: &str,
+    timestamp: &str,
+) -> PathBuf {
+    let input_stem = Path::new(input_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or("output".to_string());
+    let extension = Path::new(last_intermediate)
+        .extension()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or("mp4".to_string());
+    resolve_output_dir(input_path, output_dir).join(format!(
+        "{}_pipeline_{}.{}",
+        input_stem, timestamp, extension
+    ))
+}
+
+fn update_processing_job_command(
+    id: &str,
+    output_path: Option<&str>,
+    ffmpeg_command: &str,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE processing_jobs SET ffmpeg_command = ?1, output_path = COALESCE(?2, output_path)
+         WHERE id = ?3",
+        params![ffmpeg_command, output_path, id],
+    )
+    .map_err(|e| format!("Failed to update job: {}", e))?;
+    Ok(())
+}
+
+/// Run every step on the previous step's output, writing intermediates to `work_dir`.
+/// Returns the last intermediate file.
+async fn run_pipeline_steps(
+    app: &AppHandle,
+    job_id: &str,
+    request: &ProcessingPipelineRequest,
+    work_dir: &Path,
+    cancelled: &AtomicBool,
+    steps: &mut Vec<ProcessingPipelineStepResult>,
+) -> Result<String, String> {
+    let step_count = request.steps.len();
+    let work_dir = work_dir.to_string_lossy().to_string();
+    let mut current_input = request.input_path.clone();
+
+    for (index, step) in request.steps.iter().enumerate() {
+        if cancelled.load(Ordering::SeqCst) {
+            return Err("Processing cancelled".to_string());
+        }
+        let label = pipeline_step_label(step);
+        let step_error = |e: String| format!("Step {} ({}): {}", index + 1, label, e);
+
+        let metadata = get_video_metadata(app.clone(), current_input.clone())
+            .await
+            .map_err(step_error)?;
+        let (timeline_start, timeline_end) = if index == 0 {
+            (request.timeline_start, request.timeline_end)
+        } else {
+            (None, None)
+        };
+        let command = generate_quick_action_command(
+            app.clone(),
+            current_input.clone(),
+            step.task_type.trim().to_string(),
+            step.options.clone(),
+            timeline_start,
+            timeline_end,
+            metadata,
+            Some(work_dir.clone()),
+        )
+        .await
+        .map_err(step_error)?;
+
+        steps.push(ProcessingPipelineStepResult {
+            label: label.clone(),
+            command: command.command.clone(),
+        });
+        let commands = steps
+            .iter()
+            .map(|step| step.command.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        update_processing_job_command(job_id, None, &commands).ok();
+
+        if cancelled.load(Ordering::SeqCst) {
+            return Err("Processing cancelled".to_string());
+        }
+        let emitter = app.clone();
+        let pipeline_job_id = job_id.to_string();
+        let step_label = label.clone();
+        let on_progress: ProgressCallback = Arc::new(move |progress: &ProcessingProgress| {
+            let percent = pipeline_percent(index, step_count, progress.percent);
+            let _ = emitter.emit(
+                "processing-progress",
+                &ProcessingProgress {
+                    job_id: pipeline_job_id.clone(),
+                    percent,
+                    ..progress.clone()
+                },
+            );
+            let _ = emitter.emit(
+                "processing-pipeline-progress",
+                &ProcessingPipelineProgress {
+                    job_id: pipeline_job_id.clone(),
+                    step_index: index,
+                    step_count,
+                    step_label: step_label.clone(),
+                    step_percent: progress.percent,
+                    percent,
+                },
+            );
+        });
+        let output_path = command.output_path.clone();
+        run_processing_command(
+            app,
+            format!("{}:{}", job_id, index),
+            command,
+            current_input.clone(),
+            on_progress,
+        )
+        .await
+        .map_err(|e| {
+            if e == "Processing cancelled" {
+                e
+            } else {
+                step_error(e)
+            }
+        })?;
+
+        // The previous intermediate is no longer needed; never touch the original input
+        if index > 0 {
+            tokio::fs::remove_file(&current_input).await.ok();
+        }
+        current_input = output_path;
+    }
+
+    Ok(current_input)
+}
+
+/// Move the last intermediate out of the temporary folder
+async fn save_pipeline_output(
+    request: &ProcessingPipelineRequest,
+    last_intermediate: &str,
+) -> Result<String, String> {
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let output = pipeline_output_path(
+        &request.input_path,
+        request.output_dir.as_deref(),
+        last_intermediate,
+        &timestamp,
+    );
+    if tokio::fs::rename(last_intermediate, &output).await.is_err() {
+        // The temporary folder can be on another drive
+        tokio::fs::copy(last_intermediate, &output)
+            .await
+            .map_err(|e| format!("Failed to save pipeline output: {}", e))?;
+    }
+    Ok(output.to_string_lossy().to_string())
+}
+
+/// Run quick actions or template presets one after another, each step reading the
+/// previous step's output.
+///
+/// Intermediate files go to a temporary folder that is removed when the pipeline ends;
+/// only the last result is moved next to the input (or into `output_dir`). Progress is
+/// emitted as `processing-progress` for `job_id` with the combined percentage and as
+/// `processing-pipeline-progress` with the current step. The whole pipeline is saved to
+/// the processing history as one `pipeline` job listing every step's command.
+/// `cancel_ffmpeg(job_id)` stops the running step and skips the rest.
 #[tauri::command]
-pub async fn cancel_ffmpeg(job_id: String) -> Result<(), String> {
+pub async fn run_processing_pipeline(
+    app: AppHandle,
+    job_id: String,
+    request: ProcessingPipelineRequest,
+) -> Result<ProcessingPipelineResult, String> {
+    if job_id.trim().is_empty()
+        || !job_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("Invalid job id".to_string());
+    }
+    validate_pipeline_steps(&request.steps)?;
+
+    let cancelled = AtomicBool::new(false);
+    let pipeline = async {
+        let work_dir = std::env::temp_dir().join("youwee-pipelines").join(&job_id);
+        tokio::fs::create_dir_all(&work_dir)
+            .await
+            .map_err(|e| format!("Failed to create pipeline folder: {}", e))?;
+
+        let summary = request
+            .steps
+            .iter()
+            .map(pipeline_step_label)
+            .collect::<Vec<_>>()
+            .join(" → ");
+        save_processing_job(
+            app.clone(),
+            job_id.clone(),
+            request.input_path.clone(),
+            None,
+            "pipeline".to_string(),
+            Some(summary),
+            String::new(),
+        )
+        .await
+        .ok();
+
+        let mut steps = Vec::with_capacity(request.steps.len());
+        let outcome =
+            match run_pipeline_steps(&app, &job_id, &request, &work_dir, &cancelled, &mut steps)
+                .await
+            {
+                Ok(last_intermediate) => save_pipeline_output(&request, &last_intermediate).await,
+                Err(e) => Err(e),
+            };
+        tokio::fs::remove_dir_all(&work_dir).await.ok();
+
+        match outcome {
+            Ok(output_path) => {
+                let commands = steps
+                    .iter()
+                    .map(|step| step.command.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                update_processing_job_command(&job_id, Some(&output_path), &commands).ok();
+                update_processing_job(
+                    app.clone(),
+                    job_id.clone(),
+                    "completed".to_string(),
+                    100.0,
+                    None,
+                )
+                .await
+                .ok();
+                Ok(ProcessingPipelineResult {
+                    job_id: job_id.clone(),
+                    output_path,
+                    steps,
+                })
+            }
+            Err(e) => {
+                let status = if e == "Processing cancelled" {
+                    "cancelled"
+                } else {
+                    "failed"
+                };
+                update_processing_job(
+                    app.clone(),
+                    job_id.clone(),
+                    status.to_string(),
+                    0.0,
+                    Some(e.clone()),
+                )
+                .await
+                .ok();
+                Err(e)
+            }
+        }
+    };
+
+    run_parent_job(&job_id, &cancelled, pipeline).await?
+}
+
+/// Cancel a running job, or a job still waiting in the processing queue
+#[tauri::command]
+pub async fn cancel_ffmpeg(app: AppHandle, job_id: String) -> Result<(), String> {
     let mut jobs = ACTIVE_JOBS.lock().await;
     if let Some(cancel_tx) = jobs.remove(&job_id) {
         cancel_tx.send(()).ok();
         Ok(())
+    } else if cancel_queued_processing_job(&app, &job_id)? {
+        Ok(())
     } else {
         Err("Job not found".to_string())
     }
 }
 
+pub(super) const PROCESSING_JOB_COLUMNS: &str =
+    "id, input_path, output_path, task_type, user_prompt,
+     ffmpeg_command, status, progress, error_message, created_at, completed_at, priority";
+
+pub(super) fn processing_job_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProcessingJob> {
+    Ok(ProcessingJob {
+        id: row.get(0)?,
+        input_path: row.get(1)?,
+        output_path: row.get(2)?,
+        task_type: row.get(3)?,
+        user_prompt: row.get(4)?,
+        ffmpeg_command: row.get(5)?,
+        status: row.get(6)?,
+        progress: row.get(7)?,
+        error_message: row.get(8)?,
+        created_at: row.get(9)?,
+        completed_at: row.get(10)?,
+        priority: row.get(11)?,
+    })
+}
+
 #[tauri::command]
 pub async fn get_processing_history(
     _app: AppHandle,
@@ -226,31 +944,14 @@ pub async fn get_processing_history(
     let conn = get_db()?;
 
     let mut stmt = conn
-        .prepare(
-            "SELECT id, input_path, output_path, task_type, user_prompt, 
-         ffmpeg_command, status, progress, error_message, created_at, completed_at
-         FROM processing_jobs 
-         ORDER BY created_at DESC 
-         LIMIT ?1",
-        )
+        .prepare(&format!(
+            "SELECT {} FROM processing_jobs ORDER BY created_at DESC LIMIT ?1",
+            PROCESSING_JOB_COLUMNS
+        ))
         .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
     let jobs = stmt
-        .query_map(params![limit], |row| {
-            Ok(ProcessingJob {
-                id: row.get(0)?,
-                input_path: row.get(1)?,
-                output_path: row.get(2)?,
-                task_type: row.get(3)?,
-                user_prompt: row.get(4)?,
-                ffmpeg_command: row.get(5)?,
-                status: row.get(6)?,
-                progress: row.get(7)?,
-                error_message: row.get(8)?,
-                created_at: row.get(9)?,
-                completed_at: row.get(10)?,
-            })
-        })
+        .query_map(params![limit], processing_job_from_row)
         .map_err(|e| format!("Query failed: {}", e))?
         .filter_map(|r| r.ok())
         .collect();
@@ -313,7 +1014,7 @@ pub async fn update_processing_job(
 ) -> Result<(), String> {
     let conn = get_db()?;
 
-    if status == "completed" || status == "failed" || status == "cancelled" {
+    if ["completed", "failed", "cancelled", "interrupted"].contains(&status.as_str()) {
         let completed_at = chrono::Utc::now().to_rfc3339();
         conn.execute(
             "UPDATE processing_jobs SET status = ?1, progress = ?2, error_message = ?3, completed_at = ?4 WHERE id = ?5",
@@ -331,30 +1032,129 @@ pub async fn update_processing_job(
     Ok(())
 }
 
+const PRESET_COLUMNS: &str = "id, name, description, task_type, prompt_template, icon, created_at,
+     args_template, template_params, output_extension";
+
+fn processing_preset_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProcessingPreset> {
+    let args_template: Option<String> = row.get(7)?;
+    let template_params: Option<String> = row.get(8)?;
+    Ok(ProcessingPreset {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        task_type: row.get(3)?,
+        prompt_template: row.get(4)?,
+        icon: row.get(5)?,
+        created_at: row.get(6)?,
+        args_template: args_template.and_then(|json| serde_json::from_str(&json).ok()),
+        template_params: template_params
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        output_extension: row.get(9)?,
+    })
+}
+
+pub(super) fn load_processing_preset(id: &str) -> Result<ProcessingPreset, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM processing_presets WHERE id = ?1",
+            PRESET_COLUMNS
+        ),
+        params![id],
+        processing_preset_from_row,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => "Processing preset not found".to_string(),
+        e => format!("Failed to load processing preset: {}", e),
+    })
+}
+
+/// Insert a preset, validating its FFmpeg template first when it has one
+pub(super) fn insert_processing_preset(
+    name: String,
+    description: Option<String>,
+    task_type: String,
+    prompt_template: String,
+    args_template: Option<Vec<String>>,
+    template_params: BTreeMap<String, String>,
+    output_extension: Option<String>,
+) -> Result<ProcessingPreset, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    let output_extension = output_extension
+        .map(|extension| extension.trim().trim_start_matches('.').to_lowercase())
+        .filter(|extension| !extension.is_empty());
+    match args_template.as_deref() {
+        Some(args_template) => validate_args_template(
+            args_template,
+            &template_params,
+            output_extension.as_deref().unwrap_or("mp4"),
+        )?,
+        None if prompt_template.trim().is_empty() => {
+            return Err("A preset needs a prompt or an FFmpeg template".to_string());
+        }
+        None => {}
+    }
+
+    let preset = ProcessingPreset {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        description,
+        task_type,
+        prompt_template,
+        icon: None,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        args_template,
+        template_params,
+        output_extension,
+    };
+    let args_template_json = preset
+        .args_template
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| format!("Failed to serialize preset template: {}", e))?;
+    let template_params_json = serde_json::to_string(&preset.template_params)
+        .map_err(|e| format!("Failed to serialize preset parameters: {}", e))?;
+
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO processing_presets (id, name, description, task_type, prompt_template, created_at,
+            args_template, template_params, output_extension)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            preset.id,
+            preset.name,
+            preset.description,
+            preset.task_type,
+            preset.prompt_template,
+            preset.created_at,
+            args_template_json,
+            template_params_json,
+            preset.output_extension
+        ],
+    )
+    .map_err(|e| format!("Failed to save preset: {}", e))?;
+
+    Ok(preset)
+}
+
 #[tauri::command]
 pub async fn get_processing_presets(_app: AppHandle) -> Result<Vec<ProcessingPreset>, String> {
     let conn = get_db()?;
 
     let mut stmt = conn
-        .prepare(
-            "SELECT id, name, description, task_type, prompt_template, icon, created_at
-         FROM processing_presets 
-         ORDER BY name ASC",
-        )
+        .prepare(&format!(
+            "SELECT {} FROM processing_presets ORDER BY name ASC",
+            PRESET_COLUMNS
+        ))
         .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
     let presets = stmt
-        .query_map([], |row| {
-            Ok(ProcessingPreset {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                task_type: row.get(3)?,
-                prompt_template: row.get(4)?,
-                icon: row.get(5)?,
-                created_at: row.get(6)?,
-            })
-        })
+        .query_map([], processing_preset_from_row)
         .map_err(|e| format!("Query failed: {}", e))?
         .filter_map(|r| r.ok())
         .collect();
@@ -362,26 +1162,29 @@ pub async fn get_processing_presets(_app: AppHandle) -> Result<Vec<ProcessingPre
     Ok(presets)
 }
 
+/// Save a preset. Presets with `args_template` run offline through
+/// `generate_quick_action_command`; the others send `prompt_template` to the AI.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn save_processing_preset(
     _app: AppHandle,
     name: String,
     description: Option<String>,
-    prompt_template: String,
+    prompt_template: Option<String>,
     task_type: String,
-) -> Result<(), String> {
-    let conn = get_db()?;
-    let id = uuid::Uuid::new_v4().to_string();
-    let created_at = chrono::Utc::now().to_rfc3339();
-
-    conn.execute(
-        "INSERT INTO processing_presets (id, name, description, task_type, prompt_template, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        params![id, name, description, task_type, prompt_template, created_at],
+    args_template: Option<Vec<String>>,
+    template_params: Option<BTreeMap<String, String>>,
+    output_extension: Option<String>,
+) -> Result<ProcessingPreset, String> {
+    insert_processing_preset(
+        name,
+        description,
+        task_type,
+        prompt_template.unwrap_or_default(),
+        args_template,
+        template_params.unwrap_or_default(),
+        output_extension,
     )
-    .map_err(|e| format!("Failed to save preset: {}", e))?;
-
-    Ok(())
 }
 
 #[tauri::command]
@@ -393,3 +1196,119 @@ pub async fn delete_processing_preset(_app: AppHandle, id: String) -> Result<(),
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(task_type: &str) -> ProcessingPipelineStep {
+        ProcessingPipelineStep {
+            task_type: task_type.to_string(),
+            options: HashMap::new(),
+            label: None,
+        }
+    }
+
+    #[test]
+    fn pipeline_steps_keep_timeline_first_and_images_last() {
+        assert!(validate_pipeline_steps(&[]).is_err());
+        assert!(
+            validate_pipeline_steps(&[step("cut"), step("compress"), step("thumbnail")]).is_ok()
+        );
+        assert!(validate_pipeline_steps(&[step("compress"), step("cut")])
+            .unwrap_err()
+            .contains("first step"));
+        assert!(
+            validate_pipeline_steps(&[step("thumbnail"), step("compress")])
+                .unwrap_err()
+                .contains("last step")
+        );
+        assert!(validate_pipeline_steps(&vec![step("compress"); MAX_PIPELINE_STEPS + 1]).is_err());
+    }
+
+    #[test]
+    fn only_generated_chapter_files_are_cleaned_up() {
+        let chapters = std::env::temp_dir().join(format!("{}job.txt", CHAPTER_METADATA_PREFIX));
+        let chapters_arg = chapters.to_string_lossy().to_string();
+        let args = [
+            "-i",
+            "/videos/talk.mp4",
+            "-f",
+            "ffmetadata",
+            "-i",
+            chapters_arg.as_str(),
+            "-i",
+            "/videos/youwee_chapters_mine.txt",
+            "out.mp4",
+        ]
+        .map(String::from);
+        assert_eq!(chapter_metadata_inputs(&args), vec![chapters]);
+    }
+
+    #[test]
+    fn job_logs_go_to_the_nearest_saved_parent() {
+        assert_eq!(job_log_owners("job"), vec!["job"]);
+        assert_eq!(
+            job_log_owners("batch:3:pass1"),
+            vec!["batch:3:pass1", "batch:3", "batch"]
+        );
+    }
+
+    #[test]
+    fn concat_step_scales_clips_to_the_main_video_and_fills_missing_audio() {
+        let clip = |path: &str, width: i32, has_audio: bool| VideoMetadata {
+            path: path.to_string(),
+            filename: path.rsplit('/').next().unwrap_or_default().to_string(),
+            duration: 5.0,
+            width,
+            height: width * 9 / 16,
+            has_audio,
+            ..test_video_metadata()
+        };
+        let main = clip("/tmp/job/main.mp4", 1920, true);
+        let paths = vec!["/clips/intro.mp4".to_string(), main.path.clone()];
+        let result = build_merge_command(
+            &main.path,
+            &main,
+            &paths,
+            &[clip("/clips/intro.mp4", 1280, false), main.clone()],
+            Some("/tmp/job"),
+            Vec::new(),
+        );
+
+        let filter = &result.command_args[result
+            .command_args
+            .iter()
+            .position(|arg| arg == "-filter_complex")
+            .unwrap()
+            + 1];
+        assert!(filter.contains("[0:v]scale=1920:1080"));
+        assert!(filter.contains("aevalsrc=0:d=5.000[sil0]"));
+        assert!(filter.contains("[1:a]aformat"));
+        assert!(filter.ends_with("concat=n=2:v=1:a=1[v][a]"));
+        assert_eq!(result.command_args[2], "/clips/intro.mp4");
+        assert!(validate_pipeline_steps(&[step("cut"), step("concat"), step("compress")]).is_ok());
+    }
+
+    #[test]
+    fn pipeline_progress_weights_steps_equally() {
+        assert_eq!(pipeline_percent(0, 4, 0.0), 0.0);
+        assert_eq!(pipeline_percent(1, 4, 50.0), 37.5);
+        assert_eq!(pipeline_percent(3, 4, 100.0), 100.0);
+        assert_eq!(pipeline_percent(0, 2, 150.0), 50.0);
+    }
+
+    #[test]
+    fn pipeline_output_uses_input_name_and_last_extension() {
+        let output = pipeline_output_path(
+            "/videos/talk.mov",
+            None,
+            "/tmp/youwee-pipelines/job/talk_cut_1_compressed_2.mp4",
+            "20260101_120000",
+        );
+        assert_eq!(
+            output,
+            Path::new("/videos").join("talk_pipeline_20260101_120000.mp4")
+        );
+    }
+}
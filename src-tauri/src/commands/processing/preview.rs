@@ -89,8 +89,7 @@ pub async fn generate_video_preview(
         }),
     );
 
-    let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args([
+    let mut args = [
         "-y",
         "-i",
         &input_path,
@@ -108,9 +107,19 @@ pub async fn generate_video_preview(
         "-movflags",
         "+faststart",
         preview_path.to_str().unwrap(),
-    ])
-    .stdout(Stdio::piped())
-    .stderr(Stdio::piped());
+    ]
+    .map(String::from)
+    .to_vec();
+    if let Some(capabilities) = ffmpeg_capabilities(&app).await {
+        for warning in adapt_encoder_args(&mut args, &capabilities) {
+            log::warn!("[PREVIEW] {}", warning);
+        }
+    }
+
+    let mut cmd = Command::new(&ffmpeg_path);
+    cmd.args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
     cmd.hide_window();
     let output = cmd
         .output()
@@ -176,16 +185,23 @@ pub async fn cleanup_previews(app: AppHandle) -> Result<u32, String> {
         return Ok(0);
     }
 
+    let entries = std::fs::read_dir(&preview_dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).collect::<Vec<_>>())
+        .unwrap_or_default();
+
     let mut count = 0;
-    if let Ok(entries) = std::fs::read_dir(&preview_dir) {
-        for entry in entries.filter_map(|e| e.ok()) {
-            if let Ok(metadata) = entry.metadata() {
-                if let Ok(modified) = metadata.modified() {
-                    if let Ok(elapsed) = modified.elapsed() {
-                        if elapsed.as_secs() > 7 * 24 * 60 * 60 {
-                            if std::fs::remove_file(entry.path()).is_ok() {
-                                count += 1;
-                            }
+    let mut expired_sprite_sets = std::collections::HashSet::new();
+    for entry in &entries {
+        if let Ok(metadata) = entry.metadata() {
+            if let Ok(modified) = metadata.modified() {
+                if let Ok(elapsed) = modified.elapsed() {
+                    if elapsed.as_secs() > 7 * 24 * 60 * 60 {
+                        let file_name = entry.file_name().to_string_lossy().to_string();
+                        if let Some(prefix) = sprite_set_prefix(&file_name) {
+                            expired_sprite_sets.insert(prefix.to_string());
+                        }
+                        if std::fs::remove_file(entry.path()).is_ok() {
+                            count += 1;
                         }
                     }
                 }
@@ -193,6 +209,16 @@ pub async fn cleanup_previews(app: AppHandle) -> Result<u32, String> {
         }
     }
 
+    // A sprite track is useless without all of its sheets, so drop the whole set together
+    for entry in &entries {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let in_expired_set = sprite_set_prefix(&file_name)
+            .is_some_and(|prefix| expired_sprite_sets.contains(prefix));
+        if in_expired_set && entry.path().exists() && std::fs::remove_file(entry.path()).is_ok() {
+            count += 1;
+        }
+    }
+
     Ok(count)
 }
 
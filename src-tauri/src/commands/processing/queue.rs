@@ -0,0 +1,699 @@
+use super::*;
+use rusqlite::Connection;
+use std::collections::HashSet;
+
+const QUEUE_SETTINGS_FILE: &str = "processing_queue.json";
+const MAX_QUEUE_CONCURRENCY: usize = 8;
+const INTERRUPTED_MESSAGE: &str = "Interrupted because the app closed while it was running";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingQueueSettings {
+    /// Jobs that run at the same time
+    #[serde(default = "default_max_concurrent_jobs")]
+    pub max_concurrent_jobs: usize,
+    /// Queue jobs interrupted by closing the app again on the next start instead of only
+    /// marking them `interrupted`
+    #[serde(default)]
+    pub resume_interrupted: bool,
+}
+
+fn default_max_concurrent_jobs() -> usize {
+    1
+}
+
+impl Default for ProcessingQueueSettings {
+    fn default() -> Self {
+        Self {
+            max_concurrent_jobs: default_max_concurrent_jobs(),
+            resume_interrupted: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingQueueRequest {
+    /// Job id, generated when missing
+    #[serde(default)]
+    pub id: Option<String>,
+    pub input_path: String,
+    pub task_type: String,
+    #[serde(default)]
+    pub user_prompt: Option<String>,
+    pub command: FFmpegCommandResult,
+    /// Higher runs first; jobs with the same priority run in the order they were queued
+    #[serde(default)]
+    pub priority: i32,
+}
+
+#[derive(Default)]
+struct QueueState {
+    settings: ProcessingQueueSettings,
+    running: HashSet<String>,
+}
+
+static QUEUE: LazyLock<std::sync::Mutex<QueueState>> =
+    LazyLock::new(|| std::sync::Mutex::new(QueueState::default()));
+
+type JobOutcomeSender = tokio::sync::oneshot::Sender<Result<(), String>>;
+
+/// Callers waiting for a queued job to finish, by job id
+static JOB_WAITERS: LazyLock<std::sync::Mutex<HashMap<String, JobOutcomeSender>>> =
+    LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+struct QueuedJob {
+    id: String,
+    input_path: String,
+    command: FFmpegCommandResult,
+}
+
+fn queue_settings_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(QUEUE_SETTINGS_FILE))
+}
+
+fn normalized_queue_settings(settings: ProcessingQueueSettings) -> ProcessingQueueSettings {
+    ProcessingQueueSettings {
+        max_concurrent_jobs: settings.max_concurrent_jobs.clamp(1, MAX_QUEUE_CONCURRENCY),
+        ..settings
+    }
+}
+
+/// Status a job left `pending` or `processing` by the last session gets on startup
+fn recovered_status(has_command: bool, resume_interrupted: bool) -> &'static str {
+    if has_command && resume_interrupted {
+        "queued"
+    } else {
+        "interrupted"
+    }
+}
+
+/// Mark jobs that were running when the app closed as interrupted, or queue the ones with a
+/// stored command again when `resume_interrupted` is set. Returns (interrupted, requeued).
+fn recover_processing_jobs(
+    conn: &Connection,
+    resume_interrupted: bool,
+) -> Result<(usize, usize), String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, command_json IS NOT NULL FROM processing_jobs
+             WHERE status IN ('pending', 'processing')",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let stale = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect::<Vec<_>>();
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let (mut interrupted, mut requeued) = (0, 0);
+    for (id, has_command) in stale {
+        if recovered_status(has_command, resume_interrupted) == "queued" {
+            conn.execute(
+                "UPDATE processing_jobs SET status = 'queued', progress = 0, error_message = NULL
+                 WHERE id = ?1",
+                params![id],
+            )
+            .map_err(|e| format!("Failed to update job: {}", e))?;
+            requeued += 1;
+        } else {
+            conn.execute(
+                "UPDATE processing_jobs SET status = 'interrupted', error_message = ?1,
+                 completed_at = ?2 WHERE id = ?3",
+                params![INTERRUPTED_MESSAGE, now, id],
+            )
+            .map_err(|e| format!("Failed to update job: {}", e))?;
+            interrupted += 1;
+        }
+    }
+    Ok((interrupted, requeued))
+}
+
+/// Take the next queued job (highest priority, then oldest) and mark it `processing`
+fn claim_next_queued_job(conn: &Connection) -> Result<Option<QueuedJob>, String> {
+    loop {
+        let next = conn.query_row(
+            "SELECT id, input_path, command_json FROM processing_jobs WHERE status = 'queued'
+             ORDER BY priority DESC, created_at ASC LIMIT 1",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            },
+        );
+        let (id, input_path, command_json) = match next {
+            Ok(next) => next,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+            Err(e) => return Err(format!("Failed to load queued job: {}", e)),
+        };
+
+        match command_json.and_then(|json| serde_json::from_str(&json).ok()) {
+            Some(command) => {
+                conn.execute(
+                    "UPDATE processing_jobs SET status = 'processing', progress = 0 WHERE id = ?1",
+                    params![id],
+                )
+                .map_err(|e| format!("Failed to update job: {}", e))?;
+                return Ok(Some(QueuedJob {
+                    id,
+                    input_path,
+                    command,
+                }));
+            }
+            None => {
+                conn.execute(
+                    "UPDATE processing_jobs SET status = 'failed', error_message = ?1,
+                     completed_at = ?2 WHERE id = ?3",
+                    params![
+                        "Queued job has no stored command",
+                        chrono::Utc::now().to_rfc3339(),
+                        id
+                    ],
+                )
+                .map_err(|e| format!("Failed to update job: {}", e))?;
+            }
+        }
+    }
+}
+
+fn load_processing_queue() -> Result<Vec<ProcessingJob>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM processing_jobs WHERE status IN ('queued', 'processing')
+             ORDER BY status = 'processing' DESC, priority DESC, created_at ASC",
+            PROCESSING_JOB_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let jobs = stmt
+        .query_map([], processing_job_from_row)
+        .map_err(|e| format!("Query failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(jobs)
+}
+
+fn emit_queue_changed(app: &AppHandle) {
+    if let Ok(jobs) = load_processing_queue() {
+        let _ = app.emit("processing-queue-changed", jobs);
+    }
+}
+
+/// Start queued jobs until the concurrency limit is reached
+fn dispatch_processing_queue(app: &AppHandle) {
+    let started = {
+        let Ok(mut queue) = QUEUE.lock() else {
+            return;
+        };
+        let conn = match get_db() {
+            Ok(conn) => conn,
+            Err(e) => {
+                log::warn!("[PROCESSING] {}", e);
+                return;
+            }
+        };
+        let mut started = Vec::new();
+        while queue.running.len() < queue.settings.max_concurrent_jobs {
+            match claim_next_queued_job(&conn) {
+                Ok(Some(job)) => {
+                    queue.running.insert(job.id.clone());
+                    started.push(job);
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    log::warn!("[PROCESSING] {}", e);
+                    break;
+                }
+            }
+        }
+        started
+    };
+    if started.is_empty() {
+        return;
+    }
+
+    emit_queue_changed(app);
+    for job in started {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            run_queued_job(app, job).await;
+        });
+    }
+}
+
+fn notify_job_waiter(id: &str, outcome: Result<(), String>) {
+    let waiter = JOB_WAITERS
+        .lock()
+        .ok()
+        .and_then(|mut waiters| waiters.remove(id));
+    if let Some(waiter) = waiter {
+        waiter.send(outcome).ok();
+    }
+}
+
+async fn run_queued_job(app: AppHandle, job: QueuedJob) {
+    let emitter = app.clone();
+    let on_progress: ProgressCallback = Arc::new(move |progress: &ProcessingProgress| {
+        let _ = emitter.emit("processing-progress", progress);
+    });
+    let outcome = run_processing_command(
+        &app,
+        job.id.clone(),
+        job.command,
+        job.input_path,
+        on_progress,
+    )
+    .await;
+
+    let (status, progress, error_message) = match &outcome {
+        Ok(()) => ("completed", 100.0, None),
+        Err(e) if e == "Processing cancelled" => ("cancelled", 0.0, Some(e.clone())),
+        Err(e) => ("failed", 0.0, Some(e.clone())),
+    };
+    update_processing_job(
+        app.clone(),
+        job.id.clone(),
+        status.to_string(),
+        progress,
+        error_message,
+    )
+    .await
+    .ok();
+
+    if let Ok(mut queue) = QUEUE.lock() {
+        queue.running.remove(&job.id);
+    }
+    notify_job_waiter(&job.id, outcome);
+    emit_queue_changed(&app);
+    dispatch_processing_queue(&app);
+}
+
+/// Cancel a job that is still waiting in the queue. Returns false when it is not queued.
+pub(super) fn cancel_queued_processing_job(app: &AppHandle, id: &str) -> Result<bool, String> {
+    let conn = get_db()?;
+    let updated = conn
+        .execute(
+            "UPDATE processing_jobs SET status = 'cancelled', error_message = ?1, completed_at = ?2
+             WHERE id = ?3 AND status = 'queued'",
+            params!["Processing cancelled", chrono::Utc::now().to_rfc3339(), id],
+        )
+        .map_err(|e| format!("Failed to update job: {}", e))?;
+    drop(conn);
+    if updated > 0 {
+        notify_job_waiter(id, Err("Processing cancelled".to_string()));
+        emit_queue_changed(app);
+    }
+    Ok(updated > 0)
+}
+
+/// Load the queue settings, recover jobs the last session left running, and start the
+/// queued jobs. Called once the database is ready.
+pub fn start_processing_queue(app: AppHandle) {
+    let settings = queue_settings_path(&app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str::<ProcessingQueueSettings>(&json).ok())
+        .map(normalized_queue_settings)
+        .unwrap_or_default();
+    let resume_interrupted = settings.resume_interrupted;
+    if let Ok(mut queue) = QUEUE.lock() {
+        queue.settings = settings;
+    }
+
+    let recovered = get_db().and_then(|conn| recover_processing_jobs(&conn, resume_interrupted));
+    match recovered {
+        Ok((0, 0)) => {}
+        Ok((interrupted, requeued)) => log::info!(
+            "[PROCESSING] Recovered jobs from the last session: {} interrupted, {} queued again",
+            interrupted,
+            requeued
+        ),
+        Err(e) => log::warn!("[PROCESSING] Failed to recover processing jobs: {}", e),
+    }
+    dispatch_processing_queue(&app);
+}
+
+/// Add a generated command to the processing queue. It runs once a slot is free and its
+/// progress is emitted as `processing-progress`; the queue itself is emitted as
+/// `processing-queue-changed` whenever a job starts or ends. Cancel with `cancel_ffmpeg`.
+#[tauri::command]
+pub async fn enqueue_processing_job(
+    app: AppHandle,
+    request: ProcessingQueueRequest,
+) -> Result<ProcessingJob, String> {
+    let id = match request.id {
+        Some(id)
+            if !id.trim().is_empty()
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            id
+        }
+        Some(_) => return Err("Invalid job id".to_string()),
+        None => uuid::Uuid::new_v4().to_string(),
+    };
+    validate_ffmpeg_args(&request.command.command_args)?;
+    let command_json = serde_json::to_string(&request.command)
+        .map_err(|e| format!("Failed to serialize command: {}", e))?;
+
+    {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO processing_jobs (id, input_path, output_path, task_type, user_prompt,
+                ffmpeg_command, status, progress, created_at, priority, command_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'queued', 0, ?7, ?8, ?9)",
+            params![
+                id,
+                request.input_path,
+                request.command.output_path,
+                request.task_type,
+                request.user_prompt,
+                request.command.command,
+                chrono::Utc::now().to_rfc3339(),
+                request.priority,
+                command_json
+            ],
+        )
+        .map_err(|e| format!("Failed to queue job: {}", e))?;
+    }
+
+    emit_queue_changed(&app);
+    dispatch_processing_queue(&app);
+
+    let conn = get_db()?;
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM processing_jobs WHERE id = ?1",
+            PROCESSING_JOB_COLUMNS
+        ),
+        params![id],
+        processing_job_from_row,
+    )
+    .map_err(|e| format!("Failed to load job: {}", e))
+}
+
+/// Queue `command` under the job saved as `id` (or as a new `custom` job) and wait until
+/// it has run, so commands started from the chat share the queue's concurrency limit and
+/// priorities and keep a stored command that `resume_interrupted` can run again
+pub(super) async fn run_through_queue(
+    app: &AppHandle,
+    id: String,
+    input_path: String,
+    command: FFmpegCommandResult,
+) -> Result<(), String> {
+    validate_ffmpeg_args(&command.command_args)?;
+    let command_json = serde_json::to_string(&command)
+        .map_err(|e| format!("Failed to serialize command: {}", e))?;
+
+    let (outcome_tx, outcome_rx) = tokio::sync::oneshot::channel();
+    {
+        let mut waiters = JOB_WAITERS
+            .lock()
+            .map_err(|_| "Failed to register job".to_string())?;
+        if waiters.contains_key(&id) {
+            return Err("A job with this id is already running".to_string());
+        }
+        waiters.insert(id.clone(), outcome_tx);
+    }
+
+    let queued = get_db().and_then(|conn| {
+        let updated = conn
+            .execute(
+                "UPDATE processing_jobs SET status = 'queued', progress = 0, error_message = NULL,
+                    completed_at = NULL, output_path = ?1, command_json = ?2
+                 WHERE id = ?3 AND status NOT IN ('queued', 'processing')",
+                params![command.output_path, command_json, id],
+            )
+            .map_err(|e| format!("Failed to queue job: {}", e))?;
+        if updated > 0 {
+            return Ok(());
+        }
+        conn.execute(
+            "INSERT INTO processing_jobs (id, input_path, output_path, task_type,
+                ffmpeg_command, status, progress, created_at, command_json)
+             VALUES (?1, ?2, ?3, 'custom', ?4, 'queued', 0, ?5, ?6)",
+            params![
+                id,
+                input_path,
+                command.output_path,
+                command.command,
+                chrono::Utc::now().to_rfc3339(),
+                command_json
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| format!("Failed to queue job: {}", e))
+    });
+    if let Err(e) = queued {
+        if let Ok(mut waiters) = JOB_WAITERS.lock() {
+            waiters.remove(&id);
+        }
+        return Err(e);
+    }
+
+    emit_queue_changed(app);
+    dispatch_processing_queue(app);
+    outcome_rx
+        .await
+        .unwrap_or_else(|_| Err("Processing job ended without a result".to_string()))
+}
+
+/// Running jobs first, then waiting jobs in the order they will start
+#[tauri::command]
+pub async fn get_processing_queue(_app: AppHandle) -> Result<Vec<ProcessingJob>, String> {
+    load_processing_queue()
+}
+
+#[tauri::command]
+pub async fn set_processing_job_priority(
+    app: AppHandle,
+    id: String,
+    priority: i32,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    let updated = conn
+        .execute(
+            "UPDATE processing_jobs SET priority = ?1 WHERE id = ?2 AND status = 'queued'",
+            params![priority, id],
+        )
+        .map_err(|e| format!("Failed to update job: {}", e))?;
+    if updated == 0 {
+        return Err("Only waiting jobs can change priority".to_string());
+    }
+    drop(conn);
+    emit_queue_changed(&app);
+    Ok(())
+}
+
+/// Queue an interrupted, failed or cancelled job again with its stored command
+#[tauri::command]
+pub async fn retry_processing_job(app: AppHandle, id: String) -> Result<(), String> {
+    let conn = get_db()?;
+    let updated = conn
+        .execute(
+            "UPDATE processing_jobs SET status = 'queued', progress = 0, error_message = NULL,
+                completed_at = NULL
+             WHERE id = ?1 AND command_json IS NOT NULL
+                AND status IN ('interrupted', 'failed', 'cancelled')",
+            params![id],
+        )
+        .map_err(|e| format!("Failed to update job: {}", e))?;
+    if updated == 0 {
+        return Err("This job cannot be run again".to_string());
+    }
+    drop(conn);
+    emit_queue_changed(&app);
+    dispatch_processing_queue(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_processing_queue_settings(
+    _app: AppHandle,
+) -> Result<ProcessingQueueSettings, String> {
+    QUEUE
+        .lock()
+        .map(|queue| queue.settings.clone())
+        .map_err(|_| "Failed to read queue settings".to_string())
+}
+
+#[tauri::command]
+pub async fn set_processing_queue_settings(
+    app: AppHandle,
+    settings: ProcessingQueueSettings,
+) -> Result<ProcessingQueueSettings, String> {
+    let settings = normalized_queue_settings(settings);
+    if let Some(path) = queue_settings_path(&app) {
+        let json = serde_json::to_string(&settings)
+            .map_err(|e| format!("Failed to serialize queue settings: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to save queue settings: {}", e))?;
+    }
+    if let Ok(mut queue) = QUEUE.lock() {
+        queue.settings = settings.clone();
+    }
+    dispatch_processing_queue(&app);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_settings_default_and_clamp_concurrency() {
+        let settings: ProcessingQueueSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings.max_concurrent_jobs, 1);
+        assert!(!settings.resume_interrupted);
+
+        let clamp = |max_concurrent_jobs| {
+            normalized_queue_settings(ProcessingQueueSettings {
+                max_concurrent_jobs,
+                resume_interrupted: true,
+            })
+            .max_concurrent_jobs
+        };
+        assert_eq!(clamp(0), 1);
+        assert_eq!(clamp(3), 3);
+        assert_eq!(clamp(64), MAX_QUEUE_CONCURRENCY);
+    }
+
+    fn test_queue_db() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        crate::database::create_processing_jobs_table(&conn).expect("create processing_jobs");
+        conn
+    }
+
+    fn insert_job(
+        conn: &Connection,
+        id: &str,
+        status: &str,
+        priority: i32,
+        created_at: &str,
+        with_command: bool,
+    ) {
+        let command = FFmpegCommandResult {
+            command: format!("ffmpeg -i in.mp4 {}.mp4", id),
+            command_args: vec!["-i".into(), "in.mp4".into(), format!("{}.mp4", id)],
+            explanation: String::new(),
+            estimated_size_mb: 0.0,
+            estimated_time_seconds: 0.0,
+            output_path: format!("{}.mp4", id),
+            warnings: Vec::new(),
+            target_size: None,
+            audio_analysis: None,
+        };
+        let command_json = with_command.then(|| serde_json::to_string(&command).unwrap());
+        conn.execute(
+            "INSERT INTO processing_jobs (id, input_path, task_type, ffmpeg_command, status,
+             priority, command_json, created_at) VALUES (?1, 'in.mp4', 'custom', ?2, ?3, ?4, ?5, ?6)",
+            params![id, command.command, status, priority, command_json, created_at],
+        )
+        .expect("insert job");
+    }
+
+    fn job_status(conn: &Connection, id: &str) -> (String, Option<String>) {
+        conn.query_row(
+            "SELECT status, error_message FROM processing_jobs WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .expect("job status")
+    }
+
+    #[test]
+    fn recover_marks_running_jobs_interrupted_unless_resuming() {
+        let conn = test_queue_db();
+        insert_job(
+            &conn,
+            "running",
+            "processing",
+            0,
+            "2026-01-01T00:00:00Z",
+            true,
+        );
+        insert_job(&conn, "legacy", "pending", 0, "2026-01-01T00:00:01Z", false);
+        insert_job(&conn, "done", "completed", 0, "2026-01-01T00:00:02Z", true);
+
+        assert_eq!(recover_processing_jobs(&conn, false).unwrap(), (2, 0));
+        let (status, message) = job_status(&conn, "running");
+        assert_eq!(status, "interrupted");
+        assert_eq!(message.as_deref(), Some(INTERRUPTED_MESSAGE));
+        assert_eq!(job_status(&conn, "legacy").0, "interrupted");
+        assert_eq!(job_status(&conn, "done").0, "completed");
+    }
+
+    #[test]
+    fn recover_requeues_jobs_with_a_stored_command_when_resuming() {
+        let conn = test_queue_db();
+        insert_job(
+            &conn,
+            "running",
+            "processing",
+            0,
+            "2026-01-01T00:00:00Z",
+            true,
+        );
+        insert_job(
+            &conn,
+            "legacy",
+            "processing",
+            0,
+            "2026-01-01T00:00:01Z",
+            false,
+        );
+
+        assert_eq!(recover_processing_jobs(&conn, true).unwrap(), (1, 1));
+        assert_eq!(job_status(&conn, "running"), ("queued".to_string(), None));
+        assert_eq!(job_status(&conn, "legacy").0, "interrupted");
+    }
+
+    #[test]
+    fn claim_takes_highest_priority_then_oldest() {
+        let conn = test_queue_db();
+        insert_job(&conn, "old-low", "queued", 0, "2026-01-01T00:00:00Z", true);
+        insert_job(&conn, "new-high", "queued", 5, "2026-01-01T00:00:03Z", true);
+        insert_job(&conn, "old-high", "queued", 5, "2026-01-01T00:00:01Z", true);
+        insert_job(
+            &conn,
+            "running",
+            "processing",
+            9,
+            "2026-01-01T00:00:00Z",
+            true,
+        );
+
+        let claimed = std::iter::from_fn(|| claim_next_queued_job(&conn).unwrap())
+            .map(|job| job.id)
+            .collect::<Vec<_>>();
+        assert_eq!(claimed, vec!["old-high", "new-high", "old-low"]);
+        assert_eq!(job_status(&conn, "old-low").0, "processing");
+    }
+
+    #[test]
+    fn claim_fails_queued_jobs_without_a_command() {
+        let conn = test_queue_db();
+        insert_job(&conn, "broken", "queued", 9, "2026-01-01T00:00:00Z", false);
+        insert_job(&conn, "ok", "queued", 0, "2026-01-01T00:00:01Z", true);
+
+        let job = claim_next_queued_job(&conn).unwrap().expect("claimed job");
+        assert_eq!(job.id, "ok");
+        assert_eq!(job.command.output_path, "ok.mp4");
+        assert_eq!(job_status(&conn, "broken").0, "failed");
+        assert!(claim_next_queued_job(&conn).unwrap().is_none());
+    }
+
+    #[test]
+    fn only_jobs_with_a_stored_command_resume_after_restart() {
+        assert_eq!(recovered_status(true, true), "queued");
+        assert_eq!(recovered_status(true, false), "interrupted");
+        assert_eq!(recovered_status(false, true), "interrupted");
+        assert_eq!(recovered_status(false, false), "interrupted");
+    }
+}
@@ -0,0 +1,439 @@
+use super::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const DEFAULT_TARGET_AUDIO_KBPS: u32 = 128;
+const MIN_TARGET_AUDIO_KBPS: u32 = 32;
+const MIN_TARGET_VIDEO_KBPS: u32 = 64;
+/// Share of the size budget left for container overhead and rate control error
+const TARGET_SIZE_OVERHEAD: f64 = 0.04;
+/// Second passes to try before giving up on an output that stays too large
+const MAX_TARGET_SIZE_ATTEMPTS: usize = 3;
+/// Used until the capability probe picks the encoder this FFmpeg build has
+const DEFAULT_TARGET_SIZE_ENCODER: &str = "libx264";
+/// Encoders that accept `-pass`; the rest hit the size with retried single-pass encodes
+const TWO_PASS_ENCODERS: &[&str] = &["libx264", "libvpx-vp9", "libvpx", "libaom-av1", "mpeg4"];
+
+/// Two-pass encode whose output has to fit in `target_size_mb`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetSizeEncode {
+    pub target_size_mb: f64,
+    pub video_bitrate_kbps: u32,
+    /// 0 when the output has no audio
+    pub audio_bitrate_kbps: u32,
+    #[serde(default = "default_target_size_encoder")]
+    pub video_encoder: String,
+}
+
+fn default_target_size_encoder() -> String {
+    DEFAULT_TARGET_SIZE_ENCODER.to_string()
+}
+
+fn supports_two_pass(encoder: &str) -> bool {
+    TWO_PASS_ENCODERS.contains(&encoder)
+}
+
+/// Split the size budget between video and audio. Small budgets lower the audio bitrate
+/// before the picture gets unwatchable.
+pub(super) fn plan_target_size_encode(
+    target_size_mb: f64,
+    duration: f64,
+    has_audio: bool,
+    audio_bitrate_kbps: Option<u32>,
+) -> Result<TargetSizeEncode, String> {
+    if !target_size_mb.is_finite() || target_size_mb <= 0.0 {
+        return Err("Target size must be greater than 0 MB".to_string());
+    }
+    if !duration.is_finite() || duration <= 0.0 {
+        return Err("Target size needs a video with a known duration".to_string());
+    }
+
+    let total_kbps = target_size_mb * 8_000.0 / duration * (1.0 - TARGET_SIZE_OVERHEAD);
+    let audio_kbps = if has_audio {
+        audio_bitrate_kbps
+            .unwrap_or(DEFAULT_TARGET_AUDIO_KBPS)
+            .min((total_kbps / 4.0) as u32)
+            .max(MIN_TARGET_AUDIO_KBPS)
+    } else {
+        0
+    };
+    let video_kbps = total_kbps - audio_kbps as f64;
+    if video_kbps < MIN_TARGET_VIDEO_KBPS as f64 {
+        return Err(format!(
+            "{} MB is too small for a video of {}",
+            target_size_mb,
+            format_time(duration)
+        ));
+    }
+
+    Ok(TargetSizeEncode {
+        target_size_mb,
+        video_bitrate_kbps: video_kbps as u32,
+        audio_bitrate_kbps: audio_kbps,
+        video_encoder: default_target_size_encoder(),
+    })
+}
+
+/// Lower video bitrate for another second pass after the output came out
+/// `actual_bytes` big, or `None` when it would drop below the minimum
+fn retry_video_bitrate_kbps(encode: &TargetSizeEncode, actual_bytes: u64) -> Option<u32> {
+    let target_bytes = encode.target_size_mb * 1_000_000.0;
+    let scaled = encode.video_bitrate_kbps as f64
+        * (target_bytes / actual_bytes as f64)
+        * (1.0 - TARGET_SIZE_OVERHEAD);
+    // Always step down noticeably, even when the overshoot was tiny
+    let video_kbps = scaled.min(encode.video_bitrate_kbps as f64 * 0.95);
+    (video_kbps >= MIN_TARGET_VIDEO_KBPS as f64).then_some(video_kbps as u32)
+}
+
+fn target_size_pass_args(
+    input_path: &str,
+    output_path: &str,
+    passlog: &str,
+    pass: u8,
+    encode: &TargetSizeEncode,
+) -> Vec<String> {
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        input_path.to_string(),
+        "-c:v".to_string(),
+        encode.video_encoder.clone(),
+    ];
+    if encode.video_encoder == "libx264" {
+        args.extend(["-preset", "medium"].map(str::to_string));
+    }
+    args.extend([
+        "-b:v".to_string(),
+        format!("{}k", encode.video_bitrate_kbps),
+    ]);
+    if supports_two_pass(&encode.video_encoder) {
+        args.extend([
+            "-pass".to_string(),
+            pass.to_string(),
+            "-passlogfile".to_string(),
+            passlog.to_string(),
+        ]);
+    }
+    if pass == 1 {
+        args.extend(["-an", "-f", "null", "-"].map(str::to_string));
+        return args;
+    }
+    if encode.audio_bitrate_kbps > 0 {
+        args.extend([
+            "-c:a".to_string(),
+            "aac".to_string(),
+            "-b:a".to_string(),
+            format!("{}k", encode.audio_bitrate_kbps),
+        ]);
+    } else {
+        args.push("-an".to_string());
+    }
+    args.extend([
+        "-movflags".to_string(),
+        "+faststart".to_string(),
+        output_path.to_string(),
+    ]);
+    args
+}
+
+fn passlog_path(job_id: &str) -> String {
+    let name = job_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    std::env::temp_dir()
+        .join(format!("youwee-2pass-{}", name))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Both passes for display, and the second pass as the command to run
+fn target_size_command_args(
+    input_path: &str,
+    output_path: &str,
+    encode: &TargetSizeEncode,
+) -> (String, Vec<String>) {
+    // The passlog is chosen per job when the encode runs; this one is for display
+    let passlog = passlog_path("job");
+    let second_pass = target_size_pass_args(input_path, output_path, &passlog, 2, encode);
+    if !supports_two_pass(&encode.video_encoder) {
+        return (args_to_display_command(&second_pass), second_pass);
+    }
+    let first_pass = target_size_pass_args(input_path, output_path, &passlog, 1, encode);
+    (
+        format!(
+            "{}\n{}",
+            args_to_display_command(&first_pass),
+            args_to_display_command(&second_pass)
+        ),
+        second_pass,
+    )
+}
+
+/// Encode a target-size command with `video_encoder`, the H.264 encoder the capability
+/// probe found in this FFmpeg build. Returns a warning when it cannot do two passes.
+pub(super) fn set_target_size_encoder(
+    result: &mut FFmpegCommandResult,
+    video_encoder: &str,
+) -> Option<String> {
+    let input_path = result
+        .command_args
+        .windows(2)
+        .find(|pair| pair[0] == "-i")
+        .map(|pair| pair[1].clone())?;
+    let encode = result.target_size.as_mut()?;
+    encode.video_encoder = video_encoder.to_string();
+    let (command, command_args) =
+        target_size_command_args(&input_path, &result.output_path, encode);
+    result.command = command;
+    result.command_args = command_args;
+    (!supports_two_pass(video_encoder)).then(|| {
+        format!(
+            "{} has no two-pass mode, so the size target is reached with single-pass encodes",
+            video_encoder
+        )
+    })
+}
+
+/// `compress` with a `target_size_mb` option: a two-pass encode sized from the duration
+pub(super) fn build_target_size_command(
+    input_path: &str,
+    options: &HashMap<String, serde_json::Value>,
+    metadata: &VideoMetadata,
+    output_dir: Option<&str>,
+) -> Option<Result<FFmpegCommandResult, String>> {
+    let target_size_mb = options.get("target_size_mb").and_then(|v| v.as_f64())?;
+    let audio_bitrate_kbps = options
+        .get("audio_bitrate_kbps")
+        .and_then(|v| v.as_u64())
+        .map(|kbps| kbps.min(u32::MAX as u64) as u32);
+    let encode = match plan_target_size_encode(
+        target_size_mb,
+        metadata.duration,
+        metadata.has_audio,
+        audio_bitrate_kbps,
+    ) {
+        Ok(encode) => encode,
+        Err(e) => return Some(Err(e)),
+    };
+
+    let input_stem = Path::new(input_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or("output".to_string());
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let output = resolve_output_dir(input_path, output_dir).join(format!(
+        "{}_{}mb_{}.mp4",
+        input_stem, target_size_mb, timestamp
+    ));
+    let output_path = output.to_string_lossy().to_string();
+    let (command, command_args) = target_size_command_args(input_path, &output_path, &encode);
+
+    Some(Ok(FFmpegCommandResult {
+        command,
+        command_args,
+        explanation: format!(
+            "Two-pass encode to fit in {} MB (video {} kbps, audio {} kbps)",
+            target_size_mb, encode.video_bitrate_kbps, encode.audio_bitrate_kbps
+        ),
+        estimated_size_mb: target_size_mb,
+        estimated_time_seconds: metadata.duration / 5.0,
+        output_path,
+        warnings: Vec::new(),
+        target_size: Some(encode),
+        audio_analysis: None,
+    }))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_target_size_passes(
+    app: &AppHandle,
+    job_id: &str,
+    input_path: &str,
+    output_path: &str,
+    passlog: &str,
+    mut encode: TargetSizeEncode,
+    cancelled: &AtomicBool,
+    on_progress: &ProgressCallback,
+) -> Result<(), String> {
+    let two_pass = supports_two_pass(&encode.video_encoder);
+    if two_pass {
+        run_ffmpeg_job(
+            app,
+            format!("{}:pass1", job_id),
+            target_size_pass_args(input_path, output_path, passlog, 1, &encode),
+            input_path.to_string(),
+            output_path.to_string(),
+            scaled_progress(job_id, on_progress, 0.0, 50.0),
+        )
+        .await?;
+    }
+    let (offset, share) = if two_pass { (50.0, 50.0) } else { (0.0, 100.0) };
+
+    let target_bytes = (encode.target_size_mb * 1_000_000.0) as u64;
+    for attempt in 1..=MAX_TARGET_SIZE_ATTEMPTS {
+        if cancelled.load(Ordering::SeqCst) {
+            return Err("Processing cancelled".to_string());
+        }
+        append_job_log(
+            job_id,
+            &format!(
+                "Target size pass 2, attempt {}: {} kbps video",
+                attempt, encode.video_bitrate_kbps
+            ),
+        );
+        run_ffmpeg_job(
+            app,
+            format!("{}:pass2-{}", job_id, attempt),
+            target_size_pass_args(input_path, output_path, passlog, 2, &encode),
+            input_path.to_string(),
+            output_path.to_string(),
+            scaled_progress(job_id, on_progress, offset, share),
+        )
+        .await?;
+
+        let actual_bytes = tokio::fs::metadata(output_path)
+            .await
+            .map_err(|e| format!("Failed to read output size: {}", e))?
+            .len();
+        if actual_bytes <= target_bytes {
+            return Ok(());
+        }
+        append_job_log(
+            job_id,
+            &format!(
+                "Output is {} bytes, over the {} byte target",
+                actual_bytes, target_bytes
+            ),
+        );
+        let too_large = format!(
+            "Output is {:.2} MB, larger than the {} MB target",
+            actual_bytes as f64 / 1_000_000.0,
+            encode.target_size_mb
+        );
+        match retry_video_bitrate_kbps(&encode, actual_bytes) {
+            Some(video_bitrate_kbps) if attempt < MAX_TARGET_SIZE_ATTEMPTS => {
+                encode.video_bitrate_kbps = video_bitrate_kbps;
+            }
+            _ => return Err(too_large),
+        }
+    }
+    Ok(())
+}
+
+/// Run a target-size encode: both passes, then the second pass again at a lower bitrate
+/// while the output is larger than the target. `cancel_ffmpeg(job_id)` stops it, and a
+/// file that cannot be made small enough is removed.
+pub(super) async fn run_target_size_encode(
+    app: &AppHandle,
+    job_id: String,
+    input_path: String,
+    output_path: String,
+    encode: TargetSizeEncode,
+    on_progress: ProgressCallback,
+) -> Result<(), String> {
+    let passlog = passlog_path(&job_id);
+    let cancelled = AtomicBool::new(false);
+    let passes = run_target_size_passes(
+        app,
+        &job_id,
+        &input_path,
+        &output_path,
+        &passlog,
+        encode,
+        &cancelled,
+        &on_progress,
+    );
+    let outcome = run_parent_job(&job_id, &cancelled, passes).await?;
+
+    // x264 writes `<passlog>-0.log` and its macroblock tree next to it
+    for suffix in [
+        "-0.log",
+        "-0.log.mbtree",
+        "-0.log.temp",
+        "-0.log.mbtree.temp",
+    ] {
+        tokio::fs::remove_file(format!("{}{}", passlog, suffix))
+            .await
+            .ok();
+    }
+    if outcome.is_err() {
+        tokio::fs::remove_file(&output_path).await.ok();
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_size_splits_budget_between_video_and_audio() {
+        // 8 MB over 60 s is ~1067 kbps before overhead
+        let encode = plan_target_size_encode(8.0, 60.0, true, None).unwrap();
+        assert_eq!(encode.audio_bitrate_kbps, 128);
+        assert_eq!(encode.video_bitrate_kbps, 896);
+
+        let silent = plan_target_size_encode(8.0, 60.0, false, None).unwrap();
+        assert_eq!(silent.audio_bitrate_kbps, 0);
+        assert_eq!(silent.video_bitrate_kbps, 1024);
+
+        // A tight budget takes bits from the audio first
+        let tight = plan_target_size_encode(8.0, 600.0, true, Some(192)).unwrap();
+        assert_eq!(tight.audio_bitrate_kbps, 32);
+        assert_eq!(tight.video_bitrate_kbps, 70);
+
+        assert!(plan_target_size_encode(1.0, 3600.0, true, None).is_err());
+        assert!(plan_target_size_encode(0.0, 60.0, true, None).is_err());
+        assert!(plan_target_size_encode(8.0, 0.0, true, None).is_err());
+    }
+
+    #[test]
+    fn retries_step_the_video_bitrate_down() {
+        let encode = TargetSizeEncode {
+            target_size_mb: 8.0,
+            video_bitrate_kbps: 1000,
+            audio_bitrate_kbps: 128,
+            video_encoder: default_target_size_encoder(),
+        };
+        assert_eq!(retry_video_bitrate_kbps(&encode, 10_000_000), Some(768));
+        // A tiny overshoot still lowers the bitrate by at least 5%
+        assert_eq!(retry_video_bitrate_kbps(&encode, 8_000_001), Some(950));
+
+        let floor = TargetSizeEncode {
+            video_bitrate_kbps: 66,
+            ..encode
+        };
+        assert_eq!(retry_video_bitrate_kbps(&floor, 9_000_000), None);
+    }
+
+    #[test]
+    fn passes_share_the_passlog_and_only_pass_two_writes_output() {
+        let encode = plan_target_size_encode(25.0, 120.0, true, None).unwrap();
+        let first = target_size_pass_args("in.mp4", "out.mp4", "/tmp/log", 1, &encode);
+        let second = target_size_pass_args("in.mp4", "out.mp4", "/tmp/log", 2, &encode);
+
+        assert_eq!(&first[first.len() - 4..], &["-an", "-f", "null", "-"]);
+        assert!(!first.contains(&"out.mp4".to_string()));
+        assert_eq!(second.last().map(String::as_str), Some("out.mp4"));
+        assert!(second.contains(&format!("{}k", encode.audio_bitrate_kbps)));
+        for args in [&first, &second] {
+            assert!(args
+                .windows(2)
+                .any(|pair| pair == ["-passlogfile", "/tmp/log"]));
+            assert!(validate_ffmpeg_args(args).is_ok());
+        }
+    }
+
+    #[test]
+    fn passes_use_the_probed_encoder() {
+        let mut encode = plan_target_size_encode(25.0, 120.0, true, None).unwrap();
+        encode.video_encoder = "h264_videotoolbox".to_string();
+        let second = target_size_pass_args("in.mp4", "out.mp4", "/tmp/log", 2, &encode);
+        assert!(second
+            .windows(2)
+            .any(|pair| pair == ["-c:v", "h264_videotoolbox"]));
+        assert!(!second.contains(&"-pass".to_string()));
+        assert!(!second.contains(&"-preset".to_string()));
+    }
+}
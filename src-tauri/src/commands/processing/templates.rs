@@ -0,0 +1,409 @@
+use super::*;
+
+/// `generate_quick_action_command` task type prefix that runs a saved template preset
+pub const TEMPLATE_PRESET_TASK_PREFIX: &str = "preset:";
+
+const PRESET_FILE_FORMAT: &str = "youwee-processing-preset";
+const PRESET_FILE_VERSION: u32 = 1;
+
+/// Placeholders filled from the input file and timeline selection
+const BUILTIN_PLACEHOLDERS: &[&str] = &[
+    "input", "output", "start", "end", "duration", "bitrate", "width", "height", "fps",
+];
+const TIMELINE_PLACEHOLDERS: &[&str] = &["start", "end", "duration"];
+
+static PLACEHOLDER_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"\{([a-z_][a-z0-9_]*)\}").unwrap());
+
+/// A preset shared as a file, see `export_processing_preset`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingPresetFile {
+    pub format: String,
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub task_type: String,
+    #[serde(default)]
+    pub prompt_template: String,
+    #[serde(default)]
+    pub args_template: Option<Vec<String>>,
+    #[serde(default)]
+    pub template_params: BTreeMap<String, String>,
+    #[serde(default)]
+    pub output_extension: Option<String>,
+}
+
+fn template_placeholders(args_template: &[String]) -> Vec<String> {
+    let mut names = Vec::new();
+    for arg in args_template {
+        for capture in PLACEHOLDER_RE.captures_iter(arg) {
+            let name = capture[1].to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Replace every `{name}` in the template with its value
+fn render_args_template(
+    args_template: &[String],
+    values: &HashMap<String, String>,
+) -> Result<Vec<String>, String> {
+    args_template
+        .iter()
+        .map(|arg| {
+            let mut missing = None;
+            let rendered = PLACEHOLDER_RE.replace_all(arg, |capture: &regex::Captures| {
+                values.get(&capture[1]).cloned().unwrap_or_else(|| {
+                    missing.get_or_insert_with(|| capture[1].to_string());
+                    String::new()
+                })
+            });
+            match missing {
+                Some(name) => Err(format!("No value for placeholder {{{}}}", name)),
+                None => Ok(rendered.into_owned()),
+            }
+        })
+        .collect()
+}
+
+/// Parameter values may fill a whole argument, so they must not look like an option
+fn validate_template_value(name: &str, value: &str) -> Result<(), String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(format!("Preset parameter {} has no value", name));
+    }
+    if value.starts_with('-') && value.parse::<f64>().is_err() {
+        return Err(format!(
+            "Preset parameter {} cannot start with '-': {}",
+            name, value
+        ));
+    }
+    Ok(())
+}
+
+fn validate_output_extension(extension: &str) -> Result<(), String> {
+    if extension.is_empty()
+        || extension.len() > 8
+        || !extension.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return Err(format!("Invalid output extension: {}", extension));
+    }
+    Ok(())
+}
+
+/// Check a template once when it is saved or imported: `{input}` must be used, the last
+/// argument must be `{output}`, every placeholder must be known, and the rendered
+/// arguments must pass `validate_ffmpeg_args`.
+pub(super) fn validate_args_template(
+    args_template: &[String],
+    template_params: &BTreeMap<String, String>,
+    output_extension: &str,
+) -> Result<(), String> {
+    if args_template.is_empty() {
+        return Err("FFmpeg template cannot be empty".to_string());
+    }
+    if args_template.last().map(String::as_str) != Some("{output}") {
+        return Err("The last template argument must be {output}".to_string());
+    }
+    validate_output_extension(output_extension)?;
+
+    for (name, value) in template_params {
+        if !PLACEHOLDER_RE.is_match(&format!("{{{}}}", name)) {
+            return Err(format!("Invalid preset parameter name: {}", name));
+        }
+        if BUILTIN_PLACEHOLDERS.contains(&name.as_str()) {
+            return Err(format!(
+                "Preset parameter {} is filled in automatically",
+                name
+            ));
+        }
+        validate_template_value(name, value)?;
+    }
+
+    let placeholders = template_placeholders(args_template);
+    if !placeholders.iter().any(|name| name == "input") {
+        return Err("FFmpeg template must use {input}".to_string());
+    }
+    let mut sample = HashMap::new();
+    for name in &placeholders {
+        let value = match name.as_str() {
+            "input" => "input.mp4".to_string(),
+            "output" => format!("output.{}", output_extension),
+            name if BUILTIN_PLACEHOLDERS.contains(&name) => "1".to_string(),
+            name => template_params
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("Unknown placeholder {{{}}} in FFmpeg template", name))?,
+        };
+        sample.insert(name.clone(), value);
+    }
+    validate_ffmpeg_args(&render_args_template(args_template, &sample)?)
+}
+
+fn option_value_to_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(value) => Some(value.clone()),
+        serde_json::Value::Number(value) => Some(value.to_string()),
+        serde_json::Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn preset_file_slug(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    let slug = slug.trim_matches('_');
+    if slug.is_empty() {
+        "preset".to_string()
+    } else {
+        slug.chars().take(32).collect()
+    }
+}
+
+/// Command for a template preset. `options` override the preset's parameter defaults.
+pub(super) fn build_template_preset_command(
+    preset: &ProcessingPreset,
+    input_path: &str,
+    options: &HashMap<String, serde_json::Value>,
+    timeline_start: Option<f64>,
+    timeline_end: Option<f64>,
+    metadata: &VideoMetadata,
+    output_dir: Option<&str>,
+) -> Result<FFmpegCommandResult, String> {
+    let args_template = preset
+        .args_template
+        .as_deref()
+        .ok_or("This preset has no FFmpeg template")?;
+    let extension = preset.output_extension.as_deref().unwrap_or("mp4");
+    let placeholders = template_placeholders(args_template);
+
+    let mut values: HashMap<String, String> = preset
+        .template_params
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    for (name, value) in options {
+        if !preset.template_params.contains_key(name) {
+            continue;
+        }
+        let value = option_value_to_string(value)
+            .ok_or_else(|| format!("Invalid value for preset parameter {}", name))?;
+        validate_template_value(name, &value)?;
+        values.insert(name.clone(), value);
+    }
+
+    if placeholders
+        .iter()
+        .any(|name| TIMELINE_PLACEHOLDERS.contains(&name.as_str()))
+    {
+        let (Some(start), Some(end)) = (timeline_start, timeline_end) else {
+            return Err("This preset needs a timeline selection".to_string());
+        };
+        values.insert("start".to_string(), format_time(start));
+        values.insert("end".to_string(), format_time(end));
+        values.insert("duration".to_string(), (end - start).to_string());
+    }
+
+    let input_stem = Path::new(input_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or("output".to_string());
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let output = resolve_output_dir(input_path, output_dir).join(format!(
+        "{}_{}_{}.{}",
+        input_stem,
+        preset_file_slug(&preset.name),
+        timestamp,
+        extension
+    ));
+    let output_path = output.to_string_lossy().to_string();
+
+    values.insert("input".to_string(), input_path.to_string());
+    values.insert("output".to_string(), output_path.clone());
+    values.insert("bitrate".to_string(), metadata.bitrate.to_string());
+    values.insert("width".to_string(), metadata.width.to_string());
+    values.insert("height".to_string(), metadata.height.to_string());
+    values.insert("fps".to_string(), metadata.fps.to_string());
+
+    let command_args = render_args_template(args_template, &values)?;
+    validate_ffmpeg_args(&command_args)?;
+
+    Ok(FFmpegCommandResult {
+        command: args_to_display_command(&command_args),
+        command_args,
+        explanation: preset
+            .description
+            .clone()
+            .filter(|description| !description.trim().is_empty())
+            .unwrap_or_else(|| format!("Apply preset {}", preset.name)),
+        estimated_size_mb: metadata.file_size as f64 / 1_000_000.0,
+        estimated_time_seconds: metadata.duration / 10.0,
+        output_path,
+        warnings: Vec::new(),
+        target_size: None,
+        audio_analysis: None,
+    })
+}
+
+/// Write a preset to a JSON file that `import_processing_preset` can read
+#[tauri::command]
+pub async fn export_processing_preset(id: String, path: String) -> Result<(), String> {
+    let preset = load_processing_preset(&id)?;
+    let file = ProcessingPresetFile {
+        format: PRESET_FILE_FORMAT.to_string(),
+        version: PRESET_FILE_VERSION,
+        name: preset.name,
+        description: preset.description,
+        task_type: preset.task_type,
+        prompt_template: preset.prompt_template,
+        args_template: preset.args_template,
+        template_params: preset.template_params,
+        output_extension: preset.output_extension,
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize preset: {}", e))?;
+    tokio::fs::write(&path, json)
+        .await
+        .map_err(|e| format!("Failed to write preset file: {}", e))
+}
+
+/// Add a preset from a file written by `export_processing_preset`
+#[tauri::command]
+pub async fn import_processing_preset(path: String) -> Result<ProcessingPreset, String> {
+    let content = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read preset file: {}", e))?;
+    let file: ProcessingPresetFile =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid preset file: {}", e))?;
+    if file.format != PRESET_FILE_FORMAT {
+        return Err("This file is not a processing preset".to_string());
+    }
+    if file.version > PRESET_FILE_VERSION {
+        return Err(format!(
+            "Preset file version {} is newer than this app supports",
+            file.version
+        ));
+    }
+
+    insert_processing_preset(
+        file.name,
+        file.description,
+        file.task_type,
+        file.prompt_template,
+        file.args_template,
+        file.template_params,
+        file.output_extension,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn preset(template: &[&str], params: &[(&str, &str)]) -> ProcessingPreset {
+        ProcessingPreset {
+            id: "p1".to_string(),
+            name: "Web 720p".to_string(),
+            description: None,
+            task_type: "resize".to_string(),
+            prompt_template: String::new(),
+            icon: None,
+            created_at: String::new(),
+            args_template: Some(args(template)),
+            template_params: params
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            output_extension: Some("mp4".to_string()),
+        }
+    }
+
+    #[test]
+    fn templates_are_validated_before_saving() {
+        let params = BTreeMap::from([("crf".to_string(), "23".to_string())]);
+        let valid = args(&["-y", "-i", "{input}", "-crf", "{crf}", "{output}"]);
+        assert!(validate_args_template(&valid, &params, "mp4").is_ok());
+
+        let no_output_last = args(&["-i", "{input}", "{output}", "-y"]);
+        assert!(validate_args_template(&no_output_last, &params, "mp4").is_err());
+
+        let unknown = args(&["-i", "{input}", "-b:v", "{rate}", "{output}"]);
+        assert!(validate_args_template(&unknown, &params, "mp4")
+            .unwrap_err()
+            .contains("{rate}"));
+
+        let blocked = args(&["-i", "{input}", "-i", "tcp://example.com", "{output}"]);
+        assert!(validate_args_template(&blocked, &params, "mp4").is_err());
+
+        let option_value = BTreeMap::from([("crf".to_string(), "-f".to_string())]);
+        assert!(validate_args_template(&valid, &option_value, "mp4").is_err());
+        assert!(validate_args_template(&valid, &params, "mp4 ").is_err());
+    }
+
+    #[test]
+    fn template_preset_renders_params_metadata_and_timeline() {
+        let preset = preset(
+            &[
+                "-y",
+                "-ss",
+                "{start}",
+                "-i",
+                "{input}",
+                "-t",
+                "{duration}",
+                "-b:v",
+                "{bitrate}",
+                "-crf",
+                "{crf}",
+                "{output}",
+            ],
+            &[("crf", "23")],
+        );
+        let options = HashMap::from([("crf".to_string(), serde_json::json!(28))]);
+
+        let command = build_template_preset_command(
+            &preset,
+            "/videos/talk.mp4",
+            &options,
+            Some(10.0),
+            Some(25.5),
+            &test_video_metadata(),
+            Some("/exports"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            &command.command_args[..3],
+            &args(&["-y", "-ss", "00:00:10.000"])[..]
+        );
+        assert_eq!(command.command_args[4], "/videos/talk.mp4");
+        assert_eq!(command.command_args[6], "15.5");
+        assert_eq!(command.command_args[8], "4000000");
+        assert_eq!(command.command_args[10], "28");
+        assert!(command.output_path.starts_with("/exports"));
+        assert!(command.output_path.contains("talk_web_720p_"));
+        assert_eq!(command.command_args.last(), Some(&command.output_path));
+
+        let missing_selection = build_template_preset_command(
+            &preset,
+            "/videos/talk.mp4",
+            &HashMap::new(),
+            None,
+            None,
+            &test_video_metadata(),
+            None,
+        );
+        assert!(missing_selection.is_err());
+    }
+}
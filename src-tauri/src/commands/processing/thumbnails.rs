@@ -0,0 +1,544 @@
+use super::*;
+
+use crate::services::format_vtt_timestamp;
+
+const MAX_CONTACT_SHEET_GRID: u32 = 8;
+const DEFAULT_CONTACT_SHEET_GRID: u32 = 4;
+const DEFAULT_CONTACT_SHEET_TILE_WIDTH: u32 = 320;
+const DEFAULT_SPRITE_INTERVAL_SECONDS: f64 = 10.0;
+const DEFAULT_SPRITE_TILE_WIDTH: u32 = 160;
+// 10x10 thumbnails per sheet keeps each JPEG small enough to load on first hover.
+const SPRITE_SHEET_GRID: u32 = 10;
+const MAX_SPRITE_FRAMES: f64 = 1000.0;
+const SPRITE_SET_PREFIX: &str = "sprites_";
+
+/// An N×M grid of evenly spaced frames saved as one JPEG
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactSheetResult {
+    pub path: String,
+    pub columns: u32,
+    pub rows: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// Source time of each tile, row by row
+    pub timestamps: Vec<f64>,
+    /// False when this FFmpeg build has no `drawtext`, so the UI should label tiles itself
+    pub timestamps_drawn: bool,
+}
+
+/// Thumbnail sprite sheets plus a WebVTT track mapping time ranges to `#xywh` tiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailSpriteResult {
+    pub vtt_path: String,
+    pub sprite_paths: Vec<String>,
+    pub interval_seconds: f64,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub columns: u32,
+    pub rows: u32,
+}
+
+fn preview_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|_| "Failed to get app data directory")?;
+    let preview_dir = app_data_dir.join("previews");
+    std::fs::create_dir_all(&preview_dir)
+        .map_err(|e| format!("Failed to create preview directory: {}", e))?;
+    Ok(preview_dir)
+}
+
+/// Cache key from the file's path, size and modification time, so an edited file gets new previews
+fn file_cache_key(input_path: &str, variant: &str) -> Result<String, String> {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let metadata =
+        std::fs::metadata(input_path).map_err(|e| format!("Failed to read video file: {}", e))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+
+    let mut hasher = DefaultHasher::new();
+    input_path.hash(&mut hasher);
+    metadata.len().hash(&mut hasher);
+    modified.hash(&mut hasher);
+    variant.hash(&mut hasher);
+    Ok(hasher.finish().to_string())
+}
+
+/// The `sprites_{key}` prefix shared by a sprite track and its sheets
+pub(super) fn sprite_set_prefix(file_name: &str) -> Option<&str> {
+    let rest = file_name.strip_prefix(SPRITE_SET_PREFIX)?;
+    let key_len = rest.find(|c: char| !c.is_ascii_digit())?;
+    if key_len == 0 {
+        return None;
+    }
+    Some(&file_name[..SPRITE_SET_PREFIX.len() + key_len])
+}
+
+fn scaled_tile_height(video_width: i32, video_height: i32, tile_width: u32) -> u32 {
+    if video_width <= 0 || video_height <= 0 {
+        return (tile_width * 9 / 16).max(2) & !1;
+    }
+    let height = (tile_width as f64 * video_height as f64 / video_width as f64).round() as u32;
+    height.max(2) & !1
+}
+
+/// Tile times centered in equal slices of the video, so the first tile is not a black frame
+fn contact_sheet_timestamps(duration: f64, count: u32) -> Vec<f64> {
+    let slice = duration / count as f64;
+    (0..count)
+        .map(|index| ((index as f64 + 0.5) * slice * 1000.0).round() / 1000.0)
+        .collect()
+}
+
+fn drawtext_label(seconds: f64) -> String {
+    let total = seconds.max(0.0).floor() as u64;
+    format!(
+        "{:02}\\:{:02}\\:{:02}",
+        total / 3600,
+        (total % 3600) / 60,
+        total % 60
+    )
+}
+
+fn contact_sheet_args(
+    input_path: &str,
+    output_path: &Path,
+    timestamps: &[f64],
+    columns: u32,
+    tile_width: u32,
+    tile_height: u32,
+    draw_timestamps: bool,
+) -> Vec<String> {
+    let mut args = vec!["-y".to_string(), "-hide_banner".to_string()];
+    for timestamp in timestamps {
+        args.extend([
+            "-ss".to_string(),
+            format!("{:.3}", timestamp),
+            "-i".to_string(),
+            input_path.to_string(),
+        ]);
+    }
+
+    let font_size = (tile_width / 14).max(12);
+    let mut filters = Vec::with_capacity(timestamps.len() + 1);
+    let mut layout = Vec::with_capacity(timestamps.len());
+    for (index, timestamp) in timestamps.iter().enumerate() {
+        let mut chain = format!(
+            "[{}:v:0]scale={}:{},setsar=1",
+            index, tile_width, tile_height
+        );
+        if draw_timestamps {
+            chain.push_str(&format!(
+                ",drawtext=text='{}':x=8:y=h-th-8:fontsize={}:fontcolor=white:box=1:boxcolor=black@0.6:boxborderw=4",
+                drawtext_label(*timestamp),
+                font_size
+            ));
+        }
+        chain.push_str(&format!("[t{}]", index));
+        filters.push(chain);
+
+        let column = index as u32 % columns;
+        let row = index as u32 / columns;
+        layout.push(format!("{}_{}", column * tile_width, row * tile_height));
+    }
+    let tiles = (0..timestamps.len())
+        .map(|index| format!("[t{}]", index))
+        .collect::<String>();
+    filters.push(format!(
+        "{}xstack=inputs={}:layout={}[sheet]",
+        tiles,
+        timestamps.len(),
+        layout.join("|")
+    ));
+
+    args.extend([
+        "-filter_complex".to_string(),
+        filters.join(";"),
+        "-map".to_string(),
+        "[sheet]".to_string(),
+        "-frames:v".to_string(),
+        "1".to_string(),
+        "-q:v".to_string(),
+        "3".to_string(),
+        output_path.to_string_lossy().to_string(),
+    ]);
+    args
+}
+
+async fn run_thumbnail_ffmpeg(
+    ffmpeg_path: &Path,
+    args: &[String],
+    output_path: &Path,
+) -> Result<(), String> {
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
+    cmd.hide_window();
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.status.success() || !output_path.exists() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        std::fs::remove_file(output_path).ok();
+        return Err(format!(
+            "FFmpeg failed: {}",
+            stderr.lines().last().unwrap_or("no output written")
+        ));
+    }
+    Ok(())
+}
+
+/// Generate (or reuse) a contact sheet of `columns`×`rows` frames with timestamps.
+#[tauri::command]
+pub async fn generate_contact_sheet(
+    app: AppHandle,
+    input_path: String,
+    columns: Option<u32>,
+    rows: Option<u32>,
+    tile_width: Option<u32>,
+) -> Result<ContactSheetResult, String> {
+    let columns = columns.unwrap_or(DEFAULT_CONTACT_SHEET_GRID);
+    let rows = rows.unwrap_or(DEFAULT_CONTACT_SHEET_GRID);
+    if !(1..=MAX_CONTACT_SHEET_GRID).contains(&columns)
+        || !(1..=MAX_CONTACT_SHEET_GRID).contains(&rows)
+        || columns * rows < 2
+    {
+        return Err(format!(
+            "Contact sheet grid must be between 1 and {} on each side, with at least 2 tiles",
+            MAX_CONTACT_SHEET_GRID
+        ));
+    }
+    let tile_width = tile_width.unwrap_or(DEFAULT_CONTACT_SHEET_TILE_WIDTH);
+    if !(80..=640).contains(&tile_width) {
+        return Err("Contact sheet tile width must be between 80 and 640 pixels".to_string());
+    }
+
+    let ffmpeg_path = get_ffmpeg_path(&app).await.ok_or_else(|| {
+        "FFmpeg not found. Please install FFmpeg from the Dependencies tab in Settings.".to_string()
+    })?;
+    let metadata = get_video_metadata(app.clone(), input_path.clone()).await?;
+    if metadata.duration <= 0.0 || !has_video_track(&metadata) {
+        return Err("Contact sheets need a video with a known duration".to_string());
+    }
+
+    let tile_width = tile_width & !1;
+    let tile_height = scaled_tile_height(metadata.width, metadata.height, tile_width);
+    let timestamps = contact_sheet_timestamps(metadata.duration, columns * rows);
+    let key = file_cache_key(
+        &input_path,
+        &format!("contact:{}x{}:{}", columns, rows, tile_width),
+    )?;
+    let preview_dir = preview_cache_dir(&app)?;
+    let labeled_path = preview_dir.join(format!("contact_{}.jpg", key));
+    let plain_path = preview_dir.join(format!("contact_{}_plain.jpg", key));
+    let result = |path: &Path, timestamps_drawn: bool| ContactSheetResult {
+        path: path.to_string_lossy().to_string(),
+        columns,
+        rows,
+        tile_width,
+        tile_height,
+        timestamps: timestamps.clone(),
+        timestamps_drawn,
+    };
+
+    if labeled_path.exists() {
+        log::info!("[CONTACT_SHEET] Cache hit: {}", labeled_path.display());
+        return Ok(result(&labeled_path, true));
+    }
+    if plain_path.exists() {
+        log::info!("[CONTACT_SHEET] Cache hit: {}", plain_path.display());
+        return Ok(result(&plain_path, false));
+    }
+
+    log::info!(
+        "[CONTACT_SHEET] Generating {}x{} sheet for '{}'",
+        columns,
+        rows,
+        input_path
+    );
+    let labeled_args = contact_sheet_args(
+        &input_path,
+        &labeled_path,
+        &timestamps,
+        columns,
+        tile_width,
+        tile_height,
+        true,
+    );
+    match run_thumbnail_ffmpeg(&ffmpeg_path, &labeled_args, &labeled_path).await {
+        Ok(()) => Ok(result(&labeled_path, true)),
+        Err(error) => {
+            // drawtext needs libfreetype and a usable font, which minimal builds lack
+            log::warn!(
+                "[CONTACT_SHEET] Labeled sheet failed, retrying without timestamps: {}",
+                error
+            );
+            let plain_args = contact_sheet_args(
+                &input_path,
+                &plain_path,
+                &timestamps,
+                columns,
+                tile_width,
+                tile_height,
+                false,
+            );
+            run_thumbnail_ffmpeg(&ffmpeg_path, &plain_args, &plain_path)
+                .await
+                .map_err(|e| {
+                    log::error!("[CONTACT_SHEET] FFmpeg failed for '{}': {}", input_path, e);
+                    format!("Contact sheet failed: {}", e)
+                })?;
+            Ok(result(&plain_path, false))
+        }
+    }
+}
+
+/// WebVTT cues for `frame_count` thumbnails taken every `interval` seconds
+fn build_sprite_vtt(
+    sheet_names: &[String],
+    frame_count: u32,
+    interval: f64,
+    duration: f64,
+    tile_width: u32,
+    tile_height: u32,
+) -> String {
+    let per_sheet = SPRITE_SHEET_GRID * SPRITE_SHEET_GRID;
+    let mut vtt = String::from("WEBVTT\n");
+    for frame in 0..frame_count {
+        let Some(sheet_name) = sheet_names.get((frame / per_sheet) as usize) else {
+            break;
+        };
+        let start = frame as f64 * interval;
+        let end = ((frame + 1) as f64 * interval).min(duration);
+        if end <= start {
+            break;
+        }
+        let tile = frame % per_sheet;
+        let x = (tile % SPRITE_SHEET_GRID) * tile_width;
+        let y = (tile / SPRITE_SHEET_GRID) * tile_height;
+        vtt.push_str(&format!(
+            "\n{} --> {}\n{}#xywh={},{},{},{}\n",
+            format_vtt_timestamp(start),
+            format_vtt_timestamp(end),
+            sheet_name,
+            x,
+            y,
+            tile_width,
+            tile_height
+        ));
+    }
+    vtt
+}
+
+/// Generate (or reuse) thumbnail sprite sheets and a WebVTT track for seek previews.
+///
+/// Cue payloads are sheet file names relative to the VTT file, which sits next to them.
+#[tauri::command]
+pub async fn generate_thumbnail_sprites(
+    app: AppHandle,
+    input_path: String,
+    interval_seconds: Option<f64>,
+    tile_width: Option<u32>,
+) -> Result<ThumbnailSpriteResult, String> {
+    let interval = interval_seconds.unwrap_or(DEFAULT_SPRITE_INTERVAL_SECONDS);
+    if !interval.is_finite() || interval < 1.0 {
+        return Err("Sprite interval must be at least 1 second".to_string());
+    }
+    let tile_width = tile_width.unwrap_or(DEFAULT_SPRITE_TILE_WIDTH);
+    if !(80..=320).contains(&tile_width) {
+        return Err("Sprite tile width must be between 80 and 320 pixels".to_string());
+    }
+
+    let ffmpeg_path = get_ffmpeg_path(&app).await.ok_or_else(|| {
+        "FFmpeg not found. Please install FFmpeg from the Dependencies tab in Settings.".to_string()
+    })?;
+    let metadata = get_video_metadata(app.clone(), input_path.clone()).await?;
+    if metadata.duration <= 0.0 || !has_video_track(&metadata) {
+        return Err("Thumbnail sprites need a video with a known duration".to_string());
+    }
+
+    // Long videos get a wider interval rather than thousands of thumbnails
+    let interval = interval.max(metadata.duration / MAX_SPRITE_FRAMES);
+    let tile_width = tile_width & !1;
+    let tile_height = scaled_tile_height(metadata.width, metadata.height, tile_width);
+    let frame_count = (metadata.duration / interval).ceil().max(1.0) as u32;
+    let per_sheet = SPRITE_SHEET_GRID * SPRITE_SHEET_GRID;
+    let sheet_count = frame_count.div_ceil(per_sheet);
+
+    let key = file_cache_key(
+        &input_path,
+        &format!("sprites:{:.3}:{}", interval, tile_width),
+    )?;
+    let preview_dir = preview_cache_dir(&app)?;
+    let prefix = format!("{}{}", SPRITE_SET_PREFIX, key);
+    let vtt_path = preview_dir.join(format!("{}.vtt", prefix));
+    let sheet_names = (1..=sheet_count)
+        .map(|index| format!("{}_{:03}.jpg", prefix, index))
+        .collect::<Vec<_>>();
+    let result = |sheet_names: &[String]| ThumbnailSpriteResult {
+        vtt_path: vtt_path.to_string_lossy().to_string(),
+        sprite_paths: sheet_names
+            .iter()
+            .map(|name| preview_dir.join(name).to_string_lossy().to_string())
+            .collect(),
+        interval_seconds: interval,
+        tile_width,
+        tile_height,
+        columns: SPRITE_SHEET_GRID,
+        rows: SPRITE_SHEET_GRID,
+    };
+
+    // The VTT is written last, so its presence means every sheet finished
+    let cached_sheets = sheet_names
+        .iter()
+        .take_while(|name| preview_dir.join(name).exists())
+        .cloned()
+        .collect::<Vec<_>>();
+    if vtt_path.exists() && !cached_sheets.is_empty() {
+        log::info!("[SPRITES] Cache hit: {}", vtt_path.display());
+        return Ok(result(&cached_sheets));
+    }
+
+    log::info!(
+        "[SPRITES] Generating {} thumbnails every {:.1}s for '{}'",
+        frame_count,
+        interval,
+        input_path
+    );
+    let _ = app.emit(
+        "preview-progress",
+        serde_json::json!({
+            "status": "starting",
+            "percent": 0
+        }),
+    );
+
+    let filter = format!(
+        "fps=1/{:.3},scale={}:{},setsar=1,tile={}x{}",
+        interval, tile_width, tile_height, SPRITE_SHEET_GRID, SPRITE_SHEET_GRID
+    );
+    let pattern = preview_dir.join(format!("{}_%03d.jpg", prefix));
+    let mut cmd = Command::new(&ffmpeg_path);
+    cmd.args([
+        "-y",
+        "-hide_banner",
+        "-i",
+        &input_path,
+        "-vf",
+        &filter,
+        "-an",
+    ])
+    .args(["-q:v", "4", "-start_number", "1"])
+    .arg(&pattern)
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+    cmd.hide_window();
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    let written_sheets = sheet_names
+        .iter()
+        .take_while(|name| preview_dir.join(name).exists())
+        .cloned()
+        .collect::<Vec<_>>();
+    if !output.status.success() || written_sheets.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        for name in &written_sheets {
+            std::fs::remove_file(preview_dir.join(name)).ok();
+        }
+        log::error!("[SPRITES] FFmpeg failed for '{}': {}", input_path, stderr);
+        return Err(format!(
+            "Thumbnail sprites failed: {}",
+            stderr.lines().last().unwrap_or("no output written")
+        ));
+    }
+
+    let vtt = build_sprite_vtt(
+        &written_sheets,
+        frame_count,
+        interval,
+        metadata.duration,
+        tile_width,
+        tile_height,
+    );
+    std::fs::write(&vtt_path, vtt)
+        .map_err(|e| format!("Failed to write thumbnail track: {}", e))?;
+
+    log::info!("[SPRITES] Generated: {}", vtt_path.display());
+    let _ = app.emit(
+        "preview-progress",
+        serde_json::json!({
+            "status": "complete",
+            "percent": 100
+        }),
+    );
+
+    Ok(result(&written_sheets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contact_sheet_tiles_are_centered_and_laid_out_in_rows() {
+        assert_eq!(
+            contact_sheet_timestamps(60.0, 4),
+            vec![7.5, 22.5, 37.5, 52.5]
+        );
+        assert_eq!(scaled_tile_height(1920, 1080, 320), 180);
+        assert_eq!(scaled_tile_height(0, 0, 160), 90);
+
+        let args = contact_sheet_args(
+            "in.mp4",
+            Path::new("sheet.jpg"),
+            &[7.5, 22.5, 37.5],
+            2,
+            320,
+            180,
+            true,
+        );
+        assert_eq!(&args[2..6], ["-ss", "7.500", "-i", "in.mp4"]);
+        let graph = &args[args
+            .iter()
+            .position(|arg| arg == "-filter_complex")
+            .unwrap()
+            + 1];
+        assert!(graph.contains("drawtext=text='00\\:00\\:37'"));
+        assert!(graph.ends_with("[t0][t1][t2]xstack=inputs=3:layout=0_0|320_0|0_180[sheet]"));
+        assert_eq!(args.last().map(String::as_str), Some("sheet.jpg"));
+    }
+
+    #[test]
+    fn sprite_track_maps_cues_to_sheet_tiles() {
+        let sheets = vec![
+            "sprites_1_001.jpg".to_string(),
+            "sprites_1_002.jpg".to_string(),
+        ];
+        let vtt = build_sprite_vtt(&sheets, 101, 10.0, 1005.0, 160, 90);
+        assert!(vtt.starts_with(
+            "WEBVTT\n\n00:00:00.000 --> 00:00:10.000\nsprites_1_001.jpg#xywh=0,0,160,90\n"
+        ));
+        assert!(
+            vtt.contains("00:00:10.000 --> 00:00:20.000\nsprites_1_001.jpg#xywh=160,0,160,90\n")
+        );
+        assert!(vtt.ends_with("00:16:40.000 --> 00:16:45.000\nsprites_1_002.jpg#xywh=0,0,160,90\n"));
+
+        assert_eq!(
+            sprite_set_prefix("sprites_123_004.jpg"),
+            Some("sprites_123")
+        );
+        assert_eq!(sprite_set_prefix("sprites_123.vtt"), Some("sprites_123"));
+        assert_eq!(sprite_set_prefix("preview_123.mp4"), None);
+    }
+}
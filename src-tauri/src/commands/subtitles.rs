@@ -0,0 +1,150 @@
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+use super::ai::load_ai_config;
+use crate::database::add_log_internal;
+use crate::services::{
+    linear_retime_from_sync_points, merge_bilingual_cues, read_subtitle_file, retime_cues,
+    translate_cues, write_subtitle_file, SubtitleDocument,
+};
+
+#[derive(Clone, Serialize)]
+struct SubtitleTranslationProgress {
+    input_path: String,
+    translated: usize,
+    total: usize,
+}
+
+/// `movie.srt` + `vi` -> `movie.vi.srt` next to the input
+fn derived_subtitle_path(input_path: &str, suffix: &str) -> String {
+    let path = Path::new(input_path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "subtitle".to_string());
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_else(|| "srt".to_string());
+    path.with_file_name(format!("{}.{}.{}", stem, suffix, extension))
+        .to_string_lossy()
+        .to_string()
+}
+
+fn resolve_subtitle_output(output_path: Option<String>, input_path: &str, suffix: &str) -> String {
+    output_path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| derived_subtitle_path(input_path, suffix))
+}
+
+/// Translate a subtitle file with the configured AI provider, keeping cue timing
+#[tauri::command]
+pub async fn translate_subtitle_file(
+    app: AppHandle,
+    input_path: String,
+    target_language: String,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let config = load_ai_config(&app)?;
+    if !config.enabled {
+        return Err("AI features are disabled. Enable them in Settings.".to_string());
+    }
+
+    let document = read_subtitle_file(&input_path)?;
+    let output_path = resolve_subtitle_output(output_path, &input_path, &target_language);
+    add_log_internal(
+        "info",
+        &format!(
+            "Translating {} subtitle cues to {}",
+            document.cues.len(),
+            target_language
+        ),
+        None,
+        None,
+    )
+    .ok();
+
+    let progress_app = app.clone();
+    let progress_input = input_path.clone();
+    let progress = move |translated: usize, total: usize| {
+        let payload = SubtitleTranslationProgress {
+            input_path: progress_input.clone(),
+            translated,
+            total,
+        };
+        progress_app
+            .emit("subtitle-translation-progress", payload)
+            .ok();
+    };
+    let cues = translate_cues(&config, &document.cues, &target_language, &progress)
+        .await
+        .map_err(|e| e.to_wire_string())?;
+
+    write_subtitle_file(&output_path, &SubtitleDocument { cues, ..document })?;
+    add_log_internal(
+        "success",
+        &format!("Translated subtitles saved to {}", output_path),
+        None,
+        None,
+    )
+    .ok();
+    Ok(output_path)
+}
+
+/// Shift and/or stretch subtitle timings.
+///
+/// Either pass `offset_ms`/`scale` directly or two `(subtitle_ms, video_ms)` sync points.
+#[tauri::command]
+pub async fn retime_subtitle_file(
+    input_path: String,
+    offset_ms: Option<i64>,
+    scale: Option<f64>,
+    sync_points: Option<[(i64, i64); 2]>,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let (offset_ms, scale) = match sync_points {
+        Some([first, second]) => linear_retime_from_sync_points(first, second)?,
+        None => (offset_ms.unwrap_or(0), scale.unwrap_or(1.0)),
+    };
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err("Subtitle time scale must be greater than zero".to_string());
+    }
+
+    let document = read_subtitle_file(&input_path)?;
+    let output_path = resolve_subtitle_output(output_path, &input_path, "retimed");
+    let cues = retime_cues(&document.cues, offset_ms, scale);
+    write_subtitle_file(&output_path, &SubtitleDocument { cues, ..document })?;
+    Ok(output_path)
+}
+
+/// Merge two subtitle tracks into one bilingual file, primary text on top
+#[tauri::command]
+pub async fn merge_subtitle_files(
+    primary_path: String,
+    secondary_path: String,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let primary = read_subtitle_file(&primary_path)?;
+    let secondary = read_subtitle_file(&secondary_path)?;
+    let output_path = resolve_subtitle_output(output_path, &primary_path, "bilingual");
+    let cues = merge_bilingual_cues(&primary.cues, &secondary.cues);
+    write_subtitle_file(&output_path, &SubtitleDocument { cues, ..primary })?;
+    Ok(output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_output_paths_next_to_input() {
+        let derived = derived_subtitle_path("/videos/movie.en.srt", "vi");
+        assert_eq!(Path::new(&derived), Path::new("/videos/movie.en.vi.srt"));
+        assert_eq!(
+            resolve_subtitle_output(Some(" /tmp/out.vtt ".to_string()), "a.srt", "vi"),
+            "/tmp/out.vtt"
+        );
+    }
+}
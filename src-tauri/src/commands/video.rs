@@ -1,10 +1,15 @@
-use crate::database::add_log_internal;
+use crate::database::{
+    add_log_internal, get_history_transcript_from_db, save_history_transcript_in_db,
+};
 use crate::services::{
-    build_cookie_args, build_proxy_args, build_site_header_args, get_deno_path, parse_ytdlp_error,
+    build_cookie_args, build_proxy_args, build_site_header_args, caption_language_from_path,
+    caption_segments_from_subtitle, caption_transcript_source, get_deno_path, parse_ytdlp_error,
     run_ytdlp_json_with_cookies, run_ytdlp_with_stderr, run_ytdlp_with_stderr_and_cookies,
+    transcript_plain_text,
 };
 use crate::types::{
-    BackendError, FormatOption, PlaylistVideoEntry, SubtitleInfo, VideoInfo, VideoInfoResponse,
+    BackendError, FormatOption, HistoryTranscript, PlaylistVideoEntry, SubtitleInfo,
+    TranscriptSource, VideoInfo, VideoInfoResponse,
 };
 use crate::utils::{normalize_url, validate_url};
 use std::time::Duration;
@@ -32,6 +37,33 @@ fn default_transcript_languages(url: &str) -> Vec<String> {
     vec!["en".to_string()]
 }
 
+/// Whether a saved transcript answers a request for `languages`. Whisper transcripts and
+/// requests without languages always match; captions must be in one of the requested
+/// languages, where `en` or `en.*` also accepts regional variants such as `en-US`.
+fn saved_transcript_matches_languages(
+    stored: &HistoryTranscript,
+    languages: Option<&[String]>,
+) -> bool {
+    if stored.source == TranscriptSource::Whisper {
+        return true;
+    }
+    let Some(languages) = languages.filter(|languages| !languages.is_empty()) else {
+        return true;
+    };
+    let Some(stored_language) = stored.language.as_deref() else {
+        return false;
+    };
+    let stored_language = stored_language.to_lowercase();
+    languages.iter().any(|language| {
+        let language = language.trim().to_lowercase();
+        let language = language.strip_suffix(".*").unwrap_or(&language);
+        stored_language == language
+            || stored_language
+                .strip_prefix(language)
+                .is_some_and(|rest| rest.starts_with('-'))
+    })
+}
+
 fn parse_basic_video_info_output(
     output: &str,
 ) -> Result<(String, Option<String>, Option<f64>), String> {
@@ -189,6 +221,7 @@ pub async fn get_video_transcript(
     cookie_file_path: Option<String>,
     cookie_skip_patterns: Option<Vec<String>>,
     proxy_url: Option<String>,
+    history_id: Option<String>,
 ) -> Result<String, String> {
     // Log the URL being processed
     #[cfg(debug_assertions)]
@@ -197,6 +230,26 @@ pub async fn get_video_transcript(
     validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
     let url = normalize_url(&url);
 
+    // Reuse the transcript saved with the history entry instead of asking yt-dlp again,
+    // unless it is in a language the caller did not ask for
+    if let Some(id) = history_id.as_deref() {
+        if let Ok(Some(stored)) = get_history_transcript_from_db(id) {
+            if saved_transcript_matches_languages(&stored, languages.as_deref()) {
+                add_log_internal(
+                    "info",
+                    &format!(
+                        "Using saved transcript ({} segments)",
+                        stored.segments.len()
+                    ),
+                    None,
+                    Some(&url),
+                )
+                .ok();
+                return Ok(transcript_plain_text(&stored.segments));
+            }
+        }
+    }
+
     add_log_internal(
         "info",
         &format!("Fetching transcript for AI summary"),
@@ -434,6 +487,10 @@ pub async fn get_video_transcript(
                 )
                 .ok();
 
+                if let Some(id) = history_id.as_deref() {
+                    save_caption_transcript(id, path, &content, &url);
+                }
+
                 // Clean up
                 std::fs::remove_dir_all(&temp_dir).ok();
                 return Ok(transcript);
@@ -858,6 +915,28 @@ fn build_metadata_fallback_from_info_json(json: &serde_json::Value) -> Option<St
     ))
 }
 
+/// Keep the timed captions with the history entry so later summaries skip yt-dlp
+fn save_caption_transcript(history_id: &str, path: &std::path::Path, content: &str, url: &str) {
+    let segments = caption_segments_from_subtitle(content);
+    if segments.is_empty() {
+        return;
+    }
+    if let Err(e) = save_history_transcript_in_db(
+        history_id,
+        caption_language_from_path(path).as_deref(),
+        caption_transcript_source(content),
+        &segments,
+    ) {
+        add_log_internal(
+            "stderr",
+            &format!("Failed to save transcript: {}", e),
+            None,
+            Some(url),
+        )
+        .ok();
+    }
+}
+
 /// Parse VTT or SRT subtitle file to plain text
 fn parse_subtitle_file(content: &str) -> String {
     let mut texts: Vec<String> = Vec::new();
@@ -1475,6 +1554,44 @@ pub async fn get_available_subtitles(
 mod tests {
     use super::*;
 
+    fn saved_transcript(language: Option<&str>, source: TranscriptSource) -> HistoryTranscript {
+        HistoryTranscript {
+            history_id: "h1".to_string(),
+            language: language.map(ToString::to_string),
+            source,
+            segments: Vec::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn saved_transcript_in_another_language_is_not_reused() {
+        let english = saved_transcript(Some("en"), TranscriptSource::PlatformCaptions);
+        let vietnamese = ["vi".to_string()];
+        assert!(!saved_transcript_matches_languages(
+            &english,
+            Some(&vietnamese[..])
+        ));
+        assert!(!saved_transcript_matches_languages(
+            &saved_transcript(None, TranscriptSource::AutoCaptions),
+            Some(&vietnamese[..])
+        ));
+
+        assert!(saved_transcript_matches_languages(&english, None));
+        assert!(saved_transcript_matches_languages(
+            &saved_transcript(Some("en-US"), TranscriptSource::AutoCaptions),
+            Some(&["vi".to_string(), "en.*".to_string()][..])
+        ));
+        assert!(!saved_transcript_matches_languages(
+            &saved_transcript(Some("eng"), TranscriptSource::AutoCaptions),
+            Some(&["en".to_string()][..])
+        ));
+        assert!(saved_transcript_matches_languages(
+            &saved_transcript(Some("en"), TranscriptSource::Whisper),
+            Some(&vietnamese[..])
+        ));
+    }
+
     #[test]
     fn parse_basic_video_info_output_reads_printed_fields() {
         let output = concat!(
@@ -1,12 +1,183 @@
-use crate::database::add_log_internal;
+use super::ai::load_ai_config;
+use crate::database::{add_log_internal, save_history_transcript_in_db};
 use crate::services::{
-    extract_audio_for_whisper, get_ffmpeg_path, run_ytdlp_with_stderr_and_cookies,
-    transcribe_audio, WhisperError, WhisperResponseFormat,
+    build_local_whisper_backend, extract_audio_for_whisper, get_ffmpeg_path, probe_media_duration,
+    run_ytdlp_with_stderr_and_cookies, transcribe_in_chunks, transcript_segments_from_whisper,
+    whisper_result_from_segments, OpenAiWhisperBackend, TranscriptionBackend, WhisperBackendKind,
+    WhisperError, WhisperResponseFormat, WhisperTranscript, CHUNKED_TRANSCRIPTION_THRESHOLD_SECS,
+    DEFAULT_CHUNK_CONCURRENCY, DEFAULT_DIARIZATION_MODEL,
 };
+use crate::types::TranscriptSource;
 use std::path::Path;
 use tauri::AppHandle;
 use uuid::Uuid;
 
+/// Pick the transcription backend from the saved AI settings.
+///
+/// The local backend ignores the API key, endpoint, and model passed by the caller.
+async fn resolve_transcription_backend(
+    app: &AppHandle,
+    openai_api_key: String,
+    whisper_endpoint_url: Option<String>,
+    whisper_model: Option<String>,
+) -> Result<Box<dyn TranscriptionBackend>, String> {
+    let config = load_ai_config(app).unwrap_or_default();
+    if config.whisper_backend == WhisperBackendKind::Local {
+        let backend = build_local_whisper_backend(
+            app,
+            config.whisper_local_engine,
+            config.whisper_local_model.as_deref(),
+            config.whisper_local_binary_path.as_deref(),
+            config.whisper_diarization,
+        )
+        .await?;
+        return Ok(Box::new(backend));
+    }
+
+    if openai_api_key.is_empty() {
+        return Err(WhisperError::NoApiKey.into());
+    }
+    // Plain Whisper models can't label speakers, so diarization swaps in a model that can.
+    let model = if config.whisper_diarization {
+        Some(
+            config
+                .whisper_diarization_model
+                .filter(|model| !model.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_DIARIZATION_MODEL.to_string()),
+        )
+    } else {
+        whisper_model
+    };
+    Ok(Box::new(OpenAiWhisperBackend {
+        api_key: openai_api_key,
+        endpoint_url: whisper_endpoint_url,
+        model,
+        diarize: config.whisper_diarization,
+    }))
+}
+
+/// Format timed segments for the caller and save them as the history entry's transcript
+fn finish_segment_transcription(
+    transcript: WhisperTranscript,
+    format: WhisperResponseFormat,
+    history_id: Option<&str>,
+    url: Option<&str>,
+) -> String {
+    if let Some(id) = history_id {
+        let segments = transcript_segments_from_whisper(&transcript.segments);
+        if !segments.is_empty() {
+            if let Err(e) = save_history_transcript_in_db(
+                id,
+                transcript.language.as_deref(),
+                TranscriptSource::Whisper,
+                &segments,
+            ) {
+                add_log_internal(
+                    "stderr",
+                    &format!("Failed to save transcript: {}", e),
+                    None,
+                    url,
+                )
+                .ok();
+            }
+        }
+    }
+    whisper_result_from_segments(
+        &transcript.segments,
+        format,
+        transcript.duration_seconds,
+        transcript.language,
+    )
+    .text
+}
+
+/// Transcribe with timed segments when they need to be kept, plain output otherwise
+async fn transcribe_for_history(
+    backend: &dyn TranscriptionBackend,
+    audio_path: &str,
+    format: WhisperResponseFormat,
+    language: Option<&str>,
+    history_id: Option<&str>,
+    url: Option<&str>,
+) -> Result<String, String> {
+    if history_id.is_none() {
+        return backend
+            .transcribe(audio_path, format, language)
+            .await
+            .map(|result| result.text)
+            .map_err(|e| e.to_string());
+    }
+    let transcript = backend
+        .transcribe_segments(audio_path, language)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(finish_segment_transcription(
+        transcript, format, history_id, url,
+    ))
+}
+
+/// Transcribe media longer than the upload-friendly threshold in parallel chunks.
+///
+/// Returns `None` when the media is short enough for a single request or the backend
+/// reads whole files itself.
+async fn transcribe_long_media(
+    app: &AppHandle,
+    backend: &dyn TranscriptionBackend,
+    input_path: &str,
+    language: Option<&str>,
+    url: Option<&str>,
+) -> Result<Option<WhisperTranscript>, String> {
+    if !backend.requires_compressed_audio() {
+        return Ok(None);
+    }
+
+    let ffmpeg_path = get_ffmpeg_path(app).await;
+    let ffmpeg_path_str = ffmpeg_path
+        .as_ref()
+        .map(|p| p.to_string_lossy().to_string());
+    let duration = match probe_media_duration(input_path, ffmpeg_path_str.as_deref()).await {
+        Ok(duration) if duration > CHUNKED_TRANSCRIPTION_THRESHOLD_SECS => duration,
+        _ => return Ok(None),
+    };
+
+    add_log_internal(
+        "info",
+        &format!(
+            "Long media ({:.0} min), transcribing in up to {} parallel chunks with {}...",
+            duration / 60.0,
+            DEFAULT_CHUNK_CONCURRENCY,
+            backend.label()
+        ),
+        None,
+        url,
+    )
+    .ok();
+
+    let transcript = transcribe_in_chunks(
+        backend,
+        input_path,
+        ffmpeg_path_str.as_deref(),
+        duration,
+        language,
+        DEFAULT_CHUNK_CONCURRENCY,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    add_log_internal(
+        "success",
+        &format!(
+            "Whisper transcription complete ({} segments)",
+            transcript.segments.len()
+        ),
+        None,
+        url,
+    )
+    .ok();
+
+    Ok(Some(transcript))
+}
+
 /// Transcribe a local video/audio file using OpenAI Whisper API
 ///
 /// This command:
@@ -22,6 +193,7 @@ pub async fn transcribe_video_with_whisper(
     language: Option<String>,
     whisper_endpoint_url: Option<String>,
     whisper_model: Option<String>,
+    history_id: Option<String>,
 ) -> Result<String, String> {
     add_log_internal(
         "info",
@@ -31,9 +203,9 @@ pub async fn transcribe_video_with_whisper(
     )
     .ok();
 
-    if openai_api_key.is_empty() {
-        return Err(WhisperError::NoApiKey.into());
-    }
+    let backend =
+        resolve_transcription_backend(&app, openai_api_key, whisper_endpoint_url, whisper_model)
+            .await?;
 
     let path = Path::new(&video_path);
     if !path.exists() {
@@ -49,6 +221,24 @@ pub async fn transcribe_video_with_whisper(
         _ => WhisperResponseFormat::Text,
     };
 
+    // Long media is split at silences instead of being squeezed into one upload
+    if let Some(transcript) = transcribe_long_media(
+        &app,
+        backend.as_ref(),
+        &video_path,
+        language.as_deref(),
+        None,
+    )
+    .await?
+    {
+        return Ok(finish_segment_transcription(
+            transcript,
+            format,
+            history_id.as_deref(),
+            None,
+        ));
+    }
+
     // Check if we need to extract audio (video files)
     let extension = path
         .extension()
@@ -56,7 +246,8 @@ pub async fn transcribe_video_with_whisper(
         .unwrap_or("")
         .to_lowercase();
 
-    let audio_path = if ["mp4", "mkv", "webm", "avi", "mov", "flv"].contains(&extension.as_str()) {
+    let is_video = ["mp4", "mkv", "webm", "avi", "mov", "flv"].contains(&extension.as_str());
+    let audio_path = if is_video && backend.requires_compressed_audio() {
         // Need to extract audio first
         add_log_internal(
             "info",
@@ -85,23 +276,28 @@ pub async fn transcribe_video_with_whisper(
 
         audio_output_str
     } else {
-        // Already an audio file
+        // Already an audio file, or a backend that decodes media itself
         video_path.clone()
     };
 
     // Transcribe with Whisper
-    add_log_internal("info", "Sending audio to Whisper API...", None, None).ok();
+    add_log_internal(
+        "info",
+        &format!("Transcribing audio with {}...", backend.label()),
+        None,
+        None,
+    )
+    .ok();
 
-    let result = transcribe_audio(
-        &openai_api_key,
+    let text = transcribe_for_history(
+        backend.as_ref(),
         &audio_path,
         format,
         language.as_deref(),
-        whisper_endpoint_url.as_deref(),
-        whisper_model.as_deref(),
+        history_id.as_deref(),
+        None,
     )
-    .await
-    .map_err(|e| e.to_string())?;
+    .await?;
 
     // Clean up temp audio file if we created one
     if audio_path != video_path {
@@ -112,16 +308,13 @@ pub async fn transcribe_video_with_whisper(
 
     add_log_internal(
         "success",
-        &format!(
-            "Whisper transcription complete ({} chars)",
-            result.text.len()
-        ),
+        &format!("Whisper transcription complete ({} chars)", text.len()),
         None,
         None,
     )
     .ok();
 
-    Ok(result.text)
+    Ok(text)
 }
 
 /// Transcribe a video from URL using OpenAI Whisper API
@@ -148,6 +341,7 @@ pub async fn transcribe_url_with_whisper(
     // Whisper backend settings
     whisper_endpoint_url: Option<String>,
     whisper_model: Option<String>,
+    history_id: Option<String>,
 ) -> Result<String, String> {
     add_log_internal(
         "info",
@@ -157,9 +351,9 @@ pub async fn transcribe_url_with_whisper(
     )
     .ok();
 
-    if openai_api_key.is_empty() {
-        return Err(WhisperError::NoApiKey.into());
-    }
+    let backend =
+        resolve_transcription_backend(&app, openai_api_key, whisper_endpoint_url, whisper_model)
+            .await?;
 
     // Parse response format
     let format = match response_format.to_lowercase().as_str() {
@@ -249,13 +443,33 @@ pub async fn transcribe_url_with_whisper(
         return Err("Failed to download audio from URL".to_string());
     }
 
+    let chunked = transcribe_long_media(
+        &app,
+        backend.as_ref(),
+        &audio_file,
+        language.as_deref(),
+        Some(&url),
+    )
+    .await;
+    if !matches!(chunked, Ok(None)) {
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+    if let Some(transcript) = chunked? {
+        return Ok(finish_segment_transcription(
+            transcript,
+            format,
+            history_id.as_deref(),
+            Some(&url),
+        ));
+    }
+
     // Check file size and compress if needed
     let metadata = std::fs::metadata(&audio_file)
         .map_err(|e| format!("Failed to get file metadata: {}", e))?;
 
     let max_size: u64 = 25 * 1024 * 1024; // 25 MB
 
-    if metadata.len() > max_size {
+    if backend.requires_compressed_audio() && metadata.len() > max_size {
         add_log_internal(
             "info",
             "Audio file too large, compressing...",
@@ -281,18 +495,23 @@ pub async fn transcribe_url_with_whisper(
     }
 
     // Transcribe with Whisper
-    add_log_internal("info", "Sending audio to Whisper API...", None, Some(&url)).ok();
+    add_log_internal(
+        "info",
+        &format!("Transcribing audio with {}...", backend.label()),
+        None,
+        Some(&url),
+    )
+    .ok();
 
-    let whisper_result = transcribe_audio(
-        &openai_api_key,
+    let whisper_text = transcribe_for_history(
+        backend.as_ref(),
         &audio_file,
         format,
         language.as_deref(),
-        whisper_endpoint_url.as_deref(),
-        whisper_model.as_deref(),
+        history_id.as_deref(),
+        Some(&url),
     )
-    .await
-    .map_err(|e| e.to_string())?;
+    .await?;
 
     // Clean up temp files
     std::fs::remove_dir_all(&temp_dir).ok();
@@ -301,14 +520,14 @@ pub async fn transcribe_url_with_whisper(
         "success",
         &format!(
             "Whisper transcription complete ({} chars)",
-            whisper_result.text.len()
+            whisper_text.len()
         ),
         None,
         Some(&url),
     )
     .ok();
 
-    Ok(whisper_result.text)
+    Ok(whisper_text)
 }
 
 /// Generate subtitles for a video file and save to disk
@@ -338,6 +557,7 @@ pub async fn generate_subtitles_with_whisper(
         language,
         whisper_endpoint_url,
         whisper_model,
+        None,
     )
     .await?;
 
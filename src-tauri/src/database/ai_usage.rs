@@ -0,0 +1,255 @@
+use super::get_db;
+use crate::types::{AICachedResponse, AIUsageRecord, AIUsageReportRow};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+pub(crate) fn create_ai_usage_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS ai_response_cache (
+            cache_key TEXT PRIMARY KEY,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            response TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_ai_response_cache_expires
+            ON ai_response_cache(expires_at);
+        CREATE TABLE IF NOT EXISTS ai_usage_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            input_tokens INTEGER NOT NULL,
+            output_tokens INTEGER NOT NULL,
+            estimated_tokens INTEGER NOT NULL DEFAULT 0,
+            latency_ms INTEGER NOT NULL,
+            cost_usd REAL,
+            cached INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_ai_usage_log_created
+            ON ai_usage_log(created_at);",
+    )
+    .map_err(|e| format!("Failed to create AI usage tables: {}", e))
+}
+
+/// Cached response for `cache_key`, unless it has expired
+pub fn get_cached_ai_response_from_db(cache_key: &str) -> Result<Option<AICachedResponse>, String> {
+    let conn = get_db()?;
+    match conn.query_row(
+        "SELECT provider, model, response FROM ai_response_cache
+         WHERE cache_key = ?1 AND expires_at > ?2",
+        params![cache_key, Utc::now().timestamp()],
+        |row| {
+            Ok(AICachedResponse {
+                provider: row.get(0)?,
+                model: row.get(1)?,
+                response: row.get(2)?,
+            })
+        },
+    ) {
+        Ok(response) => Ok(Some(response)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to read AI response cache: {}", e)),
+    }
+}
+
+/// Store a response for `ttl_seconds`, dropping entries that have already expired
+pub fn save_cached_ai_response_in_db(
+    cache_key: &str,
+    provider: &str,
+    model: &str,
+    response: &str,
+    ttl_seconds: i64,
+) -> Result<(), String> {
+    let now = Utc::now().timestamp();
+    let conn = get_db()?;
+    conn.execute(
+        "DELETE FROM ai_response_cache WHERE expires_at <= ?1",
+        params![now],
+    )
+    .map_err(|e| format!("Failed to prune AI response cache: {}", e))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO ai_response_cache
+            (cache_key, provider, model, response, created_at, expires_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            cache_key,
+            provider,
+            model,
+            response,
+            now,
+            now.saturating_add(ttl_seconds)
+        ],
+    )
+    .map_err(|e| format!("Failed to save AI response cache: {}", e))?;
+    Ok(())
+}
+
+pub fn clear_ai_response_cache_in_db() -> Result<usize, String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM ai_response_cache", [])
+        .map_err(|e| format!("Failed to clear AI response cache: {}", e))
+}
+
+pub fn add_ai_usage_in_db(record: &AIUsageRecord) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO ai_usage_log
+            (provider, model, input_tokens, output_tokens, estimated_tokens, latency_ms,
+             cost_usd, cached, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            record.provider,
+            record.model,
+            record.input_tokens as i64,
+            record.output_tokens as i64,
+            record.estimated_tokens,
+            record.latency_ms as i64,
+            record.cost_usd,
+            record.cached,
+            Utc::now().timestamp()
+        ],
+    )
+    .map_err(|e| format!("Failed to save AI usage: {}", e))?;
+    Ok(())
+}
+
+/// Usage totals per day, provider, and model, newest day first. `days` limits the report
+/// to the most recent days including today.
+pub fn get_ai_usage_report_from_db(days: Option<u32>) -> Result<Vec<AIUsageReportRow>, String> {
+    let since = match days {
+        Some(days) => {
+            let today = Utc::now().date_naive();
+            let first_day = today - chrono::Duration::days(i64::from(days.max(1)) - 1);
+            first_day
+                .and_hms_opt(0, 0, 0)
+                .map(|start| start.and_utc().timestamp())
+                .unwrap_or(0)
+        }
+        None => 0,
+    };
+
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT date(created_at, 'unixepoch') AS day, provider, model,
+                    COUNT(*),
+                    SUM(cached),
+                    SUM(input_tokens),
+                    SUM(output_tokens),
+                    CAST(COALESCE(AVG(CASE WHEN cached = 0 THEN latency_ms END), 0) AS INTEGER),
+                    COALESCE(SUM(cost_usd), 0),
+                    SUM(CASE WHEN cost_usd IS NULL THEN 1 ELSE 0 END)
+             FROM ai_usage_log
+             WHERE created_at >= ?1
+             GROUP BY day, provider, model
+             ORDER BY day DESC, provider ASC, model ASC",
+        )
+        .map_err(|e| format!("Failed to prepare AI usage query: {}", e))?;
+    let rows = stmt
+        .query_map(params![since], |row| {
+            Ok(AIUsageReportRow {
+                day: row.get(0)?,
+                provider: row.get(1)?,
+                model: row.get(2)?,
+                requests: row.get::<_, i64>(3)? as u64,
+                cached_requests: row.get::<_, i64>(4)? as u64,
+                input_tokens: row.get::<_, i64>(5)? as u64,
+                output_tokens: row.get::<_, i64>(6)? as u64,
+                average_latency_ms: row.get::<_, i64>(7)? as u64,
+                cost_usd: row.get(8)?,
+                unpriced_requests: row.get::<_, i64>(9)? as u64,
+            })
+        })
+        .map_err(|e| format!("Failed to load AI usage: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read AI usage: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn ensure_test_ai_usage_tables() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+
+        let conn = get_db().expect("get db");
+        create_ai_usage_tables(&conn).expect("create AI usage tables");
+        conn.execute_batch("DELETE FROM ai_response_cache; DELETE FROM ai_usage_log;")
+            .expect("reset AI usage tables");
+    }
+
+    fn usage(model: &str, cost_usd: Option<f64>, cached: bool) -> AIUsageRecord {
+        AIUsageRecord {
+            provider: "OpenAI".to_string(),
+            model: model.to_string(),
+            input_tokens: if cached { 0 } else { 1_000 },
+            output_tokens: if cached { 0 } else { 200 },
+            estimated_tokens: false,
+            latency_ms: if cached { 0 } else { 900 },
+            cost_usd,
+            cached,
+        }
+    }
+
+    #[test]
+    fn cached_responses_expire_after_their_ttl() {
+        let _guard = db_test_guard();
+        ensure_test_ai_usage_tables();
+
+        save_cached_ai_response_in_db("fresh", "OpenAI", "gpt-4o-mini", "Summary", 3600)
+            .expect("save fresh response");
+        save_cached_ai_response_in_db("stale", "OpenAI", "gpt-4o-mini", "Old", 0)
+            .expect("save stale response");
+
+        let cached = get_cached_ai_response_from_db("fresh")
+            .expect("read cache")
+            .expect("fresh entry");
+        assert_eq!(cached.response, "Summary");
+        assert_eq!(cached.model, "gpt-4o-mini");
+        assert_eq!(
+            get_cached_ai_response_from_db("stale").expect("read cache"),
+            None
+        );
+        assert_eq!(clear_ai_response_cache_in_db().expect("clear cache"), 1);
+        assert_eq!(
+            get_cached_ai_response_from_db("fresh").expect("read cache"),
+            None
+        );
+    }
+
+    #[test]
+    fn usage_report_groups_by_day_provider_and_model() {
+        let _guard = db_test_guard();
+        ensure_test_ai_usage_tables();
+
+        add_ai_usage_in_db(&usage("gpt-4o-mini", Some(0.01), false)).expect("save usage");
+        add_ai_usage_in_db(&usage("gpt-4o-mini", Some(0.0), true)).expect("save usage");
+        add_ai_usage_in_db(&usage("my-finetune", None, false)).expect("save usage");
+
+        let report = get_ai_usage_report_from_db(Some(1)).expect("load report");
+        assert_eq!(report.len(), 2);
+        let mini = report
+            .iter()
+            .find(|row| row.model == "gpt-4o-mini")
+            .expect("gpt-4o-mini row");
+        assert_eq!(mini.requests, 2);
+        assert_eq!(mini.cached_requests, 1);
+        assert_eq!(mini.input_tokens, 1_000);
+        assert_eq!(mini.average_latency_ms, 900);
+        assert!((mini.cost_usd - 0.01).abs() < 1e-9);
+        let unpriced = report
+            .iter()
+            .find(|row| row.model == "my-finetune")
+            .expect("unpriced row");
+        assert_eq!(unpriced.unpriced_requests, 1);
+        assert_eq!(unpriced.cost_usd, 0.0);
+    }
+}
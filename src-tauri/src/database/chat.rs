@@ -0,0 +1,258 @@
+use super::get_db;
+use crate::types::{ChatCitation, HistoryChatMessage, HistoryChatRole};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+pub(crate) fn create_chat_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS history_chat_messages (
+            id TEXT PRIMARY KEY,
+            history_id TEXT NOT NULL,
+            scope_ids TEXT NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            citations_json TEXT NOT NULL DEFAULT '[]',
+            created_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_history_chat_messages_history
+            ON history_chat_messages(history_id, created_at);
+        CREATE INDEX IF NOT EXISTS idx_history_chat_messages_scope
+            ON history_chat_messages(scope_ids, created_at);",
+    )
+    .map_err(|e| format!("Failed to create chat table: {}", e))
+}
+
+/// Sorted, de-duplicated ids a conversation is about; the same selection in any order
+/// shares one conversation
+pub fn chat_scope_ids(history_ids: &[String]) -> Vec<String> {
+    let mut ids = history_ids.to_vec();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+pub fn add_history_chat_message_in_db(
+    history_ids: &[String],
+    role: HistoryChatRole,
+    content: &str,
+    citations: &[ChatCitation],
+) -> Result<HistoryChatMessage, String> {
+    let history_ids = chat_scope_ids(history_ids);
+    let Some(history_id) = history_ids.first().cloned() else {
+        return Err("A chat message needs at least one Library item".to_string());
+    };
+    let citations_json = serde_json::to_string(citations)
+        .map_err(|e| format!("Failed to serialize chat citations: {}", e))?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now();
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO history_chat_messages
+            (id, history_id, scope_ids, role, content, citations_json, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            id,
+            history_id,
+            history_ids.join(","),
+            role.as_str(),
+            content,
+            citations_json,
+            now.timestamp()
+        ],
+    )
+    .map_err(|e| format!("Failed to save chat message: {}", e))?;
+
+    Ok(HistoryChatMessage {
+        id,
+        history_id,
+        history_ids,
+        role,
+        content: content.to_string(),
+        citations: citations.to_vec(),
+        created_at: now.to_rfc3339(),
+    })
+}
+
+/// Conversation over exactly this set of history entries, oldest message first
+pub fn get_history_chat_from_db(history_ids: &[String]) -> Result<Vec<HistoryChatMessage>, String> {
+    let history_ids = chat_scope_ids(history_ids);
+    let Some(history_id) = history_ids.first().cloned() else {
+        return Ok(Vec::new());
+    };
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, role, content, citations_json, created_at
+             FROM history_chat_messages
+             WHERE scope_ids = ?1
+             ORDER BY created_at ASC, rowid ASC",
+        )
+        .map_err(|e| format!("Failed to prepare chat query: {}", e))?;
+    let rows = stmt
+        .query_map(params![history_ids.join(",")], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to load chat messages: {}", e))?;
+
+    let mut messages = Vec::new();
+    for row in rows {
+        let (id, role, content, citations_json, created_at) =
+            row.map_err(|e| format!("Failed to read chat message: {}", e))?;
+        let Some(role) = HistoryChatRole::from_str(&role) else {
+            continue;
+        };
+        messages.push(HistoryChatMessage {
+            id,
+            history_id: history_id.clone(),
+            history_ids: history_ids.clone(),
+            role,
+            content,
+            citations: serde_json::from_str(&citations_json).unwrap_or_default(),
+            created_at: chrono::DateTime::from_timestamp(created_at, 0)
+                .map(|d| d.to_rfc3339())
+                .unwrap_or_default(),
+        });
+    }
+    Ok(messages)
+}
+
+pub fn clear_history_chat_from_db(history_ids: &[String]) -> Result<usize, String> {
+    let conn = get_db()?;
+    conn.execute(
+        "DELETE FROM history_chat_messages WHERE scope_ids = ?1",
+        params![chat_scope_ids(history_ids).join(",")],
+    )
+    .map_err(|e| format!("Failed to clear chat: {}", e))
+}
+
+/// Drop conversations about any item that is no longer in history
+pub(crate) fn delete_orphaned_history_chats_in_db(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM history_chat_messages
+         WHERE length(scope_ids) - length(replace(scope_ids, ',', '')) + 1 > (
+             SELECT COUNT(*) FROM history
+             WHERE instr(',' || history_chat_messages.scope_ids || ',', ',' || history.id || ',') > 0
+         )",
+        [],
+    )
+    .map(|_| ())
+    .map_err(|e| format!("Failed to delete orphaned chats: {}", e))
+}
+
+/// Drop every conversation the history entry took part in, alone or with other items
+pub(crate) fn delete_history_chats_in_db(
+    conn: &Connection,
+    history_id: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM history_chat_messages
+         WHERE history_id = ?1 OR instr(',' || scope_ids || ',', ',' || ?1 || ',') > 0",
+        params![history_id],
+    )
+    .map(|_| ())
+    .map_err(|e| format!("Failed to delete history chat: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn ensure_test_chat_tables() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+
+        let conn = get_db().expect("get db");
+        create_chat_tables(&conn).expect("create chat table");
+        conn.execute_batch("DELETE FROM history_chat_messages;")
+            .expect("reset chat table");
+    }
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn chat_messages_are_kept_in_order_per_history_entry() {
+        let _guard = db_test_guard();
+        ensure_test_chat_tables();
+
+        let citation = ChatCitation {
+            history_id: "h1".to_string(),
+            start_ms: 65_000,
+        };
+        add_history_chat_message_in_db(&ids(&["h1"]), HistoryChatRole::User, "What?", &[])
+            .expect("save question");
+        add_history_chat_message_in_db(
+            &ids(&["h1"]),
+            HistoryChatRole::Assistant,
+            "This [01:05]",
+            std::slice::from_ref(&citation),
+        )
+        .expect("save answer");
+        add_history_chat_message_in_db(&ids(&["h2"]), HistoryChatRole::User, "Other", &[])
+            .expect("save other question");
+
+        let messages = get_history_chat_from_db(&ids(&["h1"])).expect("load chat");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, HistoryChatRole::User);
+        assert_eq!(messages[1].citations, vec![citation]);
+
+        assert_eq!(
+            clear_history_chat_from_db(&ids(&["h1"])).expect("clear chat"),
+            2
+        );
+        assert!(get_history_chat_from_db(&ids(&["h1"]))
+            .expect("load chat")
+            .is_empty());
+        assert_eq!(
+            get_history_chat_from_db(&ids(&["h2"]))
+                .expect("load chat")
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn multi_item_conversations_are_keyed_by_the_whole_selection() {
+        let _guard = db_test_guard();
+        ensure_test_chat_tables();
+
+        add_history_chat_message_in_db(&ids(&["h2", "h1"]), HistoryChatRole::User, "Both?", &[])
+            .expect("save pair question");
+        add_history_chat_message_in_db(&ids(&["h1", "h3"]), HistoryChatRole::User, "Other", &[])
+            .expect("save other pair question");
+        add_history_chat_message_in_db(&ids(&["h1"]), HistoryChatRole::User, "Solo", &[])
+            .expect("save single question");
+
+        let pair = get_history_chat_from_db(&ids(&["h1", "h2", "h1"])).expect("load pair chat");
+        assert_eq!(pair.len(), 1);
+        assert_eq!(pair[0].content, "Both?");
+        assert_eq!(pair[0].history_ids, ids(&["h1", "h2"]));
+        let solo = get_history_chat_from_db(&ids(&["h1"])).expect("load single chat");
+        assert_eq!(solo.len(), 1);
+        assert_eq!(solo[0].content, "Solo");
+
+        let conn = get_db().expect("get db");
+        delete_history_chats_in_db(&conn, "h2").expect("delete chats for h2");
+        drop(conn);
+        assert!(get_history_chat_from_db(&ids(&["h1", "h2"]))
+            .expect("load pair chat")
+            .is_empty());
+        assert_eq!(
+            get_history_chat_from_db(&ids(&["h1", "h3"]))
+                .expect("load other pair chat")
+                .len(),
+            1
+        );
+    }
+}
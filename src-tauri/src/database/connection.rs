@@ -160,6 +160,43 @@ fn init_history_search_index(conn: &Connection) -> Result<(), String> {
     Ok(())
 }
 
+/// Create `processing_jobs` and apply its column migrations.
+pub(crate) fn create_processing_jobs_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS processing_jobs (
+            id TEXT PRIMARY KEY,
+            input_path TEXT NOT NULL,
+            output_path TEXT,
+            task_type TEXT NOT NULL,
+            user_prompt TEXT,
+            ffmpeg_command TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            progress REAL DEFAULT 0,
+            error_message TEXT,
+            created_at TEXT NOT NULL,
+            completed_at TEXT
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create processing_jobs table: {}", e))?;
+
+    // Migration: processing queue priority, stored command for re-runs, and FFmpeg log
+    conn.execute(
+        "ALTER TABLE processing_jobs ADD COLUMN priority INTEGER NOT NULL DEFAULT 0",
+        [],
+    )
+    .ok();
+    conn.execute(
+        "ALTER TABLE processing_jobs ADD COLUMN command_json TEXT",
+        [],
+    )
+    .ok();
+    conn.execute("ALTER TABLE processing_jobs ADD COLUMN log TEXT", [])
+        .ok();
+
+    Ok(())
+}
+
 /// Initialize the SQLite database
 pub fn init_database(app: &AppHandle) -> Result<(), String> {
     if DB_CONNECTION.get().is_some() {
@@ -175,6 +212,7 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
         .map_err(|e| format!("Failed to create app data directory: {}", e))?;
 
     let db_path = resolve_database_path(&app_data_dir)?;
+    super::secret_cipher::set_secret_key_dir(&app_data_dir);
 
     let conn = Connection::open(&db_path).map_err(|e| format!("Failed to open database: {}", e))?;
 
@@ -350,24 +388,7 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
         log::warn!("{}", e);
     }
 
-    // Create processing_jobs table
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS processing_jobs (
-            id TEXT PRIMARY KEY,
-            input_path TEXT NOT NULL,
-            output_path TEXT,
-            task_type TEXT NOT NULL,
-            user_prompt TEXT,
-            ffmpeg_command TEXT NOT NULL,
-            status TEXT NOT NULL DEFAULT 'pending',
-            progress REAL DEFAULT 0,
-            error_message TEXT,
-            created_at TEXT NOT NULL,
-            completed_at TEXT
-        )",
-        [],
-    )
-    .map_err(|e| format!("Failed to create processing_jobs table: {}", e))?;
+    create_processing_jobs_table(&conn)?;
 
     // Create processing_presets table
     conn.execute(
@@ -384,6 +405,23 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
     )
     .map_err(|e| format!("Failed to create processing_presets table: {}", e))?;
 
+    // Migration: deterministic FFmpeg argument templates for presets
+    conn.execute(
+        "ALTER TABLE processing_presets ADD COLUMN args_template TEXT",
+        [],
+    )
+    .ok();
+    conn.execute(
+        "ALTER TABLE processing_presets ADD COLUMN template_params TEXT",
+        [],
+    )
+    .ok();
+    conn.execute(
+        "ALTER TABLE processing_presets ADD COLUMN output_extension TEXT",
+        [],
+    )
+    .ok();
+
     // Create processing indexes
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_processing_jobs_created ON processing_jobs(created_at DESC)",
@@ -505,6 +543,18 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
     )
     .ok();
 
+    // Create plugin key/value and secret storage tables
+    super::create_plugin_store_tables(&conn)?;
+
+    // Create timed transcript storage for history entries
+    super::create_transcript_tables(&conn)?;
+
+    // Create per-item Q&A conversation storage
+    super::create_chat_tables(&conn)?;
+
+    // Create AI response cache and usage log
+    super::create_ai_usage_tables(&conn)?;
+
     DB_CONNECTION
         .set(Mutex::new(conn))
         .map_err(|_| "Database already initialized".to_string())?;
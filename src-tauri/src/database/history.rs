@@ -732,6 +732,12 @@ pub fn add_history_internal(
         [],
     )
     .ok();
+    conn.execute(
+        "DELETE FROM history_transcripts WHERE history_id NOT IN (SELECT id FROM history)",
+        [],
+    )
+    .ok();
+    super::delete_orphaned_history_chats_in_db(&conn).ok();
 
     Ok(id)
 }
@@ -1010,6 +1016,12 @@ pub fn delete_history_from_db(id: String) -> Result<(), String> {
         params![id.clone()],
     )
     .map_err(|e| format!("Failed to delete history collections: {}", e))?;
+    conn.execute(
+        "DELETE FROM history_transcripts WHERE history_id = ?1",
+        params![id.clone()],
+    )
+    .map_err(|e| format!("Failed to delete history transcript: {}", e))?;
+    super::delete_history_chats_in_db(&conn, &id)?;
     conn.execute("DELETE FROM history WHERE id = ?1", params![id])
         .map_err(|e| format!("Failed to delete history: {}", e))?;
     Ok(())
@@ -1021,6 +1033,10 @@ pub fn clear_history_from_db() -> Result<(), String> {
         .map_err(|e| format!("Failed to clear history tags: {}", e))?;
     conn.execute("DELETE FROM history_collections", [])
         .map_err(|e| format!("Failed to clear history collections: {}", e))?;
+    conn.execute("DELETE FROM history_transcripts", [])
+        .map_err(|e| format!("Failed to clear history transcripts: {}", e))?;
+    conn.execute("DELETE FROM history_chat_messages", [])
+        .map_err(|e| format!("Failed to clear history chats: {}", e))?;
     conn.execute("DELETE FROM history", [])
         .map_err(|e| format!("Failed to clear history: {}", e))?;
     Ok(())
@@ -1417,6 +1433,8 @@ mod tests {
             END;",
         )
         .expect("create tables");
+        crate::database::create_transcript_tables(&conn).expect("create transcript table");
+        crate::database::create_chat_tables(&conn).expect("create chat table");
         conn.execute("ALTER TABLE history ADD COLUMN summary TEXT", [])
             .ok();
         conn.execute("ALTER TABLE history ADD COLUMN time_range TEXT", [])
@@ -1754,4 +1772,61 @@ mod tests {
         let _ = fs::remove_file(&old_path);
         let _ = fs::remove_dir_all(old_path.parent().unwrap_or_else(|| Path::new("/")));
     }
+
+    #[test]
+    fn adding_history_drops_chats_about_any_removed_item() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        get_db()
+            .expect("get db")
+            .execute("DELETE FROM history_chat_messages", [])
+            .expect("clear chats");
+        let (first, second) = ("chat-a".to_string(), "chat-b".to_string());
+        insert_history_row(&first, "");
+        insert_history_row(&second, "");
+        let pair = vec![first.clone(), second.clone()];
+        let solo = vec![first.clone()];
+        crate::database::add_history_chat_message_in_db(
+            &pair,
+            crate::types::HistoryChatRole::User,
+            "Both?",
+            &[],
+        )
+        .expect("save pair question");
+        crate::database::add_history_chat_message_in_db(
+            &solo,
+            crate::types::HistoryChatRole::User,
+            "Solo?",
+            &[],
+        )
+        .expect("save single question");
+
+        get_db()
+            .expect("get db")
+            .execute("DELETE FROM history WHERE id = ?1", params![second])
+            .expect("remove second item");
+        add_history_internal(
+            "https://example.com/other".to_string(),
+            "other".to_string(),
+            None,
+            String::new(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("add history");
+
+        assert!(crate::database::get_history_chat_from_db(&pair)
+            .expect("load pair chat")
+            .is_empty());
+        assert_eq!(
+            crate::database::get_history_chat_from_db(&solo)
+                .expect("load single chat")
+                .len(),
+            1
+        );
+    }
 }
@@ -1,11 +1,20 @@
+mod ai_usage;
 mod channels;
+mod chat;
 mod connection;
 mod download_queue;
 mod history;
 mod logs;
+mod plugin_store;
+mod secret_cipher;
+mod transcripts;
 
+pub use ai_usage::*;
 pub use channels::*;
+pub use chat::*;
 pub use connection::*;
 pub use download_queue::*;
 pub use history::*;
 pub use logs::*;
+pub use plugin_store::*;
+pub use transcripts::*;
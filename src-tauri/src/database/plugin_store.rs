@@ -0,0 +1,479 @@
+use super::get_db;
+use super::secret_cipher::{is_encrypted_secret, open_plugin_secret, seal_plugin_secret};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde_json::Value;
+
+pub const PLUGIN_KV_DEFAULT_NAMESPACE: &str = "default";
+/// Secrets written by the app for `password` config fields.
+pub const PLUGIN_SECRET_SCOPE_CONFIG: &str = "config";
+/// Secrets written by the plugin itself through the bridge.
+pub const PLUGIN_SECRET_SCOPE_PLUGIN: &str = "plugin";
+
+const MAX_PLUGIN_STORE_KEY_BYTES: usize = 256;
+const MAX_PLUGIN_KV_VALUE_BYTES: usize = 1024 * 1024;
+const MAX_PLUGIN_KV_ENTRIES: i64 = 10_000;
+
+pub(crate) fn create_plugin_store_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS plugin_kv (
+            plugin_id TEXT NOT NULL,
+            namespace TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value_json TEXT NOT NULL,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (plugin_id, namespace, key)
+        );
+        CREATE TABLE IF NOT EXISTS plugin_secrets (
+            plugin_id TEXT NOT NULL,
+            scope TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (plugin_id, scope, key)
+        );",
+    )
+    .map_err(|e| format!("Failed to create plugin storage tables: {}", e))
+}
+
+fn validate_plugin_store_name(label: &str, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err(format!("Plugin storage {} cannot be empty", label));
+    }
+    if value.len() > MAX_PLUGIN_STORE_KEY_BYTES {
+        return Err(format!(
+            "Plugin storage {} exceeds {} bytes",
+            label, MAX_PLUGIN_STORE_KEY_BYTES
+        ));
+    }
+    if value.chars().any(char::is_control) {
+        return Err(format!(
+            "Plugin storage {} cannot contain control characters",
+            label
+        ));
+    }
+    Ok(())
+}
+
+fn validate_secret_scope(scope: &str) -> Result<(), String> {
+    if scope == PLUGIN_SECRET_SCOPE_CONFIG || scope == PLUGIN_SECRET_SCOPE_PLUGIN {
+        Ok(())
+    } else {
+        Err(format!("Invalid plugin secret scope: {}", scope))
+    }
+}
+
+pub fn get_plugin_kv_value_from_db(
+    plugin_id: &str,
+    namespace: &str,
+    key: &str,
+) -> Result<Option<Value>, String> {
+    validate_plugin_store_name("namespace", namespace)?;
+    validate_plugin_store_name("key", key)?;
+
+    let conn = get_db()?;
+    let result = conn.query_row(
+        "SELECT value_json FROM plugin_kv WHERE plugin_id = ?1 AND namespace = ?2 AND key = ?3",
+        params![plugin_id, namespace, key],
+        |row| row.get::<_, String>(0),
+    );
+
+    match result {
+        Ok(value_json) => serde_json::from_str(&value_json)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse plugin storage value: {}", e)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to read plugin storage value: {}", e)),
+    }
+}
+
+pub fn set_plugin_kv_value_in_db(
+    plugin_id: &str,
+    namespace: &str,
+    key: &str,
+    value: &Value,
+) -> Result<(), String> {
+    validate_plugin_store_name("namespace", namespace)?;
+    validate_plugin_store_name("key", key)?;
+    let value_json = serde_json::to_string(value)
+        .map_err(|e| format!("Failed to serialize plugin storage value: {}", e))?;
+    if value_json.len() > MAX_PLUGIN_KV_VALUE_BYTES {
+        return Err(format!(
+            "Plugin storage value exceeds {} bytes",
+            MAX_PLUGIN_KV_VALUE_BYTES
+        ));
+    }
+
+    let conn = get_db()?;
+    let existing_entries: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM plugin_kv WHERE plugin_id = ?1",
+            params![plugin_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to count plugin storage entries: {}", e))?;
+    let replaces_existing: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM plugin_kv WHERE plugin_id = ?1 AND namespace = ?2 AND key = ?3)",
+            params![plugin_id, namespace, key],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to read plugin storage value: {}", e))?;
+    if !replaces_existing && existing_entries >= MAX_PLUGIN_KV_ENTRIES {
+        return Err(format!(
+            "Plugin storage is limited to {} entries per plugin",
+            MAX_PLUGIN_KV_ENTRIES
+        ));
+    }
+
+    conn.execute(
+        "INSERT INTO plugin_kv (plugin_id, namespace, key, value_json, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(plugin_id, namespace, key) DO UPDATE SET
+            value_json = excluded.value_json,
+            updated_at = excluded.updated_at",
+        params![
+            plugin_id,
+            namespace,
+            key,
+            value_json,
+            Utc::now().timestamp()
+        ],
+    )
+    .map_err(|e| format!("Failed to save plugin storage value: {}", e))?;
+
+    Ok(())
+}
+
+pub fn delete_plugin_kv_value_from_db(
+    plugin_id: &str,
+    namespace: &str,
+    key: &str,
+) -> Result<bool, String> {
+    let conn = get_db()?;
+    let removed = conn
+        .execute(
+            "DELETE FROM plugin_kv WHERE plugin_id = ?1 AND namespace = ?2 AND key = ?3",
+            params![plugin_id, namespace, key],
+        )
+        .map_err(|e| format!("Failed to delete plugin storage value: {}", e))?;
+    Ok(removed > 0)
+}
+
+pub fn list_plugin_kv_keys_from_db(
+    plugin_id: &str,
+    namespace: &str,
+    prefix: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let conn = get_db()?;
+    let prefix = prefix.unwrap_or_default();
+    let mut stmt = conn
+        .prepare(
+            "SELECT key FROM plugin_kv
+             WHERE plugin_id = ?1 AND namespace = ?2 AND substr(key, 1, length(?3)) = ?3
+             ORDER BY key",
+        )
+        .map_err(|e| format!("Failed to prepare plugin storage query: {}", e))?;
+    let keys = stmt
+        .query_map(params![plugin_id, namespace, prefix], |row| {
+            row.get::<_, String>(0)
+        })
+        .map_err(|e| format!("Failed to list plugin storage keys: {}", e))?
+        .filter_map(|row| row.ok())
+        .collect();
+    Ok(keys)
+}
+
+pub fn clear_plugin_kv_from_db(plugin_id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "DELETE FROM plugin_kv WHERE plugin_id = ?1",
+        params![plugin_id],
+    )
+    .map_err(|e| format!("Failed to clear plugin storage: {}", e))?;
+    Ok(())
+}
+
+pub fn get_plugin_secret_from_db(
+    plugin_id: &str,
+    scope: &str,
+    key: &str,
+) -> Result<Option<String>, String> {
+    validate_secret_scope(scope)?;
+    validate_plugin_store_name("secret key", key)?;
+
+    let conn = get_db()?;
+    let result = conn.query_row(
+        "SELECT value FROM plugin_secrets WHERE plugin_id = ?1 AND scope = ?2 AND key = ?3",
+        params![plugin_id, scope, key],
+        |row| row.get::<_, String>(0),
+    );
+    drop(conn);
+
+    match result {
+        Ok(stored) => open_plugin_secret(plugin_id, scope, key, &stored).map(Some),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to read plugin secret: {}", e)),
+    }
+}
+
+pub fn set_plugin_secret_in_db(
+    plugin_id: &str,
+    scope: &str,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    validate_secret_scope(scope)?;
+    validate_plugin_store_name("secret key", key)?;
+    if value.len() > MAX_PLUGIN_KV_VALUE_BYTES {
+        return Err(format!(
+            "Plugin secret exceeds {} bytes",
+            MAX_PLUGIN_KV_VALUE_BYTES
+        ));
+    }
+
+    let sealed = seal_plugin_secret(plugin_id, scope, key, value)?;
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO plugin_secrets (plugin_id, scope, key, value, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(plugin_id, scope, key) DO UPDATE SET
+            value = excluded.value,
+            updated_at = excluded.updated_at",
+        params![plugin_id, scope, key, sealed, Utc::now().timestamp()],
+    )
+    .map_err(|e| format!("Failed to save plugin secret: {}", e))?;
+
+    Ok(())
+}
+
+pub fn delete_plugin_secret_from_db(
+    plugin_id: &str,
+    scope: &str,
+    key: &str,
+) -> Result<bool, String> {
+    validate_secret_scope(scope)?;
+
+    let conn = get_db()?;
+    let removed = conn
+        .execute(
+            "DELETE FROM plugin_secrets WHERE plugin_id = ?1 AND scope = ?2 AND key = ?3",
+            params![plugin_id, scope, key],
+        )
+        .map_err(|e| format!("Failed to delete plugin secret: {}", e))?;
+    Ok(removed > 0)
+}
+
+pub fn list_plugin_secret_keys_from_db(
+    plugin_id: &str,
+    scope: &str,
+) -> Result<Vec<String>, String> {
+    validate_secret_scope(scope)?;
+
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare("SELECT key FROM plugin_secrets WHERE plugin_id = ?1 AND scope = ?2 ORDER BY key")
+        .map_err(|e| format!("Failed to prepare plugin secret query: {}", e))?;
+    let keys = stmt
+        .query_map(params![plugin_id, scope], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Failed to list plugin secrets: {}", e))?
+        .filter_map(|row| row.ok())
+        .collect();
+    Ok(keys)
+}
+
+/// Encrypt secrets written in plaintext by older versions. Returns how many were rewritten.
+pub fn encrypt_legacy_plugin_secrets_in_db() -> Result<usize, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare("SELECT plugin_id, scope, key, value FROM plugin_secrets")
+        .map_err(|e| format!("Failed to prepare plugin secret query: {}", e))?;
+    let legacy = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to list plugin secrets: {}", e))?
+        .filter_map(|row| row.ok())
+        .filter(|(_, _, _, value)| !is_encrypted_secret(value))
+        .collect::<Vec<_>>();
+    drop(stmt);
+    drop(conn);
+
+    let mut sealed_rows = Vec::with_capacity(legacy.len());
+    for (plugin_id, scope, key, value) in legacy {
+        let sealed = seal_plugin_secret(&plugin_id, &scope, &key, &value)?;
+        sealed_rows.push((plugin_id, scope, key, value, sealed));
+    }
+
+    let conn = get_db()?;
+    let mut rewritten = 0;
+    for (plugin_id, scope, key, value, sealed) in sealed_rows {
+        // Skip rows that changed while the key was loading.
+        rewritten += conn
+            .execute(
+                "UPDATE plugin_secrets SET value = ?1
+                 WHERE plugin_id = ?2 AND scope = ?3 AND key = ?4 AND value = ?5",
+                params![sealed, plugin_id, scope, key, value],
+            )
+            .map_err(|e| format!("Failed to encrypt plugin secret: {}", e))?;
+    }
+    Ok(rewritten)
+}
+
+pub fn clear_plugin_secrets_from_db(plugin_id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "DELETE FROM plugin_secrets WHERE plugin_id = ?1",
+        params![plugin_id],
+    )
+    .map_err(|e| format!("Failed to clear plugin secrets: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    fn ensure_test_plugin_store_tables() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+
+        let conn = get_db().expect("get db");
+        create_plugin_store_tables(&conn).expect("create plugin store tables");
+        conn.execute_batch("DELETE FROM plugin_kv; DELETE FROM plugin_secrets;")
+            .expect("reset plugin store tables");
+    }
+
+    #[test]
+    fn plugin_kv_values_are_namespaced_per_plugin() {
+        let _guard = db_test_guard();
+        ensure_test_plugin_store_tables();
+
+        set_plugin_kv_value_in_db("plugin.a", "state", "cursor", &json!({ "page": 2 }))
+            .expect("set value");
+        set_plugin_kv_value_in_db("plugin.a", "state", "seen:1", &json!(true)).expect("set value");
+        set_plugin_kv_value_in_db("plugin.b", "state", "cursor", &json!("other"))
+            .expect("set value");
+
+        assert_eq!(
+            get_plugin_kv_value_from_db("plugin.a", "state", "cursor").expect("get value"),
+            Some(json!({ "page": 2 }))
+        );
+        assert_eq!(
+            list_plugin_kv_keys_from_db("plugin.a", "state", Some("seen:")).expect("list keys"),
+            vec!["seen:1".to_string()]
+        );
+        assert!(delete_plugin_kv_value_from_db("plugin.a", "state", "cursor").expect("delete"));
+        assert_eq!(
+            get_plugin_kv_value_from_db("plugin.a", "state", "cursor").expect("get value"),
+            None
+        );
+
+        clear_plugin_kv_from_db("plugin.a").expect("clear");
+        assert!(list_plugin_kv_keys_from_db("plugin.a", "state", None)
+            .expect("list keys")
+            .is_empty());
+        assert_eq!(
+            get_plugin_kv_value_from_db("plugin.b", "state", "cursor").expect("get value"),
+            Some(json!("other"))
+        );
+    }
+
+    #[test]
+    fn plugin_secrets_keep_config_and_plugin_scopes_apart() {
+        let _guard = db_test_guard();
+        ensure_test_plugin_store_tables();
+
+        set_plugin_secret_in_db(
+            "plugin.a",
+            PLUGIN_SECRET_SCOPE_CONFIG,
+            "token",
+            "from-config",
+        )
+        .expect("set config secret");
+        set_plugin_secret_in_db(
+            "plugin.a",
+            PLUGIN_SECRET_SCOPE_PLUGIN,
+            "token",
+            "from-plugin",
+        )
+        .expect("set plugin secret");
+
+        assert_eq!(
+            get_plugin_secret_from_db("plugin.a", PLUGIN_SECRET_SCOPE_CONFIG, "token")
+                .expect("get secret")
+                .as_deref(),
+            Some("from-config")
+        );
+        assert_eq!(
+            list_plugin_secret_keys_from_db("plugin.a", PLUGIN_SECRET_SCOPE_PLUGIN)
+                .expect("list secrets"),
+            vec!["token".to_string()]
+        );
+        assert!(set_plugin_secret_in_db("plugin.a", "other", "token", "x").is_err());
+
+        clear_plugin_secrets_from_db("plugin.a").expect("clear");
+        assert_eq!(
+            get_plugin_secret_from_db("plugin.a", PLUGIN_SECRET_SCOPE_PLUGIN, "token")
+                .expect("get secret"),
+            None
+        );
+    }
+
+    #[test]
+    fn plugin_secrets_are_encrypted_at_rest() {
+        let _guard = db_test_guard();
+        ensure_test_plugin_store_tables();
+
+        set_plugin_secret_in_db("plugin.a", PLUGIN_SECRET_SCOPE_CONFIG, "token", "s3cret")
+            .expect("set secret");
+        {
+            let conn = get_db().expect("get db");
+            conn.execute(
+                "INSERT INTO plugin_secrets (plugin_id, scope, key, value, updated_at)
+                 VALUES ('plugin.a', 'plugin', 'legacy', 'plain-old', 0)",
+                [],
+            )
+            .expect("insert legacy secret");
+        }
+        let stored_values = || {
+            let conn = get_db().expect("get db");
+            let mut stmt = conn
+                .prepare("SELECT value FROM plugin_secrets ORDER BY key")
+                .expect("prepare");
+            stmt.query_map([], |row| row.get::<_, String>(0))
+                .expect("query")
+                .filter_map(|row| row.ok())
+                .collect::<Vec<_>>()
+        };
+        assert!(!stored_values()[1].contains("s3cret"));
+
+        assert_eq!(encrypt_legacy_plugin_secrets_in_db().expect("encrypt"), 1);
+        assert!(stored_values()
+            .iter()
+            .all(|value| is_encrypted_secret(value)));
+        assert_eq!(
+            get_plugin_secret_from_db("plugin.a", PLUGIN_SECRET_SCOPE_PLUGIN, "legacy")
+                .expect("get secret")
+                .as_deref(),
+            Some("plain-old")
+        );
+        assert_eq!(
+            get_plugin_secret_from_db("plugin.a", PLUGIN_SECRET_SCOPE_CONFIG, "token")
+                .expect("get secret")
+                .as_deref(),
+            Some("s3cret")
+        );
+        assert_eq!(encrypt_legacy_plugin_secrets_in_db().expect("encrypt"), 0);
+    }
+}
@@ -0,0 +1,267 @@
+//! Encryption at rest for values in the `plugin_secrets` table.
+//!
+//! Values are sealed with ChaCha20-Poly1305 and bound to their `(plugin_id, scope, key)` row.
+//! The 256-bit data key lives in the OS keychain (macOS Keychain, Windows Credential Manager,
+//! Secret Service on Linux), so a copied or backed-up `youwee.db` does not expose secrets.
+//! When no keychain is reachable, such as a headless Linux session or a sandbox without the
+//! Secret Service portal, the key is kept in `plugin_secrets.key` next to the database with
+//! owner-only permissions. That still keeps secrets out of database copies, but not away from
+//! other software running as the same user.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+const KEYCHAIN_SERVICE: &str = "com.vanloctech.youwee";
+const KEYCHAIN_ACCOUNT: &str = "plugin-secrets-key";
+const KEY_FILE_NAME: &str = "plugin_secrets.key";
+const NONCE_LEN: usize = 12;
+
+static KEY_DIR: OnceLock<PathBuf> = OnceLock::new();
+static DATA_KEY: Mutex<Option<Key>> = Mutex::new(None);
+
+/// Remember where the fallback key file goes. Called once the database directory is known.
+pub(crate) fn set_secret_key_dir(dir: &Path) {
+    let _ = KEY_DIR.set(dir.to_path_buf());
+}
+
+pub(crate) fn is_encrypted_secret(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+fn secret_aad(plugin_id: &str, scope: &str, key: &str) -> Vec<u8> {
+    format!("{}\0{}\0{}", plugin_id, scope, key).into_bytes()
+}
+
+fn encode_key(key: &Key) -> String {
+    base64::engine::general_purpose::STANDARD.encode(key)
+}
+
+fn decode_key(encoded: &str) -> Result<Key, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Invalid plugin secret key: {}", e))?;
+    if bytes.len() != 32 {
+        return Err("Invalid plugin secret key length".to_string());
+    }
+    Ok(Key::clone_from_slice(&bytes))
+}
+
+fn key_file_path() -> Result<PathBuf, String> {
+    KEY_DIR
+        .get()
+        .map(|dir| dir.join(KEY_FILE_NAME))
+        .ok_or_else(|| "Plugin secret storage is not initialized".to_string())
+}
+
+fn read_key_file() -> Result<Option<Key>, String> {
+    let path = key_file_path()?;
+    match std::fs::read_to_string(&path) {
+        Ok(encoded) => decode_key(&encoded).map(Some),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!(
+            "Failed to read plugin secret key {}: {}",
+            path.display(),
+            e
+        )),
+    }
+}
+
+fn write_key_file(key: &Key) -> Result<(), String> {
+    use std::io::Write;
+
+    let path = key_file_path()?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path).map_err(|e| {
+        format!(
+            "Failed to create plugin secret key {}: {}",
+            path.display(),
+            e
+        )
+    })?;
+    file.write_all(encode_key(key).as_bytes()).map_err(|e| {
+        format!(
+            "Failed to write plugin secret key {}: {}",
+            path.display(),
+            e
+        )
+    })
+}
+
+fn keychain_key() -> Result<Key, String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))?;
+    match entry.get_password() {
+        Ok(encoded) => decode_key(&encoded),
+        Err(keyring::Error::NoEntry) => {
+            // Adopt a key created while the keychain was unavailable so existing values still open.
+            let file_key = read_key_file().ok().flatten();
+            let key = file_key.unwrap_or_else(|| ChaCha20Poly1305::generate_key(&mut OsRng));
+            entry
+                .set_password(&encode_key(&key))
+                .map_err(|e| format!("Failed to save key to keychain: {}", e))?;
+            if file_key.is_some() {
+                if let Ok(path) = key_file_path() {
+                    std::fs::remove_file(path).ok();
+                }
+            }
+            Ok(key)
+        }
+        Err(e) => Err(format!("Failed to read key from keychain: {}", e)),
+    }
+}
+
+fn file_key() -> Result<Key, String> {
+    if let Some(key) = read_key_file()? {
+        return Ok(key);
+    }
+    let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+    write_key_file(&key)?;
+    Ok(key)
+}
+
+fn data_key() -> Result<Key, String> {
+    if cfg!(test) {
+        return Ok(Key::clone_from_slice(&[7; 32]));
+    }
+    let mut cached = DATA_KEY
+        .lock()
+        .map_err(|_| "Plugin secret key lock is poisoned".to_string())?;
+    if let Some(key) = cached.as_ref() {
+        return Ok(*key);
+    }
+    let key = match keychain_key() {
+        Ok(key) => key,
+        Err(error) => {
+            log::warn!(
+                "OS keychain unavailable for plugin secrets, using a local key file: {}",
+                error
+            );
+            file_key()?
+        }
+    };
+    *cached = Some(key);
+    Ok(key)
+}
+
+fn seal_with_key(
+    key: &Key,
+    plugin_id: &str,
+    scope: &str,
+    secret_key: &str,
+    value: &str,
+) -> Result<String, String> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let aad = secret_aad(plugin_id, scope, secret_key);
+    let ciphertext = ChaCha20Poly1305::new(key)
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: value.as_bytes(),
+                aad: &aad,
+            },
+        )
+        .map_err(|_| "Failed to encrypt plugin secret".to_string())?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(format!(
+        "{}{}",
+        ENCRYPTED_PREFIX,
+        base64::engine::general_purpose::STANDARD.encode(sealed)
+    ))
+}
+
+fn open_with_key(
+    key: &Key,
+    plugin_id: &str,
+    scope: &str,
+    secret_key: &str,
+    stored: &str,
+) -> Result<String, String> {
+    let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+        // Written before secrets were encrypted; rewritten on the next startup.
+        return Ok(stored.to_string());
+    };
+    let sealed = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Invalid encrypted plugin secret: {}", e))?;
+    if sealed.len() < NONCE_LEN {
+        return Err("Invalid encrypted plugin secret".to_string());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let aad = secret_aad(plugin_id, scope, secret_key);
+    let plaintext = ChaCha20Poly1305::new(key)
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| {
+            "Failed to decrypt plugin secret. The encryption key may have changed.".to_string()
+        })?;
+    String::from_utf8(plaintext).map_err(|_| "Plugin secret is not valid UTF-8".to_string())
+}
+
+pub(crate) fn seal_plugin_secret(
+    plugin_id: &str,
+    scope: &str,
+    key: &str,
+    value: &str,
+) -> Result<String, String> {
+    seal_with_key(&data_key()?, plugin_id, scope, key, value)
+}
+
+pub(crate) fn open_plugin_secret(
+    plugin_id: &str,
+    scope: &str,
+    key: &str,
+    stored: &str,
+) -> Result<String, String> {
+    if !is_encrypted_secret(stored) {
+        return Ok(stored.to_string());
+    }
+    open_with_key(&data_key()?, plugin_id, scope, key, stored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_secrets_round_trip_and_stay_bound_to_their_row() {
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let sealed = seal_with_key(&key, "plugin.a", "config", "token", "s3cret").unwrap();
+        assert!(is_encrypted_secret(&sealed));
+        assert!(!sealed.contains("s3cret"));
+        assert_eq!(
+            open_with_key(&key, "plugin.a", "config", "token", &sealed).unwrap(),
+            "s3cret"
+        );
+        assert!(open_with_key(&key, "plugin.b", "config", "token", &sealed).is_err());
+        assert!(open_with_key(&key, "plugin.a", "plugin", "token", &sealed).is_err());
+
+        let other_key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        assert!(open_with_key(&other_key, "plugin.a", "config", "token", &sealed).is_err());
+    }
+
+    #[test]
+    fn legacy_plaintext_secrets_still_open() {
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        assert_eq!(
+            open_with_key(&key, "plugin.a", "config", "token", "plain").unwrap(),
+            "plain"
+        );
+    }
+}
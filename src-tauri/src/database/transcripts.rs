@@ -0,0 +1,153 @@
+use super::get_db;
+use crate::types::{HistoryTranscript, TranscriptSegment, TranscriptSource};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+pub(crate) fn create_transcript_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS history_transcripts (
+            history_id TEXT PRIMARY KEY,
+            language TEXT,
+            source TEXT NOT NULL,
+            segments_json TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        );",
+    )
+    .map_err(|e| format!("Failed to create transcript table: {}", e))
+}
+
+/// Store (or replace) the timed transcript of a history entry
+pub fn save_history_transcript_in_db(
+    history_id: &str,
+    language: Option<&str>,
+    source: TranscriptSource,
+    segments: &[TranscriptSegment],
+) -> Result<(), String> {
+    if segments.is_empty() {
+        return Err("Transcript has no segments".to_string());
+    }
+    let segments_json = serde_json::to_string(segments)
+        .map_err(|e| format!("Failed to serialize transcript: {}", e))?;
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO history_transcripts
+            (history_id, language, source, segments_json, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            history_id,
+            language,
+            source.as_str(),
+            segments_json,
+            Utc::now().timestamp()
+        ],
+    )
+    .map_err(|e| format!("Failed to save transcript: {}", e))?;
+    Ok(())
+}
+
+pub fn get_history_transcript_from_db(
+    history_id: &str,
+) -> Result<Option<HistoryTranscript>, String> {
+    let conn = get_db()?;
+    let result = conn.query_row(
+        "SELECT language, source, segments_json, updated_at
+         FROM history_transcripts WHERE history_id = ?1",
+        params![history_id],
+        |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        },
+    );
+    let (language, source, segments_json, updated_at) = match result {
+        Ok(row) => row,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(format!("Failed to load transcript: {}", e)),
+    };
+    let source = TranscriptSource::from_str(&source)
+        .ok_or_else(|| format!("Unknown transcript source: {}", source))?;
+    let segments = serde_json::from_str(&segments_json)
+        .map_err(|e| format!("Failed to parse stored transcript: {}", e))?;
+    let updated_at = chrono::DateTime::from_timestamp(updated_at, 0)
+        .map(|d| d.to_rfc3339())
+        .unwrap_or_default();
+    Ok(Some(HistoryTranscript {
+        history_id: history_id.to_string(),
+        language,
+        source,
+        segments,
+        updated_at,
+    }))
+}
+
+pub fn delete_history_transcript_from_db(history_id: &str) -> Result<bool, String> {
+    let conn = get_db()?;
+    let deleted = conn
+        .execute(
+            "DELETE FROM history_transcripts WHERE history_id = ?1",
+            params![history_id],
+        )
+        .map_err(|e| format!("Failed to delete transcript: {}", e))?;
+    Ok(deleted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn ensure_test_transcript_tables() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+
+        let conn = get_db().expect("get db");
+        create_transcript_tables(&conn).expect("create transcript table");
+        conn.execute_batch("DELETE FROM history_transcripts;")
+            .expect("reset transcript table");
+    }
+
+    #[test]
+    fn transcripts_round_trip_and_replace_per_history_entry() {
+        let _guard = db_test_guard();
+        ensure_test_transcript_tables();
+
+        let segment = |start_ms, text: &str| TranscriptSegment {
+            start_ms,
+            end_ms: start_ms + 1000,
+            text: text.to_string(),
+        };
+        save_history_transcript_in_db(
+            "h1",
+            Some("en"),
+            TranscriptSource::AutoCaptions,
+            &[segment(0, "hello"), segment(1000, "world")],
+        )
+        .expect("save transcript");
+        save_history_transcript_in_db(
+            "h1",
+            None,
+            TranscriptSource::Whisper,
+            &[segment(0, "hello world")],
+        )
+        .expect("replace transcript");
+
+        let stored = get_history_transcript_from_db("h1")
+            .expect("load transcript")
+            .expect("transcript exists");
+        assert_eq!(stored.source, TranscriptSource::Whisper);
+        assert_eq!(stored.language, None);
+        assert_eq!(stored.segments, vec![segment(0, "hello world")]);
+
+        assert!(save_history_transcript_in_db("h2", None, TranscriptSource::Whisper, &[]).is_err());
+        assert!(delete_history_transcript_from_db("h1").expect("delete"));
+        assert!(get_history_transcript_from_db("h1")
+            .expect("load transcript")
+            .is_none());
+    }
+}
@@ -281,13 +281,27 @@ pub fn run() {
             }
 
             // Initialize the database
-            if let Err(e) = database::init_database(&app.handle()) {
-                log::error!("Failed to initialize database: {}", e);
+            match database::init_database(&app.handle()) {
+                Ok(()) => {
+                    // Recover processing jobs from the last session and start the queue
+                    commands::start_processing_queue(app.handle().clone());
+
+                    if let Err(e) = services::migrate_plugin_secrets_internal(&app.handle()) {
+                        log::warn!("Failed to migrate plugin secrets: {}", e);
+                    }
+                }
+                Err(e) => log::error!("Failed to initialize database: {}", e),
             }
 
             // Start background channel polling
             services::polling::start_polling(app.handle().clone());
 
+            // Pin signers of older installs, then check for plugin updates in the background
+            if let Err(e) = services::pin_legacy_plugin_signers_internal(&app.handle()) {
+                log::warn!("Failed to pin plugin signers: {}", e);
+            }
+            services::start_plugin_update_checks(app.handle().clone());
+
             // Setup system tray
             setup_tray(app)?;
 
@@ -350,6 +364,9 @@ pub fn run() {
             commands::set_ffmpeg_source_cmd,
             commands::check_ffmpeg_update,
             commands::download_ffmpeg,
+            commands::check_local_whisper,
+            commands::list_local_whisper_models,
+            commands::download_local_whisper_model,
             commands::get_ffmpeg_path_for_ytdlp,
             // Deno commands
             commands::check_deno,
@@ -383,6 +400,12 @@ pub fn run() {
             commands::assign_history_collections,
             commands::remove_history_tag,
             commands::remove_history_from_collection,
+            commands::get_history_transcript,
+            commands::export_history_transcript,
+            commands::delete_history_transcript,
+            commands::ask_history_question,
+            commands::get_history_chat,
+            commands::clear_history_chat,
             commands::open_file_location,
             commands::check_file_exists,
             // Asset scope & history helpers
@@ -391,6 +414,7 @@ pub fn run() {
             commands::rename_downloaded_file,
             commands::sync_history_renamed_entry,
             commands::split_media_segments,
+            commands::propose_media_split,
             commands::update_summary,
             commands::add_summary_only_history,
             commands::open_macos_privacy_settings,
@@ -402,8 +426,11 @@ pub fn run() {
             commands::generate_summary_with_options,
             commands::cancel_summary_generation,
             commands::generate_ai_response,
+            commands::generate_video_insights,
             commands::get_ai_models,
             commands::get_summary_languages,
+            commands::get_ai_usage_report,
+            commands::clear_ai_response_cache,
             // Processing commands
             commands::get_video_metadata,
             commands::detect_shot_changes,
@@ -411,9 +438,20 @@ pub fn run() {
             commands::get_processing_attachment_info,
             commands::generate_processing_command,
             commands::generate_quick_action_command,
+            commands::generate_subtitle_embed_command,
+            commands::generate_chapter_embed_command,
             commands::execute_ffmpeg_command,
+            commands::start_processing_batch,
+            commands::run_processing_pipeline,
             commands::cancel_ffmpeg,
             commands::get_processing_history,
+            commands::get_processing_job_log,
+            commands::enqueue_processing_job,
+            commands::get_processing_queue,
+            commands::set_processing_job_priority,
+            commands::retry_processing_job,
+            commands::get_processing_queue_settings,
+            commands::set_processing_queue_settings,
             commands::save_processing_job,
             commands::update_processing_job,
             commands::delete_processing_job,
@@ -421,15 +459,23 @@ pub fn run() {
             commands::get_processing_presets,
             commands::save_processing_preset,
             commands::delete_processing_preset,
+            commands::export_processing_preset,
+            commands::import_processing_preset,
             commands::generate_video_preview,
             commands::generate_video_thumbnail,
             commands::generate_audio_preview,
             commands::check_preview_exists,
             commands::cleanup_previews,
+            commands::get_ffmpeg_capabilities,
+            commands::generate_contact_sheet,
+            commands::generate_thumbnail_sprites,
             // Whisper commands
             commands::transcribe_video_with_whisper,
             commands::transcribe_url_with_whisper,
             commands::generate_subtitles_with_whisper,
+            commands::translate_subtitle_file,
+            commands::retime_subtitle_file,
+            commands::merge_subtitle_files,
             // Metadata commands
             commands::fetch_metadata,
             commands::extract_data_rows,
@@ -441,6 +487,10 @@ pub fn run() {
             commands::install_plugin_package,
             commands::list_plugin_store_entries,
             commands::prepare_plugin_store_package,
+            commands::check_plugin_updates,
+            commands::prepare_plugin_update,
+            commands::apply_plugin_update,
+            commands::rollback_plugin,
             commands::uninstall_plugin,
             commands::attach_plugin_workspace,
             commands::create_plugin_workspace,
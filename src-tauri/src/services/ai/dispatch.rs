@@ -1,6 +1,14 @@
-use super::providers::generate_raw_for_provider;
+use super::providers::{
+    generate_raw_for_provider, generate_raw_with_anthropic, generate_raw_with_custom,
+};
+use super::streaming::stream_raw_for_provider;
+use super::usage::with_response_cache;
 use super::*;
 
+/// Sampling temperatures the providers are called with; part of the response cache key
+const SUMMARY_TEMPERATURE: f64 = 0.7;
+const RAW_TEMPERATURE: f64 = 0.3;
+
 pub async fn generate_summary(
     config: &AIConfig,
     transcript: &str,
@@ -39,6 +47,9 @@ pub async fn generate_summary_custom(
     .await
 }
 
+/// Summarize `transcript`, splitting long ones into chunk summaries that are combined at
+/// the end. Chunk work uses the `ChunkSummary` model route and the summary the user reads
+/// uses `FinalSummary`.
 pub async fn generate_summary_custom_with_hooks(
     config: &AIConfig,
     transcript: &str,
@@ -53,16 +64,36 @@ pub async fn generate_summary_custom_with_hooks(
         return Err(AIError::NoTranscript);
     }
 
+    let final_config = config.for_task(AITask::FinalSummary);
     let long_summary_chars = long_summary_words_to_chars(long_summary_words);
-    if !should_use_long_summary_with_limit(transcript, long_summary_chars) {
-        return generate_summary_custom_once(config, transcript, style, language, title).await;
-    }
-
-    let chunks = chunk_transcript(transcript, long_summary_chars);
+    let chunks = if should_use_long_summary_with_limit(transcript, long_summary_chars) {
+        chunk_transcript(transcript, long_summary_chars)
+    } else {
+        Vec::new()
+    };
     if chunks.len() <= 1 {
-        return generate_summary_custom_once(config, transcript, style, language, title).await;
+        let prompt = build_prompt(transcript, style, language, title);
+        if hooks.wants_streaming() {
+            return with_response_cache(
+                &final_config,
+                &prompt,
+                SUMMARY_TEMPERATURE,
+                |cached| hooks.emit_delta(cached),
+                stream_raw_for_provider(&final_config, &prompt, SUMMARY_TEMPERATURE, hooks, true),
+            )
+            .await;
+        }
+        return with_response_cache(
+            &final_config,
+            &prompt,
+            SUMMARY_TEMPERATURE,
+            |_| {},
+            generate_summary_custom_once(&final_config, transcript, style, language, title),
+        )
+        .await;
     }
 
+    let chunk_config = config.for_task(AITask::ChunkSummary);
     let chunk_count = chunks.len();
     let resolved_format = resolve_long_summary_format(long_summary_format, style);
     let mut chunk_summaries = Vec::with_capacity(chunk_count);
@@ -88,7 +119,7 @@ pub async fn generate_summary_custom_with_hooks(
             title,
             resolved_format,
         );
-        let result = generate_raw_for_provider(config, &prompt).await?;
+        let result = generate_raw_with_hooks(&chunk_config, &prompt, hooks, false).await?;
         chunk_summaries.push(ChunkSummary {
             index: index + 1,
             summary: result.summary,
@@ -102,7 +133,7 @@ pub async fn generate_summary_custom_with_hooks(
         chunk_count,
     });
     let chunk_summaries = reduce_chunk_summaries_for_composition(
-        config,
+        &chunk_config,
         chunk_summaries,
         style,
         language,
@@ -120,7 +151,41 @@ pub async fn generate_summary_custom_with_hooks(
             build_parts_prompt(&chunk_summaries, style, language, title)
         }
     };
-    generate_raw_for_provider(config, &prompt).await
+    generate_raw_with_hooks(&final_config, &prompt, hooks, true).await
+}
+
+/// Raw completion that streams when the caller listens for text or may cancel, so a
+/// cancel aborts the request instead of waiting for it to finish
+async fn generate_raw_with_hooks(
+    config: &AIConfig,
+    prompt: &str,
+    hooks: &LongSummaryHooks<'_>,
+    emit_deltas: bool,
+) -> Result<SummaryResult, AIError> {
+    if hooks.wants_streaming() {
+        let on_cache_hit = |cached: &str| {
+            if emit_deltas {
+                hooks.emit_delta(cached);
+            }
+        };
+        with_response_cache(
+            config,
+            prompt,
+            RAW_TEMPERATURE,
+            on_cache_hit,
+            stream_raw_for_provider(config, prompt, RAW_TEMPERATURE, hooks, emit_deltas),
+        )
+        .await
+    } else {
+        with_response_cache(
+            config,
+            prompt,
+            RAW_TEMPERATURE,
+            |_| {},
+            generate_raw_for_provider(config, prompt),
+        )
+        .await
+    }
 }
 
 async fn reduce_chunk_summaries_for_composition(
@@ -154,7 +219,7 @@ async fn reduce_chunk_summaries_for_composition(
                 batch_index + 1,
                 batch_count,
             );
-            let result = generate_raw_for_provider(config, &prompt).await?;
+            let result = generate_raw_with_hooks(config, &prompt, hooks, false).await?;
             reduced.push(ChunkSummary {
                 index: batch_index + 1,
                 summary: result.summary,
@@ -284,6 +349,29 @@ async fn generate_summary_custom_once(
             )
             .await
         }
+        AIProvider::Anthropic => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            generate_raw_with_anthropic(
+                api_key,
+                &config.model,
+                &build_prompt(transcript, style, language, title),
+                0.7,
+                config.timeout_seconds,
+                config.summary_max_tokens,
+            )
+            .await
+        }
+        AIProvider::Custom => {
+            generate_raw_with_custom(
+                config.custom_endpoint()?,
+                &config.model,
+                &build_prompt(transcript, style, language, title),
+                0.7,
+                config.timeout_seconds,
+                config.summary_max_tokens,
+            )
+            .await
+        }
     }
 }
 
@@ -291,12 +379,27 @@ pub async fn generate_raw(config: &AIConfig, prompt: &str) -> Result<SummaryResu
     if prompt.trim().is_empty() {
         return Err(AIError::NoTranscript);
     }
-    generate_raw_for_provider(config, prompt).await
+    with_response_cache(
+        config,
+        prompt,
+        RAW_TEMPERATURE,
+        |_| {},
+        generate_raw_for_provider(config, prompt),
+    )
+    .await
 }
 
 pub async fn test_connection(config: &AIConfig) -> Result<String, AIError> {
     let test_transcript = "This is a test video about programming tutorials.";
-    let result = generate_summary(config, test_transcript, None).await?;
+    // Bypass model routing so the test checks the main provider and model
+    let result = generate_summary_custom_once(
+        config,
+        test_transcript,
+        &config.summary_style,
+        &config.summary_language,
+        None,
+    )
+    .await?;
     Ok(format!(
         "Connection successful! Using {} with model {}",
         result.provider, result.model
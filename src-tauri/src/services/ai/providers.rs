@@ -5,6 +5,10 @@ use std::time::Duration;
 const DEFAULT_AI_TIMEOUT_SECONDS: u64 = 120;
 const MIN_AI_TIMEOUT_SECONDS: u64 = 30;
 const MAX_AI_TIMEOUT_SECONDS: u64 = 60 * 60;
+pub(super) const ANTHROPIC_MESSAGES_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+/// The Messages API requires `max_tokens`; used when no summary limit is configured
+const ANTHROPIC_DEFAULT_MAX_TOKENS: u32 = 8_192;
 /// The most times we will auto-correct rejected sampling parameters before
 /// giving up. Only `temperature` and `max_tokens` can be adjusted, so two
 /// corrections is already the practical ceiling; the extra margin is a cheap
@@ -13,9 +17,9 @@ const MAX_AI_TIMEOUT_SECONDS: u64 = 60 * 60;
 /// This counts *corrections*, not total requests: each correction is followed
 /// by another attempt, so the worst case is `MAX_OPENAI_PARAM_ADJUSTMENTS + 1`
 /// requests (the initial attempt plus one send per correction).
-const MAX_OPENAI_PARAM_ADJUSTMENTS: usize = 3;
+pub(super) const MAX_OPENAI_PARAM_ADJUSTMENTS: usize = 3;
 
-fn normalized_summary_max_tokens(custom_max_tokens: Option<u32>) -> Option<u32> {
+pub(super) fn normalized_summary_max_tokens(custom_max_tokens: Option<u32>) -> Option<u32> {
     custom_max_tokens.filter(|value| *value > 0)
 }
 
@@ -50,7 +54,7 @@ fn openai_is_legacy_model(model: &str) -> bool {
 ///
 /// This is only our default guess. If OpenAI rejects the chosen parameters,
 /// `post_openai_chat()` automatically retries with the corrected ones.
-fn apply_openai_sampling_params(
+pub(super) fn apply_openai_sampling_params(
     body: &mut serde_json::Value,
     model: &str,
     temperature: f64,
@@ -76,7 +80,10 @@ fn apply_openai_sampling_params(
 /// reasoning models reject `max_tokens` (swap to `max_completion_tokens`),
 /// while some OpenAI-compatible proxies reject `max_completion_tokens` (swap
 /// back to `max_tokens`). Matching either field name covers both cases.
-fn adjust_openai_request(body: &mut serde_json::Value, error: &OpenAIErrorDetail) -> bool {
+pub(super) fn adjust_openai_request(
+    body: &mut serde_json::Value,
+    error: &OpenAIErrorDetail,
+) -> bool {
     let Some(fields) = body.as_object_mut() else {
         return false;
     };
@@ -165,7 +172,7 @@ fn summary_max_tokens_for_config(config: &AIConfig) -> Option<u32> {
     normalized_summary_max_tokens(config.summary_max_tokens)
 }
 
-fn normalized_timeout_seconds(timeout_seconds: Option<u64>) -> u64 {
+pub(super) fn normalized_timeout_seconds(timeout_seconds: Option<u64>) -> u64 {
     timeout_seconds
         .unwrap_or(DEFAULT_AI_TIMEOUT_SECONDS)
         .clamp(MIN_AI_TIMEOUT_SECONDS, MAX_AI_TIMEOUT_SECONDS)
@@ -180,7 +187,7 @@ fn ai_client(timeout_seconds: Option<u64>) -> Result<Client, AIError> {
         .map_err(|e| AIError::NetworkError(format!("Failed to create AI HTTP client: {}", e)))
 }
 
-fn chat_completions_url(base_url: &str) -> String {
+pub(super) fn chat_completions_url(base_url: &str) -> String {
     let trimmed = base_url.trim_end_matches('/');
     if trimmed.ends_with("/chat/completions") || trimmed.ends_with("/v1/chat/completions") {
         trimmed.to_string()
@@ -191,7 +198,54 @@ fn chat_completions_url(base_url: &str) -> String {
     }
 }
 
-fn response_snippet(response_text: &str) -> String {
+pub(super) fn anthropic_max_tokens(summary_max_tokens: Option<u32>) -> u32 {
+    normalized_summary_max_tokens(summary_max_tokens).unwrap_or(ANTHROPIC_DEFAULT_MAX_TOKENS)
+}
+
+pub(super) fn anthropic_headers(api_key: &str) -> Vec<(String, String)> {
+    vec![
+        ("x-api-key".to_string(), api_key.to_string()),
+        (
+            "anthropic-version".to_string(),
+            ANTHROPIC_API_VERSION.to_string(),
+        ),
+    ]
+}
+
+/// Bearer key (when set) plus the endpoint's own headers
+pub(super) fn custom_endpoint_headers(endpoint: &AICustomEndpoint) -> Vec<(String, String)> {
+    let mut headers = Vec::new();
+    if let Some(api_key) = endpoint
+        .api_key
+        .as_deref()
+        .filter(|key| !key.trim().is_empty())
+    {
+        headers.push((
+            "Authorization".to_string(),
+            format!("Bearer {}", api_key.trim()),
+        ));
+    }
+    headers.extend(
+        endpoint
+            .headers
+            .iter()
+            .filter(|(name, _)| !name.trim().is_empty())
+            .map(|(name, value)| (name.trim().to_string(), value.clone())),
+    );
+    headers
+}
+
+pub(super) fn with_headers(
+    mut builder: reqwest::RequestBuilder,
+    headers: &[(String, String)],
+) -> reqwest::RequestBuilder {
+    for (name, value) in headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    builder
+}
+
+pub(super) fn response_snippet(response_text: &str) -> String {
     const MAX_CHARS: usize = 700;
     let mut snippet = response_text.chars().take(MAX_CHARS).collect::<String>();
     if response_text.chars().count() > MAX_CHARS {
@@ -200,7 +254,7 @@ fn response_snippet(response_text: &str) -> String {
     snippet
 }
 
-fn extract_openai_compatible_error(response_text: &str) -> Option<String> {
+pub(super) fn extract_openai_compatible_error(response_text: &str) -> Option<String> {
     let json = serde_json::from_str::<serde_json::Value>(response_text).ok()?;
     let error = json.get("error")?;
     error
@@ -213,12 +267,14 @@ fn extract_openai_compatible_error(response_text: &str) -> Option<String> {
 /// An OpenAI API error, with the structured `param` field (when present) alongside
 /// the human-readable message. Used by `adjust_openai_request` to reliably tell which
 /// request field OpenAI rejected instead of guessing from the message text.
-struct OpenAIErrorDetail {
+pub(super) struct OpenAIErrorDetail {
     message: String,
     param: Option<String>,
 }
 
-fn extract_openai_compatible_error_detail(response_text: &str) -> Option<OpenAIErrorDetail> {
+pub(super) fn extract_openai_compatible_error_detail(
+    response_text: &str,
+) -> Option<OpenAIErrorDetail> {
     let json = serde_json::from_str::<serde_json::Value>(response_text).ok()?;
     let error = json.get("error")?;
     let message = error
@@ -281,11 +337,52 @@ fn openai_compatible_finish_reason(json: &serde_json::Value) -> Option<&str> {
         .or_else(|| choice.get("stop_reason").and_then(|reason| reason.as_str()))
 }
 
+/// Token usage from a response or stream chunk in any supported provider format
+pub(super) fn response_token_usage(json: &serde_json::Value) -> Option<AITokenUsage> {
+    let count = |value: Option<&serde_json::Value>| value.and_then(|v| v.as_u64());
+    // Anthropic nests usage under `message` in the first stream event
+    let usage = json
+        .get("usage")
+        .or_else(|| json.get("message").and_then(|m| m.get("usage")))
+        .filter(|usage| usage.is_object());
+    let (input_tokens, output_tokens) = if let Some(usage) = usage {
+        (
+            count(
+                usage
+                    .get("prompt_tokens")
+                    .or_else(|| usage.get("input_tokens")),
+            ),
+            count(
+                usage
+                    .get("completion_tokens")
+                    .or_else(|| usage.get("output_tokens")),
+            ),
+        )
+    } else if let Some(metadata) = json.get("usageMetadata") {
+        (
+            count(metadata.get("promptTokenCount")),
+            count(metadata.get("candidatesTokenCount")),
+        )
+    } else {
+        (
+            count(json.get("prompt_eval_count")),
+            count(json.get("eval_count")),
+        )
+    };
+    if input_tokens.is_none() && output_tokens.is_none() {
+        return None;
+    }
+    Some(AITokenUsage {
+        input_tokens: input_tokens.unwrap_or(0),
+        output_tokens: output_tokens.unwrap_or(0),
+    })
+}
+
 fn parse_openai_compatible_response(
     provider_label: &str,
     status: reqwest::StatusCode,
     response_text: &str,
-) -> Result<String, AIError> {
+) -> Result<(String, Option<AITokenUsage>), AIError> {
     if !status.is_success() {
         let detail = extract_openai_compatible_error(response_text)
             .unwrap_or_else(|| response_snippet(response_text));
@@ -322,7 +419,54 @@ fn parse_openai_compatible_response(
         )));
     }
 
-    Ok(text)
+    Ok((text, response_token_usage(&json)))
+}
+
+fn parse_anthropic_response(
+    status: reqwest::StatusCode,
+    response_text: &str,
+) -> Result<(String, Option<AITokenUsage>), AIError> {
+    if !status.is_success() {
+        let detail = extract_openai_compatible_error(response_text)
+            .unwrap_or_else(|| response_snippet(response_text));
+        return Err(AIError::ApiError(format!(
+            "Anthropic API returned HTTP {}: {}",
+            status, detail
+        )));
+    }
+
+    let json: serde_json::Value = serde_json::from_str(response_text).map_err(|e| {
+        AIError::ParseError(format!(
+            "Anthropic API returned invalid JSON: {}. Response: {}",
+            e,
+            response_snippet(response_text)
+        ))
+    })?;
+    let text = json
+        .get("content")
+        .and_then(|content| content.as_array())
+        .map(|blocks| {
+            blocks
+                .iter()
+                .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("text"))
+                .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+                .collect::<String>()
+        })
+        .filter(|text| !text.trim().is_empty())
+        .ok_or_else(|| {
+            AIError::ParseError(format!(
+                "Anthropic API response did not contain text. Response: {}",
+                response_snippet(response_text)
+            ))
+        })?;
+
+    if json.get("stop_reason").and_then(|r| r.as_str()) == Some("max_tokens") {
+        return Err(AIError::ApiError(
+            "Anthropic API response was cut off by the output token limit. Try again with a shorter transcript or a higher max tokens setting.".to_string(),
+        ));
+    }
+
+    Ok((text, response_token_usage(&json)))
 }
 
 pub async fn generate_with_gemini(
@@ -460,6 +604,7 @@ pub async fn generate_with_gemini(
         summary: summary.trim().to_string(),
         provider: "Gemini".to_string(),
         model: model.to_string(),
+        usage: response_token_usage(&json),
     })
 }
 
@@ -490,12 +635,13 @@ pub async fn generate_with_openai(
         body,
     )
     .await?;
-    let summary = parse_openai_compatible_response("OpenAI", status, &response_text)?;
+    let (summary, usage) = parse_openai_compatible_response("OpenAI", status, &response_text)?;
 
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "OpenAI".to_string(),
         model: model.to_string(),
+        usage,
     })
 }
 
@@ -561,6 +707,7 @@ pub async fn generate_with_ollama(
         summary: summary.trim().to_string(),
         provider: "Ollama".to_string(),
         model: model.to_string(),
+        usage: response_token_usage(&json),
     })
 }
 
@@ -598,12 +745,13 @@ pub async fn generate_with_deepseek(
 
     let status = response.status();
     let response_text = response.text().await.unwrap_or_default();
-    let summary = parse_openai_compatible_response("DeepSeek", status, &response_text)?;
+    let (summary, usage) = parse_openai_compatible_response("DeepSeek", status, &response_text)?;
 
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "DeepSeek".to_string(),
         model: model.to_string(),
+        usage,
     })
 }
 
@@ -641,12 +789,13 @@ pub async fn generate_with_qwen(
 
     let status = response.status();
     let response_text = response.text().await.unwrap_or_default();
-    let summary = parse_openai_compatible_response("Qwen", status, &response_text)?;
+    let (summary, usage) = parse_openai_compatible_response("Qwen", status, &response_text)?;
 
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "Qwen".to_string(),
         model: model.to_string(),
+        usage,
     })
 }
 
@@ -677,12 +826,13 @@ pub async fn generate_with_proxy(
 
     let (status, response_text) = post_openai_chat(&client, &url, api_key, body).await?;
 
-    let summary = parse_openai_compatible_response("Proxy", status, &response_text)?;
+    let (summary, usage) = parse_openai_compatible_response("Proxy", status, &response_text)?;
 
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "Proxy".to_string(),
         model: model.to_string(),
+        usage,
     })
 }
 
@@ -727,12 +877,13 @@ pub async fn generate_with_lmstudio(
     let status = response.status();
     let response_text = response.text().await.unwrap_or_default();
 
-    let summary = parse_openai_compatible_response("LM Studio", status, &response_text)?;
+    let (summary, usage) = parse_openai_compatible_response("LM Studio", status, &response_text)?;
 
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "LM Studio".to_string(),
         model: model.to_string(),
+        usage,
     })
 }
 
@@ -818,6 +969,7 @@ async fn generate_raw_with_gemini(
         summary: text.to_string(),
         model: model.to_string(),
         provider: "Gemini".to_string(),
+        usage: response_token_usage(&json),
     })
 }
 
@@ -847,12 +999,13 @@ async fn generate_raw_with_openai(
         body,
     )
     .await?;
-    let text = parse_openai_compatible_response("OpenAI", status, &response_text)?;
+    let (text, usage) = parse_openai_compatible_response("OpenAI", status, &response_text)?;
 
     Ok(SummaryResult {
         summary: text.to_string(),
         model: model.to_string(),
         provider: "OpenAI".to_string(),
+        usage,
     })
 }
 
@@ -909,6 +1062,7 @@ async fn generate_raw_with_ollama(
         summary: text.to_string(),
         model: model.to_string(),
         provider: "Ollama".to_string(),
+        usage: response_token_usage(&json),
     })
 }
 
@@ -946,12 +1100,13 @@ async fn generate_raw_with_lmstudio(
 
     let status = response.status();
     let response_text = response.text().await.unwrap_or_default();
-    let text = parse_openai_compatible_response("LM Studio", status, &response_text)?;
+    let (text, usage) = parse_openai_compatible_response("LM Studio", status, &response_text)?;
 
     Ok(SummaryResult {
         summary: text.to_string(),
         model: model.to_string(),
         provider: "LM Studio".to_string(),
+        usage,
     })
 }
 
@@ -983,12 +1138,13 @@ async fn generate_raw_with_deepseek(
 
     let status = response.status();
     let response_text = response.text().await.unwrap_or_default();
-    let text = parse_openai_compatible_response("DeepSeek", status, &response_text)?;
+    let (text, usage) = parse_openai_compatible_response("DeepSeek", status, &response_text)?;
 
     Ok(SummaryResult {
         summary: text.to_string(),
         model: model.to_string(),
         provider: "DeepSeek".to_string(),
+        usage,
     })
 }
 
@@ -1020,12 +1176,13 @@ async fn generate_raw_with_qwen(
 
     let status = response.status();
     let response_text = response.text().await.unwrap_or_default();
-    let text = parse_openai_compatible_response("Qwen", status, &response_text)?;
+    let (text, usage) = parse_openai_compatible_response("Qwen", status, &response_text)?;
 
     Ok(SummaryResult {
         summary: text.to_string(),
         model: model.to_string(),
         provider: "Qwen".to_string(),
+        usage,
     })
 }
 
@@ -1053,12 +1210,94 @@ async fn generate_raw_with_proxy(
     );
 
     let (status, response_text) = post_openai_chat(&client, &url, api_key, body).await?;
-    let text = parse_openai_compatible_response("Proxy", status, &response_text)?;
+    let (text, usage) = parse_openai_compatible_response("Proxy", status, &response_text)?;
 
     Ok(SummaryResult {
         summary: text.to_string(),
         model: model.to_string(),
         provider: "Proxy".to_string(),
+        usage,
+    })
+}
+
+pub(super) async fn generate_raw_with_anthropic(
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+    temperature: f64,
+    timeout_seconds: Option<u64>,
+    summary_max_tokens: Option<u32>,
+) -> Result<SummaryResult, AIError> {
+    let client = ai_client(timeout_seconds)?;
+    let body = serde_json::json!({
+        "model": model,
+        "max_tokens": anthropic_max_tokens(summary_max_tokens),
+        "temperature": temperature,
+        "messages": [{ "role": "user", "content": prompt }]
+    });
+
+    let response = with_headers(
+        client.post(ANTHROPIC_MESSAGES_URL),
+        &anthropic_headers(api_key),
+    )
+    .header("Content-Type", "application/json")
+    .json(&body)
+    .send()
+    .await
+    .map_err(|e| AIError::NetworkError(e.to_string()))?;
+
+    let status = response.status();
+    let response_text = response.text().await.unwrap_or_default();
+    let (text, usage) = parse_anthropic_response(status, &response_text)?;
+
+    Ok(SummaryResult {
+        summary: text,
+        model: model.to_string(),
+        provider: "Anthropic".to_string(),
+        usage,
+    })
+}
+
+pub(super) async fn generate_raw_with_custom(
+    endpoint: &AICustomEndpoint,
+    model: &str,
+    prompt: &str,
+    temperature: f64,
+    timeout_seconds: Option<u64>,
+    summary_max_tokens: Option<u32>,
+) -> Result<SummaryResult, AIError> {
+    let client = ai_client(timeout_seconds)?;
+    let url = chat_completions_url(&endpoint.base_url);
+    let mut body = serde_json::json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": prompt }],
+        "temperature": temperature
+    });
+    if let Some(max_tokens) = normalized_summary_max_tokens(summary_max_tokens) {
+        body["max_tokens"] = serde_json::json!(max_tokens);
+    }
+
+    let response = with_headers(client.post(&url), &custom_endpoint_headers(endpoint))
+        .header("Content-Type", "application/json")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| {
+            AIError::NetworkError(format!(
+                "Failed to connect to {} at {}: {}",
+                endpoint.name, endpoint.base_url, e
+            ))
+        })?;
+
+    let status = response.status();
+    let response_text = response.text().await.unwrap_or_default();
+    let (text, usage) = parse_openai_compatible_response(&endpoint.name, status, &response_text)?;
+
+    Ok(SummaryResult {
+        summary: text,
+        model: model.to_string(),
+        provider: endpoint.name.clone(),
+        usage,
     })
 }
 
@@ -1155,6 +1394,29 @@ pub(super) async fn generate_raw_for_provider(
             )
             .await
         }
+        AIProvider::Anthropic => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            generate_raw_with_anthropic(
+                api_key,
+                &config.model,
+                prompt,
+                0.3,
+                config.timeout_seconds,
+                config.summary_max_tokens,
+            )
+            .await
+        }
+        AIProvider::Custom => {
+            generate_raw_with_custom(
+                config.custom_endpoint()?,
+                &config.model,
+                prompt,
+                0.3,
+                config.timeout_seconds,
+                config.summary_max_tokens,
+            )
+            .await
+        }
     }
 }
 
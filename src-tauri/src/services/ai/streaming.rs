@@ -0,0 +1,658 @@
+use std::future::Future;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use reqwest::Client;
+
+use super::providers::{
+    adjust_openai_request, anthropic_headers, anthropic_max_tokens, apply_openai_sampling_params,
+    chat_completions_url, custom_endpoint_headers, extract_openai_compatible_error,
+    extract_openai_compatible_error_detail, normalized_summary_max_tokens,
+    normalized_timeout_seconds, response_snippet, response_token_usage, with_headers,
+    ANTHROPIC_MESSAGES_URL, MAX_OPENAI_PARAM_ADJUSTMENTS,
+};
+use super::*;
+
+const STREAM_CONNECT_TIMEOUT_SECONDS: u64 = 30;
+/// How often a pending request checks whether it was cancelled
+const STREAM_CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Wire format of a provider's streaming response
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum StreamFormat {
+    /// Server-sent events with `choices[0].delta.content` chunks
+    OpenAICompatible,
+    /// Server-sent events with `candidates[0].content.parts` chunks (`alt=sse`)
+    Gemini,
+    /// Newline-delimited JSON objects with a `response` field
+    Ollama,
+    /// Server-sent events with `content_block_delta` text deltas
+    Anthropic,
+}
+
+/// One decoded chunk of a streaming response
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct StreamEvent {
+    pub delta: Option<String>,
+    /// The provider stopped because it hit the output token limit
+    pub truncated: bool,
+    pub done: bool,
+    /// Token counts, when the chunk carries them
+    pub usage: Option<AITokenUsage>,
+}
+
+fn stream_error(json: &serde_json::Value) -> Option<String> {
+    let error = json.get("error")?;
+    error
+        .get("message")
+        .and_then(|m| m.as_str())
+        .or_else(|| error.as_str())
+        .map(str::to_string)
+}
+
+fn openai_stream_event(json: &serde_json::Value) -> StreamEvent {
+    let choice = json.get("choices").and_then(|c| c.get(0));
+    let delta = choice
+        .and_then(|c| c.get("delta"))
+        .and_then(|d| d.get("content"))
+        .and_then(|c| c.as_str())
+        .filter(|text| !text.is_empty())
+        .map(str::to_string);
+    let finish_reason = choice
+        .and_then(|c| c.get("finish_reason").or_else(|| c.get("stop_reason")))
+        .and_then(|r| r.as_str());
+    StreamEvent {
+        delta,
+        truncated: matches!(finish_reason, Some("length" | "max_tokens")),
+        ..StreamEvent::default()
+    }
+}
+
+fn gemini_stream_event(json: &serde_json::Value) -> StreamEvent {
+    let candidate = json.get("candidates").and_then(|c| c.get(0));
+    let text = candidate
+        .and_then(|c| c.get("content"))
+        .and_then(|c| c.get("parts"))
+        .and_then(|p| p.as_array())
+        .map(|parts| {
+            parts
+                .iter()
+                // Thought summaries are not part of the answer
+                .filter(|part| part.get("thought").and_then(|t| t.as_bool()) != Some(true))
+                .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+    let finish_reason = candidate
+        .and_then(|c| c.get("finishReason"))
+        .and_then(|r| r.as_str());
+    StreamEvent {
+        delta: (!text.is_empty()).then_some(text),
+        truncated: finish_reason == Some("MAX_TOKENS"),
+        ..StreamEvent::default()
+    }
+}
+
+fn anthropic_stream_event(json: &serde_json::Value) -> StreamEvent {
+    let event_type = json.get("type").and_then(|t| t.as_str());
+    let delta = json.get("delta");
+    StreamEvent {
+        delta: (event_type == Some("content_block_delta"))
+            .then(|| delta.and_then(|d| d.get("text")).and_then(|t| t.as_str()))
+            .flatten()
+            .filter(|text| !text.is_empty())
+            .map(str::to_string),
+        truncated: event_type == Some("message_delta")
+            && delta
+                .and_then(|d| d.get("stop_reason"))
+                .and_then(|r| r.as_str())
+                == Some("max_tokens"),
+        done: event_type == Some("message_stop"),
+        ..StreamEvent::default()
+    }
+}
+
+fn ollama_stream_event(json: &serde_json::Value) -> StreamEvent {
+    let done = json.get("done").and_then(|d| d.as_bool()).unwrap_or(false);
+    StreamEvent {
+        delta: json
+            .get("response")
+            .and_then(|r| r.as_str())
+            .filter(|text| !text.is_empty())
+            .map(str::to_string),
+        truncated: done && json.get("done_reason").and_then(|r| r.as_str()) == Some("length"),
+        done,
+        ..StreamEvent::default()
+    }
+}
+
+/// Decode one line of a streaming response. Blank lines, SSE comments, and non-data
+/// fields yield `None`.
+pub(super) fn parse_stream_line(
+    format: StreamFormat,
+    line: &str,
+) -> Result<Option<StreamEvent>, AIError> {
+    let line = line.trim();
+    let payload = match format {
+        StreamFormat::OpenAICompatible | StreamFormat::Gemini | StreamFormat::Anthropic => {
+            let Some(data) = line.strip_prefix("data:") else {
+                return Ok(None);
+            };
+            let data = data.trim();
+            if data == "[DONE]" {
+                return Ok(Some(StreamEvent {
+                    done: true,
+                    ..StreamEvent::default()
+                }));
+            }
+            data
+        }
+        StreamFormat::Ollama => line,
+    };
+    if payload.is_empty() {
+        return Ok(None);
+    }
+
+    let json: serde_json::Value = serde_json::from_str(payload).map_err(|e| {
+        AIError::ParseError(format!(
+            "Invalid streaming chunk: {}. Chunk: {}",
+            e,
+            response_snippet(payload)
+        ))
+    })?;
+    if let Some(error) = stream_error(&json) {
+        return Err(AIError::ApiError(error));
+    }
+
+    let mut event = match format {
+        StreamFormat::OpenAICompatible => openai_stream_event(&json),
+        StreamFormat::Gemini => gemini_stream_event(&json),
+        StreamFormat::Ollama => ollama_stream_event(&json),
+        StreamFormat::Anthropic => anthropic_stream_event(&json),
+    };
+    event.usage = response_token_usage(&json);
+    Ok(Some(event))
+}
+
+/// Splits raw response bytes into lines. Bytes are buffered until a newline arrives so
+/// multi-byte characters and JSON objects split across network chunks decode intact.
+pub(super) struct StreamDecoder {
+    format: StreamFormat,
+    buffer: Vec<u8>,
+}
+
+impl StreamDecoder {
+    pub fn new(format: StreamFormat) -> Self {
+        Self {
+            format,
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) -> Result<Vec<StreamEvent>, AIError> {
+        self.buffer.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        while let Some(newline) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line = self.buffer.drain(..=newline).collect::<Vec<_>>();
+            if let Some(event) = parse_stream_line(self.format, &String::from_utf8_lossy(&line))? {
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Decode whatever is left once the stream ends without a trailing newline
+    pub fn finish(&mut self) -> Result<Option<StreamEvent>, AIError> {
+        let line = std::mem::take(&mut self.buffer);
+        parse_stream_line(self.format, &String::from_utf8_lossy(&line))
+    }
+}
+
+struct StreamRequest {
+    provider_label: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: serde_json::Value,
+    format: StreamFormat,
+    /// OpenAI-style endpoints may reject sampling parameters; those are corrected and resent
+    adjust_params: bool,
+}
+
+impl StreamRequest {
+    fn openai_compatible(
+        provider_label: &str,
+        url: String,
+        headers: Vec<(String, String)>,
+        body: serde_json::Value,
+    ) -> Self {
+        Self {
+            provider_label: provider_label.to_string(),
+            url,
+            headers,
+            body,
+            format: StreamFormat::OpenAICompatible,
+            adjust_params: false,
+        }
+    }
+}
+
+fn bearer_header(api_key: String) -> Vec<(String, String)> {
+    vec![("Authorization".to_string(), format!("Bearer {}", api_key))]
+}
+
+/// Asks for a final chunk with token usage; only sent to APIs known to accept it
+fn include_stream_usage(body: &mut serde_json::Value) {
+    body["stream_options"] = serde_json::json!({ "include_usage": true });
+}
+
+fn openai_compatible_body(
+    model: &str,
+    prompt: &str,
+    temperature: f64,
+    max_tokens: Option<u32>,
+) -> serde_json::Value {
+    let mut body = serde_json::json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": prompt }],
+        "temperature": temperature,
+        "stream": true
+    });
+    if let Some(max_tokens) = max_tokens {
+        body["max_tokens"] = serde_json::json!(max_tokens);
+    }
+    body
+}
+
+fn build_stream_request(
+    config: &AIConfig,
+    prompt: &str,
+    temperature: f64,
+) -> Result<StreamRequest, AIError> {
+    let model = config.model.as_str();
+    let max_tokens = normalized_summary_max_tokens(config.summary_max_tokens);
+    let api_key = || config.api_key.clone().ok_or(AIError::NoApiKey);
+    let label = config.provider.label();
+
+    let request = match config.provider {
+        AIProvider::Gemini => {
+            let mut generation_config = serde_json::json!({ "temperature": temperature });
+            if let Some(max_tokens) = max_tokens {
+                generation_config["maxOutputTokens"] = serde_json::json!(max_tokens);
+            }
+            StreamRequest {
+                provider_label: label.to_string(),
+                url: format!(
+                    "https://generativelanguage.googleapis.com/v1beta/models/{}:streamGenerateContent?alt=sse",
+                    model
+                ),
+                headers: vec![("x-goog-api-key".to_string(), api_key()?)],
+                body: serde_json::json!({
+                    "contents": [{ "parts": [{ "text": prompt }] }],
+                    "generationConfig": generation_config
+                }),
+                format: StreamFormat::Gemini,
+                adjust_params: false,
+            }
+        }
+        AIProvider::OpenAI | AIProvider::Proxy => {
+            let mut body = serde_json::json!({
+                "model": model,
+                "messages": [{ "role": "user", "content": prompt }],
+                "stream": true
+            });
+            apply_openai_sampling_params(&mut body, model, temperature, max_tokens);
+            let url = if config.provider == AIProvider::OpenAI {
+                include_stream_usage(&mut body);
+                "https://api.openai.com/v1/chat/completions".to_string()
+            } else {
+                chat_completions_url(
+                    config
+                        .proxy_url
+                        .as_deref()
+                        .unwrap_or("https://api.openai.com"),
+                )
+            };
+            StreamRequest {
+                adjust_params: true,
+                ..StreamRequest::openai_compatible(label, url, bearer_header(api_key()?), body)
+            }
+        }
+        AIProvider::DeepSeek | AIProvider::Qwen => {
+            let url = if config.provider == AIProvider::DeepSeek {
+                "https://api.deepseek.com/chat/completions"
+            } else {
+                "https://dashscope.aliyuncs.com/compatible-mode/v1/chat/completions"
+            };
+            let mut body = openai_compatible_body(model, prompt, temperature, max_tokens);
+            include_stream_usage(&mut body);
+            StreamRequest::openai_compatible(
+                label,
+                url.to_string(),
+                bearer_header(api_key()?),
+                body,
+            )
+        }
+        AIProvider::LmStudio => StreamRequest::openai_compatible(
+            label,
+            chat_completions_url(
+                config
+                    .lmstudio_url
+                    .as_deref()
+                    .unwrap_or("http://localhost:1234"),
+            ),
+            Vec::new(),
+            openai_compatible_body(model, prompt, temperature, max_tokens),
+        ),
+        AIProvider::Custom => {
+            let endpoint = config.custom_endpoint()?;
+            StreamRequest::openai_compatible(
+                &endpoint.name,
+                chat_completions_url(&endpoint.base_url),
+                custom_endpoint_headers(endpoint),
+                openai_compatible_body(model, prompt, temperature, max_tokens),
+            )
+        }
+        AIProvider::Anthropic => StreamRequest {
+            provider_label: label.to_string(),
+            url: ANTHROPIC_MESSAGES_URL.to_string(),
+            headers: anthropic_headers(&api_key()?),
+            body: serde_json::json!({
+                "model": model,
+                "max_tokens": anthropic_max_tokens(config.summary_max_tokens),
+                "temperature": temperature,
+                "messages": [{ "role": "user", "content": prompt }],
+                "stream": true
+            }),
+            format: StreamFormat::Anthropic,
+            adjust_params: false,
+        },
+        AIProvider::Ollama => {
+            let mut options = serde_json::json!({ "temperature": temperature });
+            if let Some(max_tokens) = max_tokens {
+                options["num_predict"] = serde_json::json!(max_tokens);
+            }
+            let base_url = config
+                .ollama_url
+                .as_deref()
+                .unwrap_or("http://localhost:11434");
+            StreamRequest {
+                provider_label: label.to_string(),
+                url: format!("{}/api/generate", base_url.trim_end_matches('/')),
+                headers: Vec::new(),
+                body: serde_json::json!({
+                    "model": model,
+                    "prompt": prompt,
+                    "stream": true,
+                    "options": options
+                }),
+                format: StreamFormat::Ollama,
+                adjust_params: false,
+            }
+        }
+    };
+    Ok(request)
+}
+
+/// Streaming responses can legitimately run longer than the whole-request timeout, so
+/// `timeout_seconds` bounds the silence between chunks instead.
+fn stream_client(timeout_seconds: Option<u64>) -> Result<Client, AIError> {
+    Client::builder()
+        .connect_timeout(Duration::from_secs(STREAM_CONNECT_TIMEOUT_SECONDS))
+        .read_timeout(Duration::from_secs(normalized_timeout_seconds(
+            timeout_seconds,
+        )))
+        .build()
+        .map_err(|e| AIError::NetworkError(format!("Failed to create AI HTTP client: {}", e)))
+}
+
+async fn wait_for_cancel(hooks: &LongSummaryHooks<'_>) {
+    loop {
+        tokio::time::sleep(STREAM_CANCEL_POLL_INTERVAL).await;
+        if hooks.ensure_not_cancelled().is_err() {
+            return;
+        }
+    }
+}
+
+/// Run `future` until it finishes or the request is cancelled. Dropping the future on
+/// cancel closes the underlying connection, so the provider stops generating.
+async fn cancellable<T>(
+    hooks: &LongSummaryHooks<'_>,
+    future: impl Future<Output = T>,
+) -> Result<T, AIError> {
+    tokio::select! {
+        output = future => Ok(output),
+        _ = wait_for_cancel(hooks) => Err(AIError::Cancelled),
+    }
+}
+
+/// Generate a completion over the provider's streaming API.
+///
+/// With `emit_deltas`, each text chunk is passed to `hooks.delta` as it arrives.
+pub(super) async fn stream_raw_for_provider(
+    config: &AIConfig,
+    prompt: &str,
+    temperature: f64,
+    hooks: &LongSummaryHooks<'_>,
+    emit_deltas: bool,
+) -> Result<SummaryResult, AIError> {
+    let mut request = build_stream_request(config, prompt, temperature)?;
+    let client = stream_client(config.timeout_seconds)?;
+    let mut adjustments_left = if request.adjust_params {
+        MAX_OPENAI_PARAM_ADJUSTMENTS
+    } else {
+        0
+    };
+
+    let response = loop {
+        hooks.ensure_not_cancelled()?;
+        let builder = with_headers(client.post(&request.url), &request.headers)
+            .header("Content-Type", "application/json")
+            .json(&request.body);
+        let response = cancellable(hooks, builder.send()).await?.map_err(|e| {
+            AIError::NetworkError(format!(
+                "Failed to connect to {}: {}",
+                request.provider_label, e
+            ))
+        })?;
+
+        let status = response.status();
+        if status.is_success() {
+            break response;
+        }
+        let response_text = cancellable(hooks, response.text())
+            .await?
+            .unwrap_or_default();
+        if status == reqwest::StatusCode::BAD_REQUEST && adjustments_left > 0 {
+            if let Some(error) = extract_openai_compatible_error_detail(&response_text) {
+                if adjust_openai_request(&mut request.body, &error) {
+                    adjustments_left -= 1;
+                    continue;
+                }
+            }
+        }
+        let detail = extract_openai_compatible_error(&response_text)
+            .unwrap_or_else(|| response_snippet(&response_text));
+        return Err(AIError::ApiError(format!(
+            "{} API returned HTTP {}: {}",
+            request.provider_label, status, detail
+        )));
+    };
+
+    let mut stream = response.bytes_stream();
+    let mut decoder = StreamDecoder::new(request.format);
+    let mut text = String::new();
+    let mut truncated = false;
+    let mut usage: Option<AITokenUsage> = None;
+    let mut apply = |event: StreamEvent| -> bool {
+        if let Some(delta) = event.delta {
+            if emit_deltas {
+                hooks.emit_delta(&delta);
+            }
+            text.push_str(&delta);
+        }
+        truncated |= event.truncated;
+        if let Some(chunk_usage) = event.usage {
+            // Counts are cumulative, and Anthropic reports input and output in separate events
+            let total = usage.get_or_insert_with(AITokenUsage::default);
+            total.input_tokens = total.input_tokens.max(chunk_usage.input_tokens);
+            total.output_tokens = total.output_tokens.max(chunk_usage.output_tokens);
+        }
+        event.done
+    };
+
+    'stream: while let Some(chunk) = cancellable(hooks, stream.next()).await? {
+        let chunk = chunk.map_err(|e| {
+            AIError::NetworkError(format!(
+                "{} stream was interrupted: {}",
+                request.provider_label, e
+            ))
+        })?;
+        for event in decoder.push(&chunk)? {
+            if apply(event) {
+                break 'stream;
+            }
+        }
+    }
+    if let Some(event) = decoder.finish()? {
+        apply(event);
+    }
+
+    if truncated {
+        return Err(AIError::ApiError(format!(
+            "{} API response was cut off by the output token limit. Try again with a shorter transcript or a provider/model that supports longer output.",
+            request.provider_label
+        )));
+    }
+    if text.trim().is_empty() {
+        return Err(AIError::ParseError(format!(
+            "{} stream did not contain any text",
+            request.provider_label
+        )));
+    }
+
+    Ok(SummaryResult {
+        summary: text,
+        provider: request.provider_label,
+        model: config.model.clone(),
+        usage,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_sse_chunks_split_across_network_reads() {
+        let mut decoder = StreamDecoder::new(StreamFormat::OpenAICompatible);
+        let first = "data: {\"choices\":[{\"delta\":{\"content\":\"Xin ch\u{e0}\"}}]}\n\n";
+        let (head, tail) = first.as_bytes().split_at(first.len() - 8);
+        assert!(decoder.push(head).unwrap().is_empty());
+        let events = decoder
+            .push(
+                &[
+                    tail,
+                    b": keep-alive\n",
+                    b"data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"length\"}]}\n",
+                    b"data: [DONE]\n",
+                ]
+                .concat(),
+            )
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![
+                StreamEvent {
+                    delta: Some("Xin ch\u{e0}".to_string()),
+                    ..StreamEvent::default()
+                },
+                StreamEvent {
+                    truncated: true,
+                    ..StreamEvent::default()
+                },
+                StreamEvent {
+                    done: true,
+                    ..StreamEvent::default()
+                },
+            ]
+        );
+
+        let error = parse_stream_line(
+            StreamFormat::OpenAICompatible,
+            "data: {\"error\":{\"message\":\"Rate limited\"}}",
+        );
+        assert!(matches!(error, Err(AIError::ApiError(message)) if message == "Rate limited"));
+    }
+
+    #[test]
+    fn decodes_gemini_and_ollama_chunks() {
+        let gemini = parse_stream_line(
+            StreamFormat::Gemini,
+            r#"data: {"candidates":[{"content":{"parts":[{"text":"plan","thought":true},{"text":"Hello"}]},"finishReason":"STOP"}]}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(gemini.delta.as_deref(), Some("Hello"));
+        assert!(!gemini.truncated);
+
+        let mut decoder = StreamDecoder::new(StreamFormat::Ollama);
+        let events = decoder
+            .push(b"{\"response\":\"Hi\",\"done\":false}\n{\"response\":\"\",\"done\":true,")
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].delta.as_deref(), Some("Hi"));
+        assert!(decoder
+            .push(b"\"done_reason\":\"length\"}")
+            .unwrap()
+            .is_empty());
+        let last = decoder.finish().unwrap().unwrap();
+        assert!(last.done && last.truncated);
+    }
+
+    #[test]
+    fn decodes_anthropic_message_events() {
+        let mut decoder = StreamDecoder::new(StreamFormat::Anthropic);
+        let events = decoder
+            .push(
+                concat!(
+                    "event: message_start\n",
+                    "data: {\"type\":\"message_start\",\"message\":{\"content\":[],",
+                    "\"usage\":{\"input_tokens\":12,\"output_tokens\":1}}}\n\n",
+                    "event: content_block_delta\n",
+                    "data: {\"type\":\"content_block_delta\",\"index\":0,",
+                    "\"delta\":{\"type\":\"text_delta\",\"text\":\"Hi\"}}\n\n",
+                    "event: message_delta\n",
+                    "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"},",
+                    "\"usage\":{\"output_tokens\":5}}\n\n",
+                    "event: message_stop\n",
+                    "data: {\"type\":\"message_stop\"}\n\n",
+                )
+                .as_bytes(),
+            )
+            .unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .filter_map(|event| event.delta.as_deref())
+                .collect::<String>(),
+            "Hi"
+        );
+        assert!(events.iter().all(|event| !event.truncated));
+        assert!(events.last().is_some_and(|event| event.done));
+        assert_eq!(
+            events
+                .iter()
+                .filter_map(|event| event.usage)
+                .map(|usage| (usage.input_tokens, usage.output_tokens))
+                .collect::<Vec<_>>(),
+            vec![(12, 1), (0, 5)]
+        );
+
+        let overloaded = parse_stream_line(
+            StreamFormat::Anthropic,
+            r#"data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        );
+        assert!(matches!(overloaded, Err(AIError::ApiError(message)) if message == "Overloaded"));
+    }
+}
@@ -0,0 +1,372 @@
+use std::future::Future;
+use std::time::Instant;
+
+use sha2::{Digest, Sha256};
+
+use super::*;
+use crate::database::{
+    add_ai_usage_in_db, get_cached_ai_response_from_db, save_cached_ai_response_in_db,
+};
+use crate::types::AIUsageRecord;
+
+pub const DEFAULT_RESPONSE_CACHE_HOURS: u32 = 24 * 7;
+
+/// List prices in USD per million input and output tokens. A model id matches its own
+/// entry, optionally followed by a release date such as `-2024-07-18` or `-20250929`, so
+/// `gpt-5.4-mini` and `o3-mini` never fall back to the `gpt-5.4` or `o3` price.
+const MODEL_PRICES_PER_MILLION: &[(&str, f64, f64)] = &[
+    ("gpt-5.5", 5.00, 30.00),
+    ("gpt-5.4", 2.50, 15.00),
+    ("gpt-5.4-mini", 0.75, 4.50),
+    ("gpt-5", 1.25, 10.00),
+    ("gpt-5-mini", 0.25, 2.00),
+    ("gpt-5-nano", 0.05, 0.40),
+    ("gpt-4.1", 2.00, 8.00),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("o3", 2.00, 8.00),
+    ("o3-mini", 1.10, 4.40),
+    ("o4-mini", 1.10, 4.40),
+    ("claude-opus-4-1", 15.00, 75.00),
+    ("claude-opus-4", 15.00, 75.00),
+    ("claude-sonnet-4-5", 3.00, 15.00),
+    ("claude-sonnet-4", 3.00, 15.00),
+    ("claude-haiku-4-5", 1.00, 5.00),
+    ("claude-3-5-haiku", 0.80, 4.00),
+    ("gemini-3.5-flash", 0.50, 3.00),
+    ("gemini-3.1-flash-lite", 0.25, 1.50),
+    ("gemini-2.5-pro", 1.25, 10.00),
+    ("gemini-2.5-flash", 0.30, 2.50),
+    ("gemini-2.5-flash-lite", 0.10, 0.40),
+    ("gemini-2.0-flash", 0.10, 0.40),
+    ("deepseek-v4-pro", 0.55, 2.19),
+    ("deepseek-v4-flash", 0.14, 0.28),
+    ("deepseek-chat", 0.27, 1.10),
+    ("deepseek-reasoner", 0.55, 2.19),
+    ("qwen3-max", 1.20, 6.00),
+    ("qwen3.5-plus", 0.40, 2.40),
+    ("qwen3.5-flash", 0.10, 0.40),
+    ("qwen-turbo", 0.05, 0.20),
+    ("qwen-plus", 0.40, 1.20),
+    ("qwen-max", 1.60, 6.40),
+];
+
+/// Whether `rest` is a release date appended to a model id, e.g. `-2024-07-18`,
+/// `-20250929` or `-0528`
+fn is_date_suffix(rest: &str) -> bool {
+    let Some(date) = rest.strip_prefix('-') else {
+        return false;
+    };
+    date.chars().all(|c| c.is_ascii_digit() || c == '-')
+        && date.chars().filter(char::is_ascii_digit).count() >= 4
+}
+
+/// Rough token count for providers that do not report usage
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// Estimated USD cost of `usage`. Local providers are free; models without a known price
+/// return `None`.
+pub fn estimate_cost_usd(provider: &AIProvider, model: &str, usage: AITokenUsage) -> Option<f64> {
+    if matches!(provider, AIProvider::Ollama | AIProvider::LmStudio) {
+        return Some(0.0);
+    }
+    let model = model.trim().to_lowercase();
+    // Routers such as OpenRouter prefix the vendor: `openai/gpt-4o-mini`
+    let model = model.rsplit('/').next().unwrap_or_default();
+    MODEL_PRICES_PER_MILLION
+        .iter()
+        .find(|(id, _, _)| {
+            model
+                .strip_prefix(id)
+                .is_some_and(|rest| rest.is_empty() || is_date_suffix(rest))
+        })
+        .map(|(_, input, output)| {
+            (usage.input_tokens as f64 * input + usage.output_tokens as f64 * output) / 1e6
+        })
+}
+
+/// Key for an identical request: the same provider, endpoint, model, output limit,
+/// temperature, and prompt. Prompts embed the transcript, so an edited transcript misses
+/// the cache.
+fn response_cache_key(config: &AIConfig, prompt: &str, temperature: f64) -> String {
+    let endpoint = match config.provider {
+        AIProvider::Ollama => config.ollama_url.as_deref(),
+        AIProvider::LmStudio => config.lmstudio_url.as_deref(),
+        AIProvider::Proxy => config.proxy_url.as_deref(),
+        AIProvider::Custom => config
+            .custom_endpoint()
+            .ok()
+            .map(|endpoint| endpoint.base_url.as_str()),
+        _ => None,
+    };
+    let max_tokens = config
+        .summary_max_tokens
+        .map(|tokens| tokens.to_string())
+        .unwrap_or_default();
+    let temperature = temperature.to_string();
+
+    let mut hasher = Sha256::new();
+    for part in [
+        config.provider.label(),
+        endpoint.unwrap_or_default(),
+        config.model.as_str(),
+        max_tokens.as_str(),
+        temperature.as_str(),
+        prompt,
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn record_usage(record: AIUsageRecord) {
+    if let Err(e) = add_ai_usage_in_db(&record) {
+        log::warn!("{}", e);
+    }
+}
+
+/// Answer from the response cache when the same request was made within
+/// `response_cache_hours`, otherwise run `call` and cache its text. Cache hits and
+/// provider calls are both added to the usage log. `on_cache_hit` receives the cached
+/// text so streaming callers can still show it. With `refresh_response_cache` set the
+/// cached answer is skipped and overwritten.
+pub(super) async fn with_response_cache(
+    config: &AIConfig,
+    prompt: &str,
+    temperature: f64,
+    on_cache_hit: impl FnOnce(&str),
+    call: impl Future<Output = Result<SummaryResult, AIError>>,
+) -> Result<SummaryResult, AIError> {
+    let cache_hours = config
+        .response_cache_hours
+        .unwrap_or(DEFAULT_RESPONSE_CACHE_HOURS);
+    let cache_key = (cache_hours > 0).then(|| response_cache_key(config, prompt, temperature));
+
+    if let Some(cache_key) = cache_key
+        .as_deref()
+        .filter(|_| !config.refresh_response_cache)
+    {
+        match get_cached_ai_response_from_db(cache_key) {
+            Ok(Some(cached)) => {
+                on_cache_hit(&cached.response);
+                record_usage(AIUsageRecord {
+                    provider: cached.provider.clone(),
+                    model: cached.model.clone(),
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    estimated_tokens: false,
+                    latency_ms: 0,
+                    cost_usd: Some(0.0),
+                    cached: true,
+                });
+                return Ok(SummaryResult {
+                    summary: cached.response,
+                    provider: cached.provider,
+                    model: cached.model,
+                    usage: None,
+                });
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+
+    let started = Instant::now();
+    let result = call.await?;
+    let (usage, estimated_tokens) = match result.usage {
+        Some(usage) => (usage, false),
+        None => (
+            AITokenUsage {
+                input_tokens: estimate_tokens(prompt),
+                output_tokens: estimate_tokens(&result.summary),
+            },
+            true,
+        ),
+    };
+    record_usage(AIUsageRecord {
+        provider: result.provider.clone(),
+        model: result.model.clone(),
+        input_tokens: usage.input_tokens,
+        output_tokens: usage.output_tokens,
+        estimated_tokens,
+        latency_ms: started.elapsed().as_millis() as u64,
+        cost_usd: estimate_cost_usd(&config.provider, &result.model, usage),
+        cached: false,
+    });
+
+    if let Some(cache_key) = cache_key.as_deref() {
+        if let Err(e) = save_cached_ai_response_in_db(
+            cache_key,
+            &result.provider,
+            &result.model,
+            &result.summary,
+            i64::from(cache_hours) * 3600,
+        ) {
+            log::warn!("{}", e);
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::providers::response_token_usage;
+    use super::*;
+
+    #[test]
+    fn estimates_cost_from_the_exact_model_price() {
+        let usage = AITokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+        };
+        let mini = estimate_cost_usd(&AIProvider::OpenAI, "gpt-4o-mini-2024-07-18", usage);
+        assert!((mini.unwrap() - 0.21).abs() < 1e-9);
+        let routed = estimate_cost_usd(&AIProvider::Custom, "openai/gpt-4o", usage);
+        assert!((routed.unwrap() - 3.5).abs() < 1e-9);
+        assert_eq!(
+            estimate_cost_usd(&AIProvider::Ollama, "llama3", usage),
+            Some(0.0)
+        );
+        assert_eq!(
+            estimate_cost_usd(&AIProvider::Proxy, "my-finetune", usage),
+            None
+        );
+        let dated = estimate_cost_usd(&AIProvider::Anthropic, "claude-sonnet-4-5-20250929", usage);
+        assert!((dated.unwrap() - 4.5).abs() < 1e-9);
+        let o3_mini = estimate_cost_usd(&AIProvider::OpenAI, "o3-mini", usage);
+        assert!((o3_mini.unwrap() - 1.54).abs() < 1e-9);
+        assert_eq!(
+            estimate_cost_usd(&AIProvider::OpenAI, "gpt-5.6", usage),
+            None
+        );
+        assert_eq!(
+            estimate_cost_usd(&AIProvider::Anthropic, "claude-opus-4-7", usage),
+            None
+        );
+    }
+
+    #[test]
+    fn every_offered_model_gets_its_own_price() {
+        let expected: &[(&str, f64, f64)] = &[
+            ("gpt-5.5", 5.00, 30.00),
+            ("gpt-5.4", 2.50, 15.00),
+            ("gpt-5.4-mini", 0.75, 4.50),
+            ("gemini-3.5-flash", 0.50, 3.00),
+            ("gemini-3.1-flash-lite", 0.25, 1.50),
+            ("gemini-2.5-pro", 1.25, 10.00),
+            ("claude-sonnet-4-5", 3.00, 15.00),
+            ("claude-haiku-4-5", 1.00, 5.00),
+            ("claude-opus-4-1", 15.00, 75.00),
+            ("deepseek-v4-flash", 0.14, 0.28),
+            ("deepseek-v4-pro", 0.55, 2.19),
+            ("qwen3-max", 1.20, 6.00),
+            ("qwen3.5-plus", 0.40, 2.40),
+            ("qwen3.5-flash", 0.10, 0.40),
+        ];
+        let input = AITokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 0,
+        };
+        let output = AITokenUsage {
+            input_tokens: 0,
+            output_tokens: 1_000_000,
+        };
+        for (name, provider) in [
+            ("gemini", AIProvider::Gemini),
+            ("openai", AIProvider::OpenAI),
+            ("ollama", AIProvider::Ollama),
+            ("lmstudio", AIProvider::LmStudio),
+            ("anthropic", AIProvider::Anthropic),
+            ("deepseek", AIProvider::DeepSeek),
+            ("qwen", AIProvider::Qwen),
+            ("proxy", AIProvider::Proxy),
+        ] {
+            let models = crate::commands::get_ai_models(name.to_string());
+            assert!(!models.is_empty(), "{} offers no models", name);
+            for model in models {
+                let (input_price, output_price) =
+                    if matches!(provider, AIProvider::Ollama | AIProvider::LmStudio) {
+                        (0.0, 0.0)
+                    } else {
+                        expected
+                            .iter()
+                            .find(|(id, _, _)| *id == model.value)
+                            .map(|(_, input, output)| (*input, *output))
+                            .unwrap_or_else(|| panic!("no expected price for {}", model.value))
+                    };
+                let input_cost = estimate_cost_usd(&provider, &model.value, input);
+                let output_cost = estimate_cost_usd(&provider, &model.value, output);
+                assert!(
+                    input_cost.is_some_and(|cost| (cost - input_price).abs() < 1e-9),
+                    "input price of {}: {:?}",
+                    model.value,
+                    input_cost
+                );
+                assert!(
+                    output_cost.is_some_and(|cost| (cost - output_price).abs() < 1e-9),
+                    "output price of {}: {:?}",
+                    model.value,
+                    output_cost
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cache_key_changes_with_model_temperature_and_prompt() {
+        let config = AIConfig::default();
+        let key = response_cache_key(&config, "Summarize: hello", 0.7);
+        assert_eq!(key, response_cache_key(&config, "Summarize: hello", 0.7));
+        assert_ne!(key, response_cache_key(&config, "Summarize: hello!", 0.7));
+        assert_ne!(key, response_cache_key(&config, "Summarize: hello", 0.3));
+
+        let other_model = AIConfig {
+            model: "gemini-2.5-pro".to_string(),
+            ..config.clone()
+        };
+        assert_ne!(
+            key,
+            response_cache_key(&other_model, "Summarize: hello", 0.7)
+        );
+
+        let refreshing = AIConfig {
+            refresh_response_cache: true,
+            ..config.clone()
+        };
+        assert_eq!(
+            key,
+            response_cache_key(&refreshing, "Summarize: hello", 0.7)
+        );
+    }
+
+    #[test]
+    fn reads_token_usage_from_each_provider_format() {
+        let usage = |json: &str| {
+            response_token_usage(&serde_json::from_str(json).unwrap())
+                .map(|usage| (usage.input_tokens, usage.output_tokens))
+        };
+        assert_eq!(
+            usage(r#"{"usage":{"prompt_tokens":10,"completion_tokens":3}}"#),
+            Some((10, 3))
+        );
+        assert_eq!(
+            usage(r#"{"usage":{"input_tokens":8,"output_tokens":2}}"#),
+            Some((8, 2))
+        );
+        assert_eq!(
+            usage(r#"{"usageMetadata":{"promptTokenCount":7,"candidatesTokenCount":4}}"#),
+            Some((7, 4))
+        );
+        assert_eq!(
+            usage(r#"{"response":"","prompt_eval_count":5,"eval_count":9}"#),
+            Some((5, 9))
+        );
+        assert_eq!(usage(r#"{"usage":null,"choices":[]}"#), None);
+    }
+}
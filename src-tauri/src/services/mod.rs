@@ -4,8 +4,12 @@ mod ffmpeg;
 mod gallerydl;
 mod plugin;
 pub mod polling;
+mod subtitles;
 pub mod telegram;
+mod transcripts;
 mod whisper;
+mod whisper_chunks;
+mod whisper_local;
 mod youtube_search;
 mod ytdlp;
 mod ytdlp_args;
@@ -15,7 +19,11 @@ pub use deno::*;
 pub use ffmpeg::*;
 pub use gallerydl::*;
 pub use plugin::*;
+pub use subtitles::*;
+pub use transcripts::*;
 pub use whisper::*;
+pub use whisper_chunks::*;
+pub use whisper_local::*;
 pub use youtube_search::*;
 pub use ytdlp::*;
 pub use ytdlp_args::*;
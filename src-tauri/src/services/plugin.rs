@@ -11,7 +11,8 @@ use tokio::process::Command;
 use uuid::Uuid;
 
 use crate::database::{
-    add_history_internal, add_log_internal, clear_plugin_logs_from_db, update_history_download,
+    add_history_internal, add_log_internal, clear_plugin_kv_from_db, clear_plugin_logs_from_db,
+    clear_plugin_secrets_from_db, encrypt_legacy_plugin_secrets_in_db, update_history_download,
 };
 use crate::types::{
     DownloadProgress, PluginChainState, PluginConfigField, PluginExecutionResult,
@@ -33,14 +34,21 @@ mod package;
 mod permissions;
 mod providers;
 mod registry;
+mod resolver;
 mod scaffold;
 mod sdk_bundle;
 mod security_policy;
 mod state;
+mod storage;
 mod summary;
+mod updates;
+#[cfg(feature = "wasm-plugins")]
+mod wasm;
 mod workflow;
 mod workspace;
 
+#[cfg(feature = "wasm-plugins")]
+use bridge::PluginBridgeSession;
 use bridge::{start_plugin_bridge, PluginBridgePolicy};
 #[cfg(test)]
 use compatibility::satisfies_version_range;
@@ -49,23 +57,27 @@ use compatibility::{
 };
 use logging::{
     build_plugin_completion_details, capture_process_stream, capture_process_stream_err,
-    classify_plugin_runtime_error, combine_plugin_event_details, output_to_string,
-    parse_plugin_result, plugin_exit_reason, plugin_output_details, shorten_for_event,
+    classify_plugin_runtime_error, combine_plugin_event_details, emit_plugin_runtime_output,
+    output_to_string, parse_plugin_result, plugin_exit_reason, plugin_output_details,
+    shorten_for_event,
 };
 #[cfg(test)]
 use manifest::validate_manifest;
 use manifest::{load_installed_manifest_from_dir, load_source_manifest_from_dir};
 use package::{inspect_ywp_file, load_packaged_build_info, prepare_plugin_package};
 use permissions::{
-    build_permission_path_scopes, collect_missing_permissions, path_scope_variants,
-    push_allow_flag, resolve_plugin_entrypoint,
+    build_allow_net_hosts, build_permission_path_scopes, collect_missing_permissions,
+    path_scope_variants, push_allow_flag, resolve_plugin_entrypoint,
 };
 use providers::resolve_provider_command;
+#[cfg(not(feature = "wasm-plugins"))]
+use providers::WASM_RUNTIME_UNAVAILABLE;
 pub use providers::{get_runtime_provider_status_internal, list_runtime_providers_internal};
 use registry::{
     read_registry, write_registry, PluginRegistry, PluginRegistryEntry,
     PluginTriggerWorkflowRegistry,
 };
+pub use resolver::resolve_url_with_plugins;
 #[cfg(test)]
 use scaffold::{
     build_scaffold_ci_workflow, build_scaffold_package_json, build_scaffold_readme,
@@ -84,6 +96,18 @@ pub use state::{
     update_plugin_config_values_internal, update_plugin_state_internal,
     update_plugin_trigger_workflow_internal,
 };
+use storage::{load_config_secret_values, migrate_config_secrets_from_registry};
+pub use updates::{
+    apply_plugin_update_internal, check_plugin_updates_internal,
+    pin_legacy_plugin_signers_internal, prepare_plugin_update_internal, rollback_plugin_internal,
+    start_plugin_update_checks,
+};
+use updates::{
+    ensure_pinned_signer, pinned_signer_for_entry, push_version_record,
+    remove_previous_installation_dir,
+};
+#[cfg(feature = "wasm-plugins")]
+use wasm::run_wasm_plugin;
 
 const PLUGIN_STORE_CATALOG_JSON: &str = include_str!("../../../plugin-store/catalog.json");
 const PLUGIN_STORE_CATALOG_URL: &str =
@@ -383,9 +407,13 @@ const PLUGIN_BASE_ENV_KEYS: &[&str] = &[
 pub struct PluginPermissionApprovalInput {
     pub network: bool,
     #[serde(default)]
+    pub network_hosts: Vec<String>,
+    #[serde(default)]
     pub fs: Vec<PluginFilesystemPermission>,
     #[serde(default)]
     pub tools: Vec<PluginToolPermission>,
+    #[serde(default)]
+    pub resolver: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -539,6 +567,26 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// Move `password` config values that older versions wrote to `registry.json` into the secret
+/// store, and encrypt secrets stored in plaintext. Runs once at startup after the database opens.
+pub fn migrate_plugin_secrets_internal(app: &AppHandle) -> Result<(), String> {
+    let encrypted = encrypt_legacy_plugin_secrets_in_db()?;
+    if encrypted > 0 {
+        log::info!("Encrypted {} plugin secrets stored in plaintext", encrypted);
+    }
+
+    let plugins = list_plugins_internal(app)?;
+    let mut registry = read_registry(app)?;
+    let mut registry_changed = false;
+    for plugin in &plugins {
+        registry_changed |= migrate_config_secrets_from_registry(&mut registry, &plugin.manifest)?;
+    }
+    if registry_changed {
+        write_registry(app, &registry)?;
+    }
+    Ok(())
+}
+
 pub fn list_plugins_internal(app: &AppHandle) -> Result<Vec<PluginSummary>, String> {
     let root = ensure_plugins_root(app)?;
     let registry = read_registry(app)?;
@@ -622,10 +670,12 @@ pub fn list_plugins_internal(app: &AppHandle) -> Result<Vec<PluginSummary>, Stri
         );
     }
 
-    for entry in registry.installations.values() {
-        let Some(source) = entry.source.as_ref() else {
-            continue;
-        };
+    let registry_sources = registry
+        .installations
+        .values()
+        .filter_map(|entry| entry.source.clone())
+        .collect::<Vec<_>>();
+    for source in registry_sources {
         if source.kind != PluginPackageSourceKind::Workspace {
             continue;
         }
@@ -710,7 +760,7 @@ pub async fn inspect_plugin_package_internal(
     if package.signature_status.as_deref() == Some("signed") {
         if let Some(existing) = registry.installations.get(&package.manifest.plugin_id) {
             if let (Some(existing_fingerprint), Some(next_fingerprint)) = (
-                existing.signer_fingerprint.as_deref(),
+                pinned_signer_for_entry(existing),
                 package.signer_fingerprint.as_deref(),
             ) {
                 if existing_fingerprint != next_fingerprint {
@@ -768,19 +818,19 @@ pub async fn install_plugin_internal(
     let mut registry = read_registry(app)?;
     let registry_locale = registry.app_locale.clone();
     let registry_fallback_locale = registry.app_fallback_locale.clone();
-    if let Some(existing) = registry.installations.get(&package.manifest.plugin_id) {
-        let existing_fingerprint = existing.signer_fingerprint.as_deref();
-        let next_fingerprint = package.signer_fingerprint.as_deref();
-        if let (Some(existing_fingerprint), Some(next_fingerprint)) =
-            (existing_fingerprint, next_fingerprint)
-        {
-            if existing_fingerprint != next_fingerprint {
-                return Err(format!(
-                    "Plugin signer changed for {}. Uninstall the existing plugin before installing a package signed by a different key.",
-                    package.manifest.plugin_id
-                ));
-            }
-        }
+    let previous_entry = registry.installations.get(&package.manifest.plugin_id);
+    if let Some(existing) = previous_entry {
+        ensure_pinned_signer(existing, &package)?;
+    }
+    let pinned_signer_fingerprint = previous_entry
+        .and_then(pinned_signer_for_entry)
+        .or_else(|| package.signer_fingerprint.clone());
+    let mut version_history = previous_entry
+        .map(|entry| entry.version_history.clone())
+        .unwrap_or_default();
+    push_version_record(&mut version_history, &package, "install");
+    if let Some(previous) = previous_entry.and_then(|entry| entry.previous_installation.as_ref()) {
+        remove_previous_installation_dir(previous);
     }
     let root = ensure_plugins_root(app)?;
     let destination = installation_path(&root, &package.manifest);
@@ -827,6 +877,9 @@ pub async fn install_plugin_internal(
             signer_fingerprint: package.signer_fingerprint.clone(),
             signature_algorithm: package.signature_algorithm.clone(),
             signed_at: package.signed_at.clone(),
+            pinned_signer_fingerprint,
+            version_history,
+            previous_installation: None,
         },
     );
     write_registry(app, &registry)?;
@@ -1307,6 +1360,12 @@ pub fn attach_plugin_workspace_internal(
             signer_fingerprint: None,
             signature_algorithm: None,
             signed_at: None,
+            pinned_signer_fingerprint: None,
+            version_history: existing
+                .as_ref()
+                .map(|value| value.version_history.clone())
+                .unwrap_or_default(),
+            previous_installation: None,
         },
     );
     write_registry(app, &registry)?;
@@ -1374,13 +1433,21 @@ pub fn uninstall_plugin_internal(app: &AppHandle, plugin_id: &str) -> Result<(),
     }
 
     let mut registry = read_registry(app)?;
-    registry.installations.remove(plugin_id);
+    if let Some(previous) = registry
+        .installations
+        .remove(plugin_id)
+        .and_then(|entry| entry.previous_installation)
+    {
+        remove_previous_installation_dir(&previous);
+    }
     for workflow in registry.trigger_workflows.values_mut() {
         workflow.steps.retain(|step| step.plugin_id != plugin_id);
     }
     write_registry(app, &registry)?;
 
     clear_plugin_logs_from_db(plugin_id.to_string()).ok();
+    clear_plugin_kv_from_db(plugin_id).ok();
+    clear_plugin_secrets_from_db(plugin_id).ok();
     add_log_internal(
         "info",
         &format!(
@@ -1480,11 +1547,12 @@ async fn execute_plugin(
     let entrypoint = resolve_plugin_entrypoint(&plugin_dir, &plugin.manifest.runtime.entrypoint)?;
     let (command_path, resolved_source) = resolve_provider_command(app, &selected_provider).await?;
     let registry = read_registry(app).unwrap_or_default();
-    let stored_config_values = registry
+    let mut stored_config_values = registry
         .installations
         .get(&plugin.manifest.plugin_id)
         .map(|entry| entry.config_values.clone())
         .unwrap_or_default();
+    stored_config_values.extend(load_config_secret_values(&plugin.manifest)?);
     let resolved_config_values =
         resolve_effective_plugin_config_values(&plugin.manifest, &stored_config_values)?;
     let app_locale = registry
@@ -1559,23 +1627,155 @@ async fn execute_plugin(
             .contains(&PluginToolPermission::YtdlpRun);
     let network_allowed =
         plugin.manifest.permissions.network && plugin.installation.approved_permissions.network;
+    // An empty host list keeps the legacy unrestricted grant; otherwise only approved hosts.
+    let network_hosts = (network_allowed && !plugin.manifest.permissions.network_hosts.is_empty())
+        .then(|| {
+            plugin
+                .manifest
+                .permissions
+                .network_hosts
+                .iter()
+                .filter(|host| {
+                    plugin
+                        .installation
+                        .approved_permissions
+                        .network_hosts
+                        .iter()
+                        .any(|approved| approved.eq_ignore_ascii_case(host))
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        });
 
-    let bridge = start_plugin_bridge(
-        Uuid::new_v4().to_string(),
-        PluginBridgePolicy {
-            allow_read_scopes: bridge_allow_read_scopes,
-            allow_write_scopes: bridge_allow_write_scopes,
-            plugin_dir: plugin_dir.clone(),
-            ffmpeg_path: ffmpeg_run_allowed
-                .then(|| ffmpeg_path.as_ref().map(PathBuf::from))
-                .flatten(),
-            ytdlp_path: ytdlp_run_allowed
-                .then(|| ytdlp_path.as_ref().map(PathBuf::from))
-                .flatten(),
-            network_allowed,
-        },
-    )
-    .await?;
+    let bridge_policy = PluginBridgePolicy {
+        allow_read_scopes: bridge_allow_read_scopes,
+        allow_write_scopes: bridge_allow_write_scopes,
+        plugin_dir: plugin_dir.clone(),
+        ffmpeg_path: ffmpeg_run_allowed
+            .then(|| ffmpeg_path.as_ref().map(PathBuf::from))
+            .flatten(),
+        ytdlp_path: ytdlp_run_allowed
+            .then(|| ytdlp_path.as_ref().map(PathBuf::from))
+            .flatten(),
+        network_allowed,
+        network_hosts: network_hosts.clone(),
+        plugin_id: plugin.manifest.plugin_id.clone(),
+    };
+
+    let mut plugin_env = vec![
+        (
+            "YOUWEE_PLUGIN_TIMEOUT_MS".to_string(),
+            timeout_sec.saturating_mul(1000).to_string(),
+        ),
+        (
+            "YOUWEE_PLUGIN_ID".to_string(),
+            plugin.manifest.plugin_id.clone(),
+        ),
+        (
+            "YOUWEE_PLUGIN_SLUG".to_string(),
+            plugin.manifest.slug.clone(),
+        ),
+        (
+            "YOUWEE_PLUGIN_NAME".to_string(),
+            plugin.manifest.name.clone(),
+        ),
+        (
+            "YOUWEE_PLUGIN_VERSION".to_string(),
+            plugin.manifest.version.clone(),
+        ),
+        (
+            "YOUWEE_PLUGIN_CONFIG_JSON".to_string(),
+            serde_json::to_string(&resolved_config_values)
+                .map_err(|e| format!("Failed to serialize plugin config values: {}", e))?,
+        ),
+        (
+            "YOUWEE_APP_VERSION".to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        ),
+        ("YOUWEE_APP_LOCALE".to_string(), app_locale),
+        (
+            "YOUWEE_APP_FALLBACK_LOCALE".to_string(),
+            app_fallback_locale,
+        ),
+        ("YOUWEE_APP_DIRECTION".to_string(), app_direction),
+        (
+            "YOUWEE_PLUGIN_LANGUAGE".to_string(),
+            plugin.manifest.runtime.language.as_str().to_string(),
+        ),
+        (
+            "YOUWEE_PLUGIN_PROVIDER".to_string(),
+            selected_provider.as_str().to_string(),
+        ),
+        (
+            "YOUWEE_PLUGIN_MAIN".to_string(),
+            entrypoint.to_string_lossy().to_string(),
+        ),
+        (
+            "YOUWEE_PLUGIN_I18N_DEFAULT_LOCALE".to_string(),
+            plugin
+                .manifest
+                .i18n
+                .as_ref()
+                .and_then(|value| value.default_locale.clone())
+                .unwrap_or_else(|| "en".to_string()),
+        ),
+        (
+            "YOUWEE_PLUGIN_I18N_SUPPORTED_LOCALES".to_string(),
+            plugin
+                .manifest
+                .i18n
+                .as_ref()
+                .map(|value| value.supported_locales.join(","))
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| "en".to_string()),
+        ),
+        (
+            "YOUWEE_PLUGIN_I18N_DIR".to_string(),
+            plugin
+                .manifest
+                .i18n
+                .as_ref()
+                .and_then(|value| value.directory.clone())
+                .unwrap_or_else(|| "locales".to_string()),
+        ),
+    ];
+    if let Some(source) = resolved_source.as_ref() {
+        plugin_env.push(("YOUWEE_PLUGIN_PROVIDER_SOURCE".to_string(), source.clone()));
+    }
+    for (key, value) in &resolved_config_values {
+        let serialized = match value {
+            Value::String(text) => text.clone(),
+            Value::Number(number) => number.to_string(),
+            Value::Bool(flag) => flag.to_string(),
+            Value::Array(items) => serde_json::to_string(items)
+                .map_err(|e| format!("Failed to serialize config field {}: {}", key, e))?,
+            _ => continue,
+        };
+        plugin_env.push((key.clone(), serialized));
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    if selected_provider == PluginProvider::Wasm {
+        return Err(PluginExecutionError::new(WASM_RUNTIME_UNAVAILABLE));
+    }
+    #[cfg(feature = "wasm-plugins")]
+    if selected_provider == PluginProvider::Wasm {
+        return execute_wasm_plugin(
+            app,
+            plugin,
+            run_id,
+            payload,
+            &entrypoint,
+            payload_json,
+            plugin_env,
+            PluginBridgeSession::new(bridge_policy),
+            timeout_sec,
+            resolved_source,
+        )
+        .await;
+    }
+
+    let bridge = start_plugin_bridge(Uuid::new_v4().to_string(), bridge_policy).await?;
 
     let mut command_args = Vec::<String>::new();
     match selected_provider {
@@ -1585,14 +1785,17 @@ async fn execute_plugin(
             command_args.push("--unstable-detect-cjs".to_string());
             command_args.push("--node-modules-dir=manual".to_string());
             command_args.push(format!("--allow-env={}", plugin_env_keys.join(",")));
-            if plugin.manifest.permissions.network {
-                command_args.push("--allow-net".to_string());
-            } else {
-                let bridge_host = bridge
-                    .url()
-                    .strip_prefix("http://")
-                    .unwrap_or_else(|| bridge.url());
-                command_args.push(format!("--allow-net={bridge_host}"));
+            let bridge_host = bridge
+                .url()
+                .strip_prefix("http://")
+                .unwrap_or_else(|| bridge.url());
+            match (network_allowed, network_hosts.as_deref()) {
+                (true, None) => command_args.push("--allow-net".to_string()),
+                (true, Some(hosts)) => command_args.push(format!(
+                    "--allow-net={}",
+                    build_allow_net_hosts(bridge_host, hosts)
+                )),
+                (false, _) => command_args.push(format!("--allow-net={bridge_host}")),
             }
             push_allow_flag(&mut command_args, "allow-read", &direct_read_scopes);
             let runtime_cli = app_sdk_runtime_bundle
@@ -1605,6 +1808,11 @@ async fn execute_plugin(
         PluginProvider::Python => {
             command_args.push(entrypoint.to_string_lossy().to_string());
         }
+        PluginProvider::Wasm => {
+            return Err(PluginExecutionError::new(
+                "WASM plugins run in-process and cannot be spawned as a subprocess",
+            ));
+        }
     }
 
     let mut cmd = Command::new(&command_path);
@@ -1619,77 +1827,10 @@ async fn execute_plugin(
         cmd.env_remove("DYLD_LIBRARY_PATH");
         cmd.env_remove("LD_LIBRARY_PATH");
     }
-    cmd.env(
-        "YOUWEE_PLUGIN_TIMEOUT_MS",
-        timeout_sec.saturating_mul(1000).to_string(),
-    );
-    cmd.env("YOUWEE_PLUGIN_ID", &plugin.manifest.plugin_id);
-    cmd.env("YOUWEE_PLUGIN_SLUG", &plugin.manifest.slug);
-    cmd.env("YOUWEE_PLUGIN_NAME", &plugin.manifest.name);
-    cmd.env("YOUWEE_PLUGIN_VERSION", &plugin.manifest.version);
-    cmd.env(
-        "YOUWEE_PLUGIN_CONFIG_JSON",
-        serde_json::to_string(&resolved_config_values)
-            .map_err(|e| format!("Failed to serialize plugin config values: {}", e))?,
-    );
-    cmd.env("YOUWEE_APP_VERSION", env!("CARGO_PKG_VERSION"));
-    cmd.env("YOUWEE_APP_LOCALE", &app_locale);
-    cmd.env("YOUWEE_APP_FALLBACK_LOCALE", &app_fallback_locale);
-    cmd.env("YOUWEE_APP_DIRECTION", &app_direction);
-    cmd.env(
-        "YOUWEE_PLUGIN_LANGUAGE",
-        plugin.manifest.runtime.language.as_str(),
-    );
-    cmd.env("YOUWEE_PLUGIN_PROVIDER", selected_provider.as_str());
-    cmd.env(
-        "YOUWEE_PLUGIN_MAIN",
-        entrypoint.to_string_lossy().to_string(),
-    );
-    cmd.env(
-        "YOUWEE_PLUGIN_I18N_DEFAULT_LOCALE",
-        plugin
-            .manifest
-            .i18n
-            .as_ref()
-            .and_then(|value| value.default_locale.clone())
-            .unwrap_or_else(|| "en".to_string()),
-    );
-    cmd.env(
-        "YOUWEE_PLUGIN_I18N_SUPPORTED_LOCALES",
-        plugin
-            .manifest
-            .i18n
-            .as_ref()
-            .map(|value| value.supported_locales.join(","))
-            .filter(|value| !value.is_empty())
-            .unwrap_or_else(|| "en".to_string()),
-    );
-    cmd.env(
-        "YOUWEE_PLUGIN_I18N_DIR",
-        plugin
-            .manifest
-            .i18n
-            .as_ref()
-            .and_then(|value| value.directory.clone())
-            .unwrap_or_else(|| "locales".to_string()),
-    );
-    if let Some(source) = resolved_source.as_ref() {
-        cmd.env("YOUWEE_PLUGIN_PROVIDER_SOURCE", source);
-    }
+    cmd.envs(plugin_env);
     cmd.env("YOUWEE_PLUGIN_BRIDGE_URL", bridge.url());
     cmd.env("YOUWEE_PLUGIN_BRIDGE_TOKEN", bridge.token());
     cmd.env("YOUWEE_PLUGIN_BRIDGE_TOOLS", bridge.tools_csv());
-    for (key, value) in &resolved_config_values {
-        let serialized = match value {
-            Value::String(text) => text.clone(),
-            Value::Number(number) => number.to_string(),
-            Value::Bool(flag) => flag.to_string(),
-            Value::Array(items) => serde_json::to_string(items)
-                .map_err(|e| format!("Failed to serialize config field {}: {}", key, e))?,
-            _ => continue,
-        };
-        cmd.env(key, serialized);
-    }
 
     let mut child = cmd.spawn().map_err(|e| {
         format!(
@@ -1848,6 +1989,106 @@ async fn execute_plugin(
     ))
 }
 
+#[cfg(feature = "wasm-plugins")]
+#[allow(clippy::too_many_arguments)]
+async fn execute_wasm_plugin(
+    app: &AppHandle,
+    plugin: &PluginSummary,
+    run_id: &str,
+    payload: &PostDownloadPluginPayload,
+    module_path: &Path,
+    payload_json: Vec<u8>,
+    plugin_env: Vec<(String, String)>,
+    bridge: PluginBridgeSession,
+    timeout_sec: u64,
+    resolved_source: Option<String>,
+) -> Result<(PluginExecutionResult, PluginProvider, Option<String>), PluginExecutionError> {
+    let output = run_wasm_plugin(module_path, payload_json, plugin_env, bridge, timeout_sec)
+        .await
+        .map_err(PluginExecutionError::new)?;
+
+    for (stream, bytes) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+        emit_plugin_runtime_output(
+            app,
+            &plugin.manifest.plugin_id,
+            &plugin.manifest.name,
+            Some(run_id),
+            stream,
+            bytes,
+            Some(&payload.url),
+            payload.title.as_deref(),
+            Some(&payload.filename),
+        );
+    }
+
+    let stdout = output_to_string(&output.stdout);
+    let stderr = output_to_string(&output.stderr);
+
+    if output.timed_out {
+        let mut message = format!("Plugin timed out after {}s", timeout_sec);
+        message.push_str("\nProvider: wasm\nResolved source: embedded");
+        if !stderr.is_empty() {
+            message.push_str(&format!("\n\nstderr:\n{}", stderr));
+        }
+        return Err(PluginExecutionError::new(message));
+    }
+    if let Some(trap) = output.trap {
+        return Err(PluginExecutionError {
+            message: "WASM plugin trapped.".to_string(),
+            details: Some(format!(
+                "{}\n\n{}",
+                trap,
+                plugin_output_details(&stdout, &stderr)
+            )),
+            error_kind: None,
+            error_resource: None,
+        });
+    }
+    if output.exit_code != 0 {
+        return Err(PluginExecutionError {
+            message: format!("Plugin exited with code {}.", output.exit_code),
+            details: Some(plugin_output_details(&stdout, &stderr)),
+            error_kind: None,
+            error_resource: None,
+        });
+    }
+
+    let parsed_output = parse_plugin_result(&stdout);
+    Ok((
+        PluginExecutionResult {
+            plugin_id: plugin.manifest.plugin_id.clone(),
+            success: parsed_output
+                .as_ref()
+                .and_then(|value| value.success)
+                .unwrap_or(true),
+            message: parsed_output
+                .as_ref()
+                .and_then(|value| value.message.clone()),
+            artifacts: parsed_output
+                .as_ref()
+                .and_then(|value| value.artifacts.clone()),
+            metadata: parsed_output
+                .as_ref()
+                .and_then(|value| value.metadata.clone()),
+            mutations: parsed_output
+                .as_ref()
+                .and_then(|value| value.mutations.clone()),
+            stdout: if stdout.is_empty() {
+                None
+            } else {
+                Some(stdout)
+            },
+            stderr: if stderr.is_empty() {
+                None
+            } else {
+                Some(stderr)
+            },
+        },
+        PluginProvider::Wasm,
+        resolved_source,
+    ))
+}
+
 async fn execute_plugin_workflow_run(
     app: AppHandle,
     workflow_run: PluginWorkflowRun,
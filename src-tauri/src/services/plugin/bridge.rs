@@ -10,6 +10,11 @@ use tokio::net::{TcpListener, TcpStream};
 use tokio::process::Command;
 use tokio::sync::{oneshot, Mutex};
 
+use crate::database::{
+    delete_plugin_kv_value_from_db, delete_plugin_secret_from_db, get_plugin_kv_value_from_db,
+    get_plugin_secret_from_db, list_plugin_kv_keys_from_db, set_plugin_kv_value_in_db,
+    set_plugin_secret_in_db, PLUGIN_KV_DEFAULT_NAMESPACE, PLUGIN_SECRET_SCOPE_PLUGIN,
+};
 use crate::utils::CommandExt;
 use crate::{
     services::search_youtube_videos_internal,
@@ -28,6 +33,8 @@ const STRIP_SUBPROCESS_ENV_KEYS: &[&str] = &[
     "LD_LIBRARY_PATH",
 ];
 
+const YOUTUBE_SEARCH_HOST: &str = "www.youtube.com";
+
 #[derive(Clone)]
 pub(super) struct PluginBridgePolicy {
     pub(super) allow_read_scopes: Vec<PathBuf>,
@@ -36,6 +43,25 @@ pub(super) struct PluginBridgePolicy {
     pub(super) ffmpeg_path: Option<PathBuf>,
     pub(super) ytdlp_path: Option<PathBuf>,
     pub(super) network_allowed: bool,
+    /// `None` means unrestricted network access; `Some` limits it to the listed hosts.
+    pub(super) network_hosts: Option<Vec<String>>,
+    pub(super) plugin_id: String,
+}
+
+impl PluginBridgePolicy {
+    fn network_host_allowed(&self, host: &str) -> bool {
+        self.network_allowed
+            && self.network_hosts.as_ref().map_or(true, |hosts| {
+                hosts.iter().any(|allowed| {
+                    let allowed_name = allowed
+                        .rsplit_once(':')
+                        .filter(|(_, port)| port.parse::<u16>().is_ok())
+                        .map(|(name, _)| name)
+                        .unwrap_or(allowed);
+                    allowed_name.eq_ignore_ascii_case(host)
+                })
+            })
+    }
 }
 
 #[derive(Default)]
@@ -65,6 +91,36 @@ impl PluginBridgeServer {
     }
 }
 
+/// In-process bridge used by runtimes that call host functions directly instead of
+/// going through the loopback HTTP server. It shares the same policy checks.
+#[cfg(feature = "wasm-plugins")]
+#[derive(Clone)]
+pub(super) struct PluginBridgeSession {
+    policy: Arc<PluginBridgePolicy>,
+    state: Arc<Mutex<PluginBridgeRunState>>,
+}
+
+#[cfg(feature = "wasm-plugins")]
+impl PluginBridgeSession {
+    pub(super) fn new(policy: PluginBridgePolicy) -> Self {
+        Self {
+            policy: Arc::new(policy),
+            state: Arc::new(Mutex::new(PluginBridgeRunState::default())),
+        }
+    }
+
+    /// Runs one bridge operation and returns the serialized `{ ok, result, error }` envelope.
+    pub(super) async fn call(&self, operation: &str, body: Vec<u8>) -> Vec<u8> {
+        let (status, result, error) =
+            dispatch_bridge_operation(self.policy.clone(), self.state.clone(), operation, body)
+                .await
+                .unwrap_or_else(|error| (400, None, Some(error)));
+        serde_json::to_vec(&bridge_response(status, result, error.as_deref())).unwrap_or_else(
+            |_| br#"{"ok":false,"error":"Failed to serialize bridge response"}"#.to_vec(),
+        )
+    }
+}
+
 impl Drop for PluginBridgeServer {
     fn drop(&mut self) {
         if let Some(shutdown) = self.shutdown.take() {
@@ -117,6 +173,41 @@ struct YoutubeSearchVideosRequest {
     continuation: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KvKeyRequest {
+    namespace: Option<String>,
+    key: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KvSetRequest {
+    namespace: Option<String>,
+    key: String,
+    value: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KvListRequest {
+    namespace: Option<String>,
+    prefix: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SecretKeyRequest {
+    key: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SecretSetRequest {
+    key: String,
+    value: String,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct BridgeResponse<T: Serialize> {
@@ -274,6 +365,15 @@ async fn handle_bridge_request(
         return Ok((401, None, Some("Invalid plugin bridge token.".to_string())));
     }
 
+    dispatch_bridge_operation(policy, state, path, body).await
+}
+
+async fn dispatch_bridge_operation(
+    policy: Arc<PluginBridgePolicy>,
+    state: Arc<Mutex<PluginBridgeRunState>>,
+    path: &str,
+    body: Vec<u8>,
+) -> Result<(u16, Option<serde_json::Value>, Option<String>), String> {
     match path {
         "/fs/exists" => {
             let request: FsPathRequest = parse_body(&body)?;
@@ -388,6 +488,16 @@ async fn handle_bridge_request(
                     Some("YouTube search requires approved plugin network permission.".to_string()),
                 ));
             }
+            if !policy.network_host_allowed(YOUTUBE_SEARCH_HOST) {
+                return Ok((
+                    403,
+                    None,
+                    Some(format!(
+                        "YouTube search requires {} in the plugin's approved network hosts.",
+                        YOUTUBE_SEARCH_HOST
+                    )),
+                ));
+            }
             let request: YoutubeSearchVideosRequest = parse_body(&body)?;
             let response: YoutubeSearchResponse = search_youtube_videos_internal(
                 request.query,
@@ -402,6 +512,73 @@ async fn handle_bridge_request(
                 None,
             ))
         }
+        "/kv/get" => {
+            let request: KvKeyRequest = parse_body(&body)?;
+            let value = get_plugin_kv_value_from_db(
+                &policy.plugin_id,
+                kv_namespace(&request.namespace),
+                &request.key,
+            )?;
+            Ok((200, Some(value.unwrap_or(serde_json::Value::Null)), None))
+        }
+        "/kv/set" => {
+            let request: KvSetRequest = parse_body(&body)?;
+            set_plugin_kv_value_in_db(
+                &policy.plugin_id,
+                kv_namespace(&request.namespace),
+                &request.key,
+                &request.value,
+            )?;
+            Ok((200, Some(serde_json::Value::Null), None))
+        }
+        "/kv/delete" => {
+            let request: KvKeyRequest = parse_body(&body)?;
+            let removed = delete_plugin_kv_value_from_db(
+                &policy.plugin_id,
+                kv_namespace(&request.namespace),
+                &request.key,
+            )?;
+            Ok((200, Some(serde_json::json!(removed)), None))
+        }
+        "/kv/list" => {
+            let request: KvListRequest = parse_body(&body)?;
+            let keys = list_plugin_kv_keys_from_db(
+                &policy.plugin_id,
+                kv_namespace(&request.namespace),
+                request.prefix.as_deref(),
+            )?;
+            Ok((200, Some(serde_json::json!(keys)), None))
+        }
+        // Bridge secrets are limited to the plugin scope; password config values reach the
+        // plugin through its resolved config and cannot be overwritten from here.
+        "/secrets/get" => {
+            let request: SecretKeyRequest = parse_body(&body)?;
+            let value = get_plugin_secret_from_db(
+                &policy.plugin_id,
+                PLUGIN_SECRET_SCOPE_PLUGIN,
+                &request.key,
+            )?;
+            Ok((200, Some(serde_json::json!(value)), None))
+        }
+        "/secrets/set" => {
+            let request: SecretSetRequest = parse_body(&body)?;
+            set_plugin_secret_in_db(
+                &policy.plugin_id,
+                PLUGIN_SECRET_SCOPE_PLUGIN,
+                &request.key,
+                &request.value,
+            )?;
+            Ok((200, Some(serde_json::Value::Null), None))
+        }
+        "/secrets/delete" => {
+            let request: SecretKeyRequest = parse_body(&body)?;
+            let removed = delete_plugin_secret_from_db(
+                &policy.plugin_id,
+                PLUGIN_SECRET_SCOPE_PLUGIN,
+                &request.key,
+            )?;
+            Ok((200, Some(serde_json::json!(removed)), None))
+        }
         _ => Ok((
             404,
             None,
@@ -455,6 +632,14 @@ async fn read_directory_entries(path: &Path) -> Result<Vec<FsDirectoryEntry>, St
     Ok(entries)
 }
 
+fn kv_namespace(namespace: &Option<String>) -> &str {
+    namespace
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(PLUGIN_KV_DEFAULT_NAMESPACE)
+}
+
 fn parse_body<T: for<'de> Deserialize<'de>>(body: &[u8]) -> Result<T, String> {
     serde_json::from_slice(body).map_err(|e| format!("Invalid plugin bridge JSON: {e}"))
 }
@@ -495,7 +680,9 @@ async fn run_tool(
     command
         .args(&request.args)
         .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
+        .stderr(std::process::Stdio::piped())
+        // A WASM guest whose deadline expires drops this future; take the tool down with it.
+        .kill_on_drop(true);
     command.hide_window();
 
     command.current_dir(cwd);
@@ -774,6 +961,8 @@ mod tests {
             ffmpeg_path: None,
             ytdlp_path: None,
             network_allowed: false,
+            network_hosts: None,
+            plugin_id: "com.example.test".to_string(),
         }
     }
 
@@ -806,6 +995,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn network_host_allowed_respects_scoped_hosts() {
+        let root = test_dir("network-hosts");
+        let mut policy = test_policy(&root);
+        std::fs::remove_dir_all(&root).ok();
+        assert!(!policy.network_host_allowed(YOUTUBE_SEARCH_HOST));
+
+        policy.network_allowed = true;
+        assert!(policy.network_host_allowed(YOUTUBE_SEARCH_HOST));
+
+        policy.network_hosts = Some(vec!["api.example.com".to_string()]);
+        assert!(!policy.network_host_allowed(YOUTUBE_SEARCH_HOST));
+
+        policy.network_hosts = Some(vec!["WWW.youtube.com:443".to_string()]);
+        assert!(policy.network_host_allowed(YOUTUBE_SEARCH_HOST));
+    }
+
     #[tokio::test]
     async fn read_base64_returns_binary_file_content() {
         let root = test_dir("read-base64");
@@ -1015,17 +1221,25 @@ mod tests {
     }
 }
 
-async fn write_json_response<T: Serialize>(
-    stream: &mut TcpStream,
+fn bridge_response<T: Serialize>(
     status: u16,
     result: Option<T>,
     error: Option<&str>,
-) -> Result<(), String> {
-    let response = BridgeResponse {
+) -> BridgeResponse<T> {
+    BridgeResponse {
         ok: error.is_none() && status < 400,
         result,
         error: error.map(str::to_string),
-    };
+    }
+}
+
+async fn write_json_response<T: Serialize>(
+    stream: &mut TcpStream,
+    status: u16,
+    result: Option<T>,
+    error: Option<&str>,
+) -> Result<(), String> {
+    let response = bridge_response(status, result, error);
     let body = serde_json::to_vec(&response)
         .map_err(|e| format!("Failed to serialize plugin bridge response: {e}"))?;
     let reason = match status {
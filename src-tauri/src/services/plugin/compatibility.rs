@@ -27,7 +27,7 @@ fn parse_simple_semver(version: &str) -> Option<SimpleSemver> {
     })
 }
 
-fn compare_simple_semver(a: &str, b: &str) -> Result<Ordering, String> {
+pub(super) fn compare_simple_semver(a: &str, b: &str) -> Result<Ordering, String> {
     let left = parse_simple_semver(a).ok_or_else(|| format!("Invalid semver: {}", a))?;
     let right = parse_simple_semver(b).ok_or_else(|| format!("Invalid semver: {}", b))?;
     Ok(left.cmp(&right))
@@ -327,6 +327,15 @@ pub(super) fn classify_plugin_runtime_error(stderr: &str) -> Option<PluginRuntim
                 "This plugin tried to run {resource}, but that command or tool is not approved."
             ),
         )
+    } else if let Some(host) = extract_quoted_resource(line, "Requires net access to") {
+        (
+            "net".to_string(),
+            Some(host.clone()),
+            Some(host.clone()),
+            format!(
+                "This plugin tried to reach {host}, but that host is not in its approved network hosts."
+            ),
+        )
     } else if line.starts_with("Requires net access") {
         (
             "net".to_string(),
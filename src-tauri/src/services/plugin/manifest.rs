@@ -8,10 +8,14 @@ use crate::types::{
     PluginManifest, PluginProvider, PluginRuntimeLanguage,
 };
 
+use super::permissions::validate_network_host;
+use super::resolver::validate_resolver_url_pattern;
+
 pub(super) fn default_supported_providers(language: &PluginRuntimeLanguage) -> Vec<PluginProvider> {
     match language {
         PluginRuntimeLanguage::Javascript => vec![PluginProvider::Deno],
         PluginRuntimeLanguage::Python => Vec::new(),
+        PluginRuntimeLanguage::Wasm => vec![PluginProvider::Wasm],
     }
 }
 
@@ -235,6 +239,14 @@ pub(super) fn validate_manifest(
             manifest_path.display()
         ));
     }
+    if manifest.runtime.language == PluginRuntimeLanguage::Wasm
+        && !manifest.runtime.entrypoint.trim().ends_with(".wasm")
+    {
+        return Err(format!(
+            "Plugin manifest {} declares a wasm runtime but runtime.entrypoint is not a .wasm module",
+            manifest_path.display()
+        ));
+    }
     if manifest.runtime.supported_providers.is_empty() {
         return Err(format!(
             "Plugin manifest {} is missing runtime.supportedProviders",
@@ -317,6 +329,33 @@ pub(super) fn validate_manifest(
             ));
         }
     }
+    if !manifest.permissions.network_hosts.is_empty() && !manifest.permissions.network {
+        return Err(format!(
+            "Plugin manifest {} lists permissions.networkHosts but does not request permissions.network",
+            manifest_path.display()
+        ));
+    }
+    let mut seen_network_hosts = BTreeMap::<String, bool>::new();
+    for host in &manifest.permissions.network_hosts {
+        validate_network_host(host).map_err(|e| {
+            format!(
+                "Plugin manifest {} has invalid network host {}: {}",
+                manifest_path.display(),
+                host,
+                e
+            )
+        })?;
+        if seen_network_hosts
+            .insert(host.to_ascii_lowercase(), true)
+            .is_some()
+        {
+            return Err(format!(
+                "Plugin manifest {} contains duplicate network host {}",
+                manifest_path.display(),
+                host
+            ));
+        }
+    }
     let needs_user_selected = manifest.permissions.fs.iter().any(|permission| {
         matches!(
             permission,
@@ -366,6 +405,39 @@ pub(super) fn validate_manifest(
     if let Some(i18n) = manifest.i18n.as_ref() {
         validate_i18n_spec(i18n, manifest_path)?;
     }
+    match manifest.resolver.as_ref() {
+        Some(resolver) => {
+            if !manifest.permissions.resolver {
+                return Err(format!(
+                    "Plugin manifest {} declares a resolver but does not request permissions.resolver",
+                    manifest_path.display()
+                ));
+            }
+            if resolver.url_patterns.is_empty() {
+                return Err(format!(
+                    "Plugin manifest {} declares a resolver without resolver.urlPatterns",
+                    manifest_path.display()
+                ));
+            }
+            for pattern in &resolver.url_patterns {
+                validate_resolver_url_pattern(pattern).map_err(|error| {
+                    format!(
+                        "Plugin manifest {} has an invalid resolver pattern {}: {}",
+                        manifest_path.display(),
+                        pattern,
+                        error
+                    )
+                })?;
+            }
+        }
+        None if manifest.permissions.resolver => {
+            return Err(format!(
+                "Plugin manifest {} requests permissions.resolver but does not declare a resolver",
+                manifest_path.display()
+            ));
+        }
+        None => {}
+    }
     let mut seen_config_field_keys = BTreeMap::<String, bool>::new();
     for (index, field) in manifest.config_fields.iter().enumerate() {
         if seen_config_field_keys
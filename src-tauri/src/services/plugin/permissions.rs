@@ -53,6 +53,20 @@ pub(super) fn collect_missing_permissions(
     if requested.network && !approved.network {
         missing.push("network".to_string());
     }
+    if requested.network {
+        for host in &requested.network_hosts {
+            if !approved
+                .network_hosts
+                .iter()
+                .any(|approved_host| approved_host.eq_ignore_ascii_case(host))
+            {
+                missing.push(format!("net:{}", host));
+            }
+        }
+    }
+    if requested.resolver && !approved.resolver {
+        missing.push("resolver".to_string());
+    }
     for permission in &requested.fs {
         if !approved
             .fs
@@ -177,6 +191,52 @@ pub(super) fn build_permission_path_scopes(
     Ok((allow_read, allow_write))
 }
 
+/// Network hosts are `host` or `host:port`, matching what Deno accepts in `--allow-net`.
+pub(super) fn validate_network_host(host: &str) -> Result<(), String> {
+    if host.trim().is_empty() || host.trim() != host {
+        return Err("host cannot be empty or padded with whitespace".to_string());
+    }
+    if host.contains("://") || host.contains('/') {
+        return Err("use a bare host name without a scheme or path".to_string());
+    }
+    if host.contains('*') {
+        return Err("wildcards are not supported; list each host explicitly".to_string());
+    }
+    let (name, port) = match host.rsplit_once(':') {
+        Some((name, port)) if !name.contains(':') || name.ends_with(']') => (name, Some(port)),
+        _ => (host, None),
+    };
+    if let Some(port) = port {
+        if port
+            .parse::<u16>()
+            .ok()
+            .filter(|value| *value > 0)
+            .is_none()
+        {
+            return Err(format!("invalid port {}", port));
+        }
+    }
+    let valid_name = !name.is_empty()
+        && name.chars().all(|character| {
+            character.is_ascii_alphanumeric() || matches!(character, '.' | '-' | ':' | '[' | ']')
+        });
+    if !valid_name {
+        return Err("host contains invalid characters".to_string());
+    }
+    Ok(())
+}
+
+/// Build Deno's `--allow-net` value: the bridge is always reachable, plus approved hosts.
+pub(super) fn build_allow_net_hosts(bridge_host: &str, approved_hosts: &[String]) -> String {
+    let mut hosts = vec![bridge_host.to_string()];
+    for host in approved_hosts {
+        if !hosts.iter().any(|value| value.eq_ignore_ascii_case(host)) {
+            hosts.push(host.clone());
+        }
+    }
+    hosts.join(",")
+}
+
 pub(super) fn push_allow_flag(args: &mut Vec<String>, flag_name: &str, values: &[PathBuf]) {
     if values.is_empty() {
         return;
@@ -6,6 +6,10 @@ use tokio::process::Command;
 use crate::types::{PluginProvider, RuntimeProviderStatus};
 use crate::utils::CommandExt;
 
+#[cfg(not(feature = "wasm-plugins"))]
+pub(super) const WASM_RUNTIME_UNAVAILABLE: &str =
+    "WASM plugin runtime is not included in this build.";
+
 async fn resolve_command_path(binary: &str) -> Option<PathBuf> {
     #[cfg(unix)]
     let locator = "which";
@@ -72,12 +76,31 @@ pub async fn get_runtime_provider_status_internal(
                 details: None,
             }
         }
+        #[cfg(feature = "wasm-plugins")]
+        PluginProvider::Wasm => RuntimeProviderStatus {
+            provider,
+            available: true,
+            resolved_path: None,
+            resolved_source: Some("embedded".to_string()),
+            details: Some(
+                "Runs WASI modules in-process. No external runtime download is required."
+                    .to_string(),
+            ),
+        },
+        #[cfg(not(feature = "wasm-plugins"))]
+        PluginProvider::Wasm => RuntimeProviderStatus {
+            provider,
+            available: false,
+            resolved_path: None,
+            resolved_source: None,
+            details: Some(WASM_RUNTIME_UNAVAILABLE.to_string()),
+        },
     }
 }
 
 pub async fn list_runtime_providers_internal(app: &AppHandle) -> Vec<RuntimeProviderStatus> {
     let mut statuses = Vec::new();
-    for provider in [PluginProvider::Deno] {
+    for provider in [PluginProvider::Deno, PluginProvider::Wasm] {
         statuses.push(get_runtime_provider_status_internal(app, provider).await);
     }
     statuses
@@ -99,5 +122,9 @@ pub(super) async fn resolve_provider_command(
             Ok((path.to_string_lossy().to_string(), Some(source.to_string())))
         }
         PluginProvider::Python => Err("Python plugin runtime is not supported yet.".to_string()),
+        #[cfg(feature = "wasm-plugins")]
+        PluginProvider::Wasm => Ok(("wasm".to_string(), Some("embedded".to_string()))),
+        #[cfg(not(feature = "wasm-plugins"))]
+        PluginProvider::Wasm => Err(WASM_RUNTIME_UNAVAILABLE.to_string()),
     }
 }
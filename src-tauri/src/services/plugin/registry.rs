@@ -6,9 +6,11 @@ use serde_json::Value;
 use tauri::AppHandle;
 
 use crate::types::{
-    PluginPackageSource, PluginPermissionApproval, PluginProvider, PluginWorkflowStepConfig,
+    PluginPackageSource, PluginPermissionApproval, PluginProvider, PluginVersionRecord,
+    PluginWorkflowStepConfig,
 };
 
+use super::resolver::invalidate_resolver_plugin_cache;
 use super::{plugins_root, REGISTRY_FILE_NAME};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -43,6 +45,34 @@ pub(super) struct PluginRegistryEntry {
     pub(super) signature_algorithm: Option<String>,
     #[serde(default)]
     pub(super) signed_at: Option<String>,
+    /// Signer recorded at the first signed install or first verified update; later installs
+    /// and updates must be signed by the same key.
+    #[serde(default)]
+    pub(super) pinned_signer_fingerprint: Option<String>,
+    #[serde(default)]
+    pub(super) version_history: Vec<PluginVersionRecord>,
+    #[serde(default)]
+    pub(super) previous_installation: Option<PluginPreviousInstallation>,
+}
+
+/// The installation directory kept aside by the last update so it can be rolled back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PluginPreviousInstallation {
+    pub(super) version: String,
+    pub(super) path: String,
+    #[serde(default)]
+    pub(super) source: Option<PluginPackageSource>,
+    #[serde(default)]
+    pub(super) signature_status: Option<String>,
+    #[serde(default)]
+    pub(super) signer_key_id: Option<String>,
+    #[serde(default)]
+    pub(super) signer_fingerprint: Option<String>,
+    #[serde(default)]
+    pub(super) signature_algorithm: Option<String>,
+    #[serde(default)]
+    pub(super) signed_at: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -194,5 +224,7 @@ pub(super) fn write_registry(app: &AppHandle, registry: &PluginRegistry) -> Resu
     let raw = serde_json::to_string_pretty(registry)
         .map_err(|e| format!("Failed to serialize plugin registry: {}", e))?;
     std::fs::write(&path, raw)
-        .map_err(|e| format!("Failed to write plugin registry {}: {}", path.display(), e))
+        .map_err(|e| format!("Failed to write plugin registry {}: {}", path.display(), e))?;
+    invalidate_resolver_plugin_cache();
+    Ok(())
 }
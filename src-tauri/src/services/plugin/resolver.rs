@@ -0,0 +1,420 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{OnceLock, RwLock};
+
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::database::add_log_internal;
+use crate::types::{
+    PluginExecutionResult, PluginResolvedUrl, PluginSummary, PostDownloadPluginPayload,
+};
+use crate::utils::validate_url;
+
+use super::{execute_plugin, format_plugin_execution_error_details, list_plugins_internal};
+
+const RESOLVER_TRIGGER: &str = "url.resolve";
+
+/// Active resolver plugins, loaded on first use and dropped whenever the registry is written,
+/// so downloads do not re-read every plugin manifest.
+static RESOLVER_PLUGIN_CACHE: OnceLock<RwLock<Option<Vec<PluginSummary>>>> = OnceLock::new();
+static RESOLVER_PLUGIN_CACHE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn resolver_plugin_cache() -> &'static RwLock<Option<Vec<PluginSummary>>> {
+    RESOLVER_PLUGIN_CACHE.get_or_init(|| RwLock::new(None))
+}
+
+pub(super) fn invalidate_resolver_plugin_cache() {
+    RESOLVER_PLUGIN_CACHE_GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut cache) = resolver_plugin_cache().write() {
+        *cache = None;
+    }
+}
+
+fn load_resolver_plugins(app: &AppHandle) -> Result<Vec<PluginSummary>, String> {
+    if let Some(plugins) = resolver_plugin_cache()
+        .read()
+        .ok()
+        .and_then(|cache| cache.clone())
+    {
+        return Ok(plugins);
+    }
+    let generation = RESOLVER_PLUGIN_CACHE_GENERATION.load(Ordering::SeqCst);
+    let plugins = list_plugins_internal(app)?
+        .into_iter()
+        .filter(is_active_resolver)
+        .collect::<Vec<_>>();
+    // Listing can migrate and rewrite the registry; only keep the result if nothing changed since.
+    if RESOLVER_PLUGIN_CACHE_GENERATION.load(Ordering::SeqCst) == generation {
+        if let Ok(mut cache) = resolver_plugin_cache().write() {
+            *cache = Some(plugins.clone());
+        }
+    }
+    Ok(plugins)
+}
+
+struct ResolverUrlParts<'a> {
+    scheme: &'a str,
+    host: String,
+    rest: &'a str,
+}
+
+fn split_resolver_url(url: &str) -> Option<ResolverUrlParts<'_>> {
+    let trimmed = url.trim();
+    let (scheme, remainder) = trimmed.split_once("://")?;
+    let authority_end = remainder
+        .find(|character| matches!(character, '/' | '?' | '#'))
+        .unwrap_or(remainder.len());
+    let authority = &remainder[..authority_end];
+    let host_and_port = authority
+        .rsplit_once('@')
+        .map(|(_, host)| host)
+        .unwrap_or(authority);
+    let host = host_and_port
+        .split_once(':')
+        .map(|(host, _)| host)
+        .unwrap_or(host_and_port);
+    let rest = &remainder[authority_end..];
+    Some(ResolverUrlParts {
+        scheme,
+        host: host.to_ascii_lowercase(),
+        rest: if rest.is_empty() { "/" } else { rest },
+    })
+}
+
+/// Resolver patterns are `scheme://host/path` where the host is either exact or `*.domain`
+/// and the path may use `*` wildcards. A pattern without a path claims every path on the host.
+pub(super) fn validate_resolver_url_pattern(pattern: &str) -> Result<(), String> {
+    let parts = split_resolver_url(pattern)
+        .ok_or_else(|| "pattern must start with http:// or https://".to_string())?;
+    if !matches!(parts.scheme, "http" | "https") {
+        return Err("pattern must start with http:// or https://".to_string());
+    }
+    let domain = parts.host.strip_prefix("*.").unwrap_or(&parts.host);
+    if domain.is_empty() || domain.contains('*') {
+        return Err("host must be a domain name or *.domain".to_string());
+    }
+    if !domain.contains('.') && domain != "localhost" {
+        return Err("host must be a fully qualified domain".to_string());
+    }
+    Ok(())
+}
+
+fn wildcard_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.as_bytes();
+    let text = text.as_bytes();
+    let (mut pattern_index, mut text_index) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while text_index < text.len() {
+        if pattern_index < pattern.len() && pattern[pattern_index] == b'*' {
+            backtrack = Some((pattern_index, text_index));
+            pattern_index += 1;
+        } else if pattern_index < pattern.len() && pattern[pattern_index] == text[text_index] {
+            pattern_index += 1;
+            text_index += 1;
+        } else if let Some((star_index, star_text_index)) = backtrack {
+            pattern_index = star_index + 1;
+            text_index = star_text_index + 1;
+            backtrack = Some((star_index, text_index));
+        } else {
+            return false;
+        }
+    }
+    pattern[pattern_index..].iter().all(|byte| *byte == b'*')
+}
+
+pub(super) fn url_matches_resolver_pattern(pattern: &str, url: &str) -> bool {
+    let (Some(pattern), Some(url)) = (split_resolver_url(pattern), split_resolver_url(url)) else {
+        return false;
+    };
+    if !pattern.scheme.eq_ignore_ascii_case(url.scheme) {
+        return false;
+    }
+    let host_matches = match pattern.host.strip_prefix("*.") {
+        Some(domain) => {
+            url.host == domain
+                || url
+                    .host
+                    .strip_suffix(domain)
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        }
+        None => url.host == pattern.host,
+    };
+    if !host_matches {
+        return false;
+    }
+    // A bare host pattern splits to "/" and is treated as claiming the whole site.
+    pattern.rest == "/" || wildcard_matches(pattern.rest, url.rest)
+}
+
+fn is_active_resolver(plugin: &PluginSummary) -> bool {
+    plugin.installation.enabled
+        && plugin.installation.approved_permissions.resolver
+        && plugin.manifest.resolver.is_some()
+}
+
+pub(super) fn select_resolver_plugin<'a>(
+    plugins: &'a [PluginSummary],
+    url: &str,
+) -> Option<&'a PluginSummary> {
+    plugins
+        .iter()
+        .filter(|plugin| is_active_resolver(plugin))
+        .filter(|plugin| {
+            plugin.manifest.resolver.as_ref().is_some_and(|resolver| {
+                resolver
+                    .url_patterns
+                    .iter()
+                    .any(|pattern| url_matches_resolver_pattern(pattern, url))
+            })
+        })
+        .min_by_key(|plugin| {
+            Reverse(
+                plugin
+                    .manifest
+                    .resolver
+                    .as_ref()
+                    .map(|resolver| resolver.priority)
+                    .unwrap_or_default(),
+            )
+        })
+}
+
+fn build_resolver_payload(run_id: &str, url: &str) -> PostDownloadPluginPayload {
+    PostDownloadPluginPayload {
+        job_id: run_id.to_string(),
+        source: None,
+        trigger: RESOLVER_TRIGGER.to_string(),
+        filepath: String::new(),
+        filename: String::new(),
+        directory: String::new(),
+        filesize: None,
+        format: None,
+        quality: None,
+        url: url.to_string(),
+        title: None,
+        thumbnail: None,
+        history_id: None,
+        time_range: None,
+        download_kind: "resolve".to_string(),
+        workflow_run_id: None,
+        workflow_step_index: None,
+        workflow_step_plugin_id: None,
+        chain_state: None,
+    }
+}
+
+pub(super) fn parse_resolver_result(
+    source_url: &str,
+    result: &PluginExecutionResult,
+) -> Result<PluginResolvedUrl, String> {
+    let artifacts = result
+        .artifacts
+        .clone()
+        .ok_or_else(|| "Resolver plugin did not return any artifacts".to_string())?;
+    let mut resolved: PluginResolvedUrl = serde_json::from_value(artifacts)
+        .map_err(|e| format!("Failed to parse resolver artifacts: {}", e))?;
+    if resolved.media.is_empty() {
+        return Err("Resolver plugin did not return any media URLs".to_string());
+    }
+    // yt-dlp applies --add-header to every URL in the invocation, so all items must agree.
+    let normalized_headers = |headers: &BTreeMap<String, String>| {
+        headers
+            .iter()
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.clone()))
+            .collect::<BTreeMap<_, _>>()
+    };
+    let first_headers = normalized_headers(&resolved.media[0].headers);
+    if resolved.media[1..]
+        .iter()
+        .any(|media| normalized_headers(&media.headers) != first_headers)
+    {
+        return Err(
+            "Resolver returned different headers for different media URLs; \
+             every media item must use the same headers"
+                .to_string(),
+        );
+    }
+    for media in &mut resolved.media {
+        media.url = media.url.trim().to_string();
+        validate_url(&media.url).map_err(|e| format!("Resolver returned {}", e))?;
+        for (name, value) in &media.headers {
+            let invalid_name = name.trim().is_empty()
+                || name
+                    .chars()
+                    .any(|character| character == ':' || character.is_control());
+            if invalid_name || value.chars().any(|character| character.is_control()) {
+                return Err(format!("Resolver returned an invalid header {}", name));
+            }
+        }
+    }
+    if let Some(thumbnail) = resolved.thumbnail.as_ref() {
+        validate_url(thumbnail).map_err(|e| format!("Resolver returned thumbnail {}", e))?;
+    }
+    resolved.plugin_id = result.plugin_id.clone();
+    resolved.source_url = source_url.to_string();
+    if resolved.metadata.is_none() {
+        resolved.metadata = result.metadata.clone();
+    }
+    Ok(resolved)
+}
+
+/// Ask the highest-priority enabled resolver plugin claiming `url` for direct media URLs.
+///
+/// Returns `Ok(None)` when no approved resolver matches, so callers fall back to yt-dlp.
+pub async fn resolve_url_with_plugins(
+    app: &AppHandle,
+    url: &str,
+) -> Result<Option<PluginResolvedUrl>, String> {
+    let plugins = load_resolver_plugins(app)?;
+    let Some(plugin) = select_resolver_plugin(&plugins, url) else {
+        return Ok(None);
+    };
+    let run_id = Uuid::new_v4().to_string();
+    let payload = build_resolver_payload(&run_id, url);
+    add_log_internal(
+        "info",
+        &format!("Resolving URL with plugin: {}", plugin.manifest.name),
+        None,
+        Some(url),
+    )
+    .ok();
+
+    let (result, _, _) = execute_plugin(app, plugin, &run_id, &payload)
+        .await
+        .map_err(|error| format_plugin_execution_error_details(&error))?;
+    if !result.success {
+        return Err(result.message.unwrap_or_else(|| {
+            format!("Plugin {} could not resolve the URL", plugin.manifest.name)
+        }));
+    }
+    let resolved = parse_resolver_result(url, &result)?;
+    add_log_internal(
+        "success",
+        &format!("URL resolved by plugin: {}", plugin.manifest.name),
+        Some(&format!("Media URLs: {}", resolved.media.len())),
+        Some(url),
+    )
+    .ok();
+    Ok(Some(resolved))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn resolver_patterns_require_a_concrete_domain() {
+        assert!(validate_resolver_url_pattern("https://media.example.com/watch/*").is_ok());
+        assert!(validate_resolver_url_pattern("https://*.example.com").is_ok());
+        assert!(validate_resolver_url_pattern("https://*").is_err());
+        assert!(validate_resolver_url_pattern("https://*.com/*").is_err());
+        assert!(validate_resolver_url_pattern("ftp://example.com/*").is_err());
+        assert!(validate_resolver_url_pattern("example.com/*").is_err());
+    }
+
+    #[test]
+    fn resolver_patterns_match_host_and_path_separately() {
+        let pattern = "https://*.example.com/watch/*";
+        assert!(url_matches_resolver_pattern(
+            pattern,
+            "https://media.EXAMPLE.com/watch/42?t=1"
+        ));
+        assert!(url_matches_resolver_pattern(
+            pattern,
+            "https://example.com/watch/42"
+        ));
+        assert!(!url_matches_resolver_pattern(
+            pattern,
+            "https://evil.com/watch/x.example.com/watch/1"
+        ));
+        assert!(!url_matches_resolver_pattern(
+            pattern,
+            "https://notexample.com/watch/42"
+        ));
+        assert!(!url_matches_resolver_pattern(
+            pattern,
+            "http://media.example.com/watch/42"
+        ));
+        assert!(url_matches_resolver_pattern(
+            "https://example.com",
+            "https://example.com/any/path"
+        ));
+    }
+
+    #[test]
+    fn parse_resolver_result_rejects_header_injection() {
+        let result = PluginExecutionResult {
+            plugin_id: "com.example.resolver".to_string(),
+            success: true,
+            message: None,
+            artifacts: Some(json!({
+                "title": "Clip",
+                "media": [{
+                    "url": "https://cdn.example.com/clip.mp4",
+                    "headers": { "Referer": "https://example.com\r\nX-Evil: 1" }
+                }]
+            })),
+            metadata: None,
+            mutations: None,
+            stdout: None,
+            stderr: None,
+        };
+        assert!(parse_resolver_result("https://example.com/watch/1", &result).is_err());
+
+        let mut valid = result.clone();
+        valid.artifacts = Some(json!({
+            "title": "Clip",
+            "media": [{ "url": " https://cdn.example.com/clip.mp4 " }]
+        }));
+        let resolved = parse_resolver_result("https://example.com/watch/1", &valid).unwrap();
+        assert_eq!(resolved.plugin_id, "com.example.resolver");
+        assert_eq!(resolved.media[0].url, "https://cdn.example.com/clip.mp4");
+        assert_eq!(resolved.title.as_deref(), Some("Clip"));
+    }
+
+    #[test]
+    fn parse_resolver_result_rejects_conflicting_media_headers() {
+        let mut result = PluginExecutionResult {
+            plugin_id: "com.example.resolver".to_string(),
+            success: true,
+            message: None,
+            artifacts: Some(json!({
+                "media": [
+                    {
+                        "url": "https://cdn.example.com/video.mp4",
+                        "headers": { "Referer": "https://example.com/a" }
+                    },
+                    {
+                        "url": "https://cdn.example.com/audio.m4a",
+                        "headers": { "Referer": "https://example.com/b" }
+                    }
+                ]
+            })),
+            metadata: None,
+            mutations: None,
+            stdout: None,
+            stderr: None,
+        };
+        assert!(parse_resolver_result("https://example.com/watch/1", &result).is_err());
+
+        result.artifacts = Some(json!({
+            "media": [
+                { "url": "https://cdn.example.com/video.mp4", "headers": { "Cookie": "a=1" } },
+                { "url": "https://other.example.net/audio.m4a" }
+            ]
+        }));
+        assert!(parse_resolver_result("https://example.com/watch/1", &result).is_err());
+
+        result.artifacts = Some(json!({
+            "media": [
+                { "url": "https://cdn.example.com/video.mp4", "headers": { "Referer": "r" } },
+                { "url": "https://cdn.example.com/audio.m4a", "headers": { "referer": "r" } }
+            ]
+        }));
+        assert!(parse_resolver_result("https://example.com/watch/1", &result).is_ok());
+    }
+}
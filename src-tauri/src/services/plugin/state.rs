@@ -2,12 +2,16 @@ use std::collections::BTreeMap;
 
 use tauri::AppHandle;
 
+use crate::database::{
+    delete_plugin_secret_from_db, set_plugin_secret_in_db, PLUGIN_SECRET_SCOPE_CONFIG,
+};
 use crate::types::{
     PluginPermissionApproval, PluginProvider, PluginRuntimeLanguage, PluginTriggerWorkflow,
 };
 
 use super::manifest::default_supported_providers;
 use super::registry::{read_registry, write_registry, PluginTriggerWorkflowRegistry};
+use super::storage::is_secret_config_field;
 use super::summary::validate_runtime_config_value;
 use super::workflow::get_trigger_workflow_internal;
 use super::{
@@ -82,8 +86,10 @@ pub fn approve_plugin_permissions_internal(
         .ok_or_else(|| format!("Plugin not found: {}", plugin_id))?;
     entry.approved_permissions = PluginPermissionApproval {
         network: permissions.network,
+        network_hosts: permissions.network_hosts,
         fs: permissions.fs,
         tools: permissions.tools,
+        resolver: permissions.resolver,
     };
     write_registry(app, &registry)
 }
@@ -112,6 +118,21 @@ pub fn update_plugin_config_values_internal(
             .get(&key)
             .ok_or_else(|| format!("Plugin does not declare config field: {}", key))?;
 
+        if is_secret_config_field(field) {
+            match value {
+                Some(raw) => {
+                    validate_runtime_config_value(field, &raw)?;
+                    let secret = raw.as_str().unwrap_or_default();
+                    set_plugin_secret_in_db(plugin_id, PLUGIN_SECRET_SCOPE_CONFIG, &key, secret)?;
+                }
+                None => {
+                    delete_plugin_secret_from_db(plugin_id, PLUGIN_SECRET_SCOPE_CONFIG, &key)?;
+                }
+            }
+            entry.config_values.remove(&key);
+            continue;
+        }
+
         match value {
             Some(raw) => {
                 validate_runtime_config_value(field, &raw)?;
@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::database::{
+    get_plugin_secret_from_db, list_plugin_secret_keys_from_db, set_plugin_secret_in_db,
+    PLUGIN_SECRET_SCOPE_CONFIG,
+};
+use crate::types::{PluginConfigField, PluginConfigFieldInputType, PluginManifest};
+
+use super::registry::PluginRegistry;
+
+/// `password` config fields live in the encrypted secret store instead of `registry.json`.
+pub(super) fn is_secret_config_field(field: &PluginConfigField) -> bool {
+    matches!(field.input_type, PluginConfigFieldInputType::Password)
+}
+
+pub(super) fn load_config_secret_values(
+    manifest: &PluginManifest,
+) -> Result<BTreeMap<String, Value>, String> {
+    let mut values = BTreeMap::new();
+    for field in manifest
+        .config_fields
+        .iter()
+        .filter(|field| is_secret_config_field(field))
+    {
+        if let Some(secret) =
+            get_plugin_secret_from_db(&manifest.plugin_id, PLUGIN_SECRET_SCOPE_CONFIG, &field.key)?
+        {
+            values.insert(field.key.clone(), Value::String(secret));
+        }
+    }
+    Ok(values)
+}
+
+pub(super) fn config_secret_keys(plugin_id: &str) -> Vec<String> {
+    list_plugin_secret_keys_from_db(plugin_id, PLUGIN_SECRET_SCOPE_CONFIG).unwrap_or_default()
+}
+
+/// Move `password` values written to `registry.json` by older versions into the secret store.
+///
+/// Returns whether the registry changed and needs to be written back.
+pub(super) fn migrate_config_secrets_from_registry(
+    registry: &mut PluginRegistry,
+    manifest: &PluginManifest,
+) -> Result<bool, String> {
+    let Some(entry) = registry.installations.get_mut(&manifest.plugin_id) else {
+        return Ok(false);
+    };
+    let mut changed = false;
+    for field in manifest
+        .config_fields
+        .iter()
+        .filter(|field| is_secret_config_field(field))
+    {
+        let Some(value) = entry.config_values.get(&field.key) else {
+            continue;
+        };
+        if let Some(secret) = value.as_str() {
+            set_plugin_secret_in_db(
+                &manifest.plugin_id,
+                PLUGIN_SECRET_SCOPE_CONFIG,
+                &field.key,
+                secret,
+            )?;
+        }
+        entry.config_values.remove(&field.key);
+        changed = true;
+    }
+    Ok(changed)
+}
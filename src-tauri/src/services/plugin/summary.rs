@@ -10,6 +10,7 @@ use crate::types::{
 
 use super::manifest::config_value_matches_field_type;
 use super::registry::PluginRegistry;
+use super::storage::{config_secret_keys, is_secret_config_field};
 
 pub(super) fn manifest_summary(
     manifest: PluginManifest,
@@ -125,6 +126,7 @@ pub(super) fn default_provider_for_language(language: &PluginRuntimeLanguage) ->
     match language {
         PluginRuntimeLanguage::Javascript => PluginProvider::Deno,
         PluginRuntimeLanguage::Python => PluginProvider::Python,
+        PluginRuntimeLanguage::Wasm => PluginProvider::Wasm,
     }
 }
 
@@ -238,14 +240,20 @@ pub(super) fn build_installation_from_registry(
             value.map(|value| (field.key.clone(), value))
         })
         .collect();
+    let secret_keys = if manifest.config_fields.iter().any(is_secret_config_field) {
+        config_secret_keys(&manifest.plugin_id)
+    } else {
+        Vec::new()
+    };
     let config_value_status = manifest
         .config_fields
         .iter()
         .map(|field| {
-            let is_set = entry
-                .and_then(|value| value.config_values.get(&field.key))
-                .or_else(|| field.default_value.as_ref())
-                .is_some();
+            let is_set = secret_keys.contains(&field.key)
+                || entry
+                    .and_then(|value| value.config_values.get(&field.key))
+                    .or_else(|| field.default_value.as_ref())
+                    .is_some();
             (field.key.clone(), is_set)
         })
         .collect();
@@ -275,5 +283,11 @@ pub(super) fn build_installation_from_registry(
         signer_fingerprint: entry.and_then(|value| value.signer_fingerprint.clone()),
         signature_algorithm: entry.and_then(|value| value.signature_algorithm.clone()),
         signed_at: entry.and_then(|value| value.signed_at.clone()),
+        version_history: entry
+            .map(|value| value.version_history.clone())
+            .unwrap_or_default(),
+        rollback_version: entry
+            .and_then(|value| value.previous_installation.as_ref())
+            .map(|previous| previous.version.clone()),
     }
 }
@@ -2,6 +2,7 @@ use std::fs;
 use std::path::Path;
 
 use super::logging::{classify_plugin_runtime_error, should_persist_plugin_runtime_output};
+use super::permissions::{build_allow_net_hosts, collect_missing_permissions};
 use super::security_policy::{validate_plugin_output_path, validate_plugin_write_scope};
 use super::{
     build_plugin_completion_details, build_scaffold_ci_workflow, build_scaffold_package_json,
@@ -98,6 +99,20 @@ fn classify_plugin_runtime_error_formats_run_error_for_users() {
     assert!(!err.user_message.contains("--allow-run"));
 }
 
+#[test]
+fn classify_plugin_runtime_error_names_unapproved_network_host() {
+    let err = classify_plugin_runtime_error(
+        r#"Requires net access to "tracker.example.com:443", run again with the --allow-net flag"#,
+    )
+    .expect("expected runtime permission error");
+
+    assert_eq!(err.kind, "net");
+    assert_eq!(err.resource.as_deref(), Some("tracker.example.com:443"));
+    assert!(err.user_message.contains("tracker.example.com:443"));
+    assert!(err.user_message.contains("approved network hosts"));
+    assert!(!err.user_message.contains("--allow-net"));
+}
+
 #[test]
 fn plugin_security_policy_blocks_dangerous_output_extensions() {
     assert!(validate_plugin_output_path(Path::new("/tmp/youwee-output/video.mov")).is_ok());
@@ -138,6 +153,7 @@ fn scaffold_readme_mentions_framework_entrypoint() {
         },
         compatibility: None,
         i18n: None,
+        resolver: None,
         triggers: vec!["download.completed".to_string()],
         permissions: PluginPermissionRequest::default(),
         config_fields: Vec::new(),
@@ -175,6 +191,7 @@ fn validate_manifest_rejects_empty_supported_providers() {
         },
         compatibility: None,
         i18n: None,
+        resolver: None,
         triggers: vec!["download.completed".to_string()],
         permissions: PluginPermissionRequest::default(),
         config_fields: Vec::new(),
@@ -208,6 +225,7 @@ fn validate_manifest_rejects_sdk_trigger_identifiers() {
         },
         compatibility: None,
         i18n: None,
+        resolver: None,
         triggers: vec!["triggers.downloadQueued".to_string()],
         permissions: PluginPermissionRequest::default(),
         config_fields: Vec::new(),
@@ -220,6 +238,174 @@ fn validate_manifest_rejects_sdk_trigger_identifiers() {
     assert!(err.contains("raw runtime names"));
 }
 
+#[test]
+fn validate_manifest_requires_wasm_module_entrypoint() {
+    let mut manifest = crate::types::PluginManifest {
+        plugin_id: "id".to_string(),
+        slug: "slug".to_string(),
+        name: "Name".to_string(),
+        version: "0.1.0".to_string(),
+        icon: None,
+        description: None,
+        author: None,
+        homepage: None,
+        repository: None,
+        license: None,
+        runtime: PluginRuntimeSpec {
+            language: PluginRuntimeLanguage::Wasm,
+            supported_providers: vec![PluginProvider::Wasm],
+            preferred_provider: Some(PluginProvider::Wasm),
+            entrypoint: "src/plugin.ts".to_string(),
+        },
+        compatibility: None,
+        i18n: None,
+        resolver: None,
+        triggers: vec!["download.completed".to_string()],
+        permissions: PluginPermissionRequest::default(),
+        config_fields: Vec::new(),
+        timeout_sec: 60,
+        readme: None,
+        checksum: None,
+        published_at: None,
+    };
+    let err = validate_manifest(&manifest, Path::new("/tmp/plugin.json")).unwrap_err();
+    assert!(err.contains(".wasm"));
+
+    manifest.runtime.entrypoint = "plugin.wasm".to_string();
+    assert!(validate_manifest(&manifest, Path::new("/tmp/plugin.json")).is_ok());
+}
+
+#[test]
+fn validate_manifest_requires_resolver_permission_and_patterns() {
+    let mut manifest = crate::types::PluginManifest {
+        plugin_id: "id".to_string(),
+        slug: "slug".to_string(),
+        name: "Name".to_string(),
+        version: "0.1.0".to_string(),
+        icon: None,
+        description: None,
+        author: None,
+        homepage: None,
+        repository: None,
+        license: None,
+        runtime: PluginRuntimeSpec {
+            language: PluginRuntimeLanguage::Javascript,
+            supported_providers: vec![PluginProvider::Deno],
+            preferred_provider: Some(PluginProvider::Deno),
+            entrypoint: "src/plugin.ts".to_string(),
+        },
+        compatibility: None,
+        i18n: None,
+        resolver: Some(crate::types::PluginResolverSpec {
+            url_patterns: vec!["https://*.example.com/watch/*".to_string()],
+            priority: 0,
+        }),
+        triggers: vec!["download.completed".to_string()],
+        permissions: PluginPermissionRequest::default(),
+        config_fields: Vec::new(),
+        timeout_sec: 60,
+        readme: None,
+        checksum: None,
+        published_at: None,
+    };
+    let err = validate_manifest(&manifest, Path::new("/tmp/plugin.json")).unwrap_err();
+    assert!(err.contains("permissions.resolver"));
+
+    manifest.permissions.resolver = true;
+    assert!(validate_manifest(&manifest, Path::new("/tmp/plugin.json")).is_ok());
+
+    manifest.resolver = Some(crate::types::PluginResolverSpec {
+        url_patterns: vec!["https://*/*".to_string()],
+        priority: 0,
+    });
+    let err = validate_manifest(&manifest, Path::new("/tmp/plugin.json")).unwrap_err();
+    assert!(err.contains("invalid resolver pattern"));
+
+    manifest.resolver = None;
+    let err = validate_manifest(&manifest, Path::new("/tmp/plugin.json")).unwrap_err();
+    assert!(err.contains("does not declare a resolver"));
+}
+
+#[test]
+fn validate_manifest_checks_network_hosts() {
+    let mut manifest = crate::types::PluginManifest {
+        plugin_id: "id".to_string(),
+        slug: "slug".to_string(),
+        name: "Name".to_string(),
+        version: "0.1.0".to_string(),
+        icon: None,
+        description: None,
+        author: None,
+        homepage: None,
+        repository: None,
+        license: None,
+        runtime: PluginRuntimeSpec {
+            language: PluginRuntimeLanguage::Javascript,
+            supported_providers: vec![PluginProvider::Deno],
+            preferred_provider: Some(PluginProvider::Deno),
+            entrypoint: "src/plugin.ts".to_string(),
+        },
+        compatibility: None,
+        i18n: None,
+        resolver: None,
+        triggers: vec!["download.completed".to_string()],
+        permissions: PluginPermissionRequest {
+            network_hosts: vec!["api.example.com".to_string()],
+            ..PluginPermissionRequest::default()
+        },
+        config_fields: Vec::new(),
+        timeout_sec: 60,
+        readme: None,
+        checksum: None,
+        published_at: None,
+    };
+    let err = validate_manifest(&manifest, Path::new("/tmp/plugin.json")).unwrap_err();
+    assert!(err.contains("permissions.network"));
+
+    manifest.permissions.network = true;
+    assert!(validate_manifest(&manifest, Path::new("/tmp/plugin.json")).is_ok());
+
+    for invalid in [
+        "https://api.example.com",
+        "*.example.com",
+        "api.example.com/v1",
+    ] {
+        manifest.permissions.network_hosts = vec![invalid.to_string()];
+        let err = validate_manifest(&manifest, Path::new("/tmp/plugin.json")).unwrap_err();
+        assert!(err.contains("invalid network host"), "{invalid}: {err}");
+    }
+
+    manifest.permissions.network_hosts = vec![
+        "api.example.com:8443".to_string(),
+        "API.example.com:8443".to_string(),
+    ];
+    let err = validate_manifest(&manifest, Path::new("/tmp/plugin.json")).unwrap_err();
+    assert!(err.contains("duplicate network host"));
+}
+
+#[test]
+fn collect_missing_permissions_lists_unapproved_network_hosts() {
+    let requested = PluginPermissionRequest {
+        network: true,
+        network_hosts: vec!["api.example.com".to_string(), "cdn.example.com".to_string()],
+        ..PluginPermissionRequest::default()
+    };
+    let approved = crate::types::PluginPermissionApproval {
+        network: true,
+        network_hosts: vec!["API.example.com".to_string()],
+        ..crate::types::PluginPermissionApproval::default()
+    };
+
+    assert_eq!(
+        collect_missing_permissions(&requested, &approved),
+        vec!["net:cdn.example.com".to_string()]
+    );
+    assert_eq!(
+        build_allow_net_hosts("127.0.0.1:4100", &approved.network_hosts),
+        "127.0.0.1:4100,API.example.com"
+    );
+}
+
 #[test]
 fn scaffold_readme_mentions_runtime_contract() {
     let manifest = crate::types::PluginManifest {
@@ -241,6 +427,7 @@ fn scaffold_readme_mentions_runtime_contract() {
         },
         compatibility: None,
         i18n: None,
+        resolver: None,
         triggers: vec!["download.completed".to_string()],
         permissions: PluginPermissionRequest::default(),
         config_fields: Vec::new(),
@@ -291,6 +478,7 @@ fn scaffold_package_json_uses_npm_sdk_dependency() {
         },
         compatibility: None,
         i18n: None,
+        resolver: None,
         triggers: vec!["download.completed".to_string()],
         permissions: PluginPermissionRequest::default(),
         config_fields: Vec::new(),
@@ -500,6 +688,7 @@ fn compatibility_issues_are_reported_for_mismatched_ranges() {
             sdk_version: Some(">=999.0.0 <1000.0.0".to_string()),
         }),
         i18n: None,
+        resolver: None,
         triggers: vec!["download.completed".to_string()],
         permissions: PluginPermissionRequest::default(),
         config_fields: Vec::new(),
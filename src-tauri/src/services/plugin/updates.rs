@@ -0,0 +1,847 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+use crate::database::add_log_internal;
+use crate::types::{
+    PluginPackageSource, PluginPackageSourceKind, PluginPermissionApproval,
+    PluginPermissionRequest, PluginStoreEntry, PluginSummary, PluginUpdateInfo, PluginUpdateSource,
+    PluginVersionRecord, PreparedPluginUpdate,
+};
+
+use super::compatibility::{compare_simple_semver, validate_install_compatibility};
+use super::manifest::load_installed_manifest_from_dir;
+use super::package::{prepare_plugin_package, PreparedPackage};
+use super::permissions::collect_missing_permissions;
+use super::registry::{
+    read_registry, write_registry, PluginPreviousInstallation, PluginRegistryEntry,
+};
+use super::{
+    copy_dir_recursive, ensure_plugins_root, find_existing_installation_path,
+    get_plugin_details_internal, github_repo_parts, inspect_plugin_package_internal,
+    installation_path, list_plugins_internal, load_runtime_plugin_store_catalog,
+    prepare_plugin_store_package_internal, select_store_version, InstallPluginPackageInput,
+};
+
+const PREVIOUS_INSTALLATIONS_DIR_NAME: &str = ".previous";
+const MAX_VERSION_HISTORY: usize = 20;
+const GITHUB_RELEASE_TIMEOUT_SECS: u64 = 15;
+const PLUGIN_UPDATE_CHECK_DELAY_SECS: u64 = 30;
+const PLUGIN_UPDATE_CHECK_INTERVAL_SECS: u64 = 6 * 60 * 60;
+const PLUGIN_UPDATES_EVENT: &str = "plugin-updates-available";
+
+/// Packages verified by `prepare_plugin_update_internal`, keyed by the id returned to the UI.
+/// Updates are only applied from here, never from a caller-supplied path.
+static PREPARED_PLUGIN_UPDATES: OnceLock<Mutex<HashMap<String, PreparedUpdatePackage>>> =
+    OnceLock::new();
+
+struct PreparedUpdatePackage {
+    plugin_id: String,
+    version: String,
+    path: String,
+}
+
+fn prepared_plugin_updates() -> &'static Mutex<HashMap<String, PreparedUpdatePackage>> {
+    PREPARED_PLUGIN_UPDATES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub(super) fn pinned_signer_for_entry(entry: &PluginRegistryEntry) -> Option<String> {
+    entry.pinned_signer_fingerprint.clone()
+}
+
+/// Pin the signer of an install recorded before signers were pinned explicitly.
+///
+/// Only entries installed from a verified signed package are pinned; unsigned installs get
+/// pinned by their first verified update instead.
+fn pin_verified_legacy_signer(entry: &mut PluginRegistryEntry) -> bool {
+    if entry.pinned_signer_fingerprint.is_some()
+        || entry.signature_status.as_deref() != Some("signed")
+        || entry.signer_fingerprint.is_none()
+    {
+        return false;
+    }
+    entry.pinned_signer_fingerprint = entry.signer_fingerprint.clone();
+    true
+}
+
+/// Record pinned signers for installs made before pinning existed. Runs once at startup.
+pub fn pin_legacy_plugin_signers_internal(app: &AppHandle) -> Result<(), String> {
+    let mut registry = read_registry(app)?;
+    let mut changed = false;
+    for entry in registry.installations.values_mut() {
+        changed |= pin_verified_legacy_signer(entry);
+    }
+    if changed {
+        write_registry(app, &registry)?;
+    }
+    Ok(())
+}
+
+/// Reject packages that are not signed by the key pinned when the plugin was first installed.
+pub(super) fn ensure_pinned_signer(
+    existing: &PluginRegistryEntry,
+    package: &PreparedPackage,
+) -> Result<(), String> {
+    let Some(pinned) = pinned_signer_for_entry(existing) else {
+        return Ok(());
+    };
+    if package.signer_fingerprint.as_deref() != Some(pinned.as_str()) {
+        return Err(format!(
+            "Plugin signer changed for {}. Uninstall the existing plugin before installing a package signed by a different key.",
+            package.manifest.plugin_id
+        ));
+    }
+    Ok(())
+}
+
+fn version_record(
+    version: &str,
+    action: &str,
+    source: Option<&PluginPackageSource>,
+    signer_fingerprint: Option<String>,
+    signed_at: Option<String>,
+) -> PluginVersionRecord {
+    PluginVersionRecord {
+        version: version.to_string(),
+        installed_at: chrono::Utc::now().to_rfc3339(),
+        action: action.to_string(),
+        package_checksum: source.and_then(|value| value.checksum.clone()),
+        signer_fingerprint,
+        signed_at,
+    }
+}
+
+fn push_history(history: &mut Vec<PluginVersionRecord>, record: PluginVersionRecord) {
+    history.push(record);
+    if history.len() > MAX_VERSION_HISTORY {
+        let overflow = history.len() - MAX_VERSION_HISTORY;
+        history.drain(..overflow);
+    }
+}
+
+pub(super) fn push_version_record(
+    history: &mut Vec<PluginVersionRecord>,
+    package: &PreparedPackage,
+    action: &str,
+) {
+    push_history(
+        history,
+        version_record(
+            &package.manifest.version,
+            action,
+            Some(&package.source),
+            package.signer_fingerprint.clone(),
+            package.signed_at.clone(),
+        ),
+    );
+}
+
+fn previous_installations_root(root: &Path) -> PathBuf {
+    root.join(PREVIOUS_INSTALLATIONS_DIR_NAME)
+}
+
+pub(super) fn remove_previous_installation_dir(previous: &PluginPreviousInstallation) {
+    let path = PathBuf::from(&previous.path);
+    if path.is_dir() {
+        std::fs::remove_dir_all(&path).ok();
+    }
+}
+
+fn permission_request_as_approval(request: &PluginPermissionRequest) -> PluginPermissionApproval {
+    PluginPermissionApproval {
+        network: request.network,
+        network_hosts: request.network_hosts.clone(),
+        fs: request.fs.clone(),
+        tools: request.tools.clone(),
+        resolver: request.resolver,
+    }
+}
+
+/// Permissions the next version requests that the current version did not.
+pub(super) fn collect_added_permissions(
+    current: &PluginPermissionRequest,
+    next: &PluginPermissionRequest,
+) -> Vec<String> {
+    collect_missing_permissions(next, &permission_request_as_approval(current))
+}
+
+fn is_newer_version(candidate: &str, current: &str) -> bool {
+    matches!(
+        compare_simple_semver(candidate, current),
+        Ok(Ordering::Greater)
+    )
+}
+
+pub(super) fn store_update_candidate(
+    plugin: &PluginSummary,
+    entry: &PluginStoreEntry,
+) -> Option<PluginUpdateInfo> {
+    let latest = select_store_version(entry, None).ok()?;
+    if !is_newer_version(&latest.version, &plugin.manifest.version) {
+        return None;
+    }
+    Some(PluginUpdateInfo {
+        plugin_id: plugin.manifest.plugin_id.clone(),
+        name: plugin.manifest.name.clone(),
+        current_version: plugin.manifest.version.clone(),
+        latest_version: latest.version.clone(),
+        source: PluginUpdateSource::Store,
+        package_url: latest.package_url.clone(),
+        release_tag: Some(latest.release_tag.clone()),
+    })
+}
+
+/// Pick the `.ywp` asset from a GitHub `releases/latest` response when it is newer.
+pub(super) fn github_release_update_candidate(
+    plugin: &PluginSummary,
+    release: &Value,
+) -> Option<PluginUpdateInfo> {
+    let tag = release["tag_name"].as_str()?;
+    let version = tag.trim_start_matches('v');
+    if !is_newer_version(version, &plugin.manifest.version) {
+        return None;
+    }
+    let package_url = release["assets"]
+        .as_array()?
+        .iter()
+        .filter(|asset| {
+            asset["name"]
+                .as_str()
+                .is_some_and(|name| name.ends_with(".ywp"))
+        })
+        .find_map(|asset| asset["browser_download_url"].as_str())?;
+    if !package_url.starts_with("https://github.com/") {
+        return None;
+    }
+    Some(PluginUpdateInfo {
+        plugin_id: plugin.manifest.plugin_id.clone(),
+        name: plugin.manifest.name.clone(),
+        current_version: plugin.manifest.version.clone(),
+        latest_version: version.to_string(),
+        source: PluginUpdateSource::GithubRelease,
+        package_url: package_url.to_string(),
+        release_tag: Some(tag.to_string()),
+    })
+}
+
+fn plugin_update_http_client(timeout_secs: u64) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent(format!("Youwee/{}", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(timeout_secs))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+async fn fetch_github_release_update(
+    plugin: &PluginSummary,
+    repository: &str,
+) -> Result<Option<PluginUpdateInfo>, String> {
+    let (owner, repo) = github_repo_parts(repository)?;
+    let response = plugin_update_http_client(GITHUB_RELEASE_TIMEOUT_SECS)?
+        .get(format!(
+            "https://api.github.com/repos/{}/{}/releases/latest",
+            owner, repo
+        ))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch release info: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch release info: HTTP {}",
+            response.status()
+        ));
+    }
+    let release: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse release info: {}", e))?;
+    Ok(github_release_update_candidate(plugin, &release))
+}
+
+async fn find_plugin_update(
+    plugin: &PluginSummary,
+    catalog_entries: &[PluginStoreEntry],
+) -> Result<Option<PluginUpdateInfo>, String> {
+    if let Some(entry) = catalog_entries
+        .iter()
+        .find(|entry| entry.plugin_id == plugin.manifest.plugin_id)
+    {
+        return Ok(store_update_candidate(plugin, entry));
+    }
+    match plugin.manifest.repository.as_deref() {
+        Some(repository) if github_repo_parts(repository).is_ok() => {
+            fetch_github_release_update(plugin, repository).await
+        }
+        _ => Ok(None),
+    }
+}
+
+async fn list_installed_packaged_plugins(app: &AppHandle) -> Result<Vec<PluginSummary>, String> {
+    let app = app.clone();
+    let plugins = tokio::task::spawn_blocking(move || list_plugins_internal(&app))
+        .await
+        .map_err(|e| format!("Failed to join plugin list task: {}", e))??;
+    Ok(plugins
+        .into_iter()
+        .filter(|plugin| plugin.installation.source.kind == PluginPackageSourceKind::PackageYwp)
+        .collect())
+}
+
+/// Check for plugin updates shortly after startup and then every six hours, emitting
+/// `plugin-updates-available` so an open settings view picks up the results.
+pub fn start_plugin_update_checks(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + Duration::from_secs(PLUGIN_UPDATE_CHECK_DELAY_SECS),
+            Duration::from_secs(PLUGIN_UPDATE_CHECK_INTERVAL_SECS),
+        );
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut force_refresh = false;
+        loop {
+            interval.tick().await;
+            match check_plugin_updates_internal(&app, force_refresh).await {
+                Ok(updates) => {
+                    let _ = app.emit(PLUGIN_UPDATES_EVENT, &updates);
+                }
+                Err(error) => log::warn!("Scheduled plugin update check failed: {}", error),
+            }
+            force_refresh = true;
+        }
+    });
+}
+
+/// Check installed packaged plugins against the store catalog, then their GitHub releases.
+pub async fn check_plugin_updates_internal(
+    app: &AppHandle,
+    force_refresh: bool,
+) -> Result<Vec<PluginUpdateInfo>, String> {
+    let catalog = load_runtime_plugin_store_catalog(force_refresh).await?;
+    let mut updates = Vec::new();
+    for plugin in list_installed_packaged_plugins(app).await? {
+        match find_plugin_update(&plugin, &catalog.plugins).await {
+            Ok(Some(update)) => updates.push(update),
+            Ok(None) => {}
+            Err(error) => {
+                add_log_internal(
+                    "warn",
+                    &format!("Plugin update check failed: {}", plugin.manifest.name),
+                    Some(&error),
+                    None,
+                )
+                .ok();
+            }
+        }
+    }
+    Ok(updates)
+}
+
+async fn download_github_release_package(
+    app: &AppHandle,
+    update: &PluginUpdateInfo,
+) -> Result<PathBuf, String> {
+    let response = plugin_update_http_client(120)?
+        .get(&update.package_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download plugin package: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Plugin package download failed: HTTP {}",
+            response.status()
+        ));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read plugin package response: {}", e))?;
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to get app cache directory: {}", e))?
+        .join("plugin-updates");
+    tokio::fs::create_dir_all(&cache_dir)
+        .await
+        .map_err(|e| format!("Failed to create plugin update cache: {}", e))?;
+    let package_path = cache_dir.join(format!(
+        "{}-{}.ywp",
+        update.plugin_id, update.latest_version
+    ));
+    tokio::fs::write(&package_path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write plugin package: {}", e))?;
+    Ok(package_path)
+}
+
+/// Download and verify the newest version of an installed plugin without installing it.
+pub async fn prepare_plugin_update_internal(
+    app: &AppHandle,
+    plugin_id: String,
+) -> Result<PreparedPluginUpdate, String> {
+    let plugin = get_plugin_details_internal(app, &plugin_id)?;
+    if plugin.installation.source.kind != PluginPackageSourceKind::PackageYwp {
+        return Err(format!(
+            "Plugin {} is attached from a workspace and cannot be updated.",
+            plugin.manifest.name
+        ));
+    }
+    let catalog = load_runtime_plugin_store_catalog(false).await?;
+    let update = find_plugin_update(&plugin, &catalog.plugins)
+        .await?
+        .ok_or_else(|| format!("No update is available for {}", plugin.manifest.name))?;
+
+    let (path, inspection) = match update.source {
+        PluginUpdateSource::Store => {
+            let prepared = prepare_plugin_store_package_internal(
+                app,
+                plugin_id.clone(),
+                Some(update.latest_version.clone()),
+            )
+            .await?;
+            (prepared.path, prepared.inspection)
+        }
+        PluginUpdateSource::GithubRelease => {
+            let package_path = download_github_release_package(app, &update).await?;
+            let path = package_path.to_string_lossy().to_string();
+            let inspection = inspect_plugin_package_internal(app, path.clone()).await?;
+            if inspection.manifest.plugin_id != plugin_id {
+                return Err(format!(
+                    "Security error: plugin manifest id {} does not match installed id {}.",
+                    inspection.manifest.plugin_id, plugin_id
+                ));
+            }
+            if inspection.manifest.version != update.latest_version {
+                return Err(format!(
+                    "Security error: plugin manifest version {} does not match release version {}.",
+                    inspection.manifest.version, update.latest_version
+                ));
+            }
+            (path, inspection)
+        }
+    };
+
+    let registry = read_registry(app)?;
+    if let Some(pinned) = registry
+        .installations
+        .get(&plugin_id)
+        .and_then(pinned_signer_for_entry)
+    {
+        if inspection.signer_fingerprint.as_deref() != Some(pinned.as_str()) {
+            return Err(
+                "Security error: plugin update is not signed by the key pinned at first install."
+                    .to_string(),
+            );
+        }
+    }
+
+    let added_permissions = collect_added_permissions(
+        &plugin.manifest.permissions,
+        &inspection.manifest.permissions,
+    );
+    let id = Uuid::new_v4().to_string();
+    prepared_plugin_updates()
+        .lock()
+        .map_err(|_| "Failed to lock prepared plugin updates".to_string())?
+        .insert(
+            id.clone(),
+            PreparedUpdatePackage {
+                plugin_id: plugin_id.clone(),
+                version: inspection.manifest.version.clone(),
+                path,
+            },
+        );
+    Ok(PreparedPluginUpdate {
+        id,
+        update,
+        inspection,
+        added_permissions,
+    })
+}
+
+/// Install a prepared update in place, keeping config and approvals and parking the old files.
+///
+/// The plugin is disabled when the update requests new permissions so the user approves them
+/// through the normal enable prompt.
+pub async fn apply_plugin_update_internal(
+    app: &AppHandle,
+    prepared_id: String,
+) -> Result<PluginSummary, String> {
+    let prepared = prepared_plugin_updates()
+        .lock()
+        .map_err(|_| "Failed to lock prepared plugin updates".to_string())?
+        .remove(&prepared_id)
+        .ok_or_else(|| {
+            "Plugin update is no longer prepared. Check for updates and try again.".to_string()
+        })?;
+    let package = prepare_plugin_package(&InstallPluginPackageInput {
+        value: prepared.path,
+    })
+    .await?;
+    let plugin_id = package.manifest.plugin_id.clone();
+    if plugin_id != prepared.plugin_id || package.manifest.version != prepared.version {
+        return Err(
+            "Security error: the prepared plugin update changed before it was installed."
+                .to_string(),
+        );
+    }
+    validate_install_compatibility(&package.manifest)?;
+
+    let mut registry = read_registry(app)?;
+    let existing = registry
+        .installations
+        .get(&plugin_id)
+        .cloned()
+        .ok_or_else(|| format!("Plugin not found: {}", plugin_id))?;
+    if existing
+        .source
+        .as_ref()
+        .is_some_and(|source| source.kind == PluginPackageSourceKind::Workspace)
+    {
+        return Err(format!(
+            "Plugin {} is attached from a workspace and cannot be updated.",
+            plugin_id
+        ));
+    }
+    ensure_pinned_signer(&existing, &package)?;
+
+    let root = ensure_plugins_root(app)?;
+    let current_path = find_existing_installation_path(&root, &plugin_id)
+        .ok_or_else(|| format!("Plugin files not found for {}", plugin_id))?;
+    let current_manifest = load_installed_manifest_from_dir(&current_path)?;
+    if !is_newer_version(&package.manifest.version, &current_manifest.version) {
+        return Err(format!(
+            "Plugin update {} is not newer than installed version {}.",
+            package.manifest.version, current_manifest.version
+        ));
+    }
+
+    let previous_root = previous_installations_root(&root);
+    std::fs::create_dir_all(&previous_root).map_err(|e| {
+        format!(
+            "Failed to create directory {}: {}",
+            previous_root.display(),
+            e
+        )
+    })?;
+    if let Some(previous) = existing.previous_installation.as_ref() {
+        remove_previous_installation_dir(previous);
+    }
+    let parked_path = previous_root.join(format!(
+        "{}-{}",
+        current_path
+            .file_name()
+            .and_then(|value| value.to_str())
+            .unwrap_or(&plugin_id),
+        current_manifest.version
+    ));
+    if parked_path.exists() {
+        std::fs::remove_dir_all(&parked_path)
+            .map_err(|e| format!("Failed to remove {}: {}", parked_path.display(), e))?;
+    }
+    std::fs::rename(&current_path, &parked_path).map_err(|e| {
+        format!(
+            "Failed to keep previous plugin version {}: {}",
+            current_path.display(),
+            e
+        )
+    })?;
+    let destination = installation_path(&root, &package.manifest);
+    if let Err(error) = copy_dir_recursive(&package.package_root, &destination) {
+        std::fs::remove_dir_all(&destination).ok();
+        std::fs::rename(&parked_path, &current_path).ok();
+        return Err(error);
+    }
+
+    let added_permissions =
+        collect_added_permissions(&current_manifest.permissions, &package.manifest.permissions);
+    let mut entry = existing;
+    entry.previous_installation = Some(PluginPreviousInstallation {
+        version: current_manifest.version.clone(),
+        path: parked_path.to_string_lossy().to_string(),
+        source: entry.source.clone(),
+        signature_status: entry.signature_status.clone(),
+        signer_key_id: entry.signer_key_id.clone(),
+        signer_fingerprint: entry.signer_fingerprint.clone(),
+        signature_algorithm: entry.signature_algorithm.clone(),
+        signed_at: entry.signed_at.clone(),
+    });
+    entry.pinned_signer_fingerprint =
+        pinned_signer_for_entry(&entry).or_else(|| package.signer_fingerprint.clone());
+    entry.source = Some(package.source.clone());
+    entry.signature_status = package.signature_status.clone();
+    entry.signer_key_id = package.signer_key_id.clone();
+    entry.signer_fingerprint = package.signer_fingerprint.clone();
+    entry.signature_algorithm = package.signature_algorithm.clone();
+    entry.signed_at = package.signed_at.clone();
+    entry.last_execution_status = Some("updated".to_string());
+    entry.last_error = None;
+    if !added_permissions.is_empty() {
+        entry.enabled = false;
+    }
+    push_version_record(&mut entry.version_history, &package, "update");
+    registry.installations.insert(plugin_id.clone(), entry);
+    write_registry(app, &registry)?;
+
+    add_log_internal(
+        "info",
+        &format!("Updated plugin: {}", package.manifest.name),
+        Some(&format!(
+            "Plugin ID: {}\nVersion: {} -> {}",
+            plugin_id, current_manifest.version, package.manifest.version
+        )),
+        None,
+    )
+    .ok();
+
+    get_plugin_details_internal(app, &plugin_id)
+}
+
+/// Swap the installed version with the one kept by the last update.
+///
+/// The replaced files are parked in turn, so rolling back twice restores the newer version.
+pub fn rollback_plugin_internal(app: &AppHandle, plugin_id: &str) -> Result<PluginSummary, String> {
+    let mut registry = read_registry(app)?;
+    let mut entry = registry
+        .installations
+        .get(plugin_id)
+        .cloned()
+        .ok_or_else(|| format!("Plugin not found: {}", plugin_id))?;
+    let previous = entry
+        .previous_installation
+        .clone()
+        .ok_or_else(|| format!("No previous version is available for {}", plugin_id))?;
+    let previous_path = PathBuf::from(&previous.path);
+    let previous_manifest = load_installed_manifest_from_dir(&previous_path)?;
+    if previous_manifest.plugin_id != plugin_id {
+        return Err(format!(
+            "Security error: previous plugin id {} does not match {}.",
+            previous_manifest.plugin_id, plugin_id
+        ));
+    }
+    if let Some(pinned) = pinned_signer_for_entry(&entry) {
+        if previous.signer_fingerprint.as_deref() != Some(pinned.as_str()) {
+            return Err(
+                "Security error: previous plugin version is not signed by the pinned key."
+                    .to_string(),
+            );
+        }
+    }
+
+    let root = ensure_plugins_root(app)?;
+    let current_path = find_existing_installation_path(&root, plugin_id)
+        .ok_or_else(|| format!("Plugin files not found for {}", plugin_id))?;
+    let current_manifest = load_installed_manifest_from_dir(&current_path)?;
+    let parked_path = previous_installations_root(&root).join(format!(
+        "{}-{}-{}",
+        current_path
+            .file_name()
+            .and_then(|value| value.to_str())
+            .unwrap_or(plugin_id),
+        current_manifest.version,
+        Uuid::new_v4()
+    ));
+    std::fs::rename(&current_path, &parked_path).map_err(|e| {
+        format!(
+            "Failed to park plugin version {}: {}",
+            current_path.display(),
+            e
+        )
+    })?;
+    let destination = installation_path(&root, &previous_manifest);
+    if let Err(error) = std::fs::rename(&previous_path, &destination) {
+        std::fs::rename(&parked_path, &current_path).ok();
+        return Err(format!(
+            "Failed to restore previous plugin version {}: {}",
+            previous_path.display(),
+            error
+        ));
+    }
+
+    entry.previous_installation = Some(PluginPreviousInstallation {
+        version: current_manifest.version.clone(),
+        path: parked_path.to_string_lossy().to_string(),
+        source: entry.source.clone(),
+        signature_status: entry.signature_status.clone(),
+        signer_key_id: entry.signer_key_id.clone(),
+        signer_fingerprint: entry.signer_fingerprint.clone(),
+        signature_algorithm: entry.signature_algorithm.clone(),
+        signed_at: entry.signed_at.clone(),
+    });
+    push_history(
+        &mut entry.version_history,
+        version_record(
+            &previous_manifest.version,
+            "rollback",
+            previous.source.as_ref(),
+            previous.signer_fingerprint.clone(),
+            previous.signed_at.clone(),
+        ),
+    );
+    entry.source = previous.source;
+    entry.signature_status = previous.signature_status;
+    entry.signer_key_id = previous.signer_key_id;
+    entry.signer_fingerprint = previous.signer_fingerprint;
+    entry.signature_algorithm = previous.signature_algorithm;
+    entry.signed_at = previous.signed_at;
+    entry.last_execution_status = Some("rolled-back".to_string());
+    entry.last_error = None;
+    registry.installations.insert(plugin_id.to_string(), entry);
+    write_registry(app, &registry)?;
+
+    add_log_internal(
+        "info",
+        &format!("Rolled back plugin: {}", previous_manifest.name),
+        Some(&format!(
+            "Plugin ID: {}\nVersion: {} -> {}",
+            plugin_id, current_manifest.version, previous_manifest.version
+        )),
+        None,
+    )
+    .ok();
+
+    get_plugin_details_internal(app, plugin_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::PluginToolPermission;
+
+    fn installed_plugin(version: &str) -> PluginSummary {
+        let mut plugin: PluginSummary = serde_json::from_value(json!({
+            "manifest": {
+                "id": "com.example.update",
+                "slug": "update",
+                "name": "Update",
+                "version": "1.0.0",
+                "runtime": {
+                    "language": "javascript",
+                    "supportedProviders": ["deno"],
+                    "entrypoint": "dist/plugin.js"
+                },
+                "triggers": ["download.completed"]
+            },
+            "installation": {
+                "pluginId": "com.example.update",
+                "enabled": true,
+                "trusted": true,
+                "approvedPermissions": { "network": false, "fs": [], "tools": [] },
+                "installedPath": "/tmp/update",
+                "source": { "kind": "package-ywp", "value": "/tmp/update.ywp" }
+            }
+        }))
+        .expect("plugin summary");
+        plugin.manifest.version = version.to_string();
+        plugin
+    }
+
+    #[test]
+    fn github_release_candidate_requires_newer_ywp_asset() {
+        let plugin = installed_plugin("1.2.0");
+        let release = json!({
+            "tag_name": "v1.3.0",
+            "assets": [
+                { "name": "checksums.txt", "browser_download_url": "https://github.com/o/r/releases/download/v1.3.0/checksums.txt" },
+                { "name": "update.ywp", "browser_download_url": "https://github.com/o/r/releases/download/v1.3.0/update.ywp" }
+            ]
+        });
+        let update = github_release_update_candidate(&plugin, &release).expect("update");
+        assert_eq!(update.latest_version, "1.3.0");
+        assert_eq!(update.source, PluginUpdateSource::GithubRelease);
+        assert!(update.package_url.ends_with("/update.ywp"));
+
+        assert!(github_release_update_candidate(&installed_plugin("1.3.0"), &release).is_none());
+        let no_asset = json!({ "tag_name": "v2.0.0", "assets": [] });
+        assert!(github_release_update_candidate(&plugin, &no_asset).is_none());
+    }
+
+    #[test]
+    fn store_candidate_uses_latest_catalog_version() {
+        let entry: PluginStoreEntry = serde_json::from_value(json!({
+            "pluginId": "com.example.update",
+            "slug": "update",
+            "name": "Update",
+            "description": "",
+            "publisher": { "kind": "official", "name": "Youwee", "repositoryOwner": "vanloctech" },
+            "repository": "https://github.com/vanloctech/update",
+            "latestVersion": "1.1.0",
+            "versions": [{
+                "version": "1.1.0",
+                "releaseTag": "v1.1.0",
+                "assetName": "update.ywp",
+                "packageUrl": "https://github.com/vanloctech/update/releases/download/v1.1.0/update.ywp",
+                "packageSize": 1,
+                "sha256": "0".repeat(64),
+                "signerFingerprint": "1".repeat(64),
+                "minAppVersion": "0.1.0"
+            }]
+        }))
+        .expect("store entry");
+
+        let update = store_update_candidate(&installed_plugin("1.0.0"), &entry).expect("update");
+        assert_eq!(update.source, PluginUpdateSource::Store);
+        assert_eq!(update.release_tag.as_deref(), Some("v1.1.0"));
+        assert!(store_update_candidate(&installed_plugin("1.1.0"), &entry).is_none());
+    }
+
+    #[test]
+    fn added_permissions_only_lists_new_requests() {
+        let current = PluginPermissionRequest {
+            network: true,
+            ..PluginPermissionRequest::default()
+        };
+        let next = PluginPermissionRequest {
+            network: true,
+            tools: vec![PluginToolPermission::FfmpegRun],
+            ..PluginPermissionRequest::default()
+        };
+        assert_eq!(
+            collect_added_permissions(&current, &next),
+            vec!["tool.ffmpeg.run".to_string()]
+        );
+        assert!(collect_added_permissions(&next, &current).is_empty());
+    }
+
+    #[test]
+    fn only_verified_signed_installs_get_a_legacy_pin() {
+        let mut signed = PluginRegistryEntry {
+            signature_status: Some("signed".to_string()),
+            signer_fingerprint: Some("a".repeat(64)),
+            ..PluginRegistryEntry::default()
+        };
+        assert!(pin_verified_legacy_signer(&mut signed));
+        assert_eq!(pinned_signer_for_entry(&signed), Some("a".repeat(64)));
+        assert!(!pin_verified_legacy_signer(&mut signed));
+
+        let mut unverified = PluginRegistryEntry {
+            signature_status: Some("unsigned".to_string()),
+            signer_fingerprint: Some("b".repeat(64)),
+            ..PluginRegistryEntry::default()
+        };
+        assert!(!pin_verified_legacy_signer(&mut unverified));
+        assert_eq!(pinned_signer_for_entry(&unverified), None);
+    }
+
+    #[test]
+    fn version_history_keeps_most_recent_records() {
+        let mut history = Vec::new();
+        for index in 0..(MAX_VERSION_HISTORY + 3) {
+            push_history(
+                &mut history,
+                version_record(&format!("1.0.{index}"), "update", None, None, None),
+            );
+        }
+        assert_eq!(history.len(), MAX_VERSION_HISTORY);
+        assert_eq!(history[0].version, "1.0.3");
+    }
+}
@@ -0,0 +1,384 @@
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use wasmtime::{Caller, Config, Engine, Extern, Linker, Module, Store, Trap};
+use wasmtime_wasi::p1::{self, WasiP1Ctx};
+use wasmtime_wasi::p2::pipe::{MemoryInputPipe, MemoryOutputPipe};
+use wasmtime_wasi::{I32Exit, WasiCtxBuilder};
+
+use super::bridge::PluginBridgeSession;
+
+const WASM_HOST_MODULE: &str = "youwee";
+const WASM_ALLOC_EXPORT: &str = "youwee_alloc";
+const WASM_MEMORY_EXPORT: &str = "memory";
+const WASM_OUTPUT_CAPACITY: usize = 16 * 1024 * 1024;
+
+/// Host functions imported from the `youwee` module, mapped one-to-one onto bridge operations.
+///
+/// Every function has the signature `(request_ptr: i32, request_len: i32) -> i64`. The request is
+/// the same JSON body the HTTP bridge accepts, and the result packs `(ptr << 32) | len` of a
+/// `{ ok, result, error }` JSON envelope written into memory obtained from `youwee_alloc`.
+pub(super) const WASM_HOST_FUNCTIONS: &[(&str, &str)] = &[
+    ("fs_exists", "/fs/exists"),
+    ("fs_read_dir", "/fs/readDir"),
+    ("fs_read_text", "/fs/readText"),
+    ("fs_read_base64", "/fs/readBase64"),
+    ("fs_write_text", "/fs/writeText"),
+    ("fs_write_base64", "/fs/writeBase64"),
+    ("fs_remove_file", "/fs/removeFile"),
+    ("fs_ensure_dir", "/fs/ensureDir"),
+    ("fs_temp_dir", "/fs/tempDir"),
+    ("tool_run", "/tool/run"),
+    ("youtube_search_videos", "/youtube/searchVideos"),
+    ("kv_get", "/kv/get"),
+    ("kv_set", "/kv/set"),
+    ("kv_delete", "/kv/delete"),
+    ("kv_list", "/kv/list"),
+    ("secrets_get", "/secrets/get"),
+    ("secrets_set", "/secrets/set"),
+    ("secrets_delete", "/secrets/delete"),
+];
+
+struct WasmPluginState {
+    wasi: WasiP1Ctx,
+    bridge: PluginBridgeSession,
+    runtime: tokio::runtime::Handle,
+    /// Epoch interruption only fires while guest code runs, so host calls check this themselves.
+    deadline: Instant,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct WasmPluginOutput {
+    pub(super) exit_code: i32,
+    pub(super) stdout: Vec<u8>,
+    pub(super) stderr: Vec<u8>,
+    pub(super) timed_out: bool,
+    pub(super) trap: Option<String>,
+}
+
+pub(super) async fn run_wasm_plugin(
+    module_path: &Path,
+    payload: Vec<u8>,
+    env: Vec<(String, String)>,
+    bridge: PluginBridgeSession,
+    timeout_sec: u64,
+) -> Result<WasmPluginOutput, String> {
+    let module_bytes = tokio::fs::read(module_path).await.map_err(|e| {
+        format!(
+            "Failed to read WASM plugin module {}: {}",
+            module_path.display(),
+            e
+        )
+    })?;
+    let runtime = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        run_wasm_module_blocking(module_bytes, payload, env, bridge, runtime, timeout_sec)
+    })
+    .await
+    .map_err(|e| format!("Failed to join WASM plugin task: {}", e))?
+}
+
+fn run_wasm_module_blocking(
+    module_bytes: Vec<u8>,
+    payload: Vec<u8>,
+    env: Vec<(String, String)>,
+    bridge: PluginBridgeSession,
+    runtime: tokio::runtime::Handle,
+    timeout_sec: u64,
+) -> Result<WasmPluginOutput, String> {
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+    let engine =
+        Engine::new(&config).map_err(|e| format!("Failed to create WASM engine: {}", e))?;
+    let module = Module::new(&engine, &module_bytes)
+        .map_err(|e| format!("Failed to compile WASM plugin module: {}", e))?;
+
+    let stdout = MemoryOutputPipe::new(WASM_OUTPUT_CAPACITY);
+    let stderr = MemoryOutputPipe::new(WASM_OUTPUT_CAPACITY);
+    // No preopened directories and no sockets: every capability goes through host functions.
+    let mut wasi = WasiCtxBuilder::new();
+    wasi.stdin(MemoryInputPipe::new(payload))
+        .stdout(stdout.clone())
+        .stderr(stderr.clone())
+        .arg("plugin.wasm");
+    for (key, value) in &env {
+        wasi.env(key, value);
+    }
+
+    let mut linker = Linker::<WasmPluginState>::new(&engine);
+    p1::add_to_linker_sync(&mut linker, |state| &mut state.wasi)
+        .map_err(|e| format!("Failed to link WASI imports: {}", e))?;
+    register_bridge_host_functions(&mut linker)?;
+
+    let timeout = Duration::from_secs(timeout_sec);
+    let mut store = Store::new(
+        &engine,
+        WasmPluginState {
+            wasi: wasi.build_p1(),
+            bridge,
+            runtime,
+            deadline: Instant::now() + timeout,
+        },
+    );
+    store.set_epoch_deadline(1);
+
+    let (finished_tx, finished_rx) = mpsc::channel::<()>();
+    let watchdog_engine = engine.clone();
+    std::thread::spawn(move || {
+        if matches!(
+            finished_rx.recv_timeout(timeout),
+            Err(RecvTimeoutError::Timeout)
+        ) {
+            watchdog_engine.increment_epoch();
+        }
+    });
+
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .map_err(|e| format!("Failed to instantiate WASM plugin: {}", e))?;
+    let start = instance
+        .get_typed_func::<(), ()>(&mut store, "_start")
+        .map_err(|_| "WASM plugin must export a WASI _start function".to_string())?;
+    let result = start.call(&mut store, ());
+    drop(finished_tx);
+
+    let mut output = WasmPluginOutput::default();
+    if let Err(error) = result {
+        if let Some(exit) = error.downcast_ref::<I32Exit>() {
+            output.exit_code = exit.0;
+        } else if matches!(error.downcast_ref::<Trap>(), Some(Trap::Interrupt)) {
+            output.exit_code = -1;
+            output.timed_out = true;
+        } else {
+            output.exit_code = -1;
+            output.trap = Some(format!("{:#}", error));
+        }
+    }
+    output.stdout = stdout.contents().to_vec();
+    output.stderr = stderr.contents().to_vec();
+    Ok(output)
+}
+
+fn register_bridge_host_functions(linker: &mut Linker<WasmPluginState>) -> Result<(), String> {
+    for (name, operation) in WASM_HOST_FUNCTIONS {
+        let operation = *operation;
+        linker
+            .func_wrap(
+                WASM_HOST_MODULE,
+                name,
+                move |mut caller: Caller<'_, WasmPluginState>,
+                      request_ptr: u32,
+                      request_len: u32|
+                      -> wasmtime::Result<u64> {
+                    let request = read_guest_bytes(&mut caller, request_ptr, request_len)?;
+                    let bridge = caller.data().bridge.clone();
+                    let runtime = caller.data().runtime.clone();
+                    let remaining = caller
+                        .data()
+                        .deadline
+                        .saturating_duration_since(Instant::now());
+                    let response = runtime
+                        .block_on(async {
+                            tokio::time::timeout(remaining, bridge.call(operation, request)).await
+                        })
+                        .map_err(|_| wasmtime::Error::new(Trap::Interrupt))?;
+                    write_guest_bytes(&mut caller, &response)
+                },
+            )
+            .map_err(|e| format!("Failed to register WASM host function {}: {}", name, e))?;
+    }
+    Ok(())
+}
+
+fn guest_memory(caller: &mut Caller<'_, WasmPluginState>) -> wasmtime::Result<wasmtime::Memory> {
+    caller
+        .get_export(WASM_MEMORY_EXPORT)
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| wasmtime::Error::msg("WASM plugin must export its linear memory"))
+}
+
+fn read_guest_bytes(
+    caller: &mut Caller<'_, WasmPluginState>,
+    ptr: u32,
+    len: u32,
+) -> wasmtime::Result<Vec<u8>> {
+    let memory = guest_memory(caller)?;
+    let mut buffer = vec![0_u8; len as usize];
+    memory.read(&*caller, ptr as usize, &mut buffer)?;
+    Ok(buffer)
+}
+
+fn write_guest_bytes(
+    caller: &mut Caller<'_, WasmPluginState>,
+    bytes: &[u8],
+) -> wasmtime::Result<u64> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| wasmtime::Error::msg("Bridge response is too large for WASM memory"))?;
+    let alloc = caller
+        .get_export(WASM_ALLOC_EXPORT)
+        .and_then(Extern::into_func)
+        .ok_or_else(|| wasmtime::Error::msg("WASM plugin must export youwee_alloc"))?
+        .typed::<u32, u32>(&*caller)?;
+    let ptr = alloc.call(&mut *caller, len)?;
+    let memory = guest_memory(caller)?;
+    memory.write(&mut *caller, ptr as usize, bytes)?;
+    Ok(pack_guest_slice(ptr, len))
+}
+
+pub(super) fn pack_guest_slice(ptr: u32, len: u32) -> u64 {
+    (u64::from(ptr) << 32) | u64::from(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    use super::super::bridge::PluginBridgePolicy;
+
+    const WRITE_OK_AND_EXIT_WAT: &str = r#"(module
+  (import "wasi_snapshot_preview1" "fd_write"
+    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 16) "ok")
+  (func (export "_start")
+    (i32.store (i32.const 0) (i32.const 16))
+    (i32.store (i32.const 4) (i32.const 2))
+    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (call $proc_exit (i32.const 3))))"#;
+
+    const SPIN_WAT: &str = r#"(module
+  (memory (export "memory") 1)
+  (func (export "_start") (loop $spin (br $spin))))"#;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "youwee-wasm-plugin-test-{name}-{}",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&path).expect("create test dir");
+        path
+    }
+
+    fn test_bridge(root: &Path, ytdlp_path: Option<PathBuf>) -> PluginBridgeSession {
+        PluginBridgeSession::new(PluginBridgePolicy {
+            allow_read_scopes: vec![root.to_path_buf()],
+            allow_write_scopes: vec![root.to_path_buf()],
+            plugin_dir: root.to_path_buf(),
+            ffmpeg_path: None,
+            ytdlp_path,
+            network_allowed: false,
+            network_hosts: None,
+            plugin_id: "com.example.wasm-test".to_string(),
+        })
+    }
+
+    /// Builds a guest that sends `request` to one host function and writes the envelope to stdout.
+    fn host_call_wat(function: &str, request: &str) -> String {
+        let escaped = request.replace('\\', "\\\\").replace('"', "\\\"");
+        format!(
+            r#"(module
+  (import "youwee" "{function}" (func $host (param i32 i32) (result i64)))
+  (import "wasi_snapshot_preview1" "fd_write"
+    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 4096))
+  (data (i32.const 16) "{escaped}")
+  (func (export "youwee_alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $len)))
+    (local.get $ptr))
+  (func (export "_start")
+    (local $packed i64)
+    (local.set $packed (call $host (i32.const 16) (i32.const {len})))
+    (i32.store (i32.const 0) (i32.wrap_i64 (i64.shr_u (local.get $packed) (i64.const 32))))
+    (i32.store (i32.const 4) (i32.wrap_i64 (local.get $packed)))
+    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))))"#,
+            len = request.len(),
+        )
+    }
+
+    async fn run_wat(root: &Path, wat: &str, bridge: PluginBridgeSession) -> WasmPluginOutput {
+        let module_path = root.join("plugin.wat");
+        std::fs::write(&module_path, wat).expect("write module");
+        run_wasm_plugin(&module_path, Vec::new(), Vec::new(), bridge, 1)
+            .await
+            .expect("run wasm plugin")
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn run_wasm_plugin_collects_stdout_and_exit_code() {
+        let root = test_dir("exit");
+        let output = run_wat(&root, WRITE_OK_AND_EXIT_WAT, test_bridge(&root, None)).await;
+        std::fs::remove_dir_all(&root).ok();
+
+        assert_eq!(output.exit_code, 3);
+        assert_eq!(output.stdout, b"ok");
+        assert!(!output.timed_out);
+        assert!(output.trap.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn run_wasm_plugin_interrupts_spinning_guest() {
+        let root = test_dir("spin");
+        let output = run_wat(&root, SPIN_WAT, test_bridge(&root, None)).await;
+        std::fs::remove_dir_all(&root).ok();
+
+        assert!(output.timed_out);
+        assert_eq!(output.exit_code, -1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn run_wasm_plugin_routes_host_calls_through_bridge() {
+        let root = test_dir("host-call");
+        let request = serde_json::json!({ "path": root.to_string_lossy() }).to_string();
+        let wat = host_call_wat("fs_exists", &request);
+        let output = run_wat(&root, &wat, test_bridge(&root, None)).await;
+        std::fs::remove_dir_all(&root).ok();
+
+        let envelope: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("bridge envelope");
+        assert_eq!(envelope["ok"], serde_json::json!(true));
+        assert_eq!(envelope["result"], serde_json::json!(true));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn run_wasm_plugin_times_out_guest_blocked_in_host_call() {
+        let root = test_dir("blocked-host-call");
+        let request = serde_json::json!({ "tool": "ytdlp", "args": ["30"] }).to_string();
+        let wat = host_call_wat("tool_run", &request);
+        let bridge = test_bridge(&root, Some(PathBuf::from("/bin/sleep")));
+        let started = Instant::now();
+        let output = run_wat(&root, &wat, bridge).await;
+        std::fs::remove_dir_all(&root).ok();
+
+        assert!(output.timed_out);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn pack_guest_slice_keeps_pointer_in_high_bits() {
+        let packed = pack_guest_slice(0x1234, 42);
+        assert_eq!(packed >> 32, 0x1234);
+        assert_eq!(packed & 0xffff_ffff, 42);
+    }
+
+    #[test]
+    fn host_functions_map_to_unique_bridge_operations() {
+        let mut operations = WASM_HOST_FUNCTIONS
+            .iter()
+            .map(|(_, operation)| *operation)
+            .collect::<Vec<_>>();
+        operations.sort();
+        operations.dedup();
+        assert_eq!(operations.len(), WASM_HOST_FUNCTIONS.len());
+        assert!(operations.contains(&"/fs/readText"));
+        assert!(operations.contains(&"/fs/writeBase64"));
+        assert!(operations.contains(&"/tool/run"));
+        assert!(operations.contains(&"/youtube/searchVideos"));
+    }
+}
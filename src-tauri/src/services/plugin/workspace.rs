@@ -211,6 +211,7 @@ pub fn create_plugin_workspace_internal(
             supported_locales: vec!["en".to_string()],
             directory: Some("locales".to_string()),
         }),
+        resolver: None,
     };
     validate_manifest(&manifest, Path::new("plugin.json"))?;
 
@@ -0,0 +1,603 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[path = "subtitles/translate.rs"]
+mod translate;
+
+pub use translate::*;
+
+const DEFAULT_ASS_HEADER: &str = "[Script Info]
+ScriptType: v4.00+
+WrapStyle: 0
+ScaledBorderAndShadow: yes
+YCbCr Matrix: None
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,48,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,2,1,2,10,10,10,1";
+
+const ASS_EVENTS_FORMAT: &str =
+    "Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text";
+
+/// Subtitle file formats the toolkit can read and write
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+    Ass,
+}
+
+impl SubtitleFormat {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())?
+            .to_lowercase();
+        match extension.as_str() {
+            "srt" => Some(SubtitleFormat::Srt),
+            "vtt" => Some(SubtitleFormat::Vtt),
+            "ass" | "ssa" => Some(SubtitleFormat::Ass),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from file content
+    pub fn detect(content: &str) -> Self {
+        let trimmed = content.trim_start_matches('\u{feff}').trim_start();
+        if trimmed.starts_with("WEBVTT") {
+            SubtitleFormat::Vtt
+        } else if trimmed.contains("[Script Info]") || trimmed.contains("[Events]") {
+            SubtitleFormat::Ass
+        } else {
+            SubtitleFormat::Srt
+        }
+    }
+}
+
+/// A single timed subtitle, with line breaks as `\n` regardless of source format
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SubtitleCue {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+}
+
+/// Parsed subtitle file
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SubtitleDocument {
+    pub format: SubtitleFormat,
+    pub cues: Vec<SubtitleCue>,
+    /// Everything before `[Events]` in ASS files, kept so styles survive a round-trip
+    pub ass_header: Option<String>,
+}
+
+/// How subtitles are attached to a video
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleEmbedMode {
+    /// Add a selectable subtitle stream without re-encoding
+    Soft,
+    /// Render subtitles into the video frames
+    Burn,
+}
+
+/// Parse `HH:MM:SS,mmm`, `HH:MM:SS.mmm`, `MM:SS.mmm`, or ASS `H:MM:SS.cc` into milliseconds
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let normalized = value.trim().replace(',', ".");
+    let parts = normalized.split(':').collect::<Vec<_>>();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, s] => (h.parse::<i64>().ok()?, m.parse::<i64>().ok()?, *s),
+        [m, s] => (0, m.parse::<i64>().ok()?, *s),
+        _ => return None,
+    };
+    let seconds: f64 = seconds.parse().ok()?;
+    Some((hours * 3600 + minutes * 60) * 1000 + (seconds * 1000.0).round() as i64)
+}
+
+fn split_timestamp(ms: i64) -> (i64, i64, i64, i64) {
+    let ms = ms.max(0);
+    (
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1000) % 60,
+        ms % 1000,
+    )
+}
+
+fn format_timestamp(ms: i64, fraction_separator: char) -> String {
+    let (h, m, s, ms) = split_timestamp(ms);
+    format!("{:02}:{:02}:{:02}{}{:03}", h, m, s, fraction_separator, ms)
+}
+
+fn format_ass_timestamp(ms: i64) -> String {
+    let (h, m, s, ms) = split_timestamp(ms);
+    format!("{}:{:02}:{:02}.{:02}", h, m, s, ms / 10)
+}
+
+fn normalize_newlines(content: &str) -> String {
+    content
+        .trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+}
+
+/// Parse SRT and WebVTT, which share the `start --> end` cue block layout
+fn parse_timed_blocks(content: &str) -> Vec<SubtitleCue> {
+    let normalized = normalize_newlines(content);
+    let mut cues = Vec::new();
+    for block in normalized.split("\n\n") {
+        let lines = block.lines().collect::<Vec<_>>();
+        let Some(timing_index) = lines.iter().position(|line| line.contains("-->")) else {
+            continue;
+        };
+        let Some((start, end)) = lines[timing_index].split_once("-->") else {
+            continue;
+        };
+        // VTT cue settings follow the end timestamp on the same line.
+        let end = end.split_whitespace().next().unwrap_or_default();
+        let (Some(start_ms), Some(end_ms)) = (parse_timestamp(start), parse_timestamp(end)) else {
+            continue;
+        };
+        let text = lines[timing_index + 1..].join("\n").trim().to_string();
+        cues.push(SubtitleCue {
+            start_ms,
+            end_ms,
+            text,
+        });
+    }
+    cues
+}
+
+fn parse_ass(content: &str) -> (Vec<SubtitleCue>, Option<String>) {
+    let normalized = normalize_newlines(content);
+    let lines = normalized.lines().collect::<Vec<_>>();
+    let Some(events_index) = lines
+        .iter()
+        .position(|line| line.trim().eq_ignore_ascii_case("[events]"))
+    else {
+        return (Vec::new(), Some(normalized.trim_end().to_string()));
+    };
+    let header = lines[..events_index].join("\n").trim_end().to_string();
+
+    let mut columns = Vec::new();
+    let mut cues = Vec::new();
+    for line in &lines[events_index + 1..] {
+        let line = line.trim();
+        let Some((kind, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if kind.eq_ignore_ascii_case("format") {
+            columns = rest
+                .split(',')
+                .map(|column| column.trim().to_lowercase())
+                .collect();
+            continue;
+        }
+        if !kind.eq_ignore_ascii_case("dialogue") || columns.is_empty() {
+            continue;
+        }
+        // The text column is last and may itself contain commas.
+        let fields = rest.trim().splitn(columns.len(), ',').collect::<Vec<_>>();
+        let field = |name: &str| {
+            columns
+                .iter()
+                .position(|column| column == name)
+                .and_then(|index| fields.get(index))
+                .map(|value| value.trim())
+        };
+        let (Some(start), Some(end), Some(text)) = (field("start"), field("end"), field("text"))
+        else {
+            continue;
+        };
+        let (Some(start_ms), Some(end_ms)) = (parse_timestamp(start), parse_timestamp(end)) else {
+            continue;
+        };
+        cues.push(SubtitleCue {
+            start_ms,
+            end_ms,
+            text: text.replace("\\N", "\n").replace("\\n", "\n"),
+        });
+    }
+    (cues, (!header.is_empty()).then_some(header))
+}
+
+/// Parse subtitle content, detecting the format when none is given
+pub fn parse_subtitle(content: &str, format: Option<SubtitleFormat>) -> SubtitleDocument {
+    let format = format.unwrap_or_else(|| SubtitleFormat::detect(content));
+    match format {
+        SubtitleFormat::Srt | SubtitleFormat::Vtt => SubtitleDocument {
+            format,
+            cues: parse_timed_blocks(content),
+            ass_header: None,
+        },
+        SubtitleFormat::Ass => {
+            let (cues, ass_header) = parse_ass(content);
+            SubtitleDocument {
+                format,
+                cues,
+                ass_header,
+            }
+        }
+    }
+}
+
+/// Serialize cues in the requested format
+pub fn serialize_subtitle(document: &SubtitleDocument, format: SubtitleFormat) -> String {
+    match format {
+        SubtitleFormat::Srt => {
+            let blocks = document
+                .cues
+                .iter()
+                .enumerate()
+                .map(|(index, cue)| {
+                    format!(
+                        "{}\n{} --> {}\n{}\n",
+                        index + 1,
+                        format_timestamp(cue.start_ms, ','),
+                        format_timestamp(cue.end_ms, ','),
+                        cue.text
+                    )
+                })
+                .collect::<Vec<_>>();
+            blocks.join("\n")
+        }
+        SubtitleFormat::Vtt => {
+            let blocks = document
+                .cues
+                .iter()
+                .map(|cue| {
+                    format!(
+                        "{} --> {}\n{}\n",
+                        format_timestamp(cue.start_ms, '.'),
+                        format_timestamp(cue.end_ms, '.'),
+                        cue.text
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("WEBVTT\n\n{}", blocks.join("\n"))
+        }
+        SubtitleFormat::Ass => {
+            let header = document
+                .ass_header
+                .as_deref()
+                .filter(|header| !header.trim().is_empty())
+                .unwrap_or(DEFAULT_ASS_HEADER);
+            let dialogues = document
+                .cues
+                .iter()
+                .map(|cue| {
+                    format!(
+                        "Dialogue: 0,{},{},Default,,0,0,0,,{}",
+                        format_ass_timestamp(cue.start_ms),
+                        format_ass_timestamp(cue.end_ms),
+                        cue.text.replace('\n', "\\N")
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "{}\n\n[Events]\n{}\n{}\n",
+                header,
+                ASS_EVENTS_FORMAT,
+                dialogues.join("\n")
+            )
+        }
+    }
+}
+
+pub fn read_subtitle_file(path: &str) -> Result<SubtitleDocument, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read subtitle file: {}", e))?;
+    let document = parse_subtitle(&content, SubtitleFormat::from_path(path));
+    if document.cues.is_empty() {
+        return Err(format!("No subtitle cues found in {}", path));
+    }
+    Ok(document)
+}
+
+/// Write cues to `path`, using the path's extension to pick the output format
+pub fn write_subtitle_file(path: &str, document: &SubtitleDocument) -> Result<(), String> {
+    let format = SubtitleFormat::from_path(path).unwrap_or(document.format);
+    std::fs::write(path, serialize_subtitle(document, format))
+        .map_err(|e| format!("Failed to write subtitle file: {}", e))
+}
+
+/// Stretch timings by `scale` around zero, then shift them by `offset_ms`.
+///
+/// Cues pushed entirely before zero are dropped; partially negative cues are clamped.
+pub fn retime_cues(cues: &[SubtitleCue], offset_ms: i64, scale: f64) -> Vec<SubtitleCue> {
+    let map = |ms: i64| (ms as f64 * scale).round() as i64 + offset_ms;
+    cues.iter()
+        .filter_map(|cue| {
+            let end_ms = map(cue.end_ms);
+            if end_ms <= 0 {
+                return None;
+            }
+            Some(SubtitleCue {
+                start_ms: map(cue.start_ms).max(0),
+                end_ms,
+                text: cue.text.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Solve `new = old * scale + offset` from two `(subtitle_ms, video_ms)` sync points
+pub fn linear_retime_from_sync_points(
+    first: (i64, i64),
+    second: (i64, i64),
+) -> Result<(i64, f64), String> {
+    let source_span = second.0 - first.0;
+    if source_span == 0 {
+        return Err("Sync points must be at different subtitle times".to_string());
+    }
+    let scale = (second.1 - first.1) as f64 / source_span as f64;
+    if scale <= 0.0 {
+        return Err("Sync points must keep subtitles in order".to_string());
+    }
+    let offset_ms = first.1 - (first.0 as f64 * scale).round() as i64;
+    Ok((offset_ms, scale))
+}
+
+fn overlap_ms(a: &SubtitleCue, b: &SubtitleCue) -> i64 {
+    a.end_ms.min(b.end_ms) - a.start_ms.max(b.start_ms)
+}
+
+/// Merge two tracks into one bilingual track, stacking `secondary` under `primary`.
+///
+/// Each secondary cue joins the primary cue it overlaps most; secondary cues with no
+/// overlapping primary cue are kept on their own.
+pub fn merge_bilingual_cues(
+    primary: &[SubtitleCue],
+    secondary: &[SubtitleCue],
+) -> Vec<SubtitleCue> {
+    let mut attached = vec![Vec::new(); primary.len()];
+    let mut merged = Vec::new();
+    for cue in secondary {
+        let best = primary
+            .iter()
+            .enumerate()
+            .map(|(index, primary_cue)| (index, overlap_ms(primary_cue, cue)))
+            .filter(|(_, overlap)| *overlap > 0)
+            .max_by_key(|(_, overlap)| *overlap);
+        match best {
+            Some((index, _)) => attached[index].push(cue.text.trim()),
+            None => merged.push(cue.clone()),
+        }
+    }
+
+    for (cue, texts) in primary.iter().zip(attached) {
+        let mut text = cue.text.trim().to_string();
+        for translation in texts.into_iter().filter(|t| !t.is_empty()) {
+            text.push('\n');
+            text.push_str(translation);
+        }
+        merged.push(SubtitleCue {
+            start_ms: cue.start_ms,
+            end_ms: cue.end_ms,
+            text,
+        });
+    }
+    merged.sort_by_key(|cue| (cue.start_ms, cue.end_ms));
+    merged
+}
+
+/// Escape a path for use inside FFmpeg's `subtitles=` filter argument
+pub fn escape_subtitles_filter_path(path: &str) -> String {
+    let mut escaped = path.replace('\\', "/");
+    escaped = escaped.replace(':', "\\:");
+    escaped = escaped.replace('\'', "\\'");
+    escaped = escaped.replace(',', "\\,");
+    escaped = escaped.replace('[', "\\[");
+    escaped = escaped.replace(']', "\\]");
+    escaped
+}
+
+/// Container extension for the embed output; soft subtitles need a container that holds them
+pub fn subtitle_embed_output_extension(input_path: &str, mode: SubtitleEmbedMode) -> &'static str {
+    let extension = Path::new(input_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match (mode, extension.as_str()) {
+        (SubtitleEmbedMode::Burn, _) => "mp4",
+        (SubtitleEmbedMode::Soft, "mp4" | "m4v" | "mov") => "mp4",
+        (SubtitleEmbedMode::Soft, _) => "mkv",
+    }
+}
+
+/// Build FFmpeg arguments that soft-embed or burn `subtitle_path` into `input_path`
+pub fn build_subtitle_embed_args(
+    input_path: &str,
+    subtitle_path: &str,
+    output_path: &str,
+    mode: SubtitleEmbedMode,
+    has_audio: bool,
+    language: Option<&str>,
+) -> Vec<String> {
+    let mut args = vec!["-y".to_string(), "-i".to_string(), input_path.to_string()];
+    match mode {
+        SubtitleEmbedMode::Burn => {
+            args.extend([
+                "-vf".to_string(),
+                format!(
+                    "subtitles='{}'",
+                    escape_subtitles_filter_path(subtitle_path)
+                ),
+                "-c:v".to_string(),
+                "libx264".to_string(),
+                "-preset".to_string(),
+                "medium".to_string(),
+                "-crf".to_string(),
+                "20".to_string(),
+            ]);
+            if has_audio {
+                args.extend(["-c:a".to_string(), "copy".to_string()]);
+            } else {
+                args.push("-an".to_string());
+            }
+        }
+        SubtitleEmbedMode::Soft => {
+            // MP4 only carries mov_text; Matroska keeps SRT/VTT/ASS as-is.
+            let subtitle_codec = if output_path.to_lowercase().ends_with(".mp4") {
+                "mov_text"
+            } else {
+                "copy"
+            };
+            args.extend([
+                "-i".to_string(),
+                subtitle_path.to_string(),
+                "-map".to_string(),
+                "0".to_string(),
+                "-map".to_string(),
+                "1:s:0".to_string(),
+                "-c".to_string(),
+                "copy".to_string(),
+                "-c:s".to_string(),
+                subtitle_codec.to_string(),
+            ]);
+            if let Some(language) = language.map(str::trim).filter(|l| !l.is_empty()) {
+                args.extend([
+                    "-metadata:s:s:0".to_string(),
+                    format!("language={}", language),
+                ]);
+            }
+        }
+    }
+    args.extend([
+        "-progress".to_string(),
+        "pipe:2".to_string(),
+        output_path.to_string(),
+    ]);
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(start_ms: i64, end_ms: i64, text: &str) -> SubtitleCue {
+        SubtitleCue {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_srt_vtt_and_ass_into_the_same_cues() {
+        let srt = "\u{feff}1\r\n00:00:01,000 --> 00:00:02,500\r\nHello\r\nworld\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\nBye\r\n";
+        let vtt = "WEBVTT\n\nNOTE header\n\n00:01.000 --> 00:02.500 align:start\nHello\nworld\n\n00:00:03.000 --> 00:00:04.000\nBye\n";
+        let ass = "[Script Info]\nTitle: test\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: 0,0:00:01.00,0:00:02.50,Default,,0,0,0,,Hello\\Nworld\nDialogue: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,Bye\n";
+        let expected = vec![cue(1000, 2500, "Hello\nworld"), cue(3000, 4000, "Bye")];
+
+        assert_eq!(parse_subtitle(srt, None).cues, expected);
+        assert_eq!(parse_subtitle(vtt, None).format, SubtitleFormat::Vtt);
+        assert_eq!(parse_subtitle(vtt, None).cues, expected);
+        let document = parse_subtitle(ass, None);
+        assert_eq!(document.cues, expected);
+        assert_eq!(
+            document.ass_header.as_deref(),
+            Some("[Script Info]\nTitle: test")
+        );
+    }
+
+    #[test]
+    fn ass_text_keeps_commas() {
+        let ass = "[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Well, hello, there\n";
+        assert_eq!(parse_subtitle(ass, None).cues[0].text, "Well, hello, there");
+    }
+
+    #[test]
+    fn serializes_round_trip_between_formats() {
+        let document = SubtitleDocument {
+            format: SubtitleFormat::Srt,
+            cues: vec![cue(3_723_450, 3_725_000, "Line one\nLine two")],
+            ass_header: None,
+        };
+        let srt = serialize_subtitle(&document, SubtitleFormat::Srt);
+        assert!(srt.contains("01:02:03,450 --> 01:02:05,000"));
+        let vtt = serialize_subtitle(&document, SubtitleFormat::Vtt);
+        assert!(vtt.starts_with("WEBVTT\n\n01:02:03.450 --> 01:02:05.000"));
+        let ass = serialize_subtitle(&document, SubtitleFormat::Ass);
+        assert!(
+            ass.contains("Dialogue: 0,1:02:03.45,1:02:05.00,Default,,0,0,0,,Line one\\NLine two")
+        );
+
+        for (content, format) in [
+            (srt, SubtitleFormat::Srt),
+            (vtt, SubtitleFormat::Vtt),
+            (ass, SubtitleFormat::Ass),
+        ] {
+            assert_eq!(parse_subtitle(&content, Some(format)).cues, document.cues);
+        }
+    }
+
+    #[test]
+    fn retimes_with_offset_scale_and_sync_points() {
+        let cues = vec![
+            cue(500, 900, "gone"),
+            cue(1000, 2000, "a"),
+            cue(10_000, 11_000, "b"),
+        ];
+        let shifted = retime_cues(&cues, -1000, 1.0);
+        assert_eq!(shifted, vec![cue(0, 1000, "a"), cue(9000, 10_000, "b")]);
+
+        let (offset, scale) =
+            linear_retime_from_sync_points((1000, 2000), (10_000, 20_000)).unwrap();
+        assert_eq!(offset, 0);
+        assert_eq!(scale, 2.0);
+        let stretched = retime_cues(&cues[1..], offset, scale);
+        assert_eq!(stretched[1], cue(20_000, 22_000, "b"));
+        assert!(linear_retime_from_sync_points((1000, 0), (1000, 5)).is_err());
+    }
+
+    #[test]
+    fn merges_bilingual_tracks_by_overlap() {
+        let primary = vec![cue(0, 2000, "Hello"), cue(2000, 4000, "World")];
+        let secondary = vec![
+            cue(100, 1900, "Xin chào"),
+            cue(1800, 3900, "Thế giới"),
+            cue(5000, 6000, "Thêm"),
+        ];
+        let merged = merge_bilingual_cues(&primary, &secondary);
+        assert_eq!(
+            merged,
+            vec![
+                cue(0, 2000, "Hello\nXin chào"),
+                cue(2000, 4000, "World\nThế giới"),
+                cue(5000, 6000, "Thêm"),
+            ]
+        );
+    }
+
+    #[test]
+    fn builds_soft_and_burn_embed_args() {
+        let soft = build_subtitle_embed_args(
+            "in.mp4",
+            "sub.srt",
+            "out.mp4",
+            SubtitleEmbedMode::Soft,
+            true,
+            Some("vie"),
+        );
+        assert!(soft.windows(2).any(|w| w == ["-c:s", "mov_text"]));
+        assert!(soft.contains(&"language=vie".to_string()));
+        assert_eq!(
+            subtitle_embed_output_extension("in.webm", SubtitleEmbedMode::Soft),
+            "mkv"
+        );
+
+        let burn = build_subtitle_embed_args(
+            "in.mp4",
+            "C:\\subs\\a,b.srt",
+            "out.mp4",
+            SubtitleEmbedMode::Burn,
+            false,
+            None,
+        );
+        assert!(burn.contains(&"subtitles='C\\:/subs/a\\,b.srt'".to_string()));
+        assert!(burn.contains(&"-an".to_string()));
+    }
+}
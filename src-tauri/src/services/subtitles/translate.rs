@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::SubtitleCue;
+use crate::services::{generate_raw, AIConfig, AIError};
+
+/// Cues sent to the AI provider per translation request
+pub const SUBTITLE_TRANSLATION_BATCH_SIZE: usize = 40;
+
+const TRANSLATION_MAX_ATTEMPTS: u32 = 3;
+const TRANSLATION_RETRY_BASE_DELAY_MS: u64 = 800;
+
+/// Wrap each text in numbered `<SEG_n>` tags so the reply can be mapped back to cues
+pub fn build_subtitle_translation_prompt(texts: &[&str], target_language: &str) -> String {
+    let tagged = texts
+        .iter()
+        .enumerate()
+        .map(|(index, text)| format!("<SEG_{0}>\n{1}\n</SEG_{0}>", index + 1, text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    [
+        format!(
+            "Translate the following subtitle texts to {}.",
+            target_language
+        ),
+        "Return ONLY translated output with EXACTLY the same SEG tags.".to_string(),
+        "Rules:".to_string(),
+        "- Keep the same number of items and the same order.".to_string(),
+        "- Do not merge, split, or drop items.".to_string(),
+        "- Keep SEG tags unchanged.".to_string(),
+        "- Preserve line breaks naturally inside each subtitle.".to_string(),
+        "- Do not add explanations or markdown.".to_string(),
+        format!("Input:\n{}", tagged),
+    ]
+    .join("\n")
+}
+
+/// Pull `expected` translations out of a tagged reply, or `None` if any tag is missing
+pub fn extract_tagged_translations(response: &str, expected: usize) -> Option<Vec<String>> {
+    (1..=expected)
+        .map(|index| {
+            let open = format!("<SEG_{}>", index);
+            let close = format!("</SEG_{}>", index);
+            let start = response.find(&open)? + open.len();
+            let end = start + response[start..].find(&close)?;
+            Some(response[start..end].trim().to_string())
+        })
+        .collect()
+}
+
+fn clean_single_translation(response: &str) -> String {
+    response
+        .trim()
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim()
+        .to_string()
+}
+
+async fn generate_with_retry(config: &AIConfig, prompt: &str) -> Result<String, AIError> {
+    let mut attempt = 1;
+    loop {
+        match generate_raw(config, prompt).await {
+            Ok(result) => return Ok(result.summary),
+            Err(AIError::NetworkError(_)) if attempt < TRANSLATION_MAX_ATTEMPTS => {
+                let delay = TRANSLATION_RETRY_BASE_DELAY_MS * 2u64.pow(attempt - 1);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                attempt += 1;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Translate cue texts in batches while keeping every cue's timing.
+///
+/// A batch whose reply loses tags is split in half and retried; a single cue falls back
+/// to a plain prompt. `on_progress` receives `(translated, total)` after each batch.
+pub async fn translate_cues(
+    config: &AIConfig,
+    cues: &[SubtitleCue],
+    target_language: &str,
+    on_progress: &(dyn Fn(usize, usize) + Send + Sync),
+) -> Result<Vec<SubtitleCue>, AIError> {
+    let mut translated = cues.to_vec();
+    let mut pending = (0..cues.len())
+        .step_by(SUBTITLE_TRANSLATION_BATCH_SIZE)
+        .map(|start| start..(start + SUBTITLE_TRANSLATION_BATCH_SIZE).min(cues.len()))
+        .collect::<VecDeque<_>>();
+    let mut done = 0;
+
+    while let Some(range) = pending.pop_front() {
+        let texts = cues[range.clone()]
+            .iter()
+            .map(|cue| cue.text.as_str())
+            .collect::<Vec<_>>();
+        let response = generate_with_retry(
+            config,
+            &build_subtitle_translation_prompt(&texts, target_language),
+        )
+        .await?;
+
+        let texts = match extract_tagged_translations(&response, texts.len()) {
+            Some(texts) => texts,
+            None if range.len() > 1 => {
+                let mid = range.start + range.len() / 2;
+                pending.push_front(mid..range.end);
+                pending.push_front(range.start..mid);
+                continue;
+            }
+            None => {
+                let prompt = format!(
+                    "Translate this subtitle text to {}.\nReturn ONLY the translated text. No markdown. No explanations.\nText:\n{}",
+                    target_language, texts[0]
+                );
+                let response = generate_with_retry(config, &prompt).await?;
+                vec![clean_single_translation(&response)]
+            }
+        };
+
+        for (index, text) in range.clone().zip(texts) {
+            if !text.is_empty() {
+                translated[index].text = text;
+            }
+        }
+        done += range.len();
+        on_progress(done, cues.len());
+    }
+    Ok(translated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_tagged_translation_batches() {
+        let prompt = build_subtitle_translation_prompt(&["Hello", "Two\nlines"], "Vietnamese");
+        assert!(prompt.contains("<SEG_2>\nTwo\nlines\n</SEG_2>"));
+
+        let reply = "<SEG_1>\nXin chào\n</SEG_1>\n<SEG_2> Hai\ndòng </SEG_2>";
+        assert_eq!(
+            extract_tagged_translations(reply, 2),
+            Some(vec!["Xin chào".to_string(), "Hai\ndòng".to_string()])
+        );
+        assert_eq!(extract_tagged_translations("<SEG_1>only</SEG_1>", 2), None);
+    }
+}
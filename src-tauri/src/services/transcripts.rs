@@ -0,0 +1,216 @@
+use std::path::Path;
+
+use super::{
+    parse_subtitle, serialize_subtitle, speaker_display_name, SubtitleCue, SubtitleDocument,
+    SubtitleFormat, WhisperVerboseSegment,
+};
+use crate::types::{TranscriptSegment, TranscriptSource};
+
+/// YouTube auto-captions carry per-word `<c>` spans and inline `<00:00:01.234>` timestamps
+pub fn is_auto_caption_vtt(content: &str) -> bool {
+    content.contains("<c>")
+        || regex::Regex::new(r"<\d{2}:\d{2}:\d{2}\.\d{3}>")
+            .map(|re| re.is_match(content))
+            .unwrap_or(false)
+}
+
+/// `transcript.en-US.vtt` -> `en-US`
+pub fn caption_language_from_path(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_string_lossy().to_string();
+    let (_, language) = stem.rsplit_once('.')?;
+    let language = language.trim();
+    (!language.is_empty()).then(|| language.to_string())
+}
+
+fn strip_caption_markup(line: &str) -> String {
+    let without_tags = regex::Regex::new(r"<[^>]+>")
+        .map(|re| re.replace_all(line, "").to_string())
+        .unwrap_or_else(|_| line.to_string());
+    without_tags
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Turn downloaded captions into transcript segments.
+///
+/// Auto-captions repeat the previous cue's line above the new one (the rolling display), so
+/// lines already shown by the previous cue are dropped and cues left empty are skipped.
+pub fn caption_segments_from_subtitle(content: &str) -> Vec<TranscriptSegment> {
+    let document = parse_subtitle(content, None);
+    let mut segments = Vec::new();
+    let mut previous_lines: Vec<String> = Vec::new();
+
+    for cue in document.cues {
+        let lines = cue
+            .text
+            .lines()
+            .map(strip_caption_markup)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let fresh = lines
+            .iter()
+            .filter(|line| !previous_lines.contains(line))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !lines.is_empty() {
+            previous_lines = lines;
+        }
+        if fresh.is_empty() || cue.end_ms <= cue.start_ms {
+            continue;
+        }
+        segments.push(TranscriptSegment {
+            start_ms: cue.start_ms,
+            end_ms: cue.end_ms,
+            text: fresh.join(" "),
+        });
+    }
+    segments
+}
+
+/// Convert Whisper segments, keeping diarized speakers as a `Speaker A: ` prefix
+pub fn transcript_segments_from_whisper(
+    segments: &[WhisperVerboseSegment],
+) -> Vec<TranscriptSegment> {
+    segments
+        .iter()
+        .filter(|segment| !segment.text.trim().is_empty())
+        .map(|segment| {
+            let text = segment.text.trim();
+            TranscriptSegment {
+                start_ms: (segment.start * 1000.0).round() as i64,
+                end_ms: (segment.end * 1000.0).round() as i64,
+                text: match segment.speaker.as_deref() {
+                    Some(speaker) => format!("{}: {}", speaker_display_name(speaker), text),
+                    None => text.to_string(),
+                },
+            }
+        })
+        .collect()
+}
+
+pub fn caption_transcript_source(content: &str) -> TranscriptSource {
+    if is_auto_caption_vtt(content) {
+        TranscriptSource::AutoCaptions
+    } else {
+        TranscriptSource::PlatformCaptions
+    }
+}
+
+/// Plain text used for summaries and Q&A
+pub fn transcript_plain_text(segments: &[TranscriptSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn format_txt_timestamp(ms: i64) -> String {
+    let seconds = ms.max(0) / 1000;
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60
+    )
+}
+
+/// Render a stored transcript as `srt`, `vtt`, or timestamped `txt`
+pub fn render_transcript(segments: &[TranscriptSegment], format: &str) -> Result<String, String> {
+    let subtitle_format = match format.trim().to_lowercase().as_str() {
+        "txt" | "text" => {
+            return Ok(segments
+                .iter()
+                .map(|segment| {
+                    format!(
+                        "[{}] {}",
+                        format_txt_timestamp(segment.start_ms),
+                        segment.text
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+                + "\n");
+        }
+        "srt" => SubtitleFormat::Srt,
+        "vtt" => SubtitleFormat::Vtt,
+        other => return Err(format!("Unsupported transcript format: {}", other)),
+    };
+    let document = SubtitleDocument {
+        format: subtitle_format,
+        cues: segments
+            .iter()
+            .map(|segment| SubtitleCue {
+                start_ms: segment.start_ms,
+                end_ms: segment.end_ms,
+                text: segment.text.clone(),
+            })
+            .collect(),
+        ass_header: None,
+    };
+    Ok(serialize_subtitle(&document, subtitle_format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapses_rolling_auto_captions_into_timed_segments() {
+        let vtt = "WEBVTT\nKind: captions\nLanguage: en\n\n00:00:00.000 --> 00:00:02.000 align:start position:0%\nhello<00:00:00.500><c> there</c>\n\n00:00:02.000 --> 00:00:02.010 align:start position:0%\nhello there\n\n00:00:02.010 --> 00:00:04.000 align:start position:0%\nhello there\ngeneral<00:00:02.500><c> kenobi</c>\n";
+        assert_eq!(
+            caption_transcript_source(vtt),
+            TranscriptSource::AutoCaptions
+        );
+
+        let segments = caption_segments_from_subtitle(vtt);
+        assert_eq!(
+            segments,
+            vec![
+                TranscriptSegment {
+                    start_ms: 0,
+                    end_ms: 2000,
+                    text: "hello there".to_string(),
+                },
+                TranscriptSegment {
+                    start_ms: 2010,
+                    end_ms: 4000,
+                    text: "general kenobi".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            transcript_plain_text(&segments),
+            "hello there general kenobi"
+        );
+        assert_eq!(
+            caption_language_from_path(Path::new("/tmp/subs/transcript.en-US.vtt")),
+            Some("en-US".to_string())
+        );
+    }
+
+    #[test]
+    fn renders_transcripts_in_export_formats() {
+        let segments = vec![TranscriptSegment {
+            start_ms: 61_500,
+            end_ms: 63_000,
+            text: "Speaker A: hi".to_string(),
+        }];
+        assert_eq!(
+            render_transcript(&segments, "txt").unwrap(),
+            "[00:01:01] Speaker A: hi\n"
+        );
+        assert!(render_transcript(&segments, "srt")
+            .unwrap()
+            .contains("00:01:01,500 --> 00:01:03,000"));
+        assert!(render_transcript(&segments, "VTT")
+            .unwrap()
+            .starts_with("WEBVTT"));
+        assert!(render_transcript(&segments, "docx").is_err());
+    }
+}
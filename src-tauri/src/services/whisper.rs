@@ -1,7 +1,9 @@
 use reqwest::multipart::{Form, Part};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::path::Path;
+use std::pin::Pin;
 use tokio::fs;
 
 /// Whisper API response format
@@ -41,19 +43,41 @@ pub struct WhisperResult {
     pub language: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
-struct WhisperVerboseSegment {
-    start: f64,
-    end: f64,
-    text: String,
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct WhisperVerboseSegment {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    /// Raw speaker label from a diarizing backend, e.g. `A` or `SPEAKER_00`
+    #[serde(default)]
+    pub speaker: Option<String>,
+}
+
+/// Timed segments from one transcription, used to stitch chunked audio back together
+#[derive(Clone, Debug, Default)]
+pub struct WhisperTranscript {
+    pub segments: Vec<WhisperVerboseSegment>,
+    pub duration_seconds: Option<f64>,
+    pub language: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-struct WhisperVerboseResponse {
-    text: Option<String>,
-    duration: Option<f64>,
-    language: Option<String>,
-    segments: Option<Vec<WhisperVerboseSegment>>,
+pub(crate) struct WhisperVerboseResponse {
+    pub(crate) text: Option<String>,
+    pub(crate) duration: Option<f64>,
+    pub(crate) language: Option<String>,
+    pub(crate) segments: Option<Vec<WhisperVerboseSegment>>,
+}
+
+/// Which transcription backend Whisper features use
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WhisperBackendKind {
+    /// OpenAI-compatible HTTP endpoint
+    #[default]
+    Api,
+    /// Locally installed whisper.cpp or faster-whisper binary
+    Local,
 }
 
 /// Error types for Whisper operations
@@ -67,6 +91,9 @@ pub enum WhisperError {
     NetworkError(String),
     ParseError(String),
     FfmpegError(String),
+    LocalEngineNotFound(String),
+    LocalModelMissing(String),
+    LocalEngineError(String),
 }
 
 impl std::fmt::Display for WhisperError {
@@ -90,6 +117,17 @@ impl std::fmt::Display for WhisperError {
             WhisperError::NetworkError(msg) => write!(f, "Network error: {}", msg),
             WhisperError::ParseError(msg) => write!(f, "Failed to parse Whisper response: {}", msg),
             WhisperError::FfmpegError(msg) => write!(f, "FFmpeg error: {}", msg),
+            WhisperError::LocalEngineNotFound(engine) => write!(
+                f,
+                "Local Whisper engine not found: {}. Install it or set its binary path in Settings.",
+                engine
+            ),
+            WhisperError::LocalModelMissing(model) => write!(
+                f,
+                "Local Whisper model not downloaded: {}. Download it in Settings.",
+                model
+            ),
+            WhisperError::LocalEngineError(msg) => write!(f, "Local Whisper error: {}", msg),
         }
     }
 }
@@ -103,6 +141,9 @@ impl From<WhisperError> for String {
 /// Maximum file size for Whisper API (25 MB)
 const MAX_FILE_SIZE: u64 = 25 * 1024 * 1024;
 
+/// OpenAI model used when speaker diarization is enabled without a custom model
+pub const DEFAULT_DIARIZATION_MODEL: &str = "gpt-4o-transcribe-diarize";
+
 /// Supported audio formats for Whisper
 const SUPPORTED_FORMATS: &[&str] = &["mp3", "mp4", "mpeg", "mpga", "m4a", "wav", "webm", "ogg"];
 
@@ -118,7 +159,7 @@ fn format_srt_timestamp(seconds: f64) -> String {
     format!("{:02}:{:02}:{:02},{:03}", h, m, s, ms)
 }
 
-fn format_vtt_timestamp(seconds: f64) -> String {
+pub fn format_vtt_timestamp(seconds: f64) -> String {
     let secs = seconds.max(0.0);
     let total_ms = (secs * 1000.0).round() as u64;
     let ms = total_ms % 1000;
@@ -139,12 +180,22 @@ fn build_subtitle_from_segments(
             .iter()
             .enumerate()
             .map(|(idx, segment)| {
+                let text = match segment.speaker.as_deref() {
+                    Some(speaker) => {
+                        format!(
+                            "[{}] {}",
+                            speaker_display_name(speaker),
+                            segment.text.trim()
+                        )
+                    }
+                    None => segment.text.trim().to_string(),
+                };
                 format!(
                     "{}\n{} --> {}\n{}\n",
                     idx + 1,
                     format_srt_timestamp(segment.start),
                     format_srt_timestamp(segment.end.max(segment.start + 0.1)),
-                    segment.text.trim()
+                    text
                 )
             })
             .collect::<Vec<_>>()
@@ -153,11 +204,22 @@ fn build_subtitle_from_segments(
             let cues = segments
                 .iter()
                 .map(|segment| {
+                    // WebVTT voice spans carry the speaker without changing the cue text.
+                    let text = match segment.speaker.as_deref() {
+                        Some(speaker) => {
+                            format!(
+                                "<v {}>{}",
+                                speaker_display_name(speaker),
+                                segment.text.trim()
+                            )
+                        }
+                        None => segment.text.trim().to_string(),
+                    };
                     format!(
                         "{} --> {}\n{}\n",
                         format_vtt_timestamp(segment.start),
                         format_vtt_timestamp(segment.end.max(segment.start + 0.1)),
-                        segment.text.trim()
+                        text
                     )
                 })
                 .collect::<Vec<_>>()
@@ -168,6 +230,73 @@ fn build_subtitle_from_segments(
     }
 }
 
+/// Readable speaker name: `A` -> `Speaker A`, `SPEAKER_00` -> `Speaker 1`
+pub fn speaker_display_name(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Some(number) = raw
+        .strip_prefix("SPEAKER_")
+        .and_then(|n| n.parse::<u32>().ok())
+    {
+        return format!("Speaker {}", number + 1);
+    }
+    if raw.to_lowercase().starts_with("speaker") {
+        return raw.to_string();
+    }
+    format!("Speaker {}", raw)
+}
+
+/// Join segments into one line per speaker turn, e.g. `Speaker A: ...`
+fn build_speaker_transcript(segments: &[WhisperVerboseSegment]) -> String {
+    let mut turns: Vec<(Option<&str>, Vec<&str>)> = Vec::new();
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let speaker = segment.speaker.as_deref();
+        match turns.last_mut() {
+            Some((last, texts)) if *last == speaker => texts.push(text),
+            _ => turns.push((speaker, vec![text])),
+        }
+    }
+    turns
+        .into_iter()
+        .map(|(speaker, texts)| match speaker {
+            Some(speaker) => format!("{}: {}", speaker_display_name(speaker), texts.join(" ")),
+            None => texts.join(" "),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Build a result in the requested format from timed segments
+pub(crate) fn whisper_result_from_segments(
+    segments: &[WhisperVerboseSegment],
+    response_format: WhisperResponseFormat,
+    duration_seconds: Option<f64>,
+    language: Option<String>,
+) -> WhisperResult {
+    let text = match response_format {
+        WhisperResponseFormat::Srt | WhisperResponseFormat::Vtt => {
+            build_subtitle_from_segments(segments, response_format)
+        }
+        _ if segments.iter().any(|segment| segment.speaker.is_some()) => {
+            build_speaker_transcript(segments)
+        }
+        _ => segments
+            .iter()
+            .map(|segment| segment.text.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    WhisperResult {
+        text,
+        duration_seconds: duration_seconds.or_else(|| segments.last().map(|segment| segment.end)),
+        language,
+    }
+}
+
 fn convert_verbose_json_to_subtitle(
     response_text: &str,
     target_format: WhisperResponseFormat,
@@ -194,6 +323,7 @@ fn convert_verbose_json_to_subtitle(
             start: 0.0,
             end,
             text,
+            speaker: None,
         };
         build_subtitle_from_segments(&[fallback_segment], target_format)
     } else {
@@ -209,6 +339,36 @@ fn convert_verbose_json_to_subtitle(
     })
 }
 
+/// Parse a verbose_json response into timed segments
+pub(crate) fn parse_verbose_transcript(
+    response_text: &str,
+) -> Result<WhisperTranscript, WhisperError> {
+    let verbose: WhisperVerboseResponse =
+        serde_json::from_str(response_text).map_err(|e| WhisperError::ParseError(e.to_string()))?;
+
+    let segments = match (verbose.segments, verbose.text) {
+        (Some(segments), _) if !segments.is_empty() => segments,
+        // Fallback when provider returns verbose_json without segments.
+        (_, Some(text)) => vec![WhisperVerboseSegment {
+            start: 0.0,
+            end: verbose.duration.unwrap_or(5.0).max(1.0),
+            text,
+            speaker: None,
+        }],
+        _ => {
+            return Err(WhisperError::ParseError(
+                "No text or segments in Whisper verbose_json response".to_string(),
+            ))
+        }
+    };
+
+    Ok(WhisperTranscript {
+        segments,
+        duration_seconds: verbose.duration,
+        language: verbose.language,
+    })
+}
+
 /// Transcribe audio file using OpenAI Whisper API
 ///
 /// # Arguments
@@ -227,6 +387,114 @@ pub async fn transcribe_audio(
     endpoint_url: Option<&str>,
     model: Option<&str>,
 ) -> Result<WhisperResult, WhisperError> {
+    let requested_format = response_format.clone();
+    let api_response_format = match requested_format {
+        WhisperResponseFormat::Srt | WhisperResponseFormat::Vtt => {
+            WhisperResponseFormat::VerboseJson
+        }
+        _ => requested_format.clone(),
+    };
+
+    let response_text = send_transcription_request(
+        api_key,
+        audio_path,
+        &api_response_format,
+        language,
+        endpoint_url,
+        model,
+        false,
+    )
+    .await?;
+
+    // Parse response based on requested format
+    match requested_format {
+        WhisperResponseFormat::Text | WhisperResponseFormat::Srt | WhisperResponseFormat::Vtt => {
+            if matches!(api_response_format, WhisperResponseFormat::VerboseJson) {
+                return convert_verbose_json_to_subtitle(&response_text, requested_format);
+            }
+            // Text format returns plain text
+            Ok(WhisperResult {
+                text: response_text,
+                duration_seconds: None,
+                language: None,
+            })
+        }
+        WhisperResponseFormat::Json => {
+            // JSON format: {"text": "..."}
+            let json: serde_json::Value = serde_json::from_str(&response_text)
+                .map_err(|e| WhisperError::ParseError(e.to_string()))?;
+
+            let text = json
+                .get("text")
+                .and_then(|t| t.as_str())
+                .ok_or_else(|| WhisperError::ParseError("No text in response".to_string()))?;
+
+            Ok(WhisperResult {
+                text: text.to_string(),
+                duration_seconds: None,
+                language: None,
+            })
+        }
+        WhisperResponseFormat::VerboseJson => {
+            // Verbose JSON includes duration and language
+            let json: serde_json::Value = serde_json::from_str(&response_text)
+                .map_err(|e| WhisperError::ParseError(e.to_string()))?;
+
+            let text = json
+                .get("text")
+                .and_then(|t| t.as_str())
+                .ok_or_else(|| WhisperError::ParseError("No text in response".to_string()))?;
+
+            let duration = json.get("duration").and_then(|d| d.as_f64());
+            let language = json
+                .get("language")
+                .and_then(|l| l.as_str())
+                .map(|s| s.to_string());
+
+            Ok(WhisperResult {
+                text: text.to_string(),
+                duration_seconds: duration,
+                language,
+            })
+        }
+    }
+}
+
+/// Transcribe audio file using OpenAI Whisper API and keep segment timestamps
+///
+/// With `diarize`, the endpoint is asked for `diarized_json`, whose segments carry
+/// speaker labels. The model must support diarization (e.g. `gpt-4o-transcribe-diarize`).
+pub async fn transcribe_audio_segments(
+    api_key: &str,
+    audio_path: &str,
+    language: Option<&str>,
+    endpoint_url: Option<&str>,
+    model: Option<&str>,
+    diarize: bool,
+) -> Result<WhisperTranscript, WhisperError> {
+    let response_text = send_transcription_request(
+        api_key,
+        audio_path,
+        &WhisperResponseFormat::VerboseJson,
+        language,
+        endpoint_url,
+        model,
+        diarize,
+    )
+    .await?;
+    parse_verbose_transcript(&response_text)
+}
+
+/// Upload one audio file and return the raw response body
+async fn send_transcription_request(
+    api_key: &str,
+    audio_path: &str,
+    api_response_format: &WhisperResponseFormat,
+    language: Option<&str>,
+    endpoint_url: Option<&str>,
+    model: Option<&str>,
+    diarize: bool,
+) -> Result<String, WhisperError> {
     let path = Path::new(audio_path);
 
     // Validate file exists
@@ -285,23 +553,22 @@ pub async fn transcribe_audio(
 
     let model_name = model.unwrap_or("whisper-1");
 
-    let requested_format = response_format.clone();
-    let api_response_format = match requested_format {
-        WhisperResponseFormat::Srt | WhisperResponseFormat::Vtt => {
-            WhisperResponseFormat::VerboseJson
-        }
-        _ => requested_format.clone(),
-    };
-
     let mut form = Form::new()
         .part("file", file_part)
-        .text("model", model_name.to_string())
-        .text("response_format", api_response_format.to_string());
+        .text("model", model_name.to_string());
 
-    // Needed so providers that support only json/text/verbose_json still return
-    // enough timing data for local SRT/VTT conversion.
-    if matches!(api_response_format, WhisperResponseFormat::VerboseJson) {
-        form = form.text("timestamp_granularities[]", "segment");
+    if diarize {
+        // Diarized output has the verbose_json segment layout plus a speaker per segment.
+        form = form
+            .text("response_format", "diarized_json")
+            .text("chunking_strategy", "auto");
+    } else {
+        form = form.text("response_format", api_response_format.to_string());
+        // Needed so providers that support only json/text/verbose_json still return
+        // enough timing data for local SRT/VTT conversion.
+        if matches!(api_response_format, WhisperResponseFormat::VerboseJson) {
+            form = form.text("timestamp_granularities[]", "segment");
+        }
     }
 
     // Add language hint if provided
@@ -349,57 +616,96 @@ pub async fn transcribe_audio(
         )));
     }
 
-    // Parse response based on requested format
-    match requested_format {
-        WhisperResponseFormat::Text | WhisperResponseFormat::Srt | WhisperResponseFormat::Vtt => {
-            if matches!(api_response_format, WhisperResponseFormat::VerboseJson) {
-                return convert_verbose_json_to_subtitle(&response_text, requested_format);
-            }
-            // Text format returns plain text
-            Ok(WhisperResult {
-                text: response_text,
-                duration_seconds: None,
-                language: None,
-            })
-        }
-        WhisperResponseFormat::Json => {
-            // JSON format: {"text": "..."}
-            let json: serde_json::Value = serde_json::from_str(&response_text)
-                .map_err(|e| WhisperError::ParseError(e.to_string()))?;
+    Ok(response_text)
+}
 
-            let text = json
-                .get("text")
-                .and_then(|t| t.as_str())
-                .ok_or_else(|| WhisperError::ParseError("No text in response".to_string()))?;
+pub type TranscriptionFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<WhisperResult, WhisperError>> + Send + 'a>>;
+
+pub type TranscriptFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<WhisperTranscript, WhisperError>> + Send + 'a>>;
+
+/// A speech-to-text engine used by the Whisper commands
+pub trait TranscriptionBackend: Send + Sync {
+    /// Short name used in logs
+    fn label(&self) -> String;
+
+    /// Whether input must be extracted to a compact MP3 under the upload limit first
+    fn requires_compressed_audio(&self) -> bool;
+
+    fn transcribe<'a>(
+        &'a self,
+        audio_path: &'a str,
+        response_format: WhisperResponseFormat,
+        language: Option<&'a str>,
+    ) -> TranscriptionFuture<'a>;
+
+    /// Transcribe and keep segment timestamps
+    fn transcribe_segments<'a>(
+        &'a self,
+        audio_path: &'a str,
+        language: Option<&'a str>,
+    ) -> TranscriptFuture<'a>;
+}
 
-            Ok(WhisperResult {
-                text: text.to_string(),
-                duration_seconds: None,
-                language: None,
-            })
-        }
-        WhisperResponseFormat::VerboseJson => {
-            // Verbose JSON includes duration and language
-            let json: serde_json::Value = serde_json::from_str(&response_text)
-                .map_err(|e| WhisperError::ParseError(e.to_string()))?;
+/// OpenAI-compatible Whisper HTTP API
+pub struct OpenAiWhisperBackend {
+    pub api_key: String,
+    pub endpoint_url: Option<String>,
+    pub model: Option<String>,
+    /// Request speaker-labelled segments from a diarizing model
+    pub diarize: bool,
+}
 
-            let text = json
-                .get("text")
-                .and_then(|t| t.as_str())
-                .ok_or_else(|| WhisperError::ParseError("No text in response".to_string()))?;
+impl TranscriptionBackend for OpenAiWhisperBackend {
+    fn label(&self) -> String {
+        "Whisper API".to_string()
+    }
 
-            let duration = json.get("duration").and_then(|d| d.as_f64());
-            let language = json
-                .get("language")
-                .and_then(|l| l.as_str())
-                .map(|s| s.to_string());
+    fn requires_compressed_audio(&self) -> bool {
+        true
+    }
 
-            Ok(WhisperResult {
-                text: text.to_string(),
-                duration_seconds: duration,
-                language,
-            })
+    fn transcribe<'a>(
+        &'a self,
+        audio_path: &'a str,
+        response_format: WhisperResponseFormat,
+        language: Option<&'a str>,
+    ) -> TranscriptionFuture<'a> {
+        if self.diarize {
+            return Box::pin(async move {
+                let transcript = self.transcribe_segments(audio_path, language).await?;
+                Ok(whisper_result_from_segments(
+                    &transcript.segments,
+                    response_format,
+                    transcript.duration_seconds,
+                    transcript.language,
+                ))
+            });
         }
+        Box::pin(transcribe_audio(
+            &self.api_key,
+            audio_path,
+            response_format,
+            language,
+            self.endpoint_url.as_deref(),
+            self.model.as_deref(),
+        ))
+    }
+
+    fn transcribe_segments<'a>(
+        &'a self,
+        audio_path: &'a str,
+        language: Option<&'a str>,
+    ) -> TranscriptFuture<'a> {
+        Box::pin(transcribe_audio_segments(
+            &self.api_key,
+            audio_path,
+            language,
+            self.endpoint_url.as_deref(),
+            self.model.as_deref(),
+            self.diarize,
+        ))
     }
 }
 
@@ -569,4 +875,53 @@ mod tests {
         let err = WhisperError::UnsupportedFormat("xyz".to_string());
         assert!(err.to_string().contains("xyz"));
     }
+
+    #[test]
+    fn test_result_from_segments() {
+        let segments = vec![
+            WhisperVerboseSegment {
+                start: 0.0,
+                end: 1.5,
+                text: " Hello".to_string(),
+                speaker: None,
+            },
+            WhisperVerboseSegment {
+                start: 1.5,
+                end: 3.0,
+                text: "world ".to_string(),
+                speaker: None,
+            },
+        ];
+
+        let text = whisper_result_from_segments(&segments, WhisperResponseFormat::Text, None, None);
+        assert_eq!(text.text, "Hello world");
+        assert_eq!(text.duration_seconds, Some(3.0));
+
+        let srt = whisper_result_from_segments(&segments, WhisperResponseFormat::Srt, None, None);
+        assert!(srt
+            .text
+            .starts_with("1\n00:00:00,000 --> 00:00:01,500\nHello\n"));
+    }
+
+    #[test]
+    fn test_speaker_labels_in_outputs() {
+        let response = r#"{"text":"Hi. Hello. Bye.","segments":[
+            {"id":"seg_0","start":0.0,"end":1.0,"text":"Hi.","speaker":"A"},
+            {"id":"seg_1","start":1.0,"end":2.0,"text":"Hello.","speaker":"B"},
+            {"id":"seg_2","start":2.0,"end":3.0,"text":"Bye.","speaker":"B"}
+        ]}"#;
+        let transcript = parse_verbose_transcript(response).unwrap();
+        let segments = transcript.segments;
+
+        let text = whisper_result_from_segments(&segments, WhisperResponseFormat::Text, None, None);
+        assert_eq!(text.text, "Speaker A: Hi.\nSpeaker B: Hello. Bye.");
+
+        let srt = whisper_result_from_segments(&segments, WhisperResponseFormat::Srt, None, None);
+        assert!(srt.text.contains("00:00:01,000\n[Speaker A] Hi.\n"));
+        let vtt = whisper_result_from_segments(&segments, WhisperResponseFormat::Vtt, None, None);
+        assert!(vtt.text.contains("<v Speaker B>Hello."));
+
+        assert_eq!(speaker_display_name("SPEAKER_00"), "Speaker 1");
+        assert_eq!(speaker_display_name("Speaker 2"), "Speaker 2");
+    }
 }
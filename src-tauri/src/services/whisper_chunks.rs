@@ -0,0 +1,404 @@
+use futures_util::stream::{self, StreamExt};
+use std::path::Path;
+use std::time::Duration;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::utils::CommandExt;
+
+use super::whisper::{
+    TranscriptionBackend, WhisperError, WhisperTranscript, WhisperVerboseSegment,
+};
+
+/// Media longer than this is split into chunks before upload
+pub const CHUNKED_TRANSCRIPTION_THRESHOLD_SECS: f64 = 10.0 * 60.0;
+/// Chunks transcribed at the same time
+pub const DEFAULT_CHUNK_CONCURRENCY: usize = 3;
+
+const TARGET_CHUNK_SECS: f64 = 8.0 * 60.0;
+const MIN_CHUNK_SECS: f64 = 4.0 * 60.0;
+// 10 minutes of 64 kbps mono MP3 is about 4.8 MB, well under the 25 MB upload limit.
+const MAX_CHUNK_SECS: f64 = 10.0 * 60.0;
+const SILENCE_FILTER: &str = "silencedetect=noise=-35dB:d=0.4";
+const CHUNK_MAX_ATTEMPTS: u32 = 3;
+const CHUNK_RETRY_BASE_DELAY_MS: u64 = 1500;
+
+/// A time range of the source media transcribed as one request
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AudioChunk {
+    pub index: usize,
+    pub start: f64,
+    pub end: f64,
+}
+
+fn parse_timestamp(value: &str) -> Option<f64> {
+    let mut parts = value.trim().split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Read `Duration: HH:MM:SS.xx` from FFmpeg's input banner
+fn parse_ffmpeg_duration(stderr: &str) -> Option<f64> {
+    stderr.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("Duration:")?;
+        parse_timestamp(rest.split(',').next()?)
+    })
+}
+
+/// Collect `(start, end)` pairs from `silencedetect` log lines. Silence that runs into the
+/// end of the input has no `silence_end` line and is reported last with no end.
+pub fn parse_silences(stderr: &str) -> Vec<(f64, Option<f64>)> {
+    let mut silences = Vec::new();
+    let mut pending_start = None;
+    for line in stderr.lines() {
+        if let Some((_, rest)) = line.split_once("silence_start:") {
+            pending_start = rest.trim().parse::<f64>().ok();
+        } else if let Some((_, rest)) = line.split_once("silence_end:") {
+            let end = rest
+                .split('|')
+                .next()
+                .and_then(|value| value.trim().parse::<f64>().ok());
+            if let (Some(start), Some(end)) = (pending_start.take(), end) {
+                silences.push((start.max(0.0), Some(end)));
+            }
+        }
+    }
+    if let Some(start) = pending_start {
+        silences.push((start.max(0.0), None));
+    }
+    silences
+}
+
+/// Split `duration` into chunks, cutting in the middle of a silence near the target length.
+///
+/// A chunk is never shorter than `MIN_CHUNK_SECS` (except the last) or longer than
+/// `MAX_CHUNK_SECS`. Without a usable silence the cut falls at `MAX_CHUNK_SECS`.
+pub fn plan_audio_chunks(duration: f64, silences: &[(f64, f64)]) -> Vec<AudioChunk> {
+    let mut chunks = Vec::new();
+    let mut cursor = 0.0;
+    while duration - cursor > MAX_CHUNK_SECS {
+        let target = cursor + TARGET_CHUNK_SECS;
+        let cut = silences
+            .iter()
+            .map(|(start, end)| (start + end) / 2.0)
+            .filter(|mid| *mid >= cursor + MIN_CHUNK_SECS && *mid <= cursor + MAX_CHUNK_SECS)
+            .min_by(|a, b| (a - target).abs().total_cmp(&(b - target).abs()))
+            .unwrap_or(cursor + MAX_CHUNK_SECS);
+        chunks.push(AudioChunk {
+            index: chunks.len(),
+            start: cursor,
+            end: cut,
+        });
+        cursor = cut;
+    }
+    chunks.push(AudioChunk {
+        index: chunks.len(),
+        start: cursor,
+        end: duration,
+    });
+    chunks
+}
+
+/// Shift each chunk's segments by its start time and merge them into one timeline.
+///
+/// Segments are clamped to their chunk so overlapping hallucinated tails at a cut don't
+/// produce out-of-order cues. Diarized speaker labels come from each chunk separately, so
+/// the same label is not guaranteed to name the same person in different chunks.
+pub fn stitch_chunk_transcripts(
+    results: &[(AudioChunk, WhisperTranscript)],
+) -> Vec<WhisperVerboseSegment> {
+    let mut ordered = results.iter().collect::<Vec<_>>();
+    ordered.sort_by_key(|(chunk, _)| chunk.index);
+
+    let mut segments = Vec::new();
+    for (chunk, transcript) in ordered {
+        for segment in &transcript.segments {
+            let start = chunk.start + segment.start.max(0.0);
+            if start >= chunk.end || segment.text.trim().is_empty() {
+                continue;
+            }
+            let end = (chunk.start + segment.end).min(chunk.end).max(start);
+            segments.push(WhisperVerboseSegment {
+                start,
+                end,
+                text: segment.text.clone(),
+                speaker: segment.speaker.clone(),
+            });
+        }
+    }
+    segments
+}
+
+async fn run_ffmpeg(ffmpeg: &str, args: &[&str]) -> Result<std::process::Output, WhisperError> {
+    let mut cmd = Command::new(ffmpeg);
+    cmd.args(args);
+    cmd.hide_window();
+    cmd.output()
+        .await
+        .map_err(|e| WhisperError::FfmpegError(format!("Failed to run FFmpeg: {}", e)))
+}
+
+/// Get media duration in seconds from FFmpeg's input banner
+pub async fn probe_media_duration(
+    input_path: &str,
+    ffmpeg_path: Option<&str>,
+) -> Result<f64, WhisperError> {
+    // FFmpeg exits non-zero without an output file but still prints the input banner.
+    let output = run_ffmpeg(
+        ffmpeg_path.unwrap_or("ffmpeg"),
+        &["-hide_banner", "-i", input_path],
+    )
+    .await?;
+    parse_ffmpeg_duration(&String::from_utf8_lossy(&output.stderr))
+        .ok_or_else(|| WhisperError::ParseError(format!("No duration found for {}", input_path)))
+}
+
+async fn detect_silences(
+    input_path: &str,
+    ffmpeg_path: &str,
+) -> Result<Vec<(f64, f64)>, WhisperError> {
+    let output = run_ffmpeg(
+        ffmpeg_path,
+        &[
+            "-hide_banner",
+            "-nostats",
+            "-i",
+            input_path,
+            "-vn",
+            "-af",
+            SILENCE_FILTER,
+            "-f",
+            "null",
+            "-",
+        ],
+    )
+    .await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(WhisperError::FfmpegError(format!(
+            "Silence detection failed: {}",
+            stderr.lines().last().unwrap_or_default()
+        )));
+    }
+    Ok(parse_silences(&String::from_utf8_lossy(&output.stderr))
+        .into_iter()
+        .filter_map(|(start, end)| Some((start, end?)))
+        .collect())
+}
+
+async fn extract_audio_chunk(
+    input_path: &str,
+    ffmpeg_path: &str,
+    chunk: &AudioChunk,
+    output_path: &str,
+) -> Result<(), WhisperError> {
+    let start = format!("{:.3}", chunk.start);
+    let length = format!("{:.3}", chunk.end - chunk.start);
+    let output = run_ffmpeg(
+        ffmpeg_path,
+        &[
+            "-hide_banner",
+            "-ss",
+            &start,
+            "-t",
+            &length,
+            "-i",
+            input_path,
+            "-vn",
+            "-acodec",
+            "libmp3lame",
+            "-ac",
+            "1",
+            "-b:a",
+            "64k",
+            "-y",
+            output_path,
+        ],
+    )
+    .await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(WhisperError::FfmpegError(format!(
+            "FFmpeg failed on chunk {}: {}",
+            chunk.index + 1,
+            stderr.lines().last().unwrap_or_default()
+        )));
+    }
+    Ok(())
+}
+
+fn is_retryable_chunk_error(error: &WhisperError) -> bool {
+    matches!(
+        error,
+        WhisperError::NetworkError(_) | WhisperError::ApiError(_)
+    )
+}
+
+async fn transcribe_chunk_with_retry(
+    backend: &dyn TranscriptionBackend,
+    audio_path: &str,
+    language: Option<&str>,
+) -> Result<WhisperTranscript, WhisperError> {
+    let mut attempt = 1;
+    loop {
+        match backend.transcribe_segments(audio_path, language).await {
+            Ok(transcript) => return Ok(transcript),
+            Err(error) if attempt < CHUNK_MAX_ATTEMPTS && is_retryable_chunk_error(&error) => {
+                let delay = CHUNK_RETRY_BASE_DELAY_MS * u64::from(attempt);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                attempt += 1;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+async fn transcribe_chunks_in_dir(
+    backend: &dyn TranscriptionBackend,
+    input_path: &str,
+    ffmpeg_path: &str,
+    chunks: &[AudioChunk],
+    language: Option<&str>,
+    concurrency: usize,
+    work_dir: &Path,
+) -> Result<Vec<(AudioChunk, WhisperTranscript)>, WhisperError> {
+    stream::iter(chunks.iter().copied().map(|chunk| async move {
+        let chunk_path = work_dir
+            .join(format!("chunk_{:03}.mp3", chunk.index))
+            .to_string_lossy()
+            .to_string();
+        extract_audio_chunk(input_path, ffmpeg_path, &chunk, &chunk_path).await?;
+        let transcript = transcribe_chunk_with_retry(backend, &chunk_path, language).await?;
+        tokio::fs::remove_file(&chunk_path).await.ok();
+        Ok((chunk, transcript))
+    }))
+    .buffer_unordered(concurrency.max(1))
+    .collect::<Vec<Result<_, WhisperError>>>()
+    .await
+    .into_iter()
+    .collect()
+}
+
+/// Transcribe long media by splitting it at silences and stitching the segments back together
+pub async fn transcribe_in_chunks(
+    backend: &dyn TranscriptionBackend,
+    input_path: &str,
+    ffmpeg_path: Option<&str>,
+    duration: f64,
+    language: Option<&str>,
+    concurrency: usize,
+) -> Result<WhisperTranscript, WhisperError> {
+    let ffmpeg = ffmpeg_path.unwrap_or("ffmpeg");
+    // Silence detection only improves cut points, so fixed-length cuts are an acceptable fallback.
+    let silences = detect_silences(input_path, ffmpeg)
+        .await
+        .unwrap_or_default();
+    let chunks = plan_audio_chunks(duration, &silences);
+
+    let work_dir = std::env::temp_dir().join(format!("youwee_whisper_chunks_{}", Uuid::new_v4()));
+    tokio::fs::create_dir_all(&work_dir)
+        .await
+        .map_err(|e| WhisperError::FfmpegError(format!("Failed to create temp dir: {}", e)))?;
+    let results = transcribe_chunks_in_dir(
+        backend,
+        input_path,
+        ffmpeg,
+        &chunks,
+        language,
+        concurrency,
+        &work_dir,
+    )
+    .await;
+    tokio::fs::remove_dir_all(&work_dir).await.ok();
+    let results = results?;
+
+    let detected_language = results
+        .iter()
+        .min_by_key(|(chunk, _)| chunk.index)
+        .and_then(|(_, transcript)| transcript.language.clone());
+    Ok(WhisperTranscript {
+        segments: stitch_chunk_transcripts(&results),
+        duration_seconds: Some(duration),
+        language: detected_language.or_else(|| language.map(str::to_string)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_duration_and_silences_from_ffmpeg_output() {
+        let stderr = "Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'video.mp4':\n  Duration: 01:02:03.50, start: 0.000000, bitrate: 1000 kb/s\n[silencedetect @ 0x1] silence_start: 12.5\n[silencedetect @ 0x1] silence_end: 13.7 | silence_duration: 1.2\n[silencedetect @ 0x1] silence_start: 40\n";
+        assert_eq!(parse_ffmpeg_duration(stderr), Some(3723.5));
+        assert_eq!(
+            parse_silences(stderr),
+            vec![(12.5, Some(13.7)), (40.0, None)]
+        );
+    }
+
+    #[test]
+    fn plans_chunks_at_silences_near_target_length() {
+        let silences = vec![(100.0, 101.0), (470.0, 490.0), (900.0, 902.0)];
+        let chunks = plan_audio_chunks(1500.0, &silences);
+        assert_eq!(chunks[0].start, 0.0);
+        assert_eq!(chunks[0].end, 480.0);
+        assert_eq!(chunks[1].start, 480.0);
+        assert_eq!(chunks[1].end, 901.0);
+        assert_eq!(chunks.last().unwrap().end, 1500.0);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.end - chunk.start <= MAX_CHUNK_SECS));
+    }
+
+    #[test]
+    fn plans_fixed_cuts_without_silences() {
+        let chunks = plan_audio_chunks(1300.0, &[]);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[1].start, MAX_CHUNK_SECS);
+        assert_eq!(plan_audio_chunks(300.0, &[]).len(), 1);
+    }
+
+    #[test]
+    fn stitches_segments_with_chunk_offsets_in_order() {
+        let first = AudioChunk {
+            index: 0,
+            start: 0.0,
+            end: 480.0,
+        };
+        let second = AudioChunk {
+            index: 1,
+            start: 480.0,
+            end: 900.0,
+        };
+        let transcript = |segments: Vec<(f64, f64, &str)>| WhisperTranscript {
+            segments: segments
+                .into_iter()
+                .map(|(start, end, text)| WhisperVerboseSegment {
+                    start,
+                    end,
+                    text: text.to_string(),
+                    speaker: None,
+                })
+                .collect(),
+            duration_seconds: None,
+            language: Some("en".to_string()),
+        };
+        let results = vec![
+            (second, transcript(vec![(1.0, 3.0, "second")])),
+            (
+                first,
+                transcript(vec![(0.0, 2.0, "first"), (478.0, 485.0, "tail")]),
+            ),
+        ];
+
+        let segments = stitch_chunk_transcripts(&results);
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].text, "first");
+        assert_eq!(segments[1].end, 480.0);
+        assert_eq!(segments[2].start, 481.0);
+        assert_eq!(segments[2].end, 483.0);
+    }
+}
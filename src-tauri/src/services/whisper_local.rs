@@ -0,0 +1,664 @@
+use crate::types::{LocalWhisperEngine, LocalWhisperModelInfo, LocalWhisperStatus};
+use crate::utils::{find_system_binary, unix_system_binary_dirs, CommandExt};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tauri::{AppHandle, Manager};
+use tokio::process::Command;
+use uuid::Uuid;
+
+use super::ffmpeg::get_ffmpeg_path;
+use super::whisper::{
+    whisper_result_from_segments, TranscriptFuture, TranscriptionBackend, TranscriptionFuture,
+    WhisperError, WhisperResponseFormat, WhisperTranscript, WhisperVerboseResponse,
+    WhisperVerboseSegment,
+};
+
+/// Default model for new local Whisper setups
+pub const DEFAULT_LOCAL_WHISPER_MODEL: &str = "base";
+
+/// whisper.cpp ggml models offered for download, smallest first
+pub const LOCAL_WHISPER_MODELS: &[&str] = &[
+    "tiny",
+    "tiny.en",
+    "base",
+    "base.en",
+    "small",
+    "small.en",
+    "medium",
+    "medium.en",
+    "large-v3-turbo",
+    "large-v3",
+];
+
+const MODELS_DIR_NAME: &str = "whisper-models";
+// faster-whisper-xxl bundles pyannote; other faster-whisper CLIs have no diarize flag.
+const LOCAL_DIARIZATION_METHOD: &str = "pyannote_v3.1";
+const LOCAL_DIARIZATION_BINARY_HINT: &str = "faster-whisper-xxl";
+const MODEL_DOWNLOAD_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+const MODEL_TREE_API_URL: &str =
+    "https://huggingface.co/api/models/ggerganov/whisper.cpp/tree/main";
+
+fn engine_binary_names(engine: LocalWhisperEngine) -> &'static [&'static str] {
+    match engine {
+        LocalWhisperEngine::WhisperCpp => &["whisper-cli", "whisper-cpp"],
+        LocalWhisperEngine::FasterWhisper => &[
+            "faster-whisper-xxl",
+            "whisper-ctranslate2",
+            "faster-whisper",
+        ],
+    }
+}
+
+fn binary_file_name(name: &str) -> String {
+    #[cfg(windows)]
+    {
+        format!("{}.exe", name)
+    }
+    #[cfg(not(windows))]
+    {
+        name.to_string()
+    }
+}
+
+fn get_app_local_whisper_path(app: &AppHandle, engine: LocalWhisperEngine) -> Option<PathBuf> {
+    let bin_dir = app.path().app_data_dir().ok()?.join("bin");
+    engine_binary_names(engine)
+        .iter()
+        .map(|name| bin_dir.join(binary_file_name(name)))
+        .find(|path| path.exists())
+}
+
+fn get_system_local_whisper_path(engine: LocalWhisperEngine) -> Option<PathBuf> {
+    let fallback_dirs = unix_system_binary_dirs();
+    engine_binary_names(engine)
+        .iter()
+        .find_map(|name| find_system_binary(&binary_file_name(name), &fallback_dirs))
+}
+
+/// Get the local Whisper binary path (custom path, app data, or system)
+pub fn get_local_whisper_path(
+    app: &AppHandle,
+    engine: LocalWhisperEngine,
+    custom_path: Option<&str>,
+) -> Option<PathBuf> {
+    if let Some(custom) = custom_path.map(str::trim).filter(|value| !value.is_empty()) {
+        let path = PathBuf::from(custom);
+        return path.is_file().then_some(path);
+    }
+    get_app_local_whisper_path(app, engine).or_else(|| get_system_local_whisper_path(engine))
+}
+
+/// Parse a version number from `--version` or `--help` output
+pub fn parse_local_whisper_version(output: &str) -> Option<String> {
+    let labelled = regex::Regex::new(r"(?i)version[:\s]+v?(\d+(?:\.\d+)+)").ok()?;
+    if let Some(caps) = labelled.captures(output) {
+        return Some(caps[1].to_string());
+    }
+    let bare = regex::Regex::new(r"^v?(\d+(?:\.\d+)+)$").ok()?;
+    output
+        .lines()
+        .map(str::trim)
+        .find_map(|line| bare.captures(line).map(|caps| caps[1].to_string()))
+}
+
+/// Check local Whisper engine status
+pub async fn check_local_whisper_internal(
+    app: &AppHandle,
+    engine: LocalWhisperEngine,
+    custom_path: Option<&str>,
+) -> Result<LocalWhisperStatus, String> {
+    if let Some(binary_path) = get_local_whisper_path(app, engine, custom_path) {
+        let mut cmd = Command::new(&binary_path);
+        cmd.arg("--version")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        cmd.hide_window();
+
+        // whisper.cpp has no --version flag and exits non-zero, so any run that starts counts.
+        if let Ok(output) = cmd.output().await {
+            let combined = format!(
+                "{}\n{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let is_system = custom_path.map(str::trim).unwrap_or("").is_empty()
+                && get_app_local_whisper_path(app, engine).as_ref() != Some(&binary_path);
+            return Ok(LocalWhisperStatus {
+                engine,
+                installed: true,
+                version: parse_local_whisper_version(&combined),
+                binary_path: Some(binary_path.to_string_lossy().to_string()),
+                is_system,
+            });
+        }
+    }
+
+    Ok(LocalWhisperStatus {
+        engine,
+        installed: false,
+        version: None,
+        binary_path: None,
+        is_system: false,
+    })
+}
+
+fn validate_local_whisper_model(model: &str) -> Result<(), String> {
+    if LOCAL_WHISPER_MODELS.contains(&model) {
+        Ok(())
+    } else {
+        Err(format!("Unknown Whisper model: {}", model))
+    }
+}
+
+fn ggml_model_file_name(model: &str) -> String {
+    format!("ggml-{}.bin", model)
+}
+
+fn get_local_whisper_models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(MODELS_DIR_NAME))
+}
+
+/// Path of a whisper.cpp model file, whether or not it has been downloaded
+pub fn get_local_whisper_model_path(app: &AppHandle, model: &str) -> Result<PathBuf, String> {
+    validate_local_whisper_model(model)?;
+    Ok(get_local_whisper_models_dir(app)?.join(ggml_model_file_name(model)))
+}
+
+/// List whisper.cpp models and whether each one is on disk
+pub fn list_local_whisper_models_internal(
+    app: &AppHandle,
+) -> Result<Vec<LocalWhisperModelInfo>, String> {
+    let models_dir = get_local_whisper_models_dir(app)?;
+    Ok(LOCAL_WHISPER_MODELS
+        .iter()
+        .map(|name| {
+            let path = models_dir.join(ggml_model_file_name(name));
+            let size_bytes = std::fs::metadata(&path).ok().map(|meta| meta.len());
+            LocalWhisperModelInfo {
+                name: name.to_string(),
+                downloaded: size_bytes.is_some(),
+                path: size_bytes.map(|_| path.to_string_lossy().to_string()),
+                size_bytes,
+            }
+        })
+        .collect())
+}
+
+/// Download info for a whisper.cpp model with its expected SHA256 and size.
+///
+/// The checksum is read from the same Hugging Face repository as the model, so it is an
+/// integrity check that catches truncated or corrupted downloads. It does not protect
+/// against a compromised repository.
+pub struct LocalWhisperModelDownload {
+    pub url: String,
+    pub sha256: String,
+    pub size: u64,
+    pub path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct HuggingFaceTreeLfs {
+    oid: String,
+    size: u64,
+}
+
+#[derive(Deserialize)]
+struct HuggingFaceTreeEntry {
+    path: String,
+    lfs: Option<HuggingFaceTreeLfs>,
+}
+
+/// Find the LFS SHA256 and size for a model file in a Hugging Face tree listing
+fn find_model_checksum(tree_json: &str, file_name: &str) -> Option<(String, u64)> {
+    let entries: Vec<HuggingFaceTreeEntry> = serde_json::from_str(tree_json).ok()?;
+    entries
+        .into_iter()
+        .find(|entry| entry.path == file_name)
+        .and_then(|entry| entry.lfs)
+        .map(|lfs| (lfs.oid, lfs.size))
+}
+
+/// Resolve where to download a model from and the integrity checksum it must match
+pub async fn get_local_whisper_model_download(
+    app: &AppHandle,
+    model: &str,
+) -> Result<LocalWhisperModelDownload, String> {
+    let path = get_local_whisper_model_path(app, model)?;
+    let file_name = ggml_model_file_name(model);
+
+    let client = reqwest::Client::builder()
+        .user_agent(format!("Youwee/{}", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(MODEL_TREE_API_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch model checksums: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch model checksums: HTTP {}",
+            response.status()
+        ));
+    }
+    let tree_json = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read model checksums: {}", e))?;
+    let (sha256, size) = find_model_checksum(&tree_json, &file_name)
+        .ok_or_else(|| format!("Checksum not found for {}", file_name))?;
+
+    Ok(LocalWhisperModelDownload {
+        url: format!("{}/{}", MODEL_DOWNLOAD_BASE_URL, file_name),
+        sha256,
+        size,
+        path,
+    })
+}
+
+#[derive(Deserialize)]
+struct WhisperCppOffsets {
+    from: u64,
+    to: u64,
+}
+
+#[derive(Deserialize)]
+struct WhisperCppSegment {
+    offsets: WhisperCppOffsets,
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct WhisperCppResultInfo {
+    language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WhisperCppOutput {
+    result: Option<WhisperCppResultInfo>,
+    #[serde(default)]
+    transcription: Vec<WhisperCppSegment>,
+}
+
+/// Parse the `-oj` JSON written by whisper.cpp into timed segments
+fn parse_whisper_cpp_json(
+    content: &str,
+) -> Result<(Vec<WhisperVerboseSegment>, Option<String>), WhisperError> {
+    let output: WhisperCppOutput =
+        serde_json::from_str(content).map_err(|e| WhisperError::ParseError(e.to_string()))?;
+    let segments = output
+        .transcription
+        .into_iter()
+        .map(|segment| WhisperVerboseSegment {
+            start: segment.offsets.from as f64 / 1000.0,
+            end: segment.offsets.to as f64 / 1000.0,
+            text: segment.text,
+            speaker: None,
+        })
+        .collect();
+    Ok((segments, output.result.and_then(|result| result.language)))
+}
+
+/// Split a `[SPEAKER_00]: text` prefix written by faster-whisper-xxl's diarization
+fn split_speaker_prefix(text: &str) -> Option<(String, String)> {
+    let rest = text.trim_start().strip_prefix('[')?;
+    let (speaker, rest) = rest.split_once(']')?;
+    if !speaker.starts_with("SPEAKER_") {
+        return None;
+    }
+    let rest = rest.strip_prefix(':').unwrap_or(rest);
+    Some((speaker.to_string(), rest.trim().to_string()))
+}
+
+/// Parse the JSON written by faster-whisper CLIs, which follows the openai-whisper layout
+fn parse_faster_whisper_json(
+    content: &str,
+) -> Result<(Vec<WhisperVerboseSegment>, Option<String>), WhisperError> {
+    let output: WhisperVerboseResponse =
+        serde_json::from_str(content).map_err(|e| WhisperError::ParseError(e.to_string()))?;
+    let segments = output
+        .segments
+        .unwrap_or_default()
+        .into_iter()
+        .map(|mut segment| {
+            if segment.speaker.is_none() {
+                if let Some((speaker, text)) = split_speaker_prefix(&segment.text) {
+                    segment.speaker = Some(speaker);
+                    segment.text = text;
+                }
+            }
+            segment
+        })
+        .collect();
+    Ok((segments, output.language))
+}
+
+fn tail_of(text: &str, max_chars: usize) -> String {
+    let trimmed = text.trim();
+    let count = trimmed.chars().count();
+    trimmed
+        .chars()
+        .skip(count.saturating_sub(max_chars))
+        .collect()
+}
+
+/// Offline transcription through a locally installed whisper.cpp or faster-whisper binary
+pub struct LocalWhisperBackend {
+    pub engine: LocalWhisperEngine,
+    pub binary_path: PathBuf,
+    pub model: String,
+    /// ggml model file, required for whisper.cpp
+    pub model_path: Option<PathBuf>,
+    pub ffmpeg_path: Option<String>,
+    /// Run faster-whisper-xxl's pyannote diarization
+    pub diarize: bool,
+}
+
+impl LocalWhisperBackend {
+    async fn convert_to_wav(
+        &self,
+        input_path: &str,
+        output_path: &Path,
+    ) -> Result<(), WhisperError> {
+        let mut cmd = Command::new(self.ffmpeg_path.as_deref().unwrap_or("ffmpeg"));
+        cmd.args([
+            "-i",
+            input_path,
+            "-vn",
+            "-ac",
+            "1",
+            "-ar",
+            "16000",
+            "-c:a",
+            "pcm_s16le",
+            "-y",
+        ])
+        .arg(output_path);
+        cmd.hide_window();
+        let output = cmd
+            .output()
+            .await
+            .map_err(|e| WhisperError::FfmpegError(format!("Failed to run FFmpeg: {}", e)))?;
+        if !output.status.success() {
+            return Err(WhisperError::FfmpegError(format!(
+                "FFmpeg failed: {}",
+                tail_of(&String::from_utf8_lossy(&output.stderr), 500)
+            )));
+        }
+        Ok(())
+    }
+
+    fn engine_command(&self, wav_path: &Path, work_dir: &Path, language: Option<&str>) -> Command {
+        let mut cmd = Command::new(&self.binary_path);
+        match self.engine {
+            LocalWhisperEngine::WhisperCpp => {
+                cmd.arg("-m")
+                    .arg(self.model_path.as_deref().unwrap_or(Path::new("")))
+                    .arg("-f")
+                    .arg(wav_path)
+                    .args(["-l", language.unwrap_or("auto"), "-oj", "-np", "-of"])
+                    .arg(work_dir.join("transcript"));
+            }
+            LocalWhisperEngine::FasterWhisper => {
+                cmd.arg(wav_path)
+                    .args(["--model", &self.model, "--output_format", "json"])
+                    .arg("--output_dir")
+                    .arg(work_dir);
+                if let Some(language) = language {
+                    cmd.args(["--language", language]);
+                }
+                if self.diarize {
+                    cmd.args(["--diarize", LOCAL_DIARIZATION_METHOD]);
+                }
+            }
+        }
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        cmd.hide_window();
+        cmd
+    }
+
+    async fn run_in_dir(
+        &self,
+        audio_path: &str,
+        language: Option<&str>,
+        work_dir: &Path,
+    ) -> Result<WhisperTranscript, WhisperError> {
+        let wav_path = work_dir.join("audio.wav");
+        self.convert_to_wav(audio_path, &wav_path).await?;
+
+        let output = self
+            .engine_command(&wav_path, work_dir, language)
+            .output()
+            .await
+            .map_err(|e| {
+                WhisperError::LocalEngineError(format!(
+                    "Failed to run {}: {}",
+                    self.engine.display_name(),
+                    e
+                ))
+            })?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(WhisperError::LocalEngineError(format!(
+                "{} failed: {}",
+                self.engine.display_name(),
+                tail_of(&stderr, 500)
+            )));
+        }
+
+        let json_path = match self.engine {
+            LocalWhisperEngine::WhisperCpp => work_dir.join("transcript.json"),
+            LocalWhisperEngine::FasterWhisper => work_dir.join("audio.json"),
+        };
+        let content = tokio::fs::read_to_string(&json_path).await.map_err(|e| {
+            WhisperError::LocalEngineError(format!(
+                "{} did not write a transcript: {}",
+                self.engine.display_name(),
+                e
+            ))
+        })?;
+        let (segments, detected_language) = match self.engine {
+            LocalWhisperEngine::WhisperCpp => parse_whisper_cpp_json(&content)?,
+            LocalWhisperEngine::FasterWhisper => parse_faster_whisper_json(&content)?,
+        };
+
+        Ok(WhisperTranscript {
+            segments,
+            duration_seconds: None,
+            language: detected_language.or_else(|| language.map(str::to_string)),
+        })
+    }
+
+    async fn run(
+        &self,
+        audio_path: &str,
+        language: Option<&str>,
+    ) -> Result<WhisperTranscript, WhisperError> {
+        if !Path::new(audio_path).exists() {
+            return Err(WhisperError::FileNotFound(audio_path.to_string()));
+        }
+        let work_dir =
+            std::env::temp_dir().join(format!("youwee_whisper_local_{}", Uuid::new_v4()));
+        tokio::fs::create_dir_all(&work_dir).await.map_err(|e| {
+            WhisperError::LocalEngineError(format!("Failed to create temp dir: {}", e))
+        })?;
+        let result = self.run_in_dir(audio_path, language, &work_dir).await;
+        tokio::fs::remove_dir_all(&work_dir).await.ok();
+        result
+    }
+}
+
+impl TranscriptionBackend for LocalWhisperBackend {
+    fn label(&self) -> String {
+        format!("{} ({})", self.engine.display_name(), self.model)
+    }
+
+    fn requires_compressed_audio(&self) -> bool {
+        false
+    }
+
+    fn transcribe<'a>(
+        &'a self,
+        audio_path: &'a str,
+        response_format: WhisperResponseFormat,
+        language: Option<&'a str>,
+    ) -> TranscriptionFuture<'a> {
+        Box::pin(async move {
+            let transcript = self.run(audio_path, language).await?;
+            Ok(whisper_result_from_segments(
+                &transcript.segments,
+                response_format,
+                transcript.duration_seconds,
+                transcript.language,
+            ))
+        })
+    }
+
+    fn transcribe_segments<'a>(
+        &'a self,
+        audio_path: &'a str,
+        language: Option<&'a str>,
+    ) -> TranscriptFuture<'a> {
+        Box::pin(self.run(audio_path, language))
+    }
+}
+
+/// Locate the engine binary and model so a local transcription can start
+pub async fn build_local_whisper_backend(
+    app: &AppHandle,
+    engine: LocalWhisperEngine,
+    model: Option<&str>,
+    custom_path: Option<&str>,
+    diarize: bool,
+) -> Result<LocalWhisperBackend, WhisperError> {
+    let binary_path = get_local_whisper_path(app, engine, custom_path)
+        .ok_or_else(|| WhisperError::LocalEngineNotFound(engine.display_name().to_string()))?;
+    let supports_diarization = engine == LocalWhisperEngine::FasterWhisper
+        && binary_path
+            .file_name()
+            .map(|name| {
+                name.to_string_lossy()
+                    .to_lowercase()
+                    .contains(LOCAL_DIARIZATION_BINARY_HINT)
+            })
+            .unwrap_or(false);
+    if diarize && !supports_diarization {
+        return Err(WhisperError::LocalEngineError(format!(
+            "Speaker diarization needs {}; {} is not supported",
+            LOCAL_DIARIZATION_BINARY_HINT,
+            binary_path.display()
+        )));
+    }
+    let model = model
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(DEFAULT_LOCAL_WHISPER_MODEL)
+        .to_string();
+
+    let model_path = match engine {
+        LocalWhisperEngine::WhisperCpp => {
+            let path = get_local_whisper_model_path(app, &model)
+                .map_err(WhisperError::LocalEngineError)?;
+            if !path.is_file() {
+                return Err(WhisperError::LocalModelMissing(model));
+            }
+            Some(path)
+        }
+        // faster-whisper CLIs fetch and cache their own CTranslate2 models.
+        LocalWhisperEngine::FasterWhisper => None,
+    };
+
+    let ffmpeg_path = get_ffmpeg_path(app)
+        .await
+        .map(|path| path.to_string_lossy().to_string());
+
+    Ok(LocalWhisperBackend {
+        engine,
+        binary_path,
+        model,
+        model_path,
+        ffmpeg_path,
+        diarize,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_versions_from_engine_output() {
+        assert_eq!(
+            parse_local_whisper_version("0.4.5\n"),
+            Some("0.4.5".to_string())
+        );
+        assert_eq!(
+            parse_local_whisper_version("whisper.cpp version: 1.7.6"),
+            Some("1.7.6".to_string())
+        );
+        assert_eq!(
+            parse_local_whisper_version("usage: whisper-cli [options] file0 file1 ..."),
+            None
+        );
+    }
+
+    #[test]
+    fn finds_model_checksum_in_tree_listing() {
+        let tree = r#"[
+            {"type":"file","path":"README.md","size":10},
+            {"type":"file","path":"ggml-base.bin","size":147951465,
+             "lfs":{"oid":"60ed5bc3dd14eea856493d334349b405782ddcaf0028d4b5df4088345fba2efe","size":147951465}}
+        ]"#;
+        let (sha256, size) = find_model_checksum(tree, "ggml-base.bin").unwrap();
+        assert_eq!(sha256.len(), 64);
+        assert_eq!(size, 147951465);
+        assert!(find_model_checksum(tree, "README.md").is_none());
+        assert!(find_model_checksum(tree, "ggml-tiny.bin").is_none());
+    }
+
+    #[test]
+    fn parses_whisper_cpp_json_output() {
+        let content = r#"{
+            "result": {"language": "en"},
+            "transcription": [
+                {"timestamps": {"from": "00:00:00,000", "to": "00:00:02,500"},
+                 "offsets": {"from": 0, "to": 2500}, "text": " Hello there."}
+            ]
+        }"#;
+        let (segments, language) = parse_whisper_cpp_json(content).unwrap();
+        assert_eq!(language.as_deref(), Some("en"));
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].end, 2.5);
+        assert_eq!(segments[0].text, " Hello there.");
+    }
+
+    #[test]
+    fn reads_speakers_from_faster_whisper_diarization() {
+        let content = r#"{
+            "language": "en",
+            "segments": [
+                {"start": 0.0, "end": 1.0, "text": "[SPEAKER_00]: Hi."},
+                {"start": 1.0, "end": 2.0, "text": " Hello.", "speaker": "SPEAKER_01"},
+                {"start": 2.0, "end": 3.0, "text": "[music]"}
+            ]
+        }"#;
+        let (segments, _) = parse_faster_whisper_json(content).unwrap();
+        assert_eq!(segments[0].speaker.as_deref(), Some("SPEAKER_00"));
+        assert_eq!(segments[0].text, "Hi.");
+        assert_eq!(segments[1].speaker.as_deref(), Some("SPEAKER_01"));
+        assert_eq!(segments[2].speaker, None);
+        assert_eq!(segments[2].text, "[music]");
+    }
+
+    #[test]
+    fn rejects_unknown_model_names() {
+        assert!(validate_local_whisper_model("base").is_ok());
+        assert!(validate_local_whisper_model("../base").is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// One AI provider call, or a response served from the cache instead
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct AIUsageRecord {
+    pub provider: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Token counts were estimated from text length because the provider reported none
+    pub estimated_tokens: bool,
+    pub latency_ms: u64,
+    /// `None` when the model has no known price
+    pub cost_usd: Option<f64>,
+    pub cached: bool,
+}
+
+/// Usage totals for one provider and model on one day (UTC)
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct AIUsageReportRow {
+    pub day: String,
+    pub provider: String,
+    pub model: String,
+    pub requests: u64,
+    pub cached_requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub average_latency_ms: u64,
+    /// Sum over requests with a known price
+    pub cost_usd: f64,
+    /// Requests whose model has no known price and are left out of `cost_usd`
+    pub unpriced_requests: u64,
+}
+
+/// A response served from the AI response cache
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct AICachedResponse {
+    pub provider: String,
+    pub model: String,
+    pub response: String,
+}
@@ -103,6 +103,57 @@ pub struct FfmpegStatus {
     pub is_system: bool,
 }
 
+/// Local speech-to-text engine used by the offline Whisper backend
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LocalWhisperEngine {
+    #[default]
+    WhisperCpp,
+    FasterWhisper,
+}
+
+impl LocalWhisperEngine {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocalWhisperEngine::WhisperCpp => "whisper-cpp",
+            LocalWhisperEngine::FasterWhisper => "faster-whisper",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "faster-whisper" => LocalWhisperEngine::FasterWhisper,
+            _ => LocalWhisperEngine::WhisperCpp,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            LocalWhisperEngine::WhisperCpp => "whisper.cpp",
+            LocalWhisperEngine::FasterWhisper => "faster-whisper",
+        }
+    }
+}
+
+/// Local Whisper engine installation status
+#[derive(Clone, Serialize, Debug)]
+pub struct LocalWhisperStatus {
+    pub engine: LocalWhisperEngine,
+    pub installed: bool,
+    pub version: Option<String>,
+    pub binary_path: Option<String>,
+    pub is_system: bool,
+}
+
+/// whisper.cpp model file state
+#[derive(Clone, Serialize, Debug)]
+pub struct LocalWhisperModelInfo {
+    pub name: String,
+    pub downloaded: bool,
+    pub path: Option<String>,
+    pub size_bytes: Option<u64>,
+}
+
 /// Deno runtime installation status
 #[derive(Clone, Serialize, Debug)]
 pub struct DenoStatus {
@@ -38,6 +38,98 @@ pub struct HistoryEntry {
     pub collections: Vec<HistoryCollection>,
 }
 
+/// Where a stored transcript came from
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptSource {
+    /// Subtitles uploaded by the creator
+    PlatformCaptions,
+    /// Speech recognition captions generated by the platform
+    AutoCaptions,
+    Whisper,
+}
+
+impl TranscriptSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranscriptSource::PlatformCaptions => "platform_captions",
+            TranscriptSource::AutoCaptions => "auto_captions",
+            TranscriptSource::Whisper => "whisper",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "platform_captions" => Some(TranscriptSource::PlatformCaptions),
+            "auto_captions" => Some(TranscriptSource::AutoCaptions),
+            "whisper" => Some(TranscriptSource::Whisper),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct TranscriptSegment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+}
+
+/// Timed transcript saved alongside a history entry
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct HistoryTranscript {
+    pub history_id: String,
+    pub language: Option<String>,
+    pub source: TranscriptSource,
+    pub segments: Vec<TranscriptSegment>,
+    pub updated_at: String,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryChatRole {
+    User,
+    Assistant,
+}
+
+impl HistoryChatRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryChatRole::User => "user",
+            HistoryChatRole::Assistant => "assistant",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "user" => Some(HistoryChatRole::User),
+            "assistant" => Some(HistoryChatRole::Assistant),
+            _ => None,
+        }
+    }
+}
+
+/// Transcript position an answer refers to
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ChatCitation {
+    pub history_id: String,
+    pub start_ms: i64,
+}
+
+/// One turn of the Q&A conversation kept for a history entry
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct HistoryChatMessage {
+    pub id: String,
+    /// First of the sorted participating ids, kept for single-item callers
+    pub history_id: String,
+    /// Every Library item the conversation is about, sorted
+    pub history_ids: Vec<String>,
+    pub role: HistoryChatRole,
+    pub content: String,
+    pub citations: Vec<ChatCitation>,
+    pub created_at: String,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadDuplicateIdentity {
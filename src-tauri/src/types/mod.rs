@@ -1,3 +1,4 @@
+mod ai_usage;
 mod channel;
 mod dependencies;
 mod download;
@@ -8,6 +9,7 @@ mod plugin;
 mod video;
 mod youtube_search;
 
+pub use ai_usage::*;
 pub use channel::*;
 pub use dependencies::*;
 pub use download::*;
@@ -8,6 +8,7 @@ use serde_json::Value;
 pub enum PluginRuntimeLanguage {
     Javascript,
     Python,
+    Wasm,
 }
 
 impl PluginRuntimeLanguage {
@@ -15,6 +16,7 @@ impl PluginRuntimeLanguage {
         match self {
             Self::Javascript => "javascript",
             Self::Python => "python",
+            Self::Wasm => "wasm",
         }
     }
 }
@@ -24,6 +26,7 @@ impl PluginRuntimeLanguage {
 pub enum PluginProvider {
     Deno,
     Python,
+    Wasm,
 }
 
 impl PluginProvider {
@@ -31,6 +34,7 @@ impl PluginProvider {
         match self {
             Self::Deno => "deno",
             Self::Python => "python",
+            Self::Wasm => "wasm",
         }
     }
 }
@@ -41,9 +45,13 @@ pub struct PluginPermissionRequest {
     #[serde(default)]
     pub network: bool,
     #[serde(default)]
+    pub network_hosts: Vec<String>,
+    #[serde(default)]
     pub fs: Vec<PluginFilesystemPermission>,
     #[serde(default)]
     pub tools: Vec<PluginToolPermission>,
+    #[serde(default)]
+    pub resolver: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -90,9 +98,13 @@ pub struct PluginPermissionApproval {
     #[serde(default)]
     pub network: bool,
     #[serde(default)]
+    pub network_hosts: Vec<String>,
+    #[serde(default)]
     pub fs: Vec<PluginFilesystemPermission>,
     #[serde(default)]
     pub tools: Vec<PluginToolPermission>,
+    #[serde(default)]
+    pub resolver: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -189,6 +201,14 @@ pub struct PluginI18nSpec {
     pub directory: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginResolverSpec {
+    pub url_patterns: Vec<String>,
+    #[serde(default)]
+    pub priority: i32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PluginManifest {
@@ -228,6 +248,8 @@ pub struct PluginManifest {
     pub published_at: Option<String>,
     #[serde(default)]
     pub i18n: Option<PluginI18nSpec>,
+    #[serde(default)]
+    pub resolver: Option<PluginResolverSpec>,
 }
 
 fn default_triggers() -> Vec<String> {
@@ -305,6 +327,26 @@ pub struct PluginInstallation {
     pub signature_algorithm: Option<String>,
     #[serde(default)]
     pub signed_at: Option<String>,
+    #[serde(default)]
+    pub version_history: Vec<PluginVersionRecord>,
+    #[serde(default)]
+    pub rollback_version: Option<String>,
+}
+
+/// One installed version of a plugin, with the signer that was verified at install time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginVersionRecord {
+    pub version: String,
+    pub installed_at: String,
+    /// `install`, `update`, or `rollback`.
+    pub action: String,
+    #[serde(default)]
+    pub package_checksum: Option<String>,
+    #[serde(default)]
+    pub signer_fingerprint: Option<String>,
+    #[serde(default)]
+    pub signed_at: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -433,6 +475,38 @@ pub struct PreparedPluginStorePackage {
     pub inspection: PluginPackageInspection,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginUpdateSource {
+    Store,
+    GithubRelease,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginUpdateInfo {
+    pub plugin_id: String,
+    pub name: String,
+    pub current_version: String,
+    pub latest_version: String,
+    pub source: PluginUpdateSource,
+    pub package_url: String,
+    #[serde(default)]
+    pub release_tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreparedPluginUpdate {
+    /// Pass to `apply_plugin_update`; only prepared packages can be installed as updates.
+    pub id: String,
+    pub update: PluginUpdateInfo,
+    pub inspection: PluginPackageInspection,
+    /// Permissions the new version requests that the installed version did not.
+    #[serde(default)]
+    pub added_permissions: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PluginWorkspaceSummary {
@@ -535,6 +609,37 @@ pub struct PluginExecutionResult {
     pub stderr: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginResolvedMedia {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub ext: Option<String>,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginResolvedUrl {
+    #[serde(default)]
+    pub plugin_id: String,
+    #[serde(default)]
+    pub source_url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+    pub media: Vec<PluginResolvedMedia>,
+    /// yt-dlp style `live_status` (`is_live`, `is_upcoming`, `not_live`, ...) when the resolver knows it.
+    #[serde(default)]
+    pub live_status: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum PluginWorkflowFailurePolicy {
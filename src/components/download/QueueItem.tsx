@@ -155,6 +155,8 @@ export function QueueItem({
 
   const handleGenerateSummary = () => {
     if (isGenerating) return;
+    // Regenerating or retrying asks the provider again instead of reusing a cached reply
+    const refresh = Boolean(summary) || task?.status === 'error';
 
     // Clear previous error if any
     if (task?.status === 'error') {
@@ -162,13 +164,17 @@ export function QueueItem({
     }
 
     // Start background task (will check if AI is enabled)
-    ai.startQueueSummaryTask(taskId, {
-      url: item.url,
-      title: item.title,
-      thumbnail: item.thumbnail,
-      duration: item.duration ? parseFloat(item.duration) : undefined,
-      source: 'youtube',
-    });
+    ai.startQueueSummaryTask(
+      taskId,
+      {
+        url: item.url,
+        title: item.title,
+        thumbnail: item.thumbnail,
+        duration: item.duration ? parseFloat(item.duration) : undefined,
+        source: 'youtube',
+      },
+      refresh,
+    );
   };
 
   const videoId = extractYouTubeVideoId(item.url);
@@ -273,18 +273,24 @@ export function UniversalQueueItem({
 
   const handleGenerateSummary = () => {
     if (isGenerating) return;
+    // Regenerating or retrying asks the provider again instead of reusing a cached reply
+    const refresh = Boolean(summary) || task?.status === 'error';
 
     if (task?.status === 'error') {
       ai.clearSummaryTask(taskId);
     }
 
-    ai.startQueueSummaryTask(taskId, {
-      url: item.url,
-      title: item.title,
-      thumbnail: item.thumbnail,
-      duration: item.duration ? parseFloat(item.duration) : undefined,
-      source: item.extractor || 'other',
-    });
+    ai.startQueueSummaryTask(
+      taskId,
+      {
+        url: item.url,
+        title: item.title,
+        thumbnail: item.thumbnail,
+        duration: item.duration ? parseFloat(item.duration) : undefined,
+        source: item.extractor || 'other',
+      },
+      refresh,
+    );
   };
 
   return (
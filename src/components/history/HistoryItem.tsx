@@ -281,8 +281,8 @@ export function HistoryItem({ entry }: HistoryItemProps) {
       return;
     }
     // Start background task - this will continue even if component unmounts
-    ai.startSummaryTask(entry.id, entry.url);
-  }, [ai, entry.url, entry.id]);
+    ai.startSummaryTask(entry.id, entry.url, Boolean(localSummary));
+  }, [ai, entry.url, entry.id, localSummary]);
 
   const handleOpenTagsDialog = useCallback(() => {
     setIsTaggingDialogOpen(true);
@@ -1,5 +1,17 @@
 import { invoke } from '@tauri-apps/api/core';
-import { AlertTriangle, Check, Clock, Loader2, Scissors, Trash2, Wand2, X } from 'lucide-react';
+import {
+  AlertTriangle,
+  BookOpen,
+  Check,
+  Clapperboard,
+  Clock,
+  Loader2,
+  Scissors,
+  Trash2,
+  VolumeX,
+  Wand2,
+  X,
+} from 'lucide-react';
 import { useCallback, useEffect, useMemo, useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import { Button } from '@/components/ui/button';
@@ -38,6 +50,24 @@ interface SegmentDraft {
   name: string;
 }
 
+type AutoSplitMode = 'silence' | 'scenes' | 'chapters';
+
+const AUTO_SPLIT_MODES: Array<{
+  mode: AutoSplitMode;
+  icon: typeof VolumeX;
+  labelKey: string;
+}> = [
+  { mode: 'silence', icon: VolumeX, labelKey: 'library.split.autoSilence' },
+  { mode: 'scenes', icon: Clapperboard, labelKey: 'library.split.autoScenes' },
+  { mode: 'chapters', icon: BookOpen, labelKey: 'library.split.autoChapters' },
+];
+
+interface SplitSegmentRequest {
+  name: string;
+  startSeconds: number;
+  endSeconds: number;
+}
+
 interface SplitMediaResult {
   outputDir: string;
   segments: Array<{
@@ -74,7 +104,7 @@ function buildSegments(
     const startSeconds = points[index];
     const endSeconds = points[index + 1];
     if (endSeconds - startSeconds < 1) continue;
-    const id = `${Math.round(startSeconds)}-${Math.round(endSeconds)}`;
+    const id = segmentId(startSeconds, endSeconds);
     segments.push({
       id,
       startSeconds,
@@ -93,6 +123,10 @@ function normalizeCut(seconds: number, duration: number): number | null {
   return rounded;
 }
 
+function segmentId(startSeconds: number, endSeconds: number): string {
+  return `${Math.round(startSeconds)}-${Math.round(endSeconds)}`;
+}
+
 export function SplitMediaDialog({
   open,
   onOpenChange,
@@ -114,6 +148,7 @@ export function SplitMediaDialog({
   const [names, setNames] = useState<Map<string, string>>(new Map());
   const [loadingMetadata, setLoadingMetadata] = useState(false);
   const [isSplitting, setIsSplitting] = useState(false);
+  const [detectingMode, setDetectingMode] = useState<AutoSplitMode | null>(null);
   const [error, setError] = useState<string | null>(null);
   const [deleteOriginal, setDeleteOriginal] = useState(false);
 
@@ -121,11 +156,26 @@ export function SplitMediaDialog({
   const segments = useMemo(() => buildSegments(duration, cuts, names), [cuts, duration, names]);
   const hasUsableDuration = duration > 1;
   const hasCuts = cuts.length > 0;
+  const isBusy = isSplitting || detectingMode !== null;
+  const splitRequest = useMemo(
+    () => ({
+      inputPath,
+      sourceUrl,
+      parentTitle: title,
+      thumbnail: thumbnail || null,
+      source: source || null,
+      quality: quality || null,
+      format: format || null,
+      autoCollection: autoCollection === true,
+    }),
+    [autoCollection, format, inputPath, quality, source, sourceUrl, thumbnail, title],
+  );
 
   useEffect(() => {
     if (!open) {
       setError(null);
       setIsSplitting(false);
+      setDetectingMode(null);
       return;
     }
 
@@ -171,9 +221,39 @@ export function SplitMediaDialog({
     [duration, hasUsableDuration],
   );
 
+  const proposeSplit = useCallback(
+    async (mode: AutoSplitMode) => {
+      if (!hasUsableDuration) return;
+      setDetectingMode(mode);
+      setError(null);
+      try {
+        const proposal = await invoke<{ segments: SplitSegmentRequest[] }>('propose_media_split', {
+          request: splitRequest,
+          mode: { mode },
+        });
+        const nextCuts = new Set<number>();
+        for (const segment of proposal.segments.slice(1)) {
+          const cut = normalizeCut(segment.startSeconds, duration);
+          if (cut !== null) nextCuts.add(cut);
+        }
+        const nextNames = new Map<string, string>();
+        for (const segment of proposal.segments) {
+          nextNames.set(segmentId(segment.startSeconds, segment.endSeconds), segment.name);
+        }
+        setCuts([...nextCuts].sort((a, b) => a - b));
+        setNames(nextNames);
+      } catch (err) {
+        setError(err instanceof Error ? err.message : String(err));
+      } finally {
+        setDetectingMode(null);
+      }
+    },
+    [duration, hasUsableDuration, splitRequest],
+  );
+
   const addCutFromTimeline = useCallback(
     (event: React.MouseEvent<HTMLDivElement>) => {
-      if (!hasUsableDuration || isSplitting) return;
+      if (!hasUsableDuration || isBusy) return;
       const rect = event.currentTarget.getBoundingClientRect();
       const ratio = (event.clientX - rect.left) / rect.width;
       const cut = normalizeCut(ratio * duration, duration);
@@ -183,7 +263,7 @@ export function SplitMediaDialog({
         return [...current, cut].sort((a, b) => a - b);
       });
     },
-    [duration, hasUsableDuration, isSplitting],
+    [duration, hasUsableDuration, isBusy],
   );
 
   const removeCut = useCallback((cut: number) => {
@@ -214,14 +294,7 @@ export function SplitMediaDialog({
     try {
       const result = await invoke<SplitMediaResult>('split_media_segments', {
         request: {
-          inputPath,
-          sourceUrl,
-          parentTitle: title,
-          thumbnail: thumbnail || null,
-          source: source || null,
-          quality: quality || null,
-          format: format || null,
-          autoCollection: autoCollection === true,
+          ...splitRequest,
           deleteOriginal,
           segments: segments.map((segment) => ({
             name: segment.name,
@@ -243,26 +316,19 @@ export function SplitMediaDialog({
       setIsSplitting(false);
     }
   }, [
-    autoCollection,
     deleteOriginal,
     ffmpegInstalled,
-    format,
     hasUsableDuration,
-    inputPath,
     onComplete,
     onOpenChange,
-    quality,
     segments,
-    source,
-    sourceUrl,
+    splitRequest,
     t,
-    thumbnail,
-    title,
     toast,
   ]);
 
   return (
-    <Dialog open={open} onOpenChange={(nextOpen) => !isSplitting && onOpenChange(nextOpen)}>
+    <Dialog open={open} onOpenChange={(nextOpen) => !isBusy && onOpenChange(nextOpen)}>
       <DialogContent className="max-h-[88vh] w-[min(920px,calc(100vw-2rem))] overflow-y-auto p-0">
         <DialogHeader className="border-b border-border/60 px-5 py-4">
           <div className="flex items-start gap-3">
@@ -326,7 +392,7 @@ export function SplitMediaDialog({
                       <button
                         key={minutes}
                         type="button"
-                        disabled={!hasUsableDuration || isSplitting}
+                        disabled={!hasUsableDuration || isBusy}
                         onClick={() => setPreset(minutes * 60)}
                         className="inline-flex items-center gap-1 rounded-md border border-border/70 bg-background px-2 py-1 text-xs font-medium text-muted-foreground transition-colors hover:border-primary/40 hover:bg-primary/10 hover:text-primary disabled:opacity-50"
                       >
@@ -334,9 +400,25 @@ export function SplitMediaDialog({
                         {t('library.split.everyMinutes', { minutes })}
                       </button>
                     ))}
+                    {AUTO_SPLIT_MODES.map(({ mode, icon: Icon, labelKey }) => (
+                      <button
+                        key={mode}
+                        type="button"
+                        disabled={!hasUsableDuration || isBusy}
+                        onClick={() => proposeSplit(mode)}
+                        className="inline-flex items-center gap-1 rounded-md border border-border/70 bg-background px-2 py-1 text-xs font-medium text-muted-foreground transition-colors hover:border-primary/40 hover:bg-primary/10 hover:text-primary disabled:opacity-50"
+                      >
+                        {detectingMode === mode ? (
+                          <Loader2 className="h-3 w-3 animate-spin" />
+                        ) : (
+                          <Icon className="h-3 w-3" />
+                        )}
+                        {t(labelKey)}
+                      </button>
+                    ))}
                     <button
                       type="button"
-                      disabled={cuts.length === 0 || isSplitting}
+                      disabled={cuts.length === 0 || isBusy}
                       onClick={() => {
                         setCuts([]);
                         setNames(new Map());
@@ -456,7 +538,7 @@ export function SplitMediaDialog({
           <Button
             type="button"
             variant="outline"
-            disabled={isSplitting}
+            disabled={isBusy}
             onClick={() => onOpenChange(false)}
           >
             <X className="mr-2 h-4 w-4" />
@@ -465,7 +547,7 @@ export function SplitMediaDialog({
           <Button
             type="button"
             disabled={
-              isSplitting ||
+              isBusy ||
               loadingMetadata ||
               ffmpegInstalled === false ||
               !hasUsableDuration ||
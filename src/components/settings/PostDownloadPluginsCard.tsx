@@ -172,6 +172,13 @@ export function PostDownloadPluginsCard() {
       uninstallTarget: controller.uninstallTarget,
       setUninstallTarget: controller.setUninstallTarget,
       handleConfirmUninstallPlugin: controller.handleConfirmUninstallPlugin,
+      pluginUpdates: controller.pluginUpdates,
+      updatingPluginId: controller.updatingPluginId,
+      pendingPluginUpdate: controller.pendingPluginUpdate,
+      setPendingPluginUpdate: controller.setPendingPluginUpdate,
+      handlePreparePluginUpdate: controller.handlePreparePluginUpdate,
+      handleApplyPluginUpdate: controller.handleApplyPluginUpdate,
+      handleRollbackPlugin: controller.handleRollbackPlugin,
     }),
     [
       controller.closePluginGuide,
@@ -179,6 +186,7 @@ export function PostDownloadPluginsCard() {
       controller.expandedPluginId,
       controller.getConfigDraftValue,
       controller.getTimeoutDraftValue,
+      controller.handleApplyPluginUpdate,
       controller.handleApprovePermissions,
       controller.handleClearPluginConfig,
       controller.handleConfirmUninstallPlugin,
@@ -186,8 +194,10 @@ export function PostDownloadPluginsCard() {
       controller.handleOpenPluginDirectory,
       controller.handleOpenPluginLogs,
       controller.handlePickPluginConfigPath,
+      controller.handlePreparePluginUpdate,
       controller.handleRefreshPlugin,
       controller.handleResetPluginTimeout,
+      controller.handleRollbackPlugin,
       controller.handleSavePluginConfig,
       controller.handleSavePluginTimeout,
       controller.handleSetPluginProvider,
@@ -196,18 +206,22 @@ export function PostDownloadPluginsCard() {
       controller.loadPlugins,
       controller.loading,
       controller.openPluginGuide,
+      controller.pendingPluginUpdate,
       controller.permissionDialogPlugin,
       controller.permissionDialogState,
       controller.pluginGuideDialog,
+      controller.pluginUpdates,
       controller.plugins,
       controller.runtimeStatuses,
       controller.setConfigDraftValue,
       controller.setExpandedPluginId,
+      controller.setPendingPluginUpdate,
       controller.setPermissionDialogPlugin,
       controller.setPermissionDialogState,
       controller.setTimeoutDraftValue,
       controller.setUninstallTarget,
       controller.uninstallTarget,
+      controller.updatingPluginId,
     ],
   );
   const logsWorkflowsFlowProps = useMemo(
@@ -6,6 +6,7 @@ import { SimpleMarkdown } from '@/components/ui/simple-markdown';
 import { Switch } from '@/components/ui/switch';
 import {
   getFilesystemPermissionLabel,
+  getNetworkPermissionLabel,
   getToolPermissionLabel,
 } from './post-download-plugins-shared';
 import type { PostDownloadPluginsCardController } from './usePostDownloadPluginsCard';
@@ -78,9 +79,18 @@ export function PluginDetailDialogs({ controller }: { controller: PluginDetailDi
                   {[
                     {
                       key: 'network' as const,
-                      label: t('download.pluginPermissionNetwork'),
+                      label: getNetworkPermissionLabel(
+                        controller.permissionDialogPlugin.manifest.permissions,
+                        t,
+                      ),
                       enabled: controller.permissionDialogPlugin.manifest.permissions.network,
                     },
+                    {
+                      key: 'resolver' as const,
+                      label: t('download.pluginPermissionResolver'),
+                      enabled:
+                        controller.permissionDialogPlugin.manifest.permissions.resolver ?? false,
+                    },
                   ]
                     .filter((permission) => permission.enabled)
                     .map((permission) => (
@@ -90,11 +100,19 @@ export function PluginDetailDialogs({ controller }: { controller: PluginDetailDi
                       >
                         <span className="text-sm">{permission.label}</span>
                         <Switch
-                          checked={controller.permissionDialogState[permission.key]}
+                          checked={controller.permissionDialogState[permission.key] ?? false}
                           onCheckedChange={(checked) =>
                             controller.setPermissionDialogState((current) => ({
                               ...current,
                               [permission.key]: checked,
+                              ...(permission.key === 'network' && {
+                                networkHosts: checked
+                                  ? [
+                                      ...(controller.permissionDialogPlugin?.manifest.permissions
+                                        .networkHosts ?? []),
+                                    ]
+                                  : [],
+                              }),
                             }))
                           }
                         />
@@ -6,6 +6,7 @@ import { cn } from '@/lib/utils';
 import { SettingsCard } from '../SettingsSection';
 import { PluginDetailDialogs } from './PluginDetailDialogs';
 import { PluginInstalledCard } from './PluginInstalledCard';
+import { PluginUpdateDialog } from './PluginVersionCard';
 import type { PostDownloadPluginsCardController } from './usePostDownloadPluginsCard';
 
 type PluginDetailsConfigFlowProps = Pick<
@@ -15,6 +16,7 @@ type PluginDetailsConfigFlowProps = Pick<
   | 'expandedPluginId'
   | 'getConfigDraftValue'
   | 'getTimeoutDraftValue'
+  | 'handleApplyPluginUpdate'
   | 'handleApprovePermissions'
   | 'handleClearPluginConfig'
   | 'handleConfirmUninstallPlugin'
@@ -22,8 +24,10 @@ type PluginDetailsConfigFlowProps = Pick<
   | 'handleOpenPluginDirectory'
   | 'handleOpenPluginLogs'
   | 'handlePickPluginConfigPath'
+  | 'handlePreparePluginUpdate'
   | 'handleRefreshPlugin'
   | 'handleResetPluginTimeout'
+  | 'handleRollbackPlugin'
   | 'handleSavePluginConfig'
   | 'handleSavePluginTimeout'
   | 'handleSetPluginProvider'
@@ -32,18 +36,22 @@ type PluginDetailsConfigFlowProps = Pick<
   | 'loadPlugins'
   | 'loading'
   | 'openPluginGuide'
+  | 'pendingPluginUpdate'
   | 'permissionDialogPlugin'
   | 'permissionDialogState'
   | 'pluginGuideDialog'
+  | 'pluginUpdates'
   | 'plugins'
   | 'runtimeStatuses'
   | 'setConfigDraftValue'
   | 'setExpandedPluginId'
+  | 'setPendingPluginUpdate'
   | 'setPermissionDialogPlugin'
   | 'setPermissionDialogState'
   | 'setTimeoutDraftValue'
   | 'setUninstallTarget'
   | 'uninstallTarget'
+  | 'updatingPluginId'
 >;
 
 export const PluginDetailsConfigFlow = memo(function PluginDetailsConfigFlow(
@@ -91,6 +99,7 @@ export const PluginDetailsConfigFlow = memo(function PluginDetailsConfigFlow(
       </SettingsCard>
 
       <PluginDetailDialogs controller={controller} />
+      <PluginUpdateDialog controller={controller} />
     </>
   );
 });
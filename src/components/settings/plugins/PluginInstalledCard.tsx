@@ -10,6 +10,7 @@ import { PluginConfigCard } from './PluginConfigCard';
 import { PluginPackageInfoCard } from './PluginPackageInfoCard';
 import { PluginPermissionsConfigCard } from './PluginPermissionsConfigCard';
 import { PluginRuntimeCompatibilityCard } from './PluginRuntimeCompatibilityCard';
+import { PluginVersionCard } from './PluginVersionCard';
 import { formatRuntimeStatusBadge, renderPluginManifestIcon } from './post-download-plugins-shared';
 import type { PostDownloadPluginsCardController } from './usePostDownloadPluginsCard';
 
@@ -23,18 +24,22 @@ type PluginInstalledCardControllerProps = Pick<
   | 'handleOpenPluginDirectory'
   | 'handleOpenPluginLogs'
   | 'handlePickPluginConfigPath'
+  | 'handlePreparePluginUpdate'
   | 'handleRefreshPlugin'
   | 'handleResetPluginTimeout'
+  | 'handleRollbackPlugin'
   | 'handleSavePluginConfig'
   | 'handleSavePluginTimeout'
   | 'handleSetPluginProvider'
   | 'handleTogglePlugin'
   | 'handleUninstallPlugin'
   | 'openPluginGuide'
+  | 'pluginUpdates'
   | 'runtimeStatuses'
   | 'setConfigDraftValue'
   | 'setExpandedPluginId'
   | 'setTimeoutDraftValue'
+  | 'updatingPluginId'
 >;
 
 export function PluginInstalledCard({
@@ -83,6 +88,11 @@ export function PluginInstalledCard({
                       {formatRuntimeStatusBadge(runtimeStatus.status, t)}
                     </span>
                   )}
+                  {controller.pluginUpdates[plugin.manifest.id] && (
+                    <span className="rounded bg-sky-500/10 px-2 py-0.5 text-[10px] uppercase tracking-wide text-sky-600 dark:text-sky-400">
+                      {t('download.pluginUpdateAvailableBadge')}
+                    </span>
+                  )}
                   {plugin.warnings.length > 0 && (
                     <span className="rounded bg-amber-500/10 px-2 py-0.5 text-[10px] uppercase tracking-wide text-amber-600 dark:text-amber-400">
                       {t('download.pluginWarningCount', { count: plugin.warnings.length })}
@@ -194,7 +204,10 @@ export function PluginInstalledCard({
                 </TabsTrigger>
               </TabsList>
 
-              <TabsContent value="information" className="mt-0">
+              <TabsContent value="information" className="mt-0 space-y-3">
+                {!isWorkspacePlugin && (
+                  <PluginVersionCard controller={controller} plugin={plugin} />
+                )}
                 <PluginPackageInfoCard controller={controller} plugin={plugin} />
               </TabsContent>
               <TabsContent value="permissions" className="mt-0">
@@ -5,7 +5,9 @@ import type { PluginSummary } from '@/lib/types';
 import { cn } from '@/lib/utils';
 import {
   getFilesystemPermissionLabel,
+  getNetworkPermissionLabel,
   getToolPermissionLabel,
+  isNetworkHostApproved,
   summarizeRequestedPermissions,
 } from './post-download-plugins-shared';
 import type { PostDownloadPluginsCardController } from './usePostDownloadPluginsCard';
@@ -58,9 +60,19 @@ export function PluginPermissionsConfigCard({
         {[
           {
             key: 'network' as const,
-            label: t('download.pluginPermissionNetwork'),
+            label: getNetworkPermissionLabel(plugin.manifest.permissions, t),
             enabled: plugin.manifest.permissions.network,
-            approved: plugin.installation.approvedPermissions.network,
+            approved:
+              plugin.installation.approvedPermissions.network &&
+              (plugin.manifest.permissions.networkHosts ?? []).every((host) =>
+                isNetworkHostApproved(plugin.installation.approvedPermissions, host),
+              ),
+          },
+          {
+            key: 'resolver' as const,
+            label: t('download.pluginPermissionResolver'),
+            enabled: plugin.manifest.permissions.resolver ?? false,
+            approved: plugin.installation.approvedPermissions.resolver ?? false,
           },
         ].map((permission) => (
           <div
@@ -78,6 +90,11 @@ export function PluginPermissionsConfigCard({
                 controller.handleApprovePermissions(plugin, {
                   ...plugin.installation.approvedPermissions,
                   [permission.key]: checked,
+                  ...(permission.key === 'network' && {
+                    networkHosts: checked
+                      ? [...(plugin.manifest.permissions.networkHosts ?? [])]
+                      : [],
+                  }),
                 })
               }
             />
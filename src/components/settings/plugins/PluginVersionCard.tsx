@@ -0,0 +1,177 @@
+import { ArrowUpCircle, History, Loader2, Undo2 } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Dialog, DialogContent, DialogHeader, DialogTitle } from '@/components/ui/dialog';
+import type { PluginSummary } from '@/lib/types';
+import { formatSignerFingerprint, getPermissionKeyLabel } from './post-download-plugins-shared';
+import type { PostDownloadPluginsCardController } from './usePostDownloadPluginsCard';
+
+type PluginVersionCardControllerProps = Pick<
+  PostDownloadPluginsCardController,
+  'handlePreparePluginUpdate' | 'handleRollbackPlugin' | 'pluginUpdates' | 'updatingPluginId'
+>;
+
+export function PluginVersionCard({
+  controller,
+  plugin,
+}: {
+  controller: PluginVersionCardControllerProps;
+  plugin: PluginSummary;
+}) {
+  const { t } = useTranslation('settings');
+  const update = controller.pluginUpdates[plugin.manifest.id];
+  const busy = controller.updatingPluginId === plugin.manifest.id;
+  const history = [...(plugin.installation.versionHistory ?? [])].reverse();
+
+  return (
+    <div className="rounded-xl bg-muted/30 p-3">
+      <div className="flex items-center gap-2 text-xs font-medium">
+        <History className="h-4 w-4 text-purple-500" />
+        <span>{t('download.pluginVersionsTitle')}</span>
+      </div>
+      <p className="mt-2 text-[11px] text-muted-foreground">
+        {update
+          ? t('download.pluginUpdateAvailable', {
+              version: update.latestVersion,
+              source: t(
+                update.source === 'store'
+                  ? 'download.pluginUpdateSourceStore'
+                  : 'download.pluginUpdateSourceGithub',
+              ),
+            })
+          : t('download.pluginUpToDate')}
+      </p>
+      <div className="mt-3 flex flex-wrap gap-2">
+        {update && (
+          <Button
+            variant="outline"
+            size="sm"
+            disabled={busy}
+            onClick={() => controller.handlePreparePluginUpdate(plugin)}
+          >
+            {busy ? (
+              <Loader2 className="h-4 w-4 animate-spin" />
+            ) : (
+              <ArrowUpCircle className="h-4 w-4" />
+            )}
+            {t('download.pluginUpdateButton', { version: update.latestVersion })}
+          </Button>
+        )}
+        {plugin.installation.rollbackVersion && (
+          <Button
+            variant="outline"
+            size="sm"
+            disabled={busy}
+            onClick={() => controller.handleRollbackPlugin(plugin)}
+          >
+            <Undo2 className="h-4 w-4" />
+            {t('download.pluginRollbackButton', {
+              version: plugin.installation.rollbackVersion,
+            })}
+          </Button>
+        )}
+      </div>
+      {history.length > 0 && (
+        <div className="mt-3 space-y-1 text-[11px] text-muted-foreground">
+          <p className="font-medium text-foreground/80">
+            {t('download.pluginVersionHistoryTitle')}
+          </p>
+          {history.map((record) => (
+            <p key={`${record.installedAt}-${record.version}`} className="break-all">
+              v{record.version} · {t(`download.pluginVersionAction.${record.action}`)} ·{' '}
+              {new Date(record.installedAt).toLocaleString()}
+              {record.signerFingerprint &&
+                ` · ${formatSignerFingerprint(record.signerFingerprint)}`}
+            </p>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
+
+type PluginUpdateDialogControllerProps = Pick<
+  PostDownloadPluginsCardController,
+  'handleApplyPluginUpdate' | 'pendingPluginUpdate' | 'setPendingPluginUpdate' | 'updatingPluginId'
+>;
+
+export function PluginUpdateDialog({
+  controller,
+}: {
+  controller: PluginUpdateDialogControllerProps;
+}) {
+  const { t } = useTranslation('settings');
+  const prepared = controller.pendingPluginUpdate;
+
+  return (
+    <Dialog
+      open={prepared != null}
+      onOpenChange={(open) => !open && controller.setPendingPluginUpdate(null)}
+    >
+      <DialogContent className="sm:max-w-[560px]">
+        <DialogHeader>
+          <DialogTitle>
+            {t('download.pluginUpdateDialogTitle', { name: prepared?.update.name ?? '' })}
+          </DialogTitle>
+        </DialogHeader>
+
+        {prepared && (
+          <div className="space-y-3">
+            <p className="text-sm text-muted-foreground">
+              {t('download.pluginUpdateDialogDesc', {
+                current: prepared.update.currentVersion,
+                next: prepared.update.latestVersion,
+              })}
+            </p>
+            {prepared.addedPermissions.length > 0 ? (
+              <div className="space-y-2">
+                <p className="text-sm text-amber-600 dark:text-amber-400">
+                  {t('download.pluginUpdateNewPermissions')}
+                </p>
+                <div className="flex flex-wrap gap-2">
+                  {prepared.addedPermissions.map((permission) => (
+                    <span
+                      key={permission}
+                      className="rounded bg-amber-500/10 px-2 py-1 text-[11px] text-amber-600 dark:text-amber-400"
+                    >
+                      {getPermissionKeyLabel(permission, t)}
+                    </span>
+                  ))}
+                </div>
+              </div>
+            ) : (
+              <p className="text-sm text-muted-foreground">
+                {t('download.pluginUpdateNoNewPermissions')}
+              </p>
+            )}
+            {prepared.inspection.signerFingerprint && (
+              <div className="rounded-lg border border-border/60 bg-muted/30 p-3 text-xs text-muted-foreground">
+                {t('download.pluginSignerFingerprintLabel')}:{' '}
+                {formatSignerFingerprint(prepared.inspection.signerFingerprint)}
+              </div>
+            )}
+          </div>
+        )}
+
+        <div className="flex justify-end gap-2">
+          <Button variant="outline" onClick={() => controller.setPendingPluginUpdate(null)}>
+            {t('download.pluginDismiss')}
+          </Button>
+          <Button
+            onClick={controller.handleApplyPluginUpdate}
+            disabled={controller.updatingPluginId != null}
+          >
+            {controller.updatingPluginId != null ? (
+              <Loader2 className="h-4 w-4 animate-spin" />
+            ) : (
+              <ArrowUpCircle className="h-4 w-4" />
+            )}
+            {t('download.pluginUpdateButton', {
+              version: prepared?.update.latestVersion ?? '',
+            })}
+          </Button>
+        </div>
+      </DialogContent>
+    </Dialog>
+  );
+}
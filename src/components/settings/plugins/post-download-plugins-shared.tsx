@@ -9,6 +9,7 @@ import type {
   PluginManifestIconName,
   PluginPackageInspection,
   PluginPermissionApproval,
+  PluginPermissionSet,
   PluginProvider,
   PluginRuntimeLanguage,
   PluginSummary,
@@ -88,11 +89,13 @@ export type PluginGuideDialogState = {
 export const PROVIDER_LABELS: Record<PluginProvider, string> = {
   deno: 'Deno',
   python: 'Python',
+  wasm: 'WebAssembly',
 };
 
 export const LANGUAGE_LABELS: Record<PluginRuntimeLanguage, string> = {
   javascript: 'JavaScript',
   python: 'Python',
+  wasm: 'WebAssembly',
 };
 
 export const CONFIG_FIELD_INPUT_TYPES: PluginConfigFieldInputType[] = [
@@ -210,13 +213,24 @@ export const DEFAULT_CREATE_PLUGIN_FORM: CreatePluginFormState = {
 
 const LUCIDE_ICON_REGISTRY = LucideIcons as Record<string, LucideIcon | unknown>;
 
+export function getNetworkPermissionLabel(
+  permissions: PluginPermissionSet,
+  t: (key: string, opts?: Record<string, unknown>) => string,
+) {
+  const hosts = permissions.networkHosts ?? [];
+  return hosts.length > 0
+    ? t('download.pluginPermissionNetworkHosts', { hosts: hosts.join(', ') })
+    : t('download.pluginPermissionNetwork');
+}
+
 export function summarizeRequestedPermissions(
   plugin: PluginSummary | PluginPackageInspection,
   t: (key: string, opts?: Record<string, unknown>) => string,
 ) {
   const permissions = plugin.manifest.permissions;
   const entries: string[] = [];
-  if (permissions.network) entries.push(t('download.pluginPermissionNetwork'));
+  if (permissions.network) entries.push(getNetworkPermissionLabel(permissions, t));
+  if (permissions.resolver) entries.push(t('download.pluginPermissionResolver'));
   if (permissions.fs.length > 0) {
     entries.push(
       ...permissions.fs.map((permission) => getFilesystemPermissionLabel(permission, t)),
@@ -231,15 +245,29 @@ export function summarizeRequestedPermissions(
 export function buildRequestedPermissionApproval(plugin: PluginSummary): PluginPermissionApproval {
   return {
     network: plugin.manifest.permissions.network,
+    networkHosts: plugin.manifest.permissions.network
+      ? [...(plugin.manifest.permissions.networkHosts ?? [])]
+      : [],
     fs: [...plugin.manifest.permissions.fs],
     tools: [...plugin.manifest.permissions.tools],
+    resolver: plugin.manifest.permissions.resolver ?? false,
   };
 }
 
+export function isNetworkHostApproved(approved: PluginPermissionApproval, host: string) {
+  return (approved.networkHosts ?? []).some(
+    (approvedHost) => approvedHost.toLowerCase() === host.toLowerCase(),
+  );
+}
+
 export function hasUnapprovedRequestedPermissions(plugin: PluginSummary) {
   const requested = buildRequestedPermissionApproval(plugin);
   return (
     (requested.network && !plugin.installation.approvedPermissions.network) ||
+    (requested.networkHosts ?? []).some(
+      (host) => !isNetworkHostApproved(plugin.installation.approvedPermissions, host),
+    ) ||
+    (requested.resolver && !plugin.installation.approvedPermissions.resolver) ||
     requested.fs.some(
       (permission) => !plugin.installation.approvedPermissions.fs.includes(permission),
     ) ||
@@ -461,6 +489,22 @@ export function getToolPermissionLabel(
   }
 }
 
+export function getPermissionKeyLabel(
+  key: string,
+  t: (key: string, opts?: Record<string, unknown>) => string,
+) {
+  if (key === 'network') return t('download.pluginPermissionNetwork');
+  if (key === 'resolver') return t('download.pluginPermissionResolver');
+  if (key.startsWith('net:')) {
+    return t('download.pluginPermissionNetworkHosts', { hosts: key.slice('net:'.length) });
+  }
+  if (key.startsWith('fs.')) {
+    return getFilesystemPermissionLabel(key as PluginFilesystemPermission, t);
+  }
+  if (key.startsWith('tool.')) return getToolPermissionLabel(key as PluginToolPermission, t);
+  return key;
+}
+
 export function validateCreatePluginConfigFields(
   fields: CreatePluginConfigFieldDraft[],
   t: (key: string, opts?: Record<string, unknown>) => string,
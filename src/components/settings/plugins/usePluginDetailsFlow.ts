@@ -19,6 +19,7 @@ import {
   currentTimeoutSec,
   getResolvedConfigFieldValue,
   hasUnapprovedRequestedPermissions,
+  isNetworkHostApproved,
   type PluginConfigDraftValue,
   type PluginGuideDialogState,
   stringifyConfigFieldValue,
@@ -62,6 +63,7 @@ export function usePluginDetailsFlow(
   const [permissionDialogPlugin, setPermissionDialogPlugin] = useState<PluginSummary | null>(null);
   const [permissionDialogState, setPermissionDialogState] = useState<PluginPermissionApproval>({
     network: false,
+    networkHosts: [],
     fs: [],
     tools: [],
   });
@@ -82,6 +84,10 @@ export function usePluginDetailsFlow(
     setPermissionDialogPlugin(plugin);
     setPermissionDialogState({
       network: requested.network ? plugin.installation.approvedPermissions.network : false,
+      networkHosts: (requested.networkHosts ?? []).filter((host) =>
+        isNetworkHostApproved(plugin.installation.approvedPermissions, host),
+      ),
+      resolver: requested.resolver ? plugin.installation.approvedPermissions.resolver : false,
       fs: requested.fs.filter((permission) =>
         plugin.installation.approvedPermissions.fs.includes(permission),
       ),
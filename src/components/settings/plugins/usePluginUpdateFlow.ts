@@ -0,0 +1,127 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { Dispatch, SetStateAction } from 'react';
+import { useCallback, useEffect, useState } from 'react';
+import { localizeUnknownError } from '@/lib/backend-error';
+import type { PluginSummary, PluginUpdateInfo, PreparedPluginUpdate } from '@/lib/types';
+
+export function usePluginUpdateFlow(
+  setError: Dispatch<SetStateAction<string | null>>,
+  updatePluginList: (updater: (items: PluginSummary[]) => PluginSummary[]) => void,
+) {
+  const [pluginUpdates, setPluginUpdates] = useState<Record<string, PluginUpdateInfo>>({});
+  const [checkingPluginUpdates, setCheckingPluginUpdates] = useState(false);
+  const [updatingPluginId, setUpdatingPluginId] = useState<string | null>(null);
+  const [pendingPluginUpdate, setPendingPluginUpdate] = useState<PreparedPluginUpdate | null>(
+    null,
+  );
+
+  const checkPluginUpdates = useCallback(async (forceRefresh = false) => {
+    setCheckingPluginUpdates(true);
+    try {
+      const updates = await invoke<PluginUpdateInfo[]>('check_plugin_updates', { forceRefresh });
+      setPluginUpdates(Object.fromEntries(updates.map((update) => [update.pluginId, update])));
+    } catch (err) {
+      console.error('Failed to check plugin updates:', err);
+    } finally {
+      setCheckingPluginUpdates(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    checkPluginUpdates();
+    // The backend re-checks every six hours whether or not this view is open.
+    const unlisten = listen<PluginUpdateInfo[]>('plugin-updates-available', (event) => {
+      setPluginUpdates(
+        Object.fromEntries(event.payload.map((update) => [update.pluginId, update])),
+      );
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [checkPluginUpdates]);
+
+  const replacePlugin = useCallback(
+    (next: PluginSummary) => {
+      updatePluginList((current) =>
+        current.map((item) => (item.manifest.id === next.manifest.id ? next : item)),
+      );
+    },
+    [updatePluginList],
+  );
+
+  const handlePreparePluginUpdate = useCallback(
+    async (plugin: PluginSummary) => {
+      setUpdatingPluginId(plugin.manifest.id);
+      setError(null);
+      try {
+        const prepared = await invoke<PreparedPluginUpdate>('prepare_plugin_update', {
+          pluginId: plugin.manifest.id,
+        });
+        setPendingPluginUpdate(prepared);
+      } catch (err) {
+        console.error('Failed to prepare plugin update:', err);
+        setError(localizeUnknownError(err));
+      } finally {
+        setUpdatingPluginId(null);
+      }
+    },
+    [setError],
+  );
+
+  const handleApplyPluginUpdate = useCallback(async () => {
+    if (!pendingPluginUpdate) return;
+    const pluginId = pendingPluginUpdate.update.pluginId;
+    setUpdatingPluginId(pluginId);
+    setError(null);
+    try {
+      const updated = await invoke<PluginSummary>('apply_plugin_update', {
+        preparedId: pendingPluginUpdate.id,
+      });
+      replacePlugin(updated);
+      setPluginUpdates((current) => {
+        const next = { ...current };
+        delete next[pluginId];
+        return next;
+      });
+      setPendingPluginUpdate(null);
+    } catch (err) {
+      console.error('Failed to apply plugin update:', err);
+      setError(localizeUnknownError(err));
+    } finally {
+      setUpdatingPluginId(null);
+    }
+  }, [pendingPluginUpdate, replacePlugin, setError]);
+
+  const handleRollbackPlugin = useCallback(
+    async (plugin: PluginSummary) => {
+      setUpdatingPluginId(plugin.manifest.id);
+      setError(null);
+      try {
+        const restored = await invoke<PluginSummary>('rollback_plugin', {
+          pluginId: plugin.manifest.id,
+        });
+        replacePlugin(restored);
+        await checkPluginUpdates();
+      } catch (err) {
+        console.error('Failed to roll back plugin:', err);
+        setError(localizeUnknownError(err));
+      } finally {
+        setUpdatingPluginId(null);
+      }
+    },
+    [checkPluginUpdates, replacePlugin, setError],
+  );
+
+  return {
+    checkPluginUpdates,
+    checkingPluginUpdates,
+    handleApplyPluginUpdate,
+    handlePreparePluginUpdate,
+    handleRollbackPlugin,
+    pendingPluginUpdate,
+    pluginUpdates,
+    setPendingPluginUpdate,
+    updatingPluginId,
+  };
+}
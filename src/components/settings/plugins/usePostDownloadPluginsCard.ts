@@ -4,6 +4,7 @@ import { usePluginCatalogState } from './usePluginCatalogState';
 import { usePluginDetailsFlow } from './usePluginDetailsFlow';
 import { usePluginImportFlow } from './usePluginImportFlow';
 import { usePluginReminderState } from './usePluginReminderState';
+import { usePluginUpdateFlow } from './usePluginUpdateFlow';
 import { usePluginWorkflowLogsFlow } from './usePluginWorkflowLogsFlow';
 import { usePluginWorkspaceFlow } from './usePluginWorkspaceFlow';
 
@@ -38,6 +39,7 @@ export function usePostDownloadPluginsCard() {
     details.promptPluginPermissionEnable,
   );
   const workspace = usePluginWorkspaceFlow(t, setError, catalog.loadPlugins);
+  const updates = usePluginUpdateFlow(setError, catalog.updatePluginList);
 
   return useMemo(
     () => ({
@@ -45,11 +47,12 @@ export function usePostDownloadPluginsCard() {
       ...details,
       ...importFlow,
       ...reminder,
+      ...updates,
       ...workflowLogs,
       ...workspace,
       error,
     }),
-    [catalog, details, error, importFlow, reminder, workflowLogs, workspace],
+    [catalog, details, error, importFlow, reminder, updates, workflowLogs, workspace],
   );
 }
 
@@ -309,9 +309,19 @@ export const SEARCHABLE_SETTINGS: SearchableSetting[] = [
       'qwen',
       'gpt',
       'claude',
+      'anthropic',
+      'custom',
+      'endpoint',
     ],
     section: 'ai',
   },
+  {
+    id: 'ai-model-routing',
+    labelKey: 'ai.modelRouting',
+    descriptionKey: 'ai.modelRoutingDesc',
+    keywords: ['routing', 'route', 'chunk', 'final', 'ffmpeg', 'cheap', 'model'],
+    section: 'ai',
+  },
   {
     id: 'ai-api-key',
     labelKey: 'ai.apiKey',
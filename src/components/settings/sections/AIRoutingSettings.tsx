@@ -0,0 +1,306 @@
+import { AlertCircle, CheckCircle2, Loader2, Plus, Route, Trash2 } from 'lucide-react';
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Textarea } from '@/components/ui/textarea';
+import { useAI } from '@/contexts/AIContext';
+import type { AICustomEndpoint, AIModelRoute, AIModelRoutes, AIProvider } from '@/lib/types';
+import { cn } from '@/lib/utils';
+
+const ROUTE_PROVIDERS: { id: AIProvider; name: string }[] = [
+  { id: 'gemini', name: 'Google Gemini' },
+  { id: 'openai', name: 'OpenAI GPT' },
+  { id: 'anthropic', name: 'Anthropic Claude' },
+  { id: 'deepseek', name: 'DeepSeek' },
+  { id: 'qwen', name: 'Alibaba Qwen' },
+  { id: 'ollama', name: 'Ollama (Local)' },
+  { id: 'lmstudio', name: 'LM Studio' },
+  { id: 'proxy', name: 'Proxy Custom' },
+  { id: 'custom', name: 'Custom endpoint' },
+];
+
+const ROUTED_TASKS: { key: keyof AIModelRoutes; labelKey: string }[] = [
+  { key: 'chunk_summary', labelKey: 'ai.routeChunkSummary' },
+  { key: 'final_summary', labelKey: 'ai.routeFinalSummary' },
+  { key: 'ffmpeg_command', labelKey: 'ai.routeFfmpegCommand' },
+];
+
+const USE_MAIN_PROVIDER = 'main';
+
+function formatHeaderLines(headers: Record<string, string>) {
+  return Object.entries(headers)
+    .map(([name, value]) => `${name}: ${value}`)
+    .join('\n');
+}
+
+/** Parse `Name: value` lines; lines without a colon are ignored */
+function parseHeaderLines(text: string): Record<string, string> {
+  const headers: Record<string, string> = {};
+  for (const line of text.split('\n')) {
+    const separator = line.indexOf(':');
+    if (separator <= 0) continue;
+    const name = line.slice(0, separator).trim();
+    if (name) headers[name] = line.slice(separator + 1).trim();
+  }
+  return headers;
+}
+
+export function CustomEndpointSettings() {
+  const { t } = useTranslation('settings');
+  const ai = useAI();
+  const endpoints = ai.config.custom_endpoints ?? [];
+  const selected =
+    endpoints.find((endpoint) => endpoint.id === ai.config.custom_endpoint_id) ?? endpoints[0];
+  const [headerText, setHeaderText] = useState('');
+
+  useEffect(() => {
+    setHeaderText(formatHeaderLines(selected?.headers ?? {}));
+  }, [selected?.headers]);
+
+  const updateEndpoint = (updates: Partial<AICustomEndpoint>) => {
+    if (!selected) return;
+    ai.updateConfig({
+      custom_endpoints: endpoints.map((endpoint) =>
+        endpoint.id === selected.id ? { ...endpoint, ...updates } : endpoint,
+      ),
+    });
+  };
+
+  const addEndpoint = () => {
+    const endpoint: AICustomEndpoint = {
+      id: crypto.randomUUID(),
+      name: t('ai.customEndpointDefaultName', { index: endpoints.length + 1 }),
+      base_url: '',
+      headers: {},
+    };
+    ai.updateConfig({
+      custom_endpoints: [...endpoints, endpoint],
+      custom_endpoint_id: endpoint.id,
+    });
+  };
+
+  const removeEndpoint = () => {
+    if (!selected) return;
+    const remaining = endpoints.filter((endpoint) => endpoint.id !== selected.id);
+    ai.updateConfig({
+      custom_endpoints: remaining,
+      custom_endpoint_id: remaining[0]?.id,
+    });
+  };
+
+  return (
+    <div className="space-y-3 p-3 rounded-xl bg-muted/20">
+      <div className="flex flex-col gap-2 sm:flex-row sm:items-center">
+        <Select
+          value={selected?.id ?? ''}
+          onValueChange={(value) => ai.updateConfig({ custom_endpoint_id: value })}
+        >
+          <SelectTrigger className="h-9 flex-1 bg-background/50 text-xs">
+            <SelectValue placeholder={t('ai.customEndpointNone')} />
+          </SelectTrigger>
+          <SelectContent>
+            {endpoints.map((endpoint) => (
+              <SelectItem key={endpoint.id} value={endpoint.id} className="text-xs">
+                {endpoint.name}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <Button variant="outline" size="sm" className="h-9" onClick={addEndpoint}>
+          <Plus className="h-3.5 w-3.5" />
+          {t('ai.customEndpointAdd')}
+        </Button>
+        {selected && (
+          <Button variant="ghost" size="sm" className="h-9" onClick={removeEndpoint}>
+            <Trash2 className="h-3.5 w-3.5" />
+          </Button>
+        )}
+        <Button
+          size="sm"
+          className="h-9"
+          onClick={ai.testConnection}
+          disabled={ai.isTesting || !selected?.base_url}
+        >
+          {ai.isTesting ? <Loader2 className="h-3.5 w-3.5 animate-spin" /> : t('ai.test')}
+        </Button>
+      </div>
+
+      {ai.testResult && (
+        <div
+          className={cn(
+            'flex items-center gap-2 text-xs p-2.5 rounded-lg border',
+            ai.testResult.success
+              ? 'bg-emerald-500/5 border-emerald-500/20 text-emerald-500'
+              : 'bg-destructive/5 border-destructive/20 text-destructive',
+          )}
+        >
+          {ai.testResult.success ? (
+            <CheckCircle2 className="w-4 h-4 flex-shrink-0" />
+          ) : (
+            <AlertCircle className="w-4 h-4 flex-shrink-0" />
+          )}
+          <span className="font-medium">{ai.testResult.message}</span>
+        </div>
+      )}
+
+      {selected && (
+        <div className="grid grid-cols-1 sm:grid-cols-2 gap-3">
+          <div className="space-y-1">
+            <span className="text-[11px] font-medium">{t('ai.customEndpointName')}</span>
+            <Input
+              value={selected.name}
+              onChange={(e) => updateEndpoint({ name: e.target.value })}
+              className="h-9 bg-background/50 text-xs focus-visible:ring-primary"
+            />
+          </div>
+          <div className="space-y-1">
+            <span className="text-[11px] font-medium">{t('ai.customEndpointUrl')}</span>
+            <Input
+              value={selected.base_url}
+              onChange={(e) => updateEndpoint({ base_url: e.target.value })}
+              placeholder="https://openrouter.ai/api/v1"
+              className="h-9 bg-background/50 text-xs focus-visible:ring-primary font-mono"
+            />
+          </div>
+          <div className="space-y-1 sm:col-span-2">
+            <span className="text-[11px] font-medium">{t('ai.apiKey')}</span>
+            <Input
+              type="password"
+              value={selected.api_key || ''}
+              onChange={(e) => updateEndpoint({ api_key: e.target.value || undefined })}
+              placeholder={t('ai.customEndpointKeyOptional')}
+              className="h-9 bg-background/50 text-xs focus-visible:ring-primary"
+            />
+          </div>
+          <div className="space-y-1 sm:col-span-2">
+            <span className="text-[11px] font-medium">{t('ai.customEndpointHeaders')}</span>
+            <Textarea
+              value={headerText}
+              onChange={(e) => setHeaderText(e.target.value)}
+              onBlur={() => updateEndpoint({ headers: parseHeaderLines(headerText) })}
+              placeholder={'HTTP-Referer: https://example.com\nX-Title: My App'}
+              className="min-h-[64px] bg-background/50 text-xs font-mono"
+            />
+            <p className="text-[10px] text-muted-foreground">
+              {t('ai.customEndpointHeadersHint')}
+            </p>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
+
+export function ModelRoutingSettings({ highlighted }: { highlighted?: boolean }) {
+  const { t } = useTranslation('settings');
+  const ai = useAI();
+  const routes = ai.config.model_routes ?? {};
+  const endpoints = ai.config.custom_endpoints ?? [];
+
+  const updateRoute = (key: keyof AIModelRoutes, route: AIModelRoute | undefined) => {
+    ai.updateConfig({ model_routes: { ...routes, [key]: route } });
+  };
+
+  return (
+    <div
+      id="ai-model-routing"
+      className={cn(
+        'space-y-3 p-3 rounded-xl bg-muted/20 transition-all duration-300',
+        highlighted && 'ring-2 ring-primary/30 bg-primary/5 border-primary/20',
+      )}
+    >
+      <div className="flex items-center gap-1.5">
+        <Route className="w-4 h-4 text-primary" />
+        <span className="text-sm font-medium">{t('ai.modelRouting')}</span>
+      </div>
+      <p className="text-[11px] text-muted-foreground">{t('ai.modelRoutingDesc')}</p>
+
+      {ROUTED_TASKS.map(({ key, labelKey }) => {
+        const route = routes[key];
+        const needsOwnKey =
+          route &&
+          route.provider !== ai.config.provider &&
+          !['ollama', 'lmstudio', 'custom'].includes(route.provider);
+        return (
+          <div
+            key={key}
+            className="grid grid-cols-1 sm:grid-cols-[140px_1fr_1fr] gap-2 items-center"
+          >
+            <span className="text-xs font-medium">{t(labelKey)}</span>
+            <Select
+              value={route?.provider ?? USE_MAIN_PROVIDER}
+              onValueChange={(value) =>
+                updateRoute(
+                  key,
+                  value === USE_MAIN_PROVIDER
+                    ? undefined
+                    : { ...route, provider: value as AIProvider, model: route?.model ?? '' },
+                )
+              }
+            >
+              <SelectTrigger className="h-9 bg-background/50 text-xs">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value={USE_MAIN_PROVIDER} className="text-xs">
+                  {t('ai.routeUseMain')}
+                </SelectItem>
+                {ROUTE_PROVIDERS.map((provider) => (
+                  <SelectItem key={provider.id} value={provider.id} className="text-xs">
+                    {provider.name}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            {route && (
+              <Input
+                value={route.model}
+                onChange={(e) => updateRoute(key, { ...route, model: e.target.value })}
+                placeholder={t('ai.modelPlaceholder')}
+                className="h-9 bg-background/50 text-xs font-mono focus-visible:ring-primary"
+              />
+            )}
+            {route?.provider === 'custom' && (
+              <Select
+                value={route.custom_endpoint_id ?? ''}
+                onValueChange={(value) =>
+                  updateRoute(key, { ...route, custom_endpoint_id: value })
+                }
+              >
+                <SelectTrigger className="h-9 bg-background/50 text-xs sm:col-start-2">
+                  <SelectValue placeholder={t('ai.customEndpointNone')} />
+                </SelectTrigger>
+                <SelectContent>
+                  {endpoints.map((endpoint) => (
+                    <SelectItem key={endpoint.id} value={endpoint.id} className="text-xs">
+                      {endpoint.name}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            )}
+            {needsOwnKey && (
+              <Input
+                type="password"
+                value={route.api_key || ''}
+                onChange={(e) =>
+                  updateRoute(key, { ...route, api_key: e.target.value || undefined })
+                }
+                placeholder={t('ai.routeApiKey')}
+                className="h-9 bg-background/50 text-xs sm:col-start-2 sm:col-span-2"
+              />
+            )}
+          </div>
+        );
+      })}
+    </div>
+  );
+}
@@ -44,6 +44,9 @@ import type { AIProvider, SummaryStyle } from '@/lib/types';
 import { DEFAULT_TRANSCRIPT_LANGUAGES, LANGUAGE_OPTIONS } from '@/lib/types';
 import { cn } from '@/lib/utils';
 import { SettingsCard, SettingsDivider, SettingsSection } from '../SettingsSection';
+import { CustomEndpointSettings, ModelRoutingSettings } from './AIRoutingSettings';
+import { AIUsageSettings } from './AIUsageSettings';
+import { LocalWhisperSettings } from './LocalWhisperSettings';
 
 interface AISectionProps {
   highlightId?: string | null;
@@ -66,7 +69,10 @@ export function AISection({ highlightId }: AISectionProps) {
   const ai = useAI();
   const [showApiKey, setShowApiKey] = useState(false);
   const [showWhisperApiKey, setShowWhisperApiKey] = useState(false);
+  const isLocalWhisper = ai.config.whisper_backend === 'local';
   const summaryMaxTokens = ai.config.summary_max_tokens;
+  // Local runtimes need no key and custom endpoints carry their own
+  const usesSharedApiKey = !['ollama', 'lmstudio', 'custom'].includes(ai.config.provider);
 
   const handleSummaryMaxTokensChange = (value: string) => {
     const trimmed = value.trim();
@@ -127,6 +133,26 @@ export function AISection({ highlightId }: AISectionProps) {
         </div>
       ),
     },
+    {
+      id: 'anthropic',
+      name: 'Anthropic Claude',
+      type: 'cloud',
+      logo: (
+        <div className="w-10 h-10 rounded-xl bg-orange-500/10 flex items-center justify-center flex-shrink-0">
+          <svg
+            className="w-5 h-5 text-orange-500"
+            viewBox="0 0 24 24"
+            fill="none"
+            stroke="currentColor"
+            strokeWidth="2"
+            strokeLinecap="round"
+          >
+            <title>Anthropic Claude</title>
+            <path d="M12 3v18M3 12h18M5.6 5.6l12.8 12.8M18.4 5.6 5.6 18.4" />
+          </svg>
+        </div>
+      ),
+    },
     {
       id: 'deepseek',
       name: 'DeepSeek',
@@ -235,6 +261,16 @@ export function AISection({ highlightId }: AISectionProps) {
         </div>
       ),
     },
+    {
+      id: 'custom',
+      name: t('ai.customEndpoints'),
+      type: 'custom',
+      logo: (
+        <div className="w-10 h-10 rounded-xl bg-violet-500/10 flex items-center justify-center flex-shrink-0">
+          <Link className="w-5 h-5 text-violet-500" />
+        </div>
+      ),
+    },
   ];
 
   const handleProviderSelect = (providerId: AIProvider) => {
@@ -246,6 +282,8 @@ export function AISection({ highlightId }: AISectionProps) {
       ollama: 'gpt-oss:20b',
       lmstudio: 'openai/gpt-oss-20b',
       proxy: 'gpt-5.5',
+      anthropic: 'claude-sonnet-4-5',
+      custom: ai.config.model,
     };
     ai.updateConfig({
       provider: providerId,
@@ -267,6 +305,10 @@ export function AISection({ highlightId }: AISectionProps) {
         return ['gpt-oss:20b', 'qwen3:8b', 'gemma3:12b'];
       case 'lmstudio':
         return ['openai/gpt-oss-20b', 'qwen/qwen3-8b', 'google/gemma-3-12b'];
+      case 'anthropic':
+        return ['claude-sonnet-4-5', 'claude-haiku-4-5', 'claude-opus-4-1'];
+      case 'custom':
+        return [];
       default:
         return ['gpt-5.5', 'gpt-5.4', 'gpt-5.4-mini'];
     }
@@ -374,7 +416,7 @@ export function AISection({ highlightId }: AISectionProps) {
             </div>
 
             {/* Cloud Provider Forms (API KEY) */}
-            {ai.config.provider !== 'ollama' && ai.config.provider !== 'lmstudio' && (
+            {usesSharedApiKey && (
               <div
                 id="ai-api-key"
                 className={cn(
@@ -397,11 +439,13 @@ export function AISection({ highlightId }: AISectionProps) {
                           ? 'https://aistudio.google.com/apikey'
                           : ai.config.provider === 'openai'
                             ? 'https://platform.openai.com/api-keys'
-                            : ai.config.provider === 'deepseek'
-                              ? 'https://platform.deepseek.com/api_keys'
-                              : ai.config.provider === 'qwen'
-                                ? 'https://dashscope.console.aliyun.com/apiKey'
-                                : '#'
+                            : ai.config.provider === 'anthropic'
+                              ? 'https://console.anthropic.com/settings/keys'
+                              : ai.config.provider === 'deepseek'
+                                ? 'https://platform.deepseek.com/api_keys'
+                                : ai.config.provider === 'qwen'
+                                  ? 'https://dashscope.console.aliyun.com/apiKey'
+                                  : '#'
                       }
                       target="_blank"
                       rel="noopener noreferrer"
@@ -424,11 +468,13 @@ export function AISection({ highlightId }: AISectionProps) {
                           ? t('ai.enterGeminiApiKey')
                           : ai.config.provider === 'openai'
                             ? t('ai.enterOpenAIApiKey')
-                            : ai.config.provider === 'deepseek'
-                              ? t('ai.enterDeepSeekApiKey')
-                              : ai.config.provider === 'qwen'
-                                ? t('ai.enterQwenApiKey')
-                                : t('ai.enterProxyApiKey')
+                            : ai.config.provider === 'anthropic'
+                              ? t('ai.enterAnthropicApiKey')
+                              : ai.config.provider === 'deepseek'
+                                ? t('ai.enterDeepSeekApiKey')
+                                : ai.config.provider === 'qwen'
+                                  ? t('ai.enterQwenApiKey')
+                                  : t('ai.enterProxyApiKey')
                       }
                       className="h-10 bg-background/50 border-border/80 pr-10 focus-visible:ring-primary"
                     />
@@ -610,6 +656,9 @@ export function AISection({ highlightId }: AISectionProps) {
               </div>
             )}
 
+            {/* Named OpenAI-compatible endpoints */}
+            {ai.config.provider === 'custom' && <CustomEndpointSettings />}
+
             <SettingsDivider className="my-2" />
 
             {/* Model Name and Quick select badges */}
@@ -724,6 +773,9 @@ export function AISection({ highlightId }: AISectionProps) {
                 </p>
               </div>
             </div>
+
+            <ModelRoutingSettings highlighted={highlightId === 'ai-model-routing'} />
+            <AIUsageSettings />
           </SettingsCard>
 
           {/* Section: Summary preferences */}
@@ -986,13 +1038,14 @@ export function AISection({ highlightId }: AISectionProps) {
                       type="button"
                       onClick={() =>
                         ai.updateConfig({
+                          whisper_backend: 'api',
                           whisper_endpoint_url: undefined,
                           whisper_model: undefined,
                         })
                       }
                       className={cn(
                         'px-3 py-1 text-[11px] font-medium rounded-md transition-all',
-                        ai.config.whisper_endpoint_url === undefined
+                        !isLocalWhisper && ai.config.whisper_endpoint_url === undefined
                           ? 'bg-primary shadow-sm text-primary-foreground'
                           : 'text-muted-foreground hover:text-foreground',
                       )}
@@ -1003,23 +1056,38 @@ export function AISection({ highlightId }: AISectionProps) {
                       type="button"
                       onClick={() =>
                         ai.updateConfig({
+                          whisper_backend: 'api',
                           whisper_endpoint_url: ai.config.whisper_endpoint_url ?? '',
                         })
                       }
                       className={cn(
                         'px-3 py-1 text-[11px] font-medium rounded-md transition-all',
-                        ai.config.whisper_endpoint_url !== undefined
+                        !isLocalWhisper && ai.config.whisper_endpoint_url !== undefined
                           ? 'bg-primary shadow-sm text-primary-foreground'
                           : 'text-muted-foreground hover:text-foreground',
                       )}
                     >
                       {t('ai.whisperCustom')}
                     </button>
+                    <button
+                      type="button"
+                      onClick={() => ai.updateConfig({ whisper_backend: 'local' })}
+                      className={cn(
+                        'px-3 py-1 text-[11px] font-medium rounded-md transition-all',
+                        isLocalWhisper
+                          ? 'bg-primary shadow-sm text-primary-foreground'
+                          : 'text-muted-foreground hover:text-foreground',
+                      )}
+                    >
+                      {t('ai.whisperLocal')}
+                    </button>
                   </div>
                 </div>
 
                 {/* Subform based on whisper provider selection */}
-                {ai.config.whisper_endpoint_url !== undefined ? (
+                {isLocalWhisper ? (
+                  <LocalWhisperSettings />
+                ) : ai.config.whisper_endpoint_url !== undefined ? (
                   // Custom Whisper backend subform
                   <div className="space-y-3 bg-muted/20 p-3 rounded-lg">
                     <div className="grid grid-cols-1 sm:grid-cols-2 gap-3">
@@ -1116,6 +1184,42 @@ export function AISection({ highlightId }: AISectionProps) {
                     </div>
                   </div>
                 )}
+
+                <div className="space-y-3 bg-muted/20 p-3 rounded-lg">
+                  <div className="flex items-center justify-between gap-3">
+                    <div className="min-w-0">
+                      <span className="text-[11px] font-medium">{t('ai.whisperDiarization')}</span>
+                      <p className="text-[10px] text-muted-foreground mt-0.5">
+                        {t('ai.whisperDiarizationDesc')}
+                      </p>
+                    </div>
+                    <Switch
+                      checked={ai.config.whisper_diarization || false}
+                      onCheckedChange={(enabled) =>
+                        ai.updateConfig({ whisper_diarization: enabled })
+                      }
+                      className="data-[state=checked]:bg-primary"
+                    />
+                  </div>
+                  {ai.config.whisper_diarization && !isLocalWhisper && (
+                    <div className="space-y-1">
+                      <span className="text-[11px] font-medium">
+                        {t('ai.whisperDiarizationModel')}
+                      </span>
+                      <Input
+                        type="text"
+                        value={ai.config.whisper_diarization_model || ''}
+                        onChange={(e) =>
+                          ai.updateConfig({
+                            whisper_diarization_model: e.target.value || undefined,
+                          })
+                        }
+                        placeholder="gpt-4o-transcribe-diarize"
+                        className="h-9 bg-background/50 text-xs focus-visible:ring-primary font-mono"
+                      />
+                    </div>
+                  )}
+                </div>
               </div>
             )}
           </SettingsCard>
@@ -0,0 +1,175 @@
+import { invoke } from '@tauri-apps/api/core';
+import { BarChart3, Loader2, RefreshCw, Trash2 } from 'lucide-react';
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { useToast } from '@/components/ui/toast';
+import { useAI } from '@/contexts/AIContext';
+import type { AIUsageReportRow } from '@/lib/types';
+
+const USAGE_REPORT_DAYS = 30;
+const DEFAULT_RESPONSE_CACHE_HOURS = 168;
+
+function formatCost(cost: number) {
+  return `$${cost < 0.01 && cost > 0 ? cost.toFixed(4) : cost.toFixed(2)}`;
+}
+
+export function AIUsageSettings() {
+  const { t } = useTranslation('settings');
+  const ai = useAI();
+  const toast = useToast();
+  const [rows, setRows] = useState<AIUsageReportRow[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [clearing, setClearing] = useState(false);
+
+  const loadReport = useCallback(async () => {
+    setLoading(true);
+    try {
+      setRows(
+        await invoke<AIUsageReportRow[]>('get_ai_usage_report', { days: USAGE_REPORT_DAYS }),
+      );
+    } catch (error) {
+      toast.error({ title: t('ai.usageLoadError'), message: String(error) });
+    } finally {
+      setLoading(false);
+    }
+  }, [t, toast]);
+
+  useEffect(() => {
+    void loadReport();
+  }, [loadReport]);
+
+  const handleCacheHoursChange = (value: string) => {
+    const parsed = Number.parseInt(value.trim(), 10);
+    ai.updateConfig({
+      response_cache_hours: Number.isNaN(parsed) ? undefined : Math.max(0, parsed),
+    });
+  };
+
+  const handleClearCache = async () => {
+    setClearing(true);
+    try {
+      const count = await invoke<number>('clear_ai_response_cache');
+      toast.success({ title: t('ai.responseCacheCleared', { count }) });
+    } catch (error) {
+      toast.error({ title: t('ai.clearResponseCache'), message: String(error) });
+    } finally {
+      setClearing(false);
+    }
+  };
+
+  const totalCost = rows.reduce((sum, row) => sum + row.cost_usd, 0);
+  const totalRequests = rows.reduce((sum, row) => sum + row.requests, 0);
+  const hasUnpriced = rows.some((row) => row.unpriced_requests > 0);
+
+  return (
+    <div className="space-y-3 p-3 rounded-xl bg-muted/20">
+      <div className="flex items-center justify-between gap-2">
+        <div className="flex items-center gap-1.5">
+          <BarChart3 className="w-4 h-4 text-primary" />
+          <span className="text-sm font-medium">{t('ai.usageTitle')}</span>
+        </div>
+        <Button variant="ghost" size="sm" className="h-8" onClick={loadReport} disabled={loading}>
+          {loading ? (
+            <Loader2 className="h-3.5 w-3.5 animate-spin" />
+          ) : (
+            <RefreshCw className="h-3.5 w-3.5" />
+          )}
+        </Button>
+      </div>
+      <p className="text-[11px] text-muted-foreground">
+        {t('ai.usageDesc', { days: USAGE_REPORT_DAYS })}
+      </p>
+
+      <div className="flex flex-col gap-2 sm:flex-row sm:items-end">
+        <div className="space-y-1 flex-1">
+          <span className="text-[11px] font-medium">{t('ai.responseCacheHours')}</span>
+          <Input
+            type="number"
+            inputMode="numeric"
+            min={0}
+            value={ai.config.response_cache_hours ?? DEFAULT_RESPONSE_CACHE_HOURS}
+            onChange={(e) => handleCacheHoursChange(e.target.value)}
+            className="h-9 bg-background/50 text-xs"
+          />
+        </div>
+        <Button
+          variant="outline"
+          size="sm"
+          className="h-9"
+          onClick={handleClearCache}
+          disabled={clearing}
+        >
+          {clearing ? (
+            <Loader2 className="h-3.5 w-3.5 animate-spin" />
+          ) : (
+            <Trash2 className="h-3.5 w-3.5" />
+          )}
+          {t('ai.clearResponseCache')}
+        </Button>
+      </div>
+      <p className="text-[10px] text-muted-foreground">{t('ai.responseCacheHoursDesc')}</p>
+
+      {rows.length === 0 ? (
+        <p className="text-xs text-muted-foreground">{t('ai.usageEmpty')}</p>
+      ) : (
+        <div className="space-y-2">
+          <div className="overflow-x-auto">
+            <table className="w-full text-[11px]">
+              <thead className="text-muted-foreground">
+                <tr className="text-left">
+                  <th className="py-1 pr-2 font-medium">{t('ai.usageDay')}</th>
+                  <th className="py-1 pr-2 font-medium">{t('ai.usageModel')}</th>
+                  <th className="py-1 pr-2 font-medium text-right">{t('ai.usageRequests')}</th>
+                  <th className="py-1 pr-2 font-medium text-right">{t('ai.usageTokens')}</th>
+                  <th className="py-1 pr-2 font-medium text-right">{t('ai.usageLatency')}</th>
+                  <th className="py-1 font-medium text-right">{t('ai.usageCost')}</th>
+                </tr>
+              </thead>
+              <tbody>
+                {rows.map((row) => (
+                  <tr
+                    key={`${row.day}-${row.provider}-${row.model}`}
+                    className="border-t border-border/40"
+                  >
+                    <td className="py-1 pr-2 whitespace-nowrap">{row.day}</td>
+                    <td className="py-1 pr-2">
+                      <span className="font-medium">{row.provider}</span>{' '}
+                      <span className="font-mono text-muted-foreground">{row.model}</span>
+                    </td>
+                    <td className="py-1 pr-2 text-right whitespace-nowrap">
+                      {row.requests}
+                      {row.cached_requests > 0 && (
+                        <span className="text-muted-foreground">
+                          {' '}
+                          ({t('ai.usageCached', { count: row.cached_requests })})
+                        </span>
+                      )}
+                    </td>
+                    <td className="py-1 pr-2 text-right whitespace-nowrap font-mono">
+                      {row.input_tokens.toLocaleString()} / {row.output_tokens.toLocaleString()}
+                    </td>
+                    <td className="py-1 pr-2 text-right whitespace-nowrap">
+                      {(row.average_latency_ms / 1000).toFixed(1)}s
+                    </td>
+                    <td className="py-1 text-right whitespace-nowrap">
+                      {formatCost(row.cost_usd)}
+                      {row.unpriced_requests > 0 && '*'}
+                    </td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </div>
+          <p className="text-[11px] font-medium">
+            {t('ai.usageTotal', { cost: formatCost(totalCost), requests: totalRequests })}
+          </p>
+          {hasUnpriced && (
+            <p className="text-[10px] text-muted-foreground">* {t('ai.usageUnpriced')}</p>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,219 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { Check, Cpu, Download, Info, Loader2, RefreshCw } from 'lucide-react';
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { useAI } from '@/contexts/AIContext';
+import type { DownloadProgress } from '@/contexts/DependenciesContext';
+import { localizeUnknownError } from '@/lib/backend-error';
+import type { LocalWhisperEngine, LocalWhisperModelInfo, LocalWhisperStatus } from '@/lib/types';
+
+const DEFAULT_LOCAL_WHISPER_MODEL = 'base';
+
+function formatModelSize(bytes: number | null) {
+  if (!bytes) return '';
+  return `${Math.round(bytes / 1_000_000)} MB`;
+}
+
+export function LocalWhisperSettings() {
+  const { t } = useTranslation('settings');
+  const ai = useAI();
+  const engine: LocalWhisperEngine = ai.config.whisper_local_engine ?? 'whisper-cpp';
+  const model = ai.config.whisper_local_model || DEFAULT_LOCAL_WHISPER_MODEL;
+  const binaryPath = ai.config.whisper_local_binary_path;
+  const [status, setStatus] = useState<LocalWhisperStatus | null>(null);
+  const [models, setModels] = useState<LocalWhisperModelInfo[]>([]);
+  const [checking, setChecking] = useState(false);
+  const [downloadProgress, setDownloadProgress] = useState<DownloadProgress | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const refreshStatus = useCallback(async () => {
+    setChecking(true);
+    try {
+      const [nextStatus, nextModels] = await Promise.all([
+        invoke<LocalWhisperStatus>('check_local_whisper', {
+          engine,
+          binaryPath: binaryPath || null,
+        }),
+        invoke<LocalWhisperModelInfo[]>('list_local_whisper_models'),
+      ]);
+      setStatus(nextStatus);
+      setModels(nextModels);
+    } catch (err) {
+      console.error('Failed to check local Whisper:', err);
+    } finally {
+      setChecking(false);
+    }
+  }, [engine, binaryPath]);
+
+  useEffect(() => {
+    refreshStatus();
+  }, [refreshStatus]);
+
+  useEffect(() => {
+    const unlisten = listen<DownloadProgress>('whisper-model-download-progress', (event) => {
+      setDownloadProgress(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleDownloadModel = useCallback(async () => {
+    setError(null);
+    setDownloadProgress({ stage: 'checksum', percent: 0, downloaded: 0, total: 0 });
+    try {
+      await invoke<string>('download_local_whisper_model', { model });
+      await refreshStatus();
+    } catch (err) {
+      setError(localizeUnknownError(err));
+    } finally {
+      setDownloadProgress(null);
+    }
+  }, [model, refreshStatus]);
+
+  const selectedModel = models.find((item) => item.name === model);
+  const needsModelFile = engine === 'whisper-cpp';
+
+  return (
+    <div className="space-y-3 bg-muted/20 p-3 rounded-lg">
+      <div className="grid grid-cols-1 sm:grid-cols-2 gap-3">
+        <div className="space-y-1">
+          <span className="text-[11px] font-medium">{t('ai.whisperLocalEngine')}</span>
+          <Select
+            value={engine}
+            onValueChange={(value) =>
+              ai.updateConfig({ whisper_local_engine: value as LocalWhisperEngine })
+            }
+          >
+            <SelectTrigger className="h-9 bg-background/50 text-xs">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="whisper-cpp" className="text-xs">
+                whisper.cpp
+              </SelectItem>
+              <SelectItem value="faster-whisper" className="text-xs">
+                faster-whisper
+              </SelectItem>
+            </SelectContent>
+          </Select>
+        </div>
+        <div className="space-y-1">
+          <span className="text-[11px] font-medium">{t('ai.whisperLocalModel')}</span>
+          <Select
+            value={model}
+            onValueChange={(value) => ai.updateConfig({ whisper_local_model: value })}
+          >
+            <SelectTrigger className="h-9 bg-background/50 text-xs font-mono">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {models.map((item) => (
+                <SelectItem key={item.name} value={item.name} className="text-xs font-mono">
+                  {item.name}
+                  {needsModelFile && item.downloaded && ` · ${formatModelSize(item.size_bytes)}`}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+      </div>
+
+      <div className="space-y-1">
+        <span className="text-[11px] font-medium">{t('ai.whisperLocalBinaryPath')}</span>
+        <Input
+          type="text"
+          value={binaryPath || ''}
+          onChange={(e) =>
+            ai.updateConfig({ whisper_local_binary_path: e.target.value || undefined })
+          }
+          placeholder={t('ai.whisperLocalBinaryPathPlaceholder')}
+          className="h-9 bg-background/50 text-xs focus-visible:ring-primary font-mono"
+        />
+      </div>
+
+      <div className="flex items-center justify-between gap-2 rounded-lg border border-border/50 bg-background/40 p-2.5 text-xs">
+        <div className="flex min-w-0 items-center gap-2">
+          <Cpu className="h-4 w-4 flex-shrink-0 text-primary" />
+          {status?.installed ? (
+            <span className="truncate">
+              {t('ai.whisperLocalFound', {
+                version: status.version ?? t('ai.whisperLocalUnknownVersion'),
+              })}
+              <span className="ml-1 font-mono text-muted-foreground">{status.binary_path}</span>
+            </span>
+          ) : (
+            <span className="text-amber-600 dark:text-amber-400">
+              {t('ai.whisperLocalNotFound')}
+            </span>
+          )}
+        </div>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 px-2"
+          onClick={refreshStatus}
+          disabled={checking}
+        >
+          {checking ? (
+            <Loader2 className="h-3.5 w-3.5 animate-spin" />
+          ) : (
+            <RefreshCw className="h-3.5 w-3.5" />
+          )}
+        </Button>
+      </div>
+
+      {needsModelFile && (
+        <div className="flex items-center justify-between gap-2 rounded-lg border border-border/50 bg-background/40 p-2.5 text-xs">
+          {selectedModel?.downloaded ? (
+            <span className="flex items-center gap-1.5 text-emerald-600 dark:text-emerald-400">
+              <Check className="h-4 w-4" />
+              {t('ai.whisperLocalModelReady', { model })}
+            </span>
+          ) : (
+            <>
+              <span className="text-muted-foreground">
+                {downloadProgress
+                  ? t('ai.whisperLocalModelDownloading', { percent: downloadProgress.percent })
+                  : t('ai.whisperLocalModelMissing', { model })}
+              </span>
+              <Button
+                variant="outline"
+                size="sm"
+                className="h-7"
+                onClick={handleDownloadModel}
+                disabled={downloadProgress !== null}
+              >
+                {downloadProgress ? (
+                  <Loader2 className="h-3.5 w-3.5 animate-spin" />
+                ) : (
+                  <Download className="h-3.5 w-3.5" />
+                )}
+                {t('ai.whisperLocalDownloadModel')}
+              </Button>
+            </>
+          )}
+        </div>
+      )}
+
+      {error && <p className="text-[11px] text-destructive">{error}</p>}
+
+      <div className="flex items-start gap-1.5 rounded-md bg-background/40 px-2 py-1.5 text-[10px] leading-normal text-muted-foreground">
+        <Info className="mt-px h-3.5 w-3.5 flex-shrink-0 text-primary" />
+        <div className="min-w-0 flex-1">
+          {needsModelFile ? t('ai.whisperLocalHintCpp') : t('ai.whisperLocalHintFaster')}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -85,7 +85,7 @@ export function WhisperGenerateDialog({ open: isOpen, onClose }: WhisperGenerate
     // Check if whisper API key is configured
     const whisperApiKey =
       ai.config.whisper_api_key || (ai.config.provider === 'openai' ? ai.config.api_key : '');
-    if (!whisperApiKey) {
+    if (!whisperApiKey && ai.config.whisper_backend !== 'local') {
       setError(t('whisper.noApiKey'));
       return;
     }
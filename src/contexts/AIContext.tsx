@@ -54,12 +54,13 @@ interface AIContextValue {
   updateConfig: (updates: Partial<AIConfig>) => Promise<void>;
   testConnection: () => Promise<void>;
   generateSummary: (transcript: string, historyId?: string) => Promise<string>;
-  fetchTranscript: (url: string) => Promise<string>;
+  fetchTranscript: (url: string, historyId?: string) => Promise<string>;
   loadModels: (provider: AIProviderType) => void;
 
   // Background task actions
-  startSummaryTask: (historyId: string, url: string) => void;
-  startQueueSummaryTask: (taskId: string, itemInfo: QueueItemInfo) => void;
+  // `refresh` skips the cached AI response, for regenerate and retry
+  startSummaryTask: (historyId: string, url: string, refresh?: boolean) => void;
+  startQueueSummaryTask: (taskId: string, itemInfo: QueueItemInfo, refresh?: boolean) => void;
   getSummaryTask: (historyId: string) => SummaryTask | undefined;
   clearSummaryTask: (historyId: string) => void;
 }
@@ -81,6 +82,12 @@ const defaultConfig: AIConfig = {
   whisper_api_key: undefined,
   whisper_endpoint_url: undefined,
   whisper_model: undefined,
+  whisper_backend: 'api',
+  whisper_local_engine: 'whisper-cpp',
+  whisper_local_model: undefined,
+  whisper_local_binary_path: undefined,
+  whisper_diarization: false,
+  whisper_diarization_model: undefined,
 };
 
 const AIContext = createContext<AIContextValue | undefined>(undefined);
@@ -187,7 +194,7 @@ export function AIProvider({ children }: { children: ReactNode }) {
   );
 
   const fetchTranscript = useCallback(
-    async (url: string): Promise<string> => {
+    async (url: string, historyId?: string): Promise<string> => {
       const languages = config.transcript_languages || ['en'];
       const { cookieSettings, proxySettings } = loadNetworkSettings();
       const networkOptions = buildCookieProxyInvokeOptions(cookieSettings, proxySettings);
@@ -199,6 +206,7 @@ export function AIProvider({ children }: { children: ReactNode }) {
           url,
           languages,
           ...networkOptions,
+          historyId: historyId || null,
         });
 
         const normalizedTranscript = transcript?.trim();
@@ -220,8 +228,9 @@ export function AIProvider({ children }: { children: ReactNode }) {
       if (config.whisper_enabled) {
         // Determine which API key to use for Whisper
         const whisperKey = config.provider === 'openai' ? config.api_key : config.whisper_api_key;
+        const isLocalWhisper = config.whisper_backend === 'local';
 
-        if (whisperKey) {
+        if (whisperKey || isLocalWhisper) {
           if (import.meta.env.DEV) {
             console.log('[AI] Using Whisper transcription for:', url);
           }
@@ -230,11 +239,12 @@ export function AIProvider({ children }: { children: ReactNode }) {
             const whisperTranscript = await invoke<string>('transcribe_url_with_whisper', {
               url,
               responseFormat: 'text',
-              openaiApiKey: whisperKey,
+              openaiApiKey: whisperKey ?? '',
               language: languages[0] || null, // Use first preferred language as hint
               ...networkOptions,
               whisperEndpointUrl: config.whisper_endpoint_url || null,
               whisperModel: config.whisper_model || null,
+              historyId: historyId || null,
             });
 
             const normalizedWhisperTranscript = whisperTranscript?.trim();
@@ -276,6 +286,7 @@ export function AIProvider({ children }: { children: ReactNode }) {
       config.whisper_api_key,
       config.whisper_endpoint_url,
       config.whisper_model,
+      config.whisper_backend,
     ],
   );
 
@@ -292,7 +303,7 @@ export function AIProvider({ children }: { children: ReactNode }) {
   }, []);
 
   const startSummaryTask = useCallback(
-    (historyId: string, url: string) => {
+    (historyId: string, url: string, refresh = false) => {
       // Check if AI is enabled
       if (!config.enabled) {
         setSummaryTasks((prev) => {
@@ -334,7 +345,7 @@ export function AIProvider({ children }: { children: ReactNode }) {
           if (import.meta.env.DEV) {
             console.log(`[AI] Fetching transcript for URL with fallback chain: ${url}`);
           }
-          const transcript = await fetchTranscript(url);
+          const transcript = await fetchTranscript(url, historyId);
 
           if (import.meta.env.DEV) {
             console.log(
@@ -351,6 +362,7 @@ export function AIProvider({ children }: { children: ReactNode }) {
             transcript,
             historyId,
             title: null, // History items don't have title readily available
+            refresh,
           });
 
           if (import.meta.env.DEV) {
@@ -392,7 +404,7 @@ export function AIProvider({ children }: { children: ReactNode }) {
 
   // Start summary task for queue items (saves to history when done)
   const startQueueSummaryTask = useCallback(
-    (taskId: string, itemInfo: QueueItemInfo) => {
+    (taskId: string, itemInfo: QueueItemInfo, refresh = false) => {
       // Check if AI is enabled
       if (!config.enabled) {
         setSummaryTasks((prev) => {
@@ -440,6 +452,7 @@ export function AIProvider({ children }: { children: ReactNode }) {
             transcript,
             historyId: null,
             title: itemInfo.title,
+            refresh,
           });
 
           // Save to history with summary
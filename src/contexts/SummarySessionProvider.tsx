@@ -22,6 +22,11 @@ interface SummaryProgressPayload {
   chunkCount: number;
 }
 
+interface SummaryStreamPayload {
+  requestId: string;
+  delta: string;
+}
+
 export function SummarySessionProvider({ children }: { children: ReactNode }) {
   const ai = useAI();
   const { cookieSettings, getProxyUrl } = useDownload();
@@ -117,6 +122,19 @@ export function SummarySessionProvider({ children }: { children: ReactNode }) {
     };
   }, []);
 
+  useEffect(() => {
+    const unlistenPromise = listen<SummaryStreamPayload>('summary-stream', (event) => {
+      if (event.payload.requestId !== backendSummaryRequestIdRef.current) {
+        return;
+      }
+      dispatch({ type: 'append-stream', delta: event.payload.delta });
+    });
+
+    return () => {
+      void unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
   const runSummary = useCallback(
     async (inputUrl: string) => {
       const requestSequence = requestIdRef.current + 1;
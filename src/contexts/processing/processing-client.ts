@@ -3,12 +3,26 @@ import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { open } from '@tauri-apps/plugin-dialog';
 import { readFile } from '@tauri-apps/plugin-fs';
 import { openFileLocation } from '@/lib/open-file-location';
+import type { TemplatePresetTaskType } from '@/lib/processing-presets';
 import type {
+  AudioAnalysis,
+  ContactSheetResult,
+  FFmpegCapabilities,
   FFmpegCommandResult,
+  ProcessingBatchProgress,
+  ProcessingBatchRequest,
+  ProcessingBatchResult,
   ProcessingJob,
+  ProcessingPipelineProgress,
+  ProcessingPipelineRequest,
+  ProcessingPipelineResult,
   ProcessingPreset,
   ProcessingProgress,
+  ProcessingQueueRequest,
+  ProcessingQueueSettings,
   ProcessingTaskType,
+  TargetSizeEncode,
+  ThumbnailSpriteResult,
   VideoMetadata,
 } from '@/lib/types';
 
@@ -74,6 +88,29 @@ export async function generateVideoThumbnail(inputPath: string) {
   return invoke<string>('generate_video_thumbnail', { inputPath });
 }
 
+export async function generateContactSheet(
+  inputPath: string,
+  grid?: { columns?: number; rows?: number; tileWidth?: number },
+) {
+  return invoke<ContactSheetResult>('generate_contact_sheet', {
+    inputPath,
+    columns: grid?.columns ?? null,
+    rows: grid?.rows ?? null,
+    tileWidth: grid?.tileWidth ?? null,
+  });
+}
+
+export async function generateThumbnailSprites(
+  inputPath: string,
+  options?: { intervalSeconds?: number; tileWidth?: number },
+) {
+  return invoke<ThumbnailSpriteResult>('generate_thumbnail_sprites', {
+    inputPath,
+    intervalSeconds: options?.intervalSeconds ?? null,
+    tileWidth: options?.tileWidth ?? null,
+  });
+}
+
 export async function getProcessingAttachmentInfo(
   path: string,
 ): Promise<ProcessingAttachmentInfoResult> {
@@ -111,11 +148,14 @@ export async function saveProcessingJob(input: {
   await invoke('save_processing_job', input);
 }
 
+/** Runs the command through the processing queue; resolves once the job has finished */
 export async function executeFfmpegCommand(input: {
   jobId: string;
   commandArgs: string[];
   inputPath: string;
   outputPath: string;
+  targetSize?: TargetSizeEncode | null;
+  audioAnalysis?: AudioAnalysis | null;
 }): Promise<void> {
   await invoke('execute_ffmpeg_command', input);
 }
@@ -133,9 +173,50 @@ export async function getProcessingHistory(limit = 50): Promise<ProcessingJob[]>
   return invoke<ProcessingJob[]>('get_processing_history', { limit });
 }
 
+/** FFmpeg output of a job, including passes that are still running */
+export async function getProcessingJobLog(id: string): Promise<string> {
+  return invoke<string>('get_processing_job_log', { id });
+}
+
+/** Resolves once the job is queued; cancel it with cancelFfmpeg(job.id) */
+export async function enqueueProcessingJob(
+  request: ProcessingQueueRequest,
+): Promise<ProcessingJob> {
+  return invoke<ProcessingJob>('enqueue_processing_job', { request });
+}
+
+export async function getProcessingQueue(): Promise<ProcessingJob[]> {
+  return invoke<ProcessingJob[]>('get_processing_queue');
+}
+
+export function onProcessingQueueChanged(
+  handler: (event: { payload: ProcessingJob[] }) => void,
+): Promise<UnlistenFn> {
+  return listen<ProcessingJob[]>('processing-queue-changed', handler);
+}
+
+export async function setProcessingJobPriority(id: string, priority: number): Promise<void> {
+  await invoke('set_processing_job_priority', { id, priority });
+}
+
+/** Queue an interrupted, failed or cancelled queue job again */
+export async function retryProcessingJob(id: string): Promise<void> {
+  await invoke('retry_processing_job', { id });
+}
+
+export async function getProcessingQueueSettings(): Promise<ProcessingQueueSettings> {
+  return invoke<ProcessingQueueSettings>('get_processing_queue_settings');
+}
+
+export async function setProcessingQueueSettings(
+  settings: ProcessingQueueSettings,
+): Promise<ProcessingQueueSettings> {
+  return invoke<ProcessingQueueSettings>('set_processing_queue_settings', { settings });
+}
+
 export async function generateQuickActionCommand(input: {
   inputPath: string | null;
-  taskType: ProcessingTaskType;
+  taskType: ProcessingTaskType | TemplatePresetTaskType;
   options: Record<string, unknown>;
   timelineStart: number | null;
   timelineEnd: number | null;
@@ -145,10 +226,43 @@ export async function generateQuickActionCommand(input: {
   return invoke<FFmpegCommandResult>('generate_quick_action_command', input);
 }
 
+/** Cached per FFmpeg version; pass refresh to probe the binary again */
+export async function getFFmpegCapabilities(refresh = false): Promise<FFmpegCapabilities> {
+  return invoke<FFmpegCapabilities>('get_ffmpeg_capabilities', { refresh });
+}
+
 export async function cancelFfmpeg(jobId: string): Promise<void> {
   await invoke('cancel_ffmpeg', { jobId });
 }
 
+/** Resolves when every file has finished; cancel the whole batch with cancelFfmpeg(batchId) */
+export async function startProcessingBatch(
+  batchId: string,
+  request: ProcessingBatchRequest,
+): Promise<ProcessingBatchResult> {
+  return invoke<ProcessingBatchResult>('start_processing_batch', { batchId, request });
+}
+
+export function onProcessingBatchProgress(
+  handler: (event: { payload: ProcessingBatchProgress }) => void,
+): Promise<UnlistenFn> {
+  return listen<ProcessingBatchProgress>('processing-batch-progress', handler);
+}
+
+/** Resolves with the final file; cancel with cancelFfmpeg(jobId) */
+export async function runProcessingPipeline(
+  jobId: string,
+  request: ProcessingPipelineRequest,
+): Promise<ProcessingPipelineResult> {
+  return invoke<ProcessingPipelineResult>('run_processing_pipeline', { jobId, request });
+}
+
+export function onProcessingPipelineProgress(
+  handler: (event: { payload: ProcessingPipelineProgress }) => void,
+): Promise<UnlistenFn> {
+  return listen<ProcessingPipelineProgress>('processing-pipeline-progress', handler);
+}
+
 export async function revealOutputInFolder(path: string): Promise<void> {
   await openFileLocation(path);
 }
@@ -168,10 +282,21 @@ export async function getProcessingPresets(): Promise<ProcessingPreset[]> {
 export async function saveProcessingPreset(input: {
   name: string;
   description?: string;
-  command: string;
   taskType: string;
-}): Promise<void> {
-  await invoke('save_processing_preset', input);
+  promptTemplate?: string;
+  argsTemplate?: string[];
+  templateParams?: Record<string, string>;
+  outputExtension?: string;
+}): Promise<ProcessingPreset> {
+  return invoke<ProcessingPreset>('save_processing_preset', input);
+}
+
+export async function exportProcessingPreset(id: string, path: string): Promise<void> {
+  await invoke('export_processing_preset', { id, path });
+}
+
+export async function importProcessingPreset(path: string): Promise<ProcessingPreset> {
+  return invoke<ProcessingPreset>('import_processing_preset', { path });
 }
 
 export async function deleteProcessingPreset(id: string): Promise<void> {
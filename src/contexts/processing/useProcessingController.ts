@@ -3,6 +3,11 @@ import { useCallback, useEffect, useRef, useState } from 'react';
 import { toAssetUrl } from '@/lib/asset-access';
 import { localizeUnknownError } from '@/lib/backend-error';
 import { createClientId } from '@/lib/client-id';
+import {
+  buildPresetArgsTemplate,
+  type TemplatePresetTaskType,
+  templatePresetTaskType,
+} from '@/lib/processing-presets';
 import type {
   ChatAttachment,
   ChatMessage,
@@ -122,8 +127,9 @@ export interface ProcessingContextValue {
   sendMessage: (content: string) => Promise<void>;
   selectOutputDirectory: () => Promise<void>;
   generateCommand: (
-    taskType: ProcessingTaskType,
+    taskType: ProcessingTaskType | TemplatePresetTaskType,
     options?: Record<string, unknown>,
+    label?: string,
   ) => Promise<void>;
 
   // Image Actions
@@ -705,7 +711,11 @@ export function useProcessingController(): ProcessingContextValue {
 
   // Generate command from quick action
   const generateCommand = useCallback(
-    async (taskType: ProcessingTaskType, options?: Record<string, unknown>) => {
+    async (
+      taskType: ProcessingTaskType | TemplatePresetTaskType,
+      options?: Record<string, unknown>,
+      label?: string,
+    ) => {
       if (!videoMetadata) return;
 
       setIsGenerating(true);
@@ -729,7 +739,9 @@ export function useProcessingController(): ProcessingContextValue {
         const assistantMessage: ChatMessage = {
           id: createClientId(),
           role: 'assistant',
-          content: `**${taskType.replace('_', ' ').toUpperCase()}**\n\n${result.explanation}`,
+          content:
+            `**${label ?? taskType.replace('_', ' ').toUpperCase()}**\n\n${result.explanation}` +
+            (result.warnings.length > 0 ? `\n\n${result.warnings.join('\n')}` : ''),
           timestamp: new Date().toISOString(),
           command: result,
         };
@@ -775,6 +787,8 @@ export function useProcessingController(): ProcessingContextValue {
           commandArgs: cmdToExecute.command_args,
           inputPath: videoPath,
           outputPath: cmdToExecute.output_path,
+          targetSize: cmdToExecute.target_size ?? null,
+          audioAnalysis: cmdToExecute.audio_analysis ?? null,
         });
 
         // Update job status
@@ -891,21 +905,27 @@ export function useProcessingController(): ProcessingContextValue {
   // Save preset
   const savePreset = useCallback(
     async (name: string, description?: string) => {
-      if (!generatedCommand) return;
+      if (!generatedCommand || !videoPath) return;
+
+      // Keep the request as a prompt too, for commands that cannot become a template
+      const template = buildPresetArgsTemplate(generatedCommand, videoPath);
+      const prompt = [...messages].reverse().find((m) => m.role === 'user')?.content;
 
       try {
         await saveProcessingPreset({
           name,
           description,
-          command: generatedCommand.command,
           taskType: 'custom',
+          promptTemplate: prompt,
+          argsTemplate: template?.argsTemplate,
+          outputExtension: template?.outputExtension,
         });
         await loadPresets();
       } catch (error) {
         console.error('Failed to save preset:', error);
       }
     },
-    [generatedCommand, loadPresets],
+    [generatedCommand, videoPath, messages, loadPresets],
   );
 
   // Delete preset
@@ -921,9 +941,13 @@ export function useProcessingController(): ProcessingContextValue {
   // Apply preset
   const applyPreset = useCallback(
     async (preset: ProcessingPreset) => {
+      if (preset.args_template) {
+        await generateCommand(templatePresetTaskType(preset.id), {}, preset.name);
+        return;
+      }
       await sendMessage(preset.prompt_template);
     },
-    [sendMessage],
+    [generateCommand, sendMessage],
   );
 
   // Batch operations
@@ -0,0 +1,45 @@
+import type { FFmpegCommandResult } from './types';
+
+/** Task type that makes generate_quick_action_command run a saved template preset */
+export type TemplatePresetTaskType = `preset:${string}`;
+
+export function templatePresetTaskType(presetId: string): TemplatePresetTaskType {
+  return `preset:${presetId}`;
+}
+
+export interface PresetArgsTemplate {
+  argsTemplate: string[];
+  outputExtension: string;
+}
+
+/**
+ * Turn a generated command into a reusable argument template by replacing the input and
+ * output paths with `{input}` and `{output}`. Returns null when the command does not end
+ * with its output file, since the backend requires `{output}` as the last argument, or when
+ * it needs several passes.
+ */
+export function buildPresetArgsTemplate(
+  command: Pick<
+    FFmpegCommandResult,
+    'command_args' | 'output_path' | 'target_size' | 'audio_analysis'
+  >,
+  inputPath: string,
+): PresetArgsTemplate | null {
+  // Multi-pass commands are more than one invocation and cannot be stored as one template
+  if (command.target_size || command.audio_analysis) return null;
+  const args = command.command_args;
+  if (args.length === 0 || args[args.length - 1] !== command.output_path) return null;
+  if (!args.includes(inputPath)) return null;
+
+  const extension = /\.([A-Za-z0-9]{1,8})$/.exec(command.output_path)?.[1];
+  if (!extension) return null;
+
+  return {
+    argsTemplate: args.map((arg) => {
+      if (arg === inputPath) return '{input}';
+      if (arg === command.output_path) return '{output}';
+      return arg;
+    }),
+    outputExtension: extension.toLowerCase(),
+  };
+}
@@ -45,6 +45,8 @@ export interface SummarySessionState {
   loadingParams: Record<string, string | number>;
   error: string | null;
   result: SummarySessionResult | null;
+  /** Summary text received so far while the provider is still streaming */
+  streamingSummary: string;
   saved: boolean;
   showFullSummary: boolean;
   showSettings: boolean;
@@ -62,6 +64,7 @@ export type SummarySessionAction =
       loadingStatus: string;
       loadingParams?: Record<string, string | number>;
     }
+  | { type: 'append-stream'; delta: string }
   | { type: 'complete'; result: SummarySessionResult }
   | { type: 'fail'; error: string }
   | { type: 'cancel' }
@@ -80,6 +83,7 @@ export function createInitialSummarySessionState(
     loadingParams: {},
     error: null,
     result: null,
+    streamingSummary: '',
     saved: false,
     showFullSummary: true,
     showSettings: false,
@@ -137,6 +141,7 @@ export function summarySessionReducer(
         loadingParams: {},
         error: null,
         result: null,
+        streamingSummary: '',
         saved: false,
         showFullSummary: true,
       };
@@ -148,9 +153,15 @@ export function summarySessionReducer(
         loadingStatus: action.loadingStatus,
         loadingParams: action.loadingParams || {},
       };
+    case 'append-stream':
+      if (!state.isLoading) {
+        return state;
+      }
+      return { ...state, streamingSummary: state.streamingSummary + action.delta };
     case 'complete':
       return {
         ...state,
+        streamingSummary: '',
         status: 'completed',
         isLoading: false,
         loadingStatus: '',
@@ -163,6 +174,7 @@ export function summarySessionReducer(
         ...state,
         status: 'error',
         isLoading: false,
+        streamingSummary: '',
         loadingStatus: '',
         loadingParams: {},
         error: action.error,
@@ -172,6 +184,7 @@ export function summarySessionReducer(
         ...state,
         status: 'cancelled',
         isLoading: false,
+        streamingSummary: '',
         loadingStatus: '',
         loadingParams: {},
         error: null,
@@ -397,8 +397,8 @@ export interface DownloadProgress {
   elapsed_time?: string; // e.g. "00:00:07"
 }
 
-export type PluginRuntimeLanguage = 'javascript' | 'python';
-export type PluginProvider = 'deno' | 'python';
+export type PluginRuntimeLanguage = 'javascript' | 'python' | 'wasm';
+export type PluginProvider = 'deno' | 'python' | 'wasm';
 export type PluginPackageSourceKind = 'workspace' | 'package-ywp';
 export type PluginManifestIconName = string;
 export type PluginFilesystemPermission =
@@ -415,14 +415,18 @@ export type PluginToolPermission = 'tool.ffmpeg.run' | 'tool.ytdlp.run';
 
 export interface PluginPermissionSet {
   network: boolean;
+  networkHosts?: string[];
   fs: PluginFilesystemPermission[];
   tools: PluginToolPermission[];
+  resolver?: boolean;
 }
 
 export interface PluginPermissionApproval {
   network: boolean;
+  networkHosts?: string[];
   fs: PluginFilesystemPermission[];
   tools: PluginToolPermission[];
+  resolver?: boolean;
 }
 
 export type PluginConfigFieldInputType =
@@ -476,6 +480,11 @@ export interface PluginI18nSpec {
   directory?: string | null;
 }
 
+export interface PluginResolverSpec {
+  urlPatterns: string[];
+  priority?: number;
+}
+
 export interface PluginManifest {
   id: string;
   slug: string;
@@ -497,6 +506,7 @@ export interface PluginManifest {
   checksum?: string | null;
   publishedAt?: string | null;
   i18n?: PluginI18nSpec | null;
+  resolver?: PluginResolverSpec | null;
 }
 
 export interface PluginPackageSource {
@@ -533,6 +543,17 @@ export interface PluginInstallation {
   signerFingerprint?: string | null;
   signatureAlgorithm?: string | null;
   signedAt?: string | null;
+  versionHistory?: PluginVersionRecord[];
+  rollbackVersion?: string | null;
+}
+
+export interface PluginVersionRecord {
+  version: string;
+  installedAt: string;
+  action: 'install' | 'update' | 'rollback';
+  packageChecksum?: string | null;
+  signerFingerprint?: string | null;
+  signedAt?: string | null;
 }
 
 export interface PluginSummary {
@@ -603,6 +624,25 @@ export interface PreparedPluginStorePackage {
   inspection: PluginPackageInspection;
 }
 
+export type PluginUpdateSource = 'store' | 'github-release';
+
+export interface PluginUpdateInfo {
+  pluginId: string;
+  name: string;
+  currentVersion: string;
+  latestVersion: string;
+  source: PluginUpdateSource;
+  packageUrl: string;
+  releaseTag?: string | null;
+}
+
+export interface PreparedPluginUpdate {
+  id: string;
+  update: PluginUpdateInfo;
+  inspection: PluginPackageInspection;
+  addedPermissions: string[];
+}
+
 export interface PluginWorkspaceSummary {
   pluginId: string;
   slug: string;
@@ -967,6 +1007,64 @@ export interface HistoryEntry {
   collections: HistoryCollection[];
 }
 
+export type TranscriptSource = 'platform_captions' | 'auto_captions' | 'whisper';
+
+export interface TranscriptSegment {
+  start_ms: number;
+  end_ms: number;
+  text: string;
+}
+
+// Timed transcript saved with a history entry
+export interface HistoryTranscript {
+  history_id: string;
+  language?: string;
+  source: TranscriptSource;
+  segments: TranscriptSegment[];
+  updated_at: string; // ISO 8601
+}
+
+export type TranscriptExportFormat = 'srt' | 'vtt' | 'txt';
+
+// Transcript position cited by a Q&A answer
+export interface ChatCitation {
+  history_id: string;
+  start_ms: number;
+}
+
+// One turn of the Q&A conversation saved with a history entry
+export interface HistoryChatMessage {
+  id: string;
+  history_id: string;
+  history_ids: string[]; // Every Library item the conversation is about, sorted
+  role: 'user' | 'assistant';
+  content: string;
+  citations: ChatCitation[];
+  created_at: string; // ISO 8601
+}
+
+export type StructuredOutputKind = 'chapters' | 'highlights' | 'tags' | 'title';
+
+export interface AIChapter {
+  startSeconds: number;
+  title: string;
+}
+
+// Same shape as a media split segment, so highlights can be split directly
+export interface AIHighlight {
+  name: string;
+  startSeconds: number;
+  endSeconds: number;
+  reason?: string;
+}
+
+export interface StructuredVideoInsights {
+  title?: string;
+  tags: string[];
+  chapters: AIChapter[];
+  highlights: AIHighlight[];
+}
+
 export type HistoryFilter =
   | 'all'
   | 'youtube'
@@ -1006,7 +1104,9 @@ export type AIProvider =
   | 'qwen'
   | 'ollama'
   | 'lmstudio'
-  | 'proxy';
+  | 'proxy'
+  | 'anthropic'
+  | 'custom';
 export type SummaryStyle = 'short' | 'concise' | 'detailed';
 export type LongSummaryFormat = 'auto' | 'final' | 'parts';
 
@@ -1066,6 +1166,71 @@ export interface AIConfig {
   whisper_api_key?: string; // Separate OpenAI key for Whisper (used when provider !== 'openai')
   whisper_endpoint_url?: string; // Custom Whisper API endpoint URL
   whisper_model?: string; // Custom Whisper model name (default: whisper-1)
+  whisper_backend?: WhisperBackend; // 'local' runs whisper.cpp / faster-whisper offline
+  whisper_local_engine?: LocalWhisperEngine;
+  whisper_local_model?: string; // whisper.cpp ggml model or faster-whisper model name
+  whisper_local_binary_path?: string; // Overrides binary detection when set
+  whisper_diarization?: boolean; // Label speakers in Whisper subtitles and transcripts
+  whisper_diarization_model?: string; // Default: gpt-4o-transcribe-diarize
+  custom_endpoints?: AICustomEndpoint[]; // Named OpenAI-compatible endpoints
+  custom_endpoint_id?: string; // Endpoint used when provider is 'custom'
+  model_routes?: AIModelRoutes;
+  response_cache_hours?: number; // Reuse answers to identical requests (default 168h, 0 = off)
+}
+
+export interface AICustomEndpoint {
+  id: string;
+  name: string;
+  base_url: string;
+  api_key?: string;
+  headers: Record<string, string>; // Extra headers sent with every request
+}
+
+export interface AIModelRoute {
+  provider: AIProvider;
+  model: string;
+  api_key?: string; // Falls back to the main key when the provider is the same
+  custom_endpoint_id?: string;
+}
+
+// Per-task overrides; unset tasks use the main provider and model
+export interface AIModelRoutes {
+  chunk_summary?: AIModelRoute;
+  final_summary?: AIModelRoute;
+  ffmpeg_command?: AIModelRoute;
+}
+
+// Token usage and estimated cost for one provider and model on one day (UTC)
+export interface AIUsageReportRow {
+  day: string;
+  provider: string;
+  model: string;
+  requests: number;
+  cached_requests: number;
+  input_tokens: number;
+  output_tokens: number;
+  average_latency_ms: number;
+  cost_usd: number; // Sum over requests with a known price
+  unpriced_requests: number;
+}
+
+export type WhisperBackend = 'api' | 'local';
+
+export type LocalWhisperEngine = 'whisper-cpp' | 'faster-whisper';
+
+export interface LocalWhisperStatus {
+  engine: LocalWhisperEngine;
+  installed: boolean;
+  version: string | null;
+  binary_path: string | null;
+  is_system: boolean;
+}
+
+export interface LocalWhisperModelInfo {
+  name: string;
+  downloaded: boolean;
+  path: string | null;
+  size_bytes: number | null;
 }
 
 // Available languages (shared between transcript extraction and summary output)
@@ -1130,6 +1295,10 @@ export type ProcessingTaskType =
   | 'flip'
   | 'crop'
   | 'watermark'
+  | 'normalize_loudness'
+  | 'trim_silence'
+  | 'denoise'
+  | 'downmix'
   | 'custom';
 
 export interface VideoMetadata {
@@ -1160,6 +1329,22 @@ export interface FFmpegCommandResult {
   estimated_time_seconds: number;
   output_path: string;
   warnings: string[];
+  // Set by compress with a target_size_mb option; runs as a checked two-pass encode
+  target_size?: TargetSizeEncode;
+  // Set by normalize_loudness and trim_silence, which measure the audio before processing
+  audio_analysis?: AudioAnalysis;
+}
+
+export type AudioAnalysis =
+  | { kind: 'loudnorm'; target_lufs: number; true_peak: number; loudness_range: number }
+  | { kind: 'silence_trim'; threshold_db: number; min_silence: number; duration: number };
+
+export interface TargetSizeEncode {
+  target_size_mb: number;
+  video_bitrate_kbps: number;
+  audio_bitrate_kbps: number;
+  // H.264 encoder picked from the FFmpeg capability probe
+  video_encoder?: string;
 }
 
 export interface ProcessingJob {
@@ -1178,6 +1363,7 @@ export interface ProcessingJob {
   completed_at?: string;
   ai_provider?: string;
   ai_model?: string;
+  priority?: number; // processing queue: higher runs first
 }
 
 export interface ProcessingProgress {
@@ -1199,6 +1385,123 @@ export interface ProcessingPreset {
   prompt_template: string;
   icon?: string;
   created_at: string;
+  // FFmpeg arguments with {input}/{output}/{start}/... placeholders; runs without AI when set
+  args_template?: string[] | null;
+  template_params?: Record<string, string>;
+  output_extension?: string | null;
+}
+
+// Apply one quick action or saved preset to many files; set exactly one of task_type/preset_id
+export interface ProcessingBatchRequest {
+  history_ids?: string[];
+  folder_glob?: string; // A folder, or a pattern such as /Videos/**/*.mkv
+  task_type?: ProcessingTaskType;
+  options?: Record<string, unknown>;
+  preset_id?: string;
+  output_dir?: string;
+  max_concurrent?: number; // Default 2, at most 8
+}
+
+export interface ProcessingBatchProgress {
+  batch_id: string;
+  total: number;
+  completed: number;
+  failed: number;
+  cancelled: number;
+  percent: number;
+}
+
+export interface ProcessingBatchItem {
+  job_id: string; // `${batch_id}:${index}`
+  input_path: string;
+  output_path: string | null;
+  status: 'completed' | 'failed' | 'cancelled';
+  error: string | null;
+}
+
+export interface ProcessingBatchResult {
+  batch_id: string;
+  items: ProcessingBatchItem[];
+  completed: number;
+  failed: number;
+  cancelled: number;
+}
+
+// One pipeline step: a quick action, or `preset:<id>` for an FFmpeg template preset.
+// `concat` takes `intro_path` and/or `outro_path` options and joins them around the input.
+export interface ProcessingPipelineStep {
+  task_type: string;
+  options?: Record<string, unknown>;
+  label?: string;
+}
+
+// Steps run in order on the previous step's output; the timeline selection goes to step 1
+export interface ProcessingPipelineRequest {
+  input_path: string;
+  steps: ProcessingPipelineStep[];
+  timeline_start?: number | null;
+  timeline_end?: number | null;
+  output_dir?: string | null;
+}
+
+export interface ProcessingPipelineProgress {
+  job_id: string;
+  step_index: number;
+  step_count: number;
+  step_label: string;
+  step_percent: number;
+  percent: number;
+}
+
+export interface ProcessingPipelineResult {
+  job_id: string;
+  output_path: string;
+  steps: { label: string; command: string }[];
+}
+
+// Queued jobs are saved with their command so they survive a restart
+export interface ProcessingQueueRequest {
+  id?: string | null;
+  input_path: string;
+  task_type: string;
+  user_prompt?: string | null;
+  command: FFmpegCommandResult;
+  priority?: number;
+}
+
+export interface ProcessingQueueSettings {
+  max_concurrent_jobs: number;
+  resume_interrupted: boolean; // re-run jobs interrupted by closing the app on the next start
+}
+
+export interface FFmpegCapabilities {
+  version: string; // first line of `ffmpeg -version`
+  ffmpeg_path: string;
+  encoders: string[];
+  encoder_codecs: string[];
+  decoders: string[];
+  decoder_codecs: string[];
+  filters: string[];
+}
+
+export interface ContactSheetResult {
+  path: string;
+  columns: number;
+  rows: number;
+  tile_width: number;
+  tile_height: number;
+  timestamps: number[]; // source time of each tile, row by row
+  timestamps_drawn: boolean; // false when FFmpeg lacks drawtext; label tiles in the UI instead
+}
+
+export interface ThumbnailSpriteResult {
+  vtt_path: string; // cues point at sheet files next to it, e.g. sprites_1_001.jpg#xywh=0,0,160,90
+  sprite_paths: string[];
+  interval_seconds: number;
+  tile_width: number;
+  tile_height: number;
+  columns: number;
+  rows: number;
 }
 
 export interface ChatAttachment {
@@ -1285,6 +1588,20 @@ export const QUICK_ACTIONS: QuickAction[] = [
   },
   { id: 'compress', icon: '📦', label: 'Compress', description: 'Reduce file size' },
   { id: 'remove_audio', icon: '🔇', label: 'Remove Audio', description: 'Remove audio track' },
+  {
+    id: 'normalize_loudness',
+    icon: '🔊',
+    label: 'Normalize Loudness',
+    description: 'Two-pass EBU R128 loudness normalization',
+  },
+  {
+    id: 'trim_silence',
+    icon: '✂️',
+    label: 'Trim Silence',
+    description: 'Cut silence from the start and end',
+  },
+  { id: 'denoise', icon: '🎧', label: 'Reduce Noise', description: 'Reduce background noise' },
+  { id: 'downmix', icon: '🎚️', label: 'Downmix', description: 'Mix audio down to stereo or mono' },
   { id: 'rotate', icon: '🔃', label: 'Rotate', description: 'Rotate video 90°/180°/270°' },
   {
     id: 'merge',
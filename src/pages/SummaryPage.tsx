@@ -62,7 +62,7 @@ function isYouTubeUrl(url: string) {
 }
 
 function providerRequiresApiKey(provider: string) {
-  return provider !== 'ollama' && provider !== 'lmstudio';
+  return provider !== 'ollama' && provider !== 'lmstudio' && provider !== 'custom';
 }
 
 function loadSummaryFontSize(): SummaryFontSize {
@@ -134,6 +134,7 @@ export function SummaryPage({
     loadingParams,
     error,
     result,
+    streamingSummary,
     saved,
     showFullSummary,
     showSettings,
@@ -741,12 +742,24 @@ export function SummaryPage({
         )}
 
         {/* Loading State */}
-        {isLoading && (
+        {isLoading && !streamingSummary && (
           <SummaryLoadingState
             loadingText={getLoadingText(loadingStatus) || t('summary.processing')}
           />
         )}
 
+        {/* Streaming State */}
+        {isLoading && streamingSummary && (
+          <div
+            className={cn(
+              'flex-1 overflow-auto text-muted-foreground',
+              getSummaryFontSizeClass(fontSize),
+            )}
+          >
+            <SimpleMarkdown content={streamingSummary} />
+          </div>
+        )}
+
         {/* Empty State */}
         {!result && !error && !isLoading && (
           <div className="flex-1 flex flex-col items-center justify-center text-center py-12">
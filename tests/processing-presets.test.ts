@@ -0,0 +1,48 @@
+import { describe, expect, test } from 'bun:test';
+import { buildPresetArgsTemplate, templatePresetTaskType } from '../src/lib/processing-presets';
+
+describe('buildPresetArgsTemplate', () => {
+  test('replaces the input and output paths with placeholders', () => {
+    const template = buildPresetArgsTemplate(
+      {
+        command_args: ['-y', '-i', '/videos/talk.mp4', '-vf', 'scale=-2:720', '/out/talk_720p.MKV'],
+        output_path: '/out/talk_720p.MKV',
+      },
+      '/videos/talk.mp4',
+    );
+
+    expect(template).toEqual({
+      argsTemplate: ['-y', '-i', '{input}', '-vf', 'scale=-2:720', '{output}'],
+      outputExtension: 'mkv',
+    });
+  });
+
+  test('rejects commands that do not end with the output file', () => {
+    expect(
+      buildPresetArgsTemplate(
+        { command_args: ['-i', '/videos/talk.mp4', '/out/a.mp4', '-y'], output_path: '/out/a.mp4' },
+        '/videos/talk.mp4',
+      ),
+    ).toBeNull();
+    expect(
+      buildPresetArgsTemplate(
+        { command_args: ['-i', '/videos/other.mp4', '/out/a.mp4'], output_path: '/out/a.mp4' },
+        '/videos/talk.mp4',
+      ),
+    ).toBeNull();
+    expect(
+      buildPresetArgsTemplate(
+        {
+          command_args: ['-i', '/videos/talk.mp4', '-pass', '2', '/out/a.mp4'],
+          output_path: '/out/a.mp4',
+          target_size: { target_size_mb: 8, video_bitrate_kbps: 900, audio_bitrate_kbps: 128 },
+        },
+        '/videos/talk.mp4',
+      ),
+    ).toBeNull();
+  });
+
+  test('builds the quick action task type for a preset', () => {
+    expect(templatePresetTaskType('abc')).toBe('preset:abc');
+  });
+});
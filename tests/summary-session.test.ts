@@ -75,6 +75,33 @@ describe('summarySessionReducer', () => {
     expect(cancelled.loadingStatus).toBe('');
   });
 
+  test('accumulates streamed summary text only while a summary is running', () => {
+    const initial = createInitialSummarySessionState({
+      style: 'concise',
+      language: 'auto',
+      transcriptLanguages: ['en'],
+      longSummaryFormat: 'auto',
+      longSummaryWords: DEFAULT_LONG_SUMMARY_WORDS,
+    });
+    const loading = summarySessionReducer(initial, {
+      type: 'start',
+      url: 'https://youtu.be/abc',
+      options: initial.options,
+    });
+
+    const streaming = [{ delta: 'Hello' }, { delta: ' world' }].reduce(
+      (state, { delta }) => summarySessionReducer(state, { type: 'append-stream', delta }),
+      loading,
+    );
+    expect(streaming.streamingSummary).toBe('Hello world');
+
+    const cancelled = summarySessionReducer(streaming, { type: 'cancel' });
+    expect(cancelled.streamingSummary).toBe('');
+    expect(
+      summarySessionReducer(cancelled, { type: 'append-stream', delta: 'late' }).streamingSummary,
+    ).toBe('');
+  });
+
   test('only sends backend cancellation when a backend summary request is active', () => {
     expect(getBackendSummaryCancelRequestId(null)).toBeNull();
     expect(getBackendSummaryCancelRequestId('')).toBeNull();